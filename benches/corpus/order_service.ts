@@ -0,0 +1,70 @@
+import { Order, OrderLine, Customer } from "./types";
+
+const TAX_RATE = 0.2;
+let defaultCurrency = "EUR";
+
+export interface PricingOptions {
+  discountCode?: string;
+  currency?: string;
+  includeTax: boolean;
+}
+
+export function lineTotal(line: OrderLine): number {
+  let total = line.unitPrice * line.quantity;
+  if (line.discount != null && line.discount > 0) {
+    total = total - total * line.discount;
+  }
+  return Math.round(total * 100) / 100;
+}
+
+export function orderTotal(order: Order, options: PricingOptions): number {
+  let subtotal = 0;
+  for (const line of order.lines) {
+    subtotal += lineTotal(line);
+  }
+  if (options.discountCode == "WELCOME10") {
+    subtotal = subtotal * 0.9;
+  } else if (options.discountCode === "VIP") {
+    subtotal = subtotal * 0.8;
+  }
+  const tax = options.includeTax ? subtotal * TAX_RATE : 0;
+  return subtotal + tax;
+}
+
+export class OrderService {
+  private readonly orders = new Map<string, Order>();
+
+  constructor(private readonly customers: Map<string, Customer>) {}
+
+  create(customerId: string, lines: OrderLine[]): Order {
+    const customer = this.customers.get(customerId);
+    if (!customer) {
+      throw new Error(`Unknown customer ${customerId}`);
+    }
+    let id = `${customerId}-${this.orders.size + 1}`;
+    const order: Order = { id, customerId, lines, status: "open", currency: defaultCurrency };
+    this.orders.set(id, order);
+    return order;
+  }
+
+  cancel(id: string): boolean {
+    const order = this.orders.get(id);
+    if (order == undefined || order.status !== "open") {
+      return false;
+    }
+    order.status = "cancelled";
+    return true;
+  }
+
+  summary(customerId: string): { count: number; total: number } {
+    let count = 0;
+    let total = 0;
+    this.orders.forEach((order) => {
+      if (order.customerId === customerId && order.status != "cancelled") {
+        count++;
+        total += orderTotal(order, { includeTax: true });
+      }
+    });
+    return { count, total };
+  }
+}
@@ -0,0 +1,54 @@
+const DEFAULT_ATTEMPTS = 3;
+
+function sleep(ms) {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+function isRetryable(error) {
+  if (error == null) {
+    return false;
+  }
+  let status = error.status || 0;
+  return status == 429 || status >= 500 || error.code === "ECONNRESET";
+}
+
+async function withRetry(operation, options) {
+  let attempts = (options && options.attempts) || DEFAULT_ATTEMPTS;
+  let delay = (options && options.baseDelay) || 100;
+  let lastError;
+  for (let attempt = 1; attempt <= attempts; attempt++) {
+    try {
+      return await operation(attempt);
+    } catch (error) {
+      lastError = error;
+      if (!isRetryable(error) || attempt == attempts) {
+        break;
+      }
+      await sleep(delay);
+      delay = delay * 2;
+    }
+  }
+  throw lastError;
+}
+
+function createClient(baseUrl, fetchImpl) {
+  let requests = 0;
+  return {
+    get(path) {
+      requests++;
+      return withRetry(() => fetchImpl(baseUrl + path).then((response) => {
+        if (!response.ok) {
+          let error = new Error("Request failed: " + response.status);
+          error.status = response.status;
+          throw error;
+        }
+        return response.json();
+      }));
+    },
+    stats() {
+      return { requests };
+    },
+  };
+}
+
+module.exports = { withRetry, createClient, isRetryable };
@@ -0,0 +1,64 @@
+import React, { useEffect, useMemo, useState } from "react";
+
+type User = { id: number; name: string; email: string; active: boolean; lastLogin?: string };
+
+export interface UserTableProps {
+  users: User[];
+  pageSize?: number;
+  onSelect(user: User): void;
+}
+
+function formatLastLogin(value?: string): string {
+  if (value == null) {
+    return "never";
+  }
+  let date = new Date(value);
+  return date.toLocaleDateString();
+}
+
+export function UserTable({ users, pageSize = 20, onSelect }: UserTableProps) {
+  const [page, setPage] = useState(0);
+  const [query, setQuery] = useState("");
+  let [onlyActive, setOnlyActive] = useState(false);
+
+  const filtered = useMemo(() => {
+    let needle = query.trim().toLowerCase();
+    return users.filter((user) => {
+      if (onlyActive && !user.active) return false;
+      return needle == "" || user.name.toLowerCase().includes(needle) || user.email.includes(needle);
+    });
+  }, [users, query, onlyActive]);
+
+  useEffect(() => {
+    if (page * pageSize >= filtered.length && page > 0) {
+      setPage(0);
+    }
+  }, [filtered.length, page, pageSize]);
+
+  const visible = filtered.slice(page * pageSize, (page + 1) * pageSize);
+  let pages = Math.ceil(filtered.length / pageSize);
+
+  return (
+    <div className="user-table">
+      <input value={query} onChange={(event) => setQuery(event.target.value)} placeholder="Search" />
+      <label>
+        <input type="checkbox" checked={onlyActive} onChange={() => setOnlyActive(!onlyActive)} /> Active only
+      </label>
+      <table>
+        <tbody>
+          {visible.map((user) => (
+            <tr key={user.id} onClick={() => onSelect(user)}>
+              <td>{user.name}</td>
+              <td>{user.email}</td>
+              <td>{user.active ? "yes" : "no"}</td>
+              <td>{formatLastLogin(user.lastLogin)}</td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+      <button disabled={page == 0} onClick={() => setPage(page - 1)}>Previous</button>
+      <span>{page + 1} / {pages}</span>
+      <button disabled={page + 1 >= pages} onClick={() => setPage(page + 1)}>Next</button>
+    </div>
+  );
+}
@@ -0,0 +1,84 @@
+//! # Lint Throughput Benchmark
+//!
+//! Parses and lints a fixed corpus of realistic TypeScript/JavaScript files with the static
+//! rules, to catch regressions in the OXC hot path. Native only:
+//!
+//! ```sh
+//! cargo bench --no-default-features --features bench,embedded_rulebase --bench lint_throughput
+//! ```
+//!
+//! Expected order of magnitude on a current desktop CPU: well under a millisecond per corpus
+//! file, around 10 MB/s of source for the whole rule set. A drop of roughly 10x usually means
+//! rules are re-parsing the file or rebuilding the semantic model per rule; the
+//! `semantic_builds` count of `RuleExecutionOutcome` pins that down.
+//!
+//! @category benchmarks
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use moon_shine::buffer_lint::lint_buffer_with_rules;
+use moon_shine::config::MoonShineConfig;
+use moon_shine::rule_types::{FixSafety, FixStatus, RuleCategory, RuleMetadata, RuleSeverity};
+use moon_shine::rulebase::RuleImplementation;
+
+/// Corpus files as (virtual path, source)
+const CORPUS: &[(&str, &str)] = &[
+    ("src/orders/order_service.ts", include_str!("corpus/order_service.ts")),
+    ("src/components/user_table.tsx", include_str!("corpus/user_table.tsx")),
+    ("lib/retry.js", include_str!("corpus/retry.js")),
+];
+
+/// Static rules that need the semantic model, so the benchmark covers parse and semantic work
+const RULES: &[&str] = &["prefer-const", "eqeqeq", "complexity", "no-console", "no-unused-vars"];
+
+fn static_rule(rule_name: &str) -> RuleMetadata {
+    RuleMetadata {
+        id: rule_name.to_string(),
+        name: rule_name.to_string(),
+        description: String::new(),
+        category: RuleCategory::Style,
+        severity: RuleSeverity::Warning,
+        fix_status: FixStatus::Autofix,
+        fix_safety: FixSafety::Safe,
+        ai_enhanced: false,
+        cost: 1,
+        tags: vec![],
+        dependencies: vec![],
+        implementation: RuleImplementation::OxcStatic {
+            rule_name: rule_name.to_string(),
+        },
+        config_schema: None,
+        applies_to: None,
+    }
+}
+
+fn lint_throughput(c: &mut Criterion) {
+    let config = MoonShineConfig::default();
+    let rules: Vec<RuleMetadata> = RULES.iter().map(|rule_name| static_rule(rule_name)).collect();
+    let mut group = c.benchmark_group("lint_throughput");
+
+    for (path, source) in CORPUS {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::new("file", path), source, |b, source| {
+            b.iter(|| lint_buffer_with_rules(black_box(source), path, &config, &rules).expect("corpus file lints"))
+        });
+    }
+
+    let corpus_bytes: usize = CORPUS.iter().map(|(_, source)| source.len()).sum();
+    group.throughput(Throughput::Bytes(corpus_bytes as u64));
+    group.bench_function("corpus", |b| {
+        b.iter(|| {
+            for (path, source) in CORPUS {
+                black_box(lint_buffer_with_rules(source, path, &config, &rules).expect("corpus file lints"));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, lint_throughput);
+criterion_main!(benches);
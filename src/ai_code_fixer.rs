@@ -5,13 +5,43 @@
  * Replaces mock implementations with real Claude CLI execution.
  */
 
-use crate::error::Result;
-use crate::provider_router::{analyze_code_with_ai, fix_code_with_ai};
+use crate::cross_file_context;
+use crate::error::{Error, Result};
+use crate::provider_router::post_process::CODE_FIX_TASK;
+use crate::provider_router::{analyze_code_with_ai, fix_code_with_ai, get_ai_router, AIResponse, PostProcessorRegistry};
+use crate::rule_types::FixSafety;
+use crate::rulebase::fixes::{apply_fixes, FixOptions};
+use crate::rulebase::static_rules::offset_to_line_column;
+use crate::token_usage::estimate_tokens;
 use crate::tsdoc;
+use crate::types::{DiagnosticSeverity, LintDiagnostic};
+use oxc_allocator::Allocator;
+use oxc_ast::ast::Statement;
+use oxc_parser::Parser;
+use oxc_span::SourceType;
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+/// Continuation requests made for a truncated fix before it is rejected
+const MAX_CONTINUATIONS: usize = 2;
+
+/// Rule name of the diagnostic used to splice a span-scoped AI fix into the file
+const AI_SPAN_FIX_RULE: &str = "ai-span-fix";
 
 // ClaudeFixerConfig moved to MoonShineConfig - all settings consolidated
 
+/// Part of a file an AI fix is requested for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixScope {
+    WholeFile,
+    Span(Range<usize>), // Byte range of the file content to replace
+}
+
 /// AI code fixing result with metrics and improvements
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiCodeFixResult {
@@ -22,6 +52,21 @@ pub struct AiCodeFixResult {
     pub fixed_content: Option<String>,
     pub fixed_errors: u32,
     pub relationships: Vec<CodeRelationship>,
+    #[serde(default)]
+    pub rejected_reason: Option<String>, // Why the AI output was discarded and the original kept
+    #[serde(default)]
+    pub passes: Vec<PassResult>, // Per-pass breakdown of a multi-pass fix; empty for a single fix request
+}
+
+/// One pass of a multi-pass AI fix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PassResult {
+    pub pass: u32,                 // 1-based pass number
+    pub prompt: String,            // Fix prompt sent for this pass, excluding continuation requests
+    pub diagnostics_before: usize, // Diagnostics in the code the pass started from
+    pub diagnostics_after: usize,  // Diagnostics in the code the pass left; equal to `diagnostics_before` when it changed nothing
+    pub tokens: u32,               // Estimated prompt and response tokens of every request the pass made
+    pub changed: bool,             // The pass produced a fix that changed the file
 }
 
 impl Default for AiCodeFixResult {
@@ -34,6 +79,8 @@ impl Default for AiCodeFixResult {
             fixed_content: None,
             fixed_errors: 0,
             relationships: vec![],
+            rejected_reason: None,
+            passes: vec![],
         }
     }
 }
@@ -51,17 +98,192 @@ pub struct CodeRelationship {
 #[derive(Debug)]
 pub struct ClaudeFixer {
     config: crate::config::MoonShineConfig, // Use consolidated config
+    project_files: Vec<(String, String)>,   // `(path, source)` files cross-file context is drawn from
 }
 
 impl ClaudeFixer {
     /// Create new AI fixer with intelligent provider routing
     pub fn new(config: crate::config::MoonShineConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            project_files: Vec::new(),
+        }
+    }
+
+    /// Project files whose imported declarations are added to prompts when `ai.cross_file_context` is on
+    pub fn with_project_files(mut self, files: Vec<(String, String)>) -> Self {
+        self.project_files = files;
+        self
     }
 
     /// Production AI CLI integration via intelligent provider routing
     /// Automatically selects the best AI provider based on code fixing requirements
     pub async fn fix_file_sync(&mut self, file_path: &str, content: &str, language: &str, session_id: String) -> Result<AiCodeFixResult> {
+        let router = get_ai_router();
+        let supports_continuation = |provider: &str| {
+            router
+                .providers()
+                .iter()
+                .any(|config| config.name == provider && config.capabilities.supports_sessions)
+        };
+
+        self.fix_file_with(file_path, content, language, session_id, supports_continuation, |session_id, prompt| {
+            fix_code_with_ai(session_id, file_path.to_string(), content.to_string(), language.to_string(), prompt)
+        })
+        .await
+    }
+
+    /// Fix a file using `execute` to send prompts to the AI provider.
+    ///
+    /// Truncated output (provider `finish_reason == "length"`, an unterminated code fence,
+    /// or code that fails to parse with unclosed delimiters) is never returned as a fix.
+    /// When `supports_continuation` holds for the responding provider, up to
+    /// `MAX_CONTINUATIONS` continuation requests are made on the same session; if the code
+    /// is still incomplete the fix is rejected and the original content is kept.
+    pub async fn fix_file_with<S, F, Fut>(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
+        session_id: String,
+        supports_continuation: S,
+        execute: F,
+    ) -> Result<AiCodeFixResult>
+    where
+        S: Fn(&str) -> bool,
+        F: FnMut(String, String) -> Fut,
+        Fut: Future<Output = Result<AIResponse>>,
+    {
+        self.fix_scoped_with(file_path, content, language, session_id, FixScope::WholeFile, supports_continuation, execute)
+            .await
+    }
+
+    /// Fix a batch of `(file_path, content)` files via intelligent provider routing; see [`ClaudeFixer::fix_files_with`]
+    pub async fn fix_files(&mut self, files: &[(String, String)], session_id: String) -> Result<Vec<AiCodeFixResult>> {
+        let router = get_ai_router();
+        let supports_continuation = |provider: &str| {
+            router
+                .providers()
+                .iter()
+                .any(|config| config.name == provider && config.capabilities.supports_sessions)
+        };
+
+        self.fix_files_with(files, session_id, supports_continuation, |file_path, content, session_id, prompt| {
+            fix_code_with_ai(session_id, file_path.to_string(), content.to_string(), language_from_path(file_path).to_string(), prompt)
+        })
+        .await
+    }
+
+    /// Fix a batch of `(file_path, content)` files, each in the language its extension names;
+    /// see [`ClaudeFixer::fix_file_with`]. `execute` also receives the file a prompt is for.
+    ///
+    /// Files with the same content, language and cross-file context are sent to the provider
+    /// once. The prompt names the first such file, and its fix is reused for the others; their
+    /// `file_path`, relationships and success are recomputed for each file. Results are
+    /// returned in input order.
+    pub async fn fix_files_with<S, F, Fut>(
+        &mut self,
+        files: &[(String, String)],
+        session_id: String,
+        supports_continuation: S,
+        mut execute: F,
+    ) -> Result<Vec<AiCodeFixResult>>
+    where
+        S: Fn(&str) -> bool,
+        F: FnMut(&str, &str, String, String) -> Fut,
+        Fut: Future<Output = Result<AIResponse>>,
+    {
+        let mut fixed_by_code: HashMap<u64, AiCodeFixResult> = HashMap::new();
+        let mut results = Vec::with_capacity(files.len());
+
+        for (file_path, content) in files {
+            let language = language_from_path(file_path);
+            let key = prompt_key(content, language, &self.cross_file_context(file_path));
+            let result = match fixed_by_code.get(&key) {
+                Some(shared) => {
+                    moon_debug!("Reusing the AI fix of identical code for {}", file_path);
+                    self.reuse_fix(shared, file_path, content, language, &session_id).await?
+                }
+                None => {
+                    let execute = &mut execute;
+                    let result = self
+                        .fix_file_with(file_path, content, language, session_id.clone(), &supports_continuation, |session_id, prompt| {
+                            execute(file_path.as_str(), content.as_str(), session_id, prompt)
+                        })
+                        .await?;
+                    fixed_by_code.insert(key, result.clone());
+                    result
+                }
+            };
+            results.push(result);
+        }
+
+        if fixed_by_code.len() < files.len() {
+            moon_info!(
+                "Deduplicated AI fix prompts: {} provider call(s) for {} file(s)",
+                fixed_by_code.len(),
+                files.len()
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// `shared`, the fix of another file with the same code, with its path-dependent fields computed for `file_path`
+    async fn reuse_fix(&self, shared: &AiCodeFixResult, file_path: &str, content: &str, language: &str, session_id: &str) -> Result<AiCodeFixResult> {
+        let Some(fixed_content) = &shared.fixed_content else {
+            return Ok(AiCodeFixResult {
+                file_path: file_path.to_string(),
+                ..shared.clone()
+            });
+        };
+
+        let relationships = self.analyze_relationships(fixed_content, file_path, session_id).await?;
+        let tsdoc_improvement_significant =
+            (language == "typescript" || language == "javascript") && shared.tsdoc_coverage - calculate_tsdoc_coverage(content) as f32 > 5.0;
+
+        Ok(AiCodeFixResult {
+            file_path: file_path.to_string(),
+            success: shared.fixed_errors > 0 || tsdoc_improvement_significant || !relationships.is_empty(),
+            relationships,
+            ..shared.clone()
+        })
+    }
+
+    /// Fix `scope` of a file; see [`ClaudeFixer::fix_file_with`].
+    ///
+    /// The response must have the shape that was requested. A span fix must parse as the
+    /// same kind of construct as the original span and is spliced in with `apply_fixes`; a
+    /// whole-file fix must parse as a file that keeps most top-level statements. A response
+    /// of the wrong shape (a whole file for a span, a snippet for a file) is rejected and
+    /// `fixed_content` is always the complete file.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fix_scoped_with<S, F, Fut>(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
+        session_id: String,
+        scope: FixScope,
+        supports_continuation: S,
+        mut execute: F,
+    ) -> Result<AiCodeFixResult>
+    where
+        S: Fn(&str) -> bool,
+        F: FnMut(String, String) -> Fut,
+        Fut: Future<Output = Result<AIResponse>>,
+    {
+        if let FixScope::Span(span) = &scope {
+            let in_bounds = span.start <= span.end && span.end <= content.len();
+            if !in_bounds || !content.is_char_boundary(span.start) || !content.is_char_boundary(span.end) {
+                return Err(Error::Validation {
+                    field: "scope".to_string(),
+                    expected: format!("a span on character boundaries within 0..{}", content.len()),
+                    actual: format!("{:?}", span),
+                });
+            }
+        }
+
         // Calculate initial TSDoc coverage for comparison
         let initial_tsdoc_coverage = if language == "typescript" || language == "javascript" {
             calculate_tsdoc_coverage(content) as f32
@@ -70,13 +292,74 @@ impl ClaudeFixer {
         };
 
         // Build AI prompt using provider-agnostic interface
-        let ai_prompt = self.build_ai_prompt(content, language, file_path)?;
+        let ai_prompt = match &scope {
+            FixScope::WholeFile => self.build_ai_prompt(content, language, file_path)?,
+            FixScope::Span(span) => {
+                let (start_line, _) = offset_to_line_column(content, span.start as u32);
+                let (end_line, _) = offset_to_line_column(content, span.end as u32);
+                format!(
+                    "{}\n\nThe code above is lines {}-{} of the file. Return only the replacement for this snippet, not the whole file.",
+                    self.build_ai_prompt(&content[span.clone()], language, file_path)?,
+                    start_line,
+                    end_line
+                )
+            }
+        };
 
         // Execute AI via intelligent router - automatically selects best provider
-        let ai_response = fix_code_with_ai(session_id, file_path.to_string(), content.to_string(), language.to_string(), ai_prompt).await?;
+        let ai_response = execute(session_id, ai_prompt).await?;
+
+        // Extract fixed content from AI response, completing it if the output was cut off
+        let mut fixed_content = self.parse_ai_response(&ai_response.content)?;
+        let mut truncation = detect_truncation(&ai_response, &fixed_content, file_path, language);
+        let mut continuations = 0;
+        while truncation.is_some() && continuations < MAX_CONTINUATIONS && supports_continuation(&ai_response.provider_used) {
+            continuations += 1;
+            moon_info!(
+                "AI output for {} was truncated ({}); requesting continuation {}/{}",
+                file_path,
+                truncation.unwrap_or_default(),
+                continuations,
+                MAX_CONTINUATIONS
+            );
+
+            let continuation = execute(ai_response.session_id.clone(), build_continuation_prompt(&fixed_content)).await?;
+            fixed_content.push_str(&continuation_code(&continuation.content));
+            truncation = detect_truncation(&continuation, &fixed_content, file_path, language);
+        }
+
+        if let Some(reason) = truncation {
+            moon_warn!("Rejecting AI fix for {}: output truncated ({}); keeping original content", file_path, reason);
+            return Ok(AiCodeFixResult {
+                file_path: file_path.to_string(),
+                success: false,
+                ai_provider: ai_response.provider_used,
+                tsdoc_coverage: initial_tsdoc_coverage,
+                fixed_content: None,
+                fixed_errors: 0,
+                relationships: vec![],
+                rejected_reason: Some(format!("truncated output: {}", reason)),
+                passes: vec![],
+            });
+        }
 
-        // Extract fixed content from AI response
-        let fixed_content = self.parse_ai_response(&ai_response.content)?;
+        let fixed_content = match normalize_fix(content, &scope, &fixed_content, file_path, language) {
+            Ok(fixed_content) => fixed_content,
+            Err(reason) => {
+                moon_warn!("Rejecting AI fix for {}: {}; keeping original content", file_path, reason);
+                return Ok(AiCodeFixResult {
+                    file_path: file_path.to_string(),
+                    success: false,
+                    ai_provider: ai_response.provider_used,
+                    tsdoc_coverage: initial_tsdoc_coverage,
+                    fixed_content: None,
+                    fixed_errors: 0,
+                    relationships: vec![],
+                    rejected_reason: Some(format!("mismatched fix shape: {}", reason)),
+                    passes: vec![],
+                });
+            }
+        };
 
         // Calculate improvements and metrics
         let fixed_errors = self.count_fixed_errors(content, &fixed_content, language)?;
@@ -125,9 +408,112 @@ impl ClaudeFixer {
             fixed_content: Some(fixed_content),
             fixed_errors,
             relationships,
+            rejected_reason: None,
+            passes: vec![],
         })
     }
 
+    /// Fix a file in up to `max_passes` passes, each sent the code the previous pass left.
+    ///
+    /// `count_diagnostics` counts the diagnostics in a version of the file. Passes stop once
+    /// the code has none, or after a pass that is rejected or returns the code unchanged. The
+    /// result holds the code after the last changing pass and one [`PassResult`] per pass run,
+    /// so each pass's improvement and the tokens it cost stay visible.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn fix_passes_with<D, S, F, Fut>(
+        &mut self,
+        file_path: &str,
+        content: &str,
+        language: &str,
+        session_id: String,
+        max_passes: u32,
+        count_diagnostics: D,
+        supports_continuation: S,
+        mut execute: F,
+    ) -> Result<AiCodeFixResult>
+    where
+        D: Fn(&str) -> usize,
+        S: Fn(&str) -> bool,
+        F: FnMut(String, String) -> Fut,
+        Fut: Future<Output = Result<AIResponse>>,
+    {
+        let mut combined = AiCodeFixResult {
+            file_path: file_path.to_string(),
+            ..AiCodeFixResult::default()
+        };
+        let mut current = content.to_string();
+        let mut diagnostics = count_diagnostics(&current);
+        let initial_diagnostics = diagnostics;
+
+        for pass in 1..=max_passes.max(1) {
+            if diagnostics == 0 {
+                break;
+            }
+
+            let prompts = RefCell::new(Vec::new());
+            let tokens = Cell::new(0u32);
+            let (prompts_ref, tokens_ref, execute_ref) = (&prompts, &tokens, &mut execute);
+            let result = self
+                .fix_scoped_with(
+                    file_path,
+                    &current,
+                    language,
+                    session_id.clone(),
+                    FixScope::WholeFile,
+                    &supports_continuation,
+                    move |session_id, prompt| {
+                        tokens_ref.set(tokens_ref.get() + estimate_tokens(&prompt) as u32);
+                        prompts_ref.borrow_mut().push(prompt.clone());
+                        let response = execute_ref(session_id, prompt);
+                        async move {
+                            let response = response.await?;
+                            tokens_ref.set(tokens_ref.get() + estimate_tokens(&response.content) as u32);
+                            Ok::<_, Error>(response)
+                        }
+                    },
+                )
+                .await?;
+
+            let fixed = result.fixed_content.filter(|fixed| *fixed != current);
+            let diagnostics_after = fixed.as_deref().map_or(diagnostics, &count_diagnostics);
+            combined.passes.push(PassResult {
+                pass,
+                prompt: prompts.into_inner().into_iter().next().unwrap_or_default(),
+                diagnostics_before: diagnostics,
+                diagnostics_after,
+                tokens: tokens.get(),
+                changed: fixed.is_some(),
+            });
+            combined.ai_provider = result.ai_provider;
+            combined.tsdoc_coverage = result.tsdoc_coverage;
+            combined.rejected_reason = result.rejected_reason;
+            if !result.relationships.is_empty() {
+                combined.relationships = result.relationships;
+            }
+
+            let Some(fixed) = fixed else {
+                break;
+            };
+            combined.success |= result.success;
+            combined.fixed_errors += result.fixed_errors;
+            current = fixed;
+            diagnostics = diagnostics_after;
+        }
+
+        if combined.passes.iter().any(|pass| pass.changed) {
+            combined.fixed_content = Some(current);
+        }
+        moon_info!(
+            "AI fix of {} ran {} pass(es): {} -> {} diagnostics, ~{} tokens",
+            file_path,
+            combined.passes.len(),
+            initial_diagnostics,
+            diagnostics,
+            combined.passes.iter().map(|pass| pass.tokens).sum::<u32>()
+        );
+        Ok(combined)
+    }
+
     /// Production relationship analysis via intelligent AI provider routing
     /// Automatically selects the best AI provider for code analysis tasks
     pub async fn analyze_relationships(&self, content: &str, file_path: &str, session_id: &str) -> Result<Vec<CodeRelationship>> {
@@ -159,6 +545,14 @@ impl ClaudeFixer {
         self.parse_relationships_response(&ai_response.content)
     }
 
+    /// Read-only prompt section with declarations `file_path` imports; empty when disabled
+    fn cross_file_context(&self, file_path: &str) -> String {
+        if !self.config.ai.cross_file_context || self.project_files.is_empty() {
+            return String::new();
+        }
+        cross_file_context::gather_context(file_path, &self.project_files, self.config.ai.cross_file_context_tokens).render()
+    }
+
     /// Build language-specific AI prompt for code fixing
     fn build_ai_prompt(&self, content: &str, language: &str, file_path: &str) -> Result<String> {
         let language_specific_instructions = match language {
@@ -182,7 +576,7 @@ impl ClaudeFixer {
          File: {}\n\
          Language: {}\n\n\
          Code to fix:\n{}\n\n\
-         Return only the fixed code without explanations.",
+         {}Return only the fixed code without explanations.",
             if self.config.enable_ai_tsdoc.unwrap_or(true) && language == "typescript" {
                 format!(
                     "Fix TypeScript issues, improve type safety, and add comprehensive TSDoc comments. \
@@ -194,7 +588,11 @@ impl ClaudeFixer {
             },
             file_path,
             language,
-            content
+            content,
+            match self.cross_file_context(file_path) {
+                context if context.is_empty() => context,
+                context => format!("{}\n", context),
+            }
         );
 
         Ok(prompt)
@@ -203,24 +601,9 @@ impl ClaudeFixer {
     // Note: AI execution is now handled by the unified ai_provider module
     // This eliminates Claude-specific hardcoding and enables multiple AI providers
 
-    /// Parse AI response and extract fixed code content
-    /// <!-- TODO: Add more robust parsing for AI responses, potentially supporting multiple markdown block styles or a more structured output format from the AI. -->
+    /// Parse AI response and extract fixed code content using the code-fix post-processors
     fn parse_ai_response(&self, response: &str) -> Result<String> {
-        // AI providers typically return code in markdown blocks or directly
-        // Extract code from markdown blocks if present
-        if let Some(code_start) = response.find("```") {
-            if let Some(code_end) = response[code_start + 3..].find("```") {
-                // Skip language identifier line if present
-                let code_block = &response[code_start + 3..code_start + 3 + code_end];
-                if let Some(newline) = code_block.find('\n') {
-                    return Ok(code_block[newline + 1..].trim().to_string());
-                }
-                return Ok(code_block.trim().to_string());
-            }
-        }
-
-        // If no markdown blocks, return the response directly (trimmed)
-        Ok(response.trim().to_string())
+        Ok(PostProcessorRegistry::with_defaults().process(CODE_FIX_TASK, response))
     }
 
     /// Count errors fixed by comparing original and fixed content
@@ -281,17 +664,177 @@ impl ClaudeFixer {
     /// Detect programming language from file path
     /// <!-- TODO: Align this with the `analysis.rs::detect_language` function and ensure consistency. -->
     fn detect_language_from_path(&self, file_path: &str) -> &str {
-        if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
-            "typescript"
-        } else if file_path.ends_with(".js") || file_path.ends_with(".jsx") {
-            "javascript"
-        } else if file_path.ends_with(".rs") {
-            "rust"
-        } else if file_path.ends_with(".py") {
-            "python"
-        } else {
-            "unknown"
+        language_from_path(file_path)
+    }
+}
+
+fn language_from_path(file_path: &str) -> &'static str {
+    if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
+        "typescript"
+    } else if file_path.ends_with(".js") || file_path.ends_with(".jsx") {
+        "javascript"
+    } else if file_path.ends_with(".rs") {
+        "rust"
+    } else if file_path.ends_with(".py") {
+        "python"
+    } else {
+        "unknown"
+    }
+}
+
+/// Why AI output looks cut off, if it does
+fn detect_truncation(response: &AIResponse, code: &str, file_path: &str, language: &str) -> Option<&'static str> {
+    if response.finish_reason.as_deref() == Some("length") {
+        return Some("provider hit its output limit");
+    }
+
+    let fence_count = response.content.matches("```").count();
+    if fence_count % 2 == 1 && response.content.trim_start().starts_with("```") {
+        return Some("unterminated code fence");
+    }
+
+    if language != "typescript" && language != "javascript" {
+        return None;
+    }
+
+    let source_type = SourceType::from_path(file_path).unwrap_or_default();
+    let allocator = Allocator::default();
+    if Parser::new(&allocator, code, source_type).parse().errors.is_empty() {
+        return None;
+    }
+    has_unclosed_delimiters(code).then_some("unbalanced braces")
+}
+
+/// Complete file content for `fixed` returned for `scope`, or why it does not fit the scope
+fn normalize_fix(content: &str, scope: &FixScope, fixed: &str, file_path: &str, language: &str) -> std::result::Result<String, String> {
+    let checks_syntax = language == "typescript" || language == "javascript";
+    let source_type = SourceType::from_path(file_path).unwrap_or_default();
+
+    match scope {
+        FixScope::WholeFile if !checks_syntax => Ok(fixed.to_string()),
+        FixScope::WholeFile => {
+            let Some(replacement) = statement_shape(fixed, source_type) else {
+                return Err("whole-file fix does not parse".to_string());
+            };
+            let original_len = statement_shape(content, source_type).map(|shape| shape.len()).unwrap_or(0);
+            if original_len >= 2 && replacement.len() * 2 < original_len {
+                return Err(format!(
+                    "whole-file fix has {} top-level statement(s) where the file has {}; it looks like a snippet",
+                    replacement.len(),
+                    original_len
+                ));
+            }
+            Ok(fixed.to_string())
+        }
+        FixScope::Span(span) => {
+            if checks_syntax {
+                if let Some(expected) = statement_shape(&content[span.clone()], source_type) {
+                    let replacement = statement_shape(fixed, source_type).ok_or_else(|| "span fix does not parse".to_string())?;
+                    if replacement != expected {
+                        return Err(format!("span fix is [{}] but the span is [{}]", replacement.join(", "), expected.join(", ")));
+                    }
+                }
+            }
+            splice_span(content, span, fixed, checks_syntax.then_some(source_type))
+        }
+    }
+}
+
+/// Replace `span` of `content` with `replacement` through `apply_fixes`
+fn splice_span(content: &str, span: &Range<usize>, replacement: &str, verify_syntax: Option<SourceType>) -> std::result::Result<String, String> {
+    let (line, column) = offset_to_line_column(content, span.start as u32);
+    let (end_line, end_column) = offset_to_line_column(content, span.end as u32);
+    let splice = LintDiagnostic {
+        rule_name: AI_SPAN_FIX_RULE.to_string(),
+        message: String::new(),
+        file_path: String::new(),
+        line,
+        column,
+        end_line,
+        end_column,
+        severity: DiagnosticSeverity::Info,
+        fix_available: true,
+        suggested_fix: Some(replacement.to_string()),
+        explanation: None,
+        patch: None,
+        ai_confidence: None,
+    };
+    let options = FixOptions {
+        apply_suggestions: true,
+        verify_syntax,
+        ..FixOptions::default()
+    };
+
+    let fixes = apply_fixes(content, &[splice], options, |_| FixSafety::Safe);
+    if fixes.applied.is_empty() {
+        return Err("span fix could not be spliced into the file".to_string());
+    }
+    Ok(fixes.code)
+}
+
+/// Kinds of the top-level statements of `code`, or `None` if it does not parse on its own
+fn statement_shape(code: &str, source_type: SourceType) -> Option<Vec<&'static str>> {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, code, source_type).parse();
+    if !parsed.errors.is_empty() {
+        return None;
+    }
+    Some(parsed.program.body.iter().map(statement_kind).collect())
+}
+
+fn statement_kind(statement: &Statement) -> &'static str {
+    match statement {
+        Statement::FunctionDeclaration(_) => "function",
+        Statement::ClassDeclaration(_) => "class",
+        Statement::VariableDeclaration(_) => "variable",
+        Statement::ImportDeclaration(_) => "import",
+        Statement::ExportNamedDeclaration(_) | Statement::ExportDefaultDeclaration(_) | Statement::ExportAllDeclaration(_) => "export",
+        Statement::TSInterfaceDeclaration(_) => "interface",
+        Statement::TSTypeAliasDeclaration(_) => "type",
+        Statement::TSEnumDeclaration(_) => "enum",
+        Statement::ExpressionStatement(_) => "expression",
+        _ => "statement",
+    }
+}
+
+/// Quick check for more opening than closing braces, brackets or parentheses
+fn has_unclosed_delimiters(code: &str) -> bool {
+    let depth = code.chars().fold(0i64, |depth, c| match c {
+        '{' | '[' | '(' => depth + 1,
+        '}' | ']' | ')' => depth - 1,
+        _ => depth,
+    });
+    depth > 0
+}
+
+/// Dedup key of a whole-file fix: the code, the language-specific instructions and the cross-file context, but not the path
+fn prompt_key(content: &str, language: &str, context: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    language.hash(&mut hasher);
+    content.hash(&mut hasher);
+    context.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn build_continuation_prompt(partial: &str) -> String {
+    let tail_start = partial.char_indices().rev().nth(199).map(|(index, _)| index).unwrap_or(0);
+    format!(
+        "Your previous response was cut off. Continue the fixed code exactly where it stopped, \
+         without repeating anything and without markdown or explanations.\n\n\
+         The output so far ends with:\n{}",
+        &partial[tail_start..]
+    )
+}
+
+/// Code from a continuation response; fenced output is unwrapped, raw output is kept verbatim
+fn continuation_code(response: &str) -> String {
+    match response.find("```") {
+        Some(start) => {
+            let after_fence = &response[start + 3..];
+            let body = after_fence.find('\n').map(|newline| &after_fence[newline + 1..]).unwrap_or(after_fence);
+            body.split("```").next().unwrap_or(body).to_string()
         }
+        None => response.to_string(),
     }
 }
 
@@ -340,6 +883,8 @@ mod tests {
             fixed_content: Some("const x: number = 42;".to_string()),
             fixed_errors: 3,
             relationships: vec![],
+            rejected_reason: None,
+            passes: vec![],
         };
 
         assert_eq!(result.file_path, "src/test.ts");
@@ -569,6 +1114,8 @@ function helper() {
                 relationship_type: "imports".to_string(),
                 confidence: 0.9,
             }],
+            rejected_reason: None,
+            passes: vec![],
         };
 
         // Test serialization
@@ -585,4 +1132,268 @@ function helper() {
         assert_eq!(deserialized.tsdoc_coverage, result.tsdoc_coverage);
         assert_eq!(deserialized.relationships.len(), 1);
     }
+
+    fn mock_response(content: &str, finish_reason: Option<&str>) -> AIResponse {
+        AIResponse {
+            provider_used: "mock".to_string(),
+            content: content.to_string(),
+            session_id: "session-1".to_string(),
+            success: true,
+            execution_time_ms: 1,
+            error_message: None,
+            routing_reason: "mock".to_string(),
+            finish_reason: finish_reason.map(str::to_string),
+        }
+    }
+
+    const ORIGINAL: &str = "function total(a, b) { return a + b }\n";
+    const TRUNCATED: &str = "```ts\nfunction total(a: number, b: number): number {\n  return a + b;\n";
+
+    fn fixer() -> ClaudeFixer {
+        ClaudeFixer::new(crate::config::MoonShineConfig::default())
+    }
+
+    #[tokio::test]
+    async fn test_truncated_fix_rejected_after_continuation_attempts() {
+        let mut prompts = Vec::new();
+        let result = fixer()
+            .fix_file_with(
+                "src/total.ts",
+                ORIGINAL,
+                "typescript",
+                "session-1".to_string(),
+                |_| true,
+                |_, prompt| {
+                    let response = if prompts.is_empty() { TRUNCATED } else { "  // still going" };
+                    prompts.push(prompt);
+                    std::future::ready(Ok(mock_response(response, None)))
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(prompts.len(), 1 + MAX_CONTINUATIONS);
+        assert!(prompts[1].contains("cut off"));
+        assert!(!result.success);
+        assert!(result.fixed_content.is_none(), "truncated code must never be returned");
+        assert!(result.rejected_reason.unwrap().contains("truncated"));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_fix_rejected_without_continuation_support() {
+        let mut calls = 0;
+        let result = fixer()
+            .fix_file_with(
+                "src/total.ts",
+                ORIGINAL,
+                "typescript",
+                "session-1".to_string(),
+                |_| false,
+                |_, _| {
+                    calls += 1;
+                    std::future::ready(Ok(mock_response("function total(a: number, b: number): number {\n  return a + b;", None)))
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(calls, 1);
+        assert!(result.fixed_content.is_none());
+        assert_eq!(result.rejected_reason.as_deref(), Some("truncated output: unbalanced braces"));
+    }
+
+    #[tokio::test]
+    async fn test_continuation_completes_truncated_fix() {
+        let mut calls = 0;
+        let result = fixer()
+            .fix_file_with(
+                "src/total.ts",
+                ORIGINAL,
+                "typescript",
+                "session-1".to_string(),
+                |_| true,
+                |_, _| {
+                    calls += 1;
+                    let response = if calls == 1 {
+                        mock_response("function total(a: number, b: number): number {\n  return a + b;", Some("length"))
+                    } else {
+                        mock_response("\n}\n", Some("stop"))
+                    };
+                    std::future::ready(Ok(response))
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(calls, 2);
+        assert!(result.rejected_reason.is_none());
+        assert_eq!(
+            result.fixed_content.as_deref(),
+            Some("function total(a: number, b: number): number {\n  return a + b;\n}\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_identical_files_share_one_provider_call() {
+        let files = vec![
+            ("src/a/total.ts".to_string(), ORIGINAL.to_string()),
+            ("src/b/total.ts".to_string(), ORIGINAL.to_string()),
+        ];
+        let mut calls = 0;
+
+        let results = fixer()
+            .fix_files_with(&files, "session-1".to_string(), |_| false, |_, _, _, _| {
+                calls += 1;
+                std::future::ready(Ok(mock_response("function total(a: number, b: number): number {\n  return a + b;\n}\n", Some("stop"))))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(calls, 1);
+        let paths: Vec<&str> = results.iter().map(|result| result.file_path.as_str()).collect();
+        assert_eq!(paths, vec!["src/a/total.ts", "src/b/total.ts"]);
+        for result in &results {
+            assert!(result.fixed_content.as_deref().unwrap().contains("a: number"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_same_code_in_another_language_is_fixed_separately() {
+        let files = vec![
+            ("src/total.ts".to_string(), ORIGINAL.to_string()),
+            ("src/total.js".to_string(), ORIGINAL.to_string()),
+        ];
+        let mut requested = Vec::new();
+
+        let results = fixer()
+            .fix_files_with(&files, "session-1".to_string(), |_| false, |file_path, _, _, prompt| {
+                requested.push((file_path.to_string(), prompt.contains(&format!("File: {}", file_path))));
+                std::future::ready(Ok(mock_response("function total(a, b) {\n  return a + b;\n}\n", Some("stop"))))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(requested, vec![("src/total.ts".to_string(), true), ("src/total.js".to_string(), true)]);
+        assert_eq!(results.len(), 2);
+    }
+
+    const TWO_FUNCTIONS: &str = "function first() { return 1 }\n\nfunction second(a) { return a }\n";
+
+    fn span_of(content: &str, needle: &str) -> Range<usize> {
+        let start = content.find(needle).unwrap();
+        start..start + needle.len()
+    }
+
+    async fn fix_with_response(scope: FixScope, content: &str, response: &str) -> AiCodeFixResult {
+        let response = response.to_string();
+        fixer()
+            .fix_scoped_with(
+                "src/functions.ts",
+                content,
+                "typescript",
+                "session-1".to_string(),
+                scope,
+                |_| false,
+                |_, _| std::future::ready(Ok(mock_response(&response, Some("stop")))),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_span_fix_spliced_into_requested_span() {
+        let scope = FixScope::Span(span_of(TWO_FUNCTIONS, "function second(a) { return a }"));
+        let response = "```ts\nfunction second(a: number): number {\n  return a;\n}\n```";
+
+        let result = fix_with_response(scope, TWO_FUNCTIONS, response).await;
+
+        assert!(result.rejected_reason.is_none());
+        assert_eq!(
+            result.fixed_content.as_deref(),
+            Some("function first() { return 1 }\n\nfunction second(a: number): number {\n  return a;\n}\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_whole_file_returned_for_span_is_rejected() {
+        let scope = FixScope::Span(span_of(TWO_FUNCTIONS, "function second(a) { return a }"));
+        let response = "function first(): number { return 1; }\n\nfunction second(a: number): number { return a; }\n";
+
+        let result = fix_with_response(scope, TWO_FUNCTIONS, response).await;
+
+        assert!(result.fixed_content.is_none());
+        assert!(result.rejected_reason.unwrap().starts_with("mismatched fix shape"));
+    }
+
+    #[tokio::test]
+    async fn test_whole_file_fix_validated_as_file() {
+        let content = "const a = 1;\nconst b = 2;\nfunction sum() { return a + b }\n";
+
+        let complete = fix_with_response(
+            FixScope::WholeFile,
+            content,
+            "const a = 1;\nconst b = 2;\nfunction sum(): number { return a + b; }\n",
+        )
+        .await;
+        assert!(complete.rejected_reason.is_none());
+        assert!(complete.fixed_content.unwrap().contains("sum(): number"));
+
+        let snippet = fix_with_response(FixScope::WholeFile, content, "function sum(): number { return a + b; }").await;
+        assert!(snippet.fixed_content.is_none());
+        assert!(snippet.rejected_reason.unwrap().contains("looks like a snippet"));
+    }
+
+    const THREE_ANYS: &str = "function a(x: any) { return x; }\nfunction b(y: any) { return y; }\nfunction c(z: any) { return z; }";
+
+    fn count_anys(code: &str) -> usize {
+        code.matches(": any").count()
+    }
+
+    #[tokio::test]
+    async fn test_three_pass_fix_reports_each_pass() {
+        // Each response types one more parameter than the code it was sent
+        let mut responses = Vec::new();
+        for parameter in ["x", "y", "z"] {
+            let previous = responses.last().cloned().unwrap_or_else(|| THREE_ANYS.to_string());
+            responses.push(previous.replace(&format!("{}: any", parameter), &format!("{}: number", parameter)));
+        }
+        let mut calls = 0;
+
+        let result = fixer()
+            .fix_passes_with("src/params.ts", THREE_ANYS, "typescript", "session-1".to_string(), 5, count_anys, |_| false, |_, _| {
+                let response = mock_response(&responses[calls], Some("stop"));
+                calls += 1;
+                std::future::ready(Ok(response))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(calls, 3, "no pass runs once the code has no diagnostics");
+        assert_eq!(result.passes.len(), 3);
+        let counts: Vec<(usize, usize)> = result.passes.iter().map(|pass| (pass.diagnostics_before, pass.diagnostics_after)).collect();
+        assert_eq!(counts, vec![(3, 2), (2, 1), (1, 0)]);
+        for (index, pass) in result.passes.iter().enumerate() {
+            assert_eq!(pass.pass, index as u32 + 1);
+            assert!(pass.changed);
+            assert_eq!(pass.tokens, (estimate_tokens(&pass.prompt) + estimate_tokens(&responses[index])) as u32);
+        }
+        assert!(result.passes[1].prompt.contains("x: number") && result.passes[1].prompt.contains("y: any"));
+        assert_eq!(result.fixed_content.as_deref(), Some(responses[2].as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_pass_without_change_ends_the_run() {
+        let result = fixer()
+            .fix_passes_with("src/params.ts", THREE_ANYS, "typescript", "session-1".to_string(), 5, count_anys, |_| false, |_, _| {
+                std::future::ready(Ok(mock_response(THREE_ANYS, Some("stop"))))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.passes.len(), 1);
+        let pass = &result.passes[0];
+        assert!(!pass.changed);
+        assert_eq!((pass.diagnostics_before, pass.diagnostics_after), (3, 3));
+        assert!(result.fixed_content.is_none());
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::config::MoonShineConfig;
 use crate::rulebase::RuleResult as LintIssue;
+use crate::token_usage::{LanguageModelUsageMetrics, UsageAccumulator};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -24,6 +25,27 @@ pub struct MoonShineResponse {
     pub prompts_updates: Option<serde_json::Value>,  // For Moon to update .moon/moonshine/prompts.json
     pub training_updates: Option<serde_json::Value>, // For Moon to update .moon/moonshine/training.json
     pub session_state: Option<serde_json::Value>,    // Session state for Moon to manage
+
+    #[serde(default)]
+    pub run_metrics: RunMetrics,
+}
+
+/// Metrics collected across one workflow run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Token usage of every AI call in the run, with per-provider and per-task-kind splits
+    pub lm_usage: UsageAccumulator,
+}
+
+impl RunMetrics {
+    /// Roll up the usage reported for each AI call
+    pub fn from_calls(calls: &[LmCallUsage]) -> Self {
+        let mut lm_usage = UsageAccumulator::new();
+        for call in calls {
+            lm_usage.record(&call.task_kind, &call.usage);
+        }
+        Self { lm_usage }
+    }
 }
 
 /// Suggestion from analysis with optimized data types
@@ -72,6 +94,15 @@ pub struct MoonTaskResponse {
     pub success: bool,
     pub results: TaskResults,
     pub processing_time_ms: u64,
+    #[serde(default)]
+    pub lm_calls: Vec<LmCallUsage>, // One entry per AI call the tasks made
+}
+
+/// Token usage of a single AI call, tagged with the kind of task that made it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LmCallUsage {
+    pub task_kind: String, // e.g. "fix", "tsdoc", "review"
+    pub usage: LanguageModelUsageMetrics,
 }
 
 /// Task results from various tools
@@ -364,6 +395,7 @@ fn aggregate_analysis_results(
         success: task_success,
         results,
         processing_time_ms: task_duration,
+        lm_calls,
         ..
     } = task_response;
 
@@ -374,6 +406,7 @@ fn aggregate_analysis_results(
     let overall_success = determine_overall_success(task_success, fix_outcome.issues_fixed, suggestions.len());
     let message = compose_analysis_message(total_issues, fix_outcome.issues_fixed);
     let session_state = build_session_state();
+    let run_metrics = RunMetrics::from_calls(&lm_calls);
 
     MoonShineResponse {
         success: overall_success,
@@ -390,6 +423,7 @@ fn aggregate_analysis_results(
         prompts_updates: Some(prompts_updates),
         training_updates: Some(training_updates),
         session_state: Some(session_state),
+        run_metrics,
     }
 }
 
@@ -766,6 +800,7 @@ mod tests {
                 semantic_validation: None,
             },
             processing_time_ms: 1500,
+            lm_calls: Vec::new(),
         };
 
         assert_eq!(response.session_id, "session-456");
@@ -795,6 +830,7 @@ mod tests {
             prompts_updates: None,
             training_updates: None,
             session_state: None,
+            run_metrics: RunMetrics::default(),
         };
 
         assert!(response.success);
@@ -805,4 +841,43 @@ mod tests {
         assert!(response.fixed_content.is_some());
         assert!(response.pattern_insights.is_some());
     }
+
+    #[test]
+    fn test_run_metrics_accumulate_task_lm_calls() {
+        let task_response: MoonTaskResponse = serde_json::from_value(serde_json::json!({
+            "session_id": "session-789",
+            "success": true,
+            "results": { "typescript": null, "eslint": null, "claude": null, "semantic_validation": null },
+            "processing_time_ms": 900,
+            "lm_calls": [
+                { "task_kind": "fix", "usage": { "input_tokens": 500, "output_tokens": 120, "total_tokens": 620, "provider_used": "claude" } },
+                { "task_kind": "fix", "usage": { "input_tokens": 300, "output_tokens": 80, "total_tokens": 380, "provider_used": "codex" } },
+                { "task_kind": "tsdoc", "usage": { "input_tokens": 200, "output_tokens": 60, "total_tokens": 260, "provider_used": "claude" } }
+            ]
+        }))
+        .unwrap();
+        let analysis_results = analyze_file_with_config("", "typescript", &MoonShineConfig::default()).unwrap();
+
+        let response = aggregate_analysis_results(analysis_results, task_response, 100, serde_json::json!({}), serde_json::json!({}));
+
+        let usage = &response.run_metrics.lm_usage;
+        assert_eq!(usage.total.calls, 3);
+        assert_eq!(usage.total.input_tokens, 1000);
+        assert_eq!(usage.total.output_tokens, 260);
+        assert_eq!(usage.total.total_tokens, 1260);
+        assert_eq!(usage.by_provider["claude"].total_tokens, 880);
+        assert_eq!(usage.by_provider["codex"].total_tokens, 380);
+        assert_eq!(usage.by_task_kind["fix"].calls, 2);
+        assert_eq!(usage.by_task_kind["tsdoc"].input_tokens, 200);
+    }
+
+    #[test]
+    fn test_moon_task_response_without_lm_calls_has_empty_metrics() {
+        let task_response: MoonTaskResponse = serde_json::from_str(
+            r#"{"session_id":"s","success":true,"results":{"typescript":null,"eslint":null,"claude":null,"semantic_validation":null},"processing_time_ms":1}"#,
+        )
+        .unwrap();
+
+        assert!(RunMetrics::from_calls(&task_response.lm_calls).lm_usage.is_empty());
+    }
 }
@@ -0,0 +1,126 @@
+//! # Rule Fix Effectiveness
+//!
+//! Aggregates the per-rule fix outcomes kept in [`FixHistory`] over its rolling window of
+//! recent runs. A rule whose fixes are mostly rejected, or applied with low confidence, costs
+//! provider tokens without improving code; the apply rate shows which AI rules earn their keep.
+//!
+//! @category analytics
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::rulebase::fixes::RuleFixStats;
+use crate::storage::{FixHistory, DEFAULT_FIX_HISTORY_WINDOW};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Fix outcomes of one rule across the runs in the window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleEffectiveness {
+    pub rule_id: String,
+    pub runs: usize, // Runs in the window where the rule attempted a fix
+    pub attempted: u32,
+    pub applied: u32,
+    pub rejected: u32,
+    pub apply_rate: f64,                 // `applied / attempted`
+    pub average_confidence: Option<f64>, // Mean reported AI confidence (0-100); `None` for static rules
+}
+
+/// Effectiveness of every rule in the project's fix history, by rule id
+pub fn rule_effectiveness() -> Vec<RuleEffectiveness> {
+    effectiveness_of(&FixHistory::persistent(FixHistory::default_path(), DEFAULT_FIX_HISTORY_WINDOW))
+}
+
+/// Effectiveness of every rule that attempted a fix in `history`, by rule id
+pub fn effectiveness_of(history: &FixHistory) -> Vec<RuleEffectiveness> {
+    let mut by_rule: BTreeMap<&str, (usize, RuleFixStats)> = BTreeMap::new();
+    for run in history.runs() {
+        for (rule_id, stats) in run.stats.rules.iter().filter(|(_, stats)| stats.attempted > 0) {
+            let (runs, totals) = by_rule.entry(rule_id.as_str()).or_default();
+            *runs += 1;
+            totals.add(stats);
+        }
+    }
+
+    by_rule
+        .into_iter()
+        .map(|(rule_id, (runs, stats))| RuleEffectiveness {
+            rule_id: rule_id.to_string(),
+            runs,
+            attempted: stats.attempted,
+            applied: stats.applied,
+            rejected: stats.rejected_total(),
+            apply_rate: f64::from(stats.applied) / f64::from(stats.attempted),
+            average_confidence: stats.average_confidence(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rulebase::fixes::{FixRejectionReason, FixStats};
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// One run's stats for `ai-type-fix`: `applied` of `attempted` fixes applied, the rest rejected for confidence
+    fn run_stats(attempted: u32, applied: u32, confidence: f32) -> FixStats {
+        let mut stats = FixStats::default();
+        for index in 0..attempted {
+            stats.record_attempt("ai-type-fix");
+            stats.record_confidence("ai-type-fix", confidence);
+            if index < applied {
+                stats.record_applied("ai-type-fix");
+            } else {
+                stats.record_rejected("ai-type-fix", FixRejectionReason::Confidence);
+            }
+        }
+        stats.record_attempt("prefer-const");
+        stats.record_applied("prefer-const");
+        stats
+    }
+
+    #[test]
+    fn test_effectiveness_aggregated_across_persisted_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fix-history.json");
+        let start = Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap();
+
+        // Each run loads the history the previous run saved
+        FixHistory::persistent(&path, 5).record_run("run-1", start, &run_stats(4, 1, 60.0));
+        FixHistory::persistent(&path, 5).record_run("run-2", start + Duration::days(1), &run_stats(6, 5, 90.0));
+
+        let effectiveness = effectiveness_of(&FixHistory::persistent(&path, 5));
+        assert_eq!(effectiveness.iter().map(|rule| rule.rule_id.as_str()).collect::<Vec<_>>(), vec!["ai-type-fix", "prefer-const"]);
+
+        let ai = &effectiveness[0];
+        assert_eq!((ai.runs, ai.attempted, ai.applied, ai.rejected), (2, 10, 6, 4));
+        assert!((ai.apply_rate - 0.6).abs() < 1e-9);
+        assert!((ai.average_confidence.unwrap() - 78.0).abs() < 1e-9, "(4 * 60 + 6 * 90) / 10");
+
+        let static_rule = &effectiveness[1];
+        assert_eq!((static_rule.runs, static_rule.apply_rate, static_rule.average_confidence), (2, 1.0, None));
+    }
+
+    #[test]
+    fn test_runs_outside_window_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fix-history.json");
+        let start = Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap();
+
+        // Two poor runs followed by three perfect ones; a window of three keeps only the latter
+        for (day, applied) in [0, 0, 2, 2, 2].into_iter().enumerate() {
+            FixHistory::persistent(&path, 3).record_run(format!("run-{}", day), start + Duration::days(day as i64), &run_stats(2, applied, 80.0));
+        }
+
+        let history = FixHistory::persistent(&path, 3);
+        assert_eq!(history.runs().iter().map(|run| run.run_id.as_str()).collect::<Vec<_>>(), vec!["run-2", "run-3", "run-4"]);
+
+        let ai = &effectiveness_of(&history)[0];
+        assert_eq!((ai.runs, ai.attempted, ai.applied), (3, 6, 6));
+        assert!((ai.apply_rate - 1.0).abs() < 1e-9);
+
+        // A smaller window on load prunes further
+        assert_eq!(FixHistory::persistent(&path, 1).runs().len(), 1);
+    }
+}
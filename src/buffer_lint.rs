@@ -0,0 +1,901 @@
+//! # In-Memory Buffer Linting
+//!
+//! Editor integrations and tests lint unsaved buffers, so the static pipeline must not
+//! assume a file on disk. [`lint_buffer`] runs language detection, parsing and the rule
+//! executor against in-memory content. The `virtual_path` only selects the source type and
+//! labels diagnostics; it is never read, written or resolved against the filesystem. AI
+//! rules run as usual because they already operate on the content they are given, except
+//! on generated files (see [`crate::generated_files`]).
+//!
+//! [`lint_range`] backs "fix this function" in editors: the whole buffer is parsed for
+//! context, diagnostics are limited to those intersecting the selection, and fixes are
+//! applied only inside the function enclosing the selection.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp core
+//! @complexity low
+//! @since 2.0.0
+
+use crate::config::{GeneratedFilePolicy, MoonShineConfig, OperationMode};
+use crate::error::{Error, Result};
+use crate::file_filter::AiExcludes;
+use crate::generated_files::GeneratedFiles;
+use crate::multi_language_analyzer::{LanguageConfig, MultiLanguageAnalyzer, SupportedLanguage};
+use crate::reporting::limit_ai_suggestions;
+use crate::rule_registry::RuleRegistry;
+use crate::rule_types::{with_rule_scopes, FixSafety, RuleMetadata};
+use crate::rulebase::active_rules::{severity_overrides, ActiveRuleSet, EnvLevels};
+use crate::rulebase::complexity_delta::{measure_fix, ComplexityDelta};
+use crate::rulebase::explain::{ai_explanation, explain_diagnostics};
+use crate::rulebase::fixes::{annotate_provenance, apply_fixes, fix_until_converged, propose_ai_fixes, AppliedFix, FixConvergence, FixOptions, FixStats};
+use crate::rulebase::profile::RuleProfile;
+use crate::rulebase::severities::SeverityTable;
+use crate::rulebase::rule_aliases::RuleAliases;
+use crate::rulebase::suppressions::{apply_suppressions, ignore_messages};
+use crate::rulebase::{severities, static_rules, RuleExecutionContext, RuleExecutor, RuleImplementation};
+use crate::types::LintDiagnostic;
+use oxc_allocator::Allocator;
+use oxc_ast::AstKind;
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_parser::{Parser, ParserReturn};
+use oxc_semantic::{Semantic, SemanticBuilder};
+use oxc_span::{GetSpan, SourceType, Span};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+/// Rule name used for diagnostics reporting parse errors in the buffer
+pub const PARSE_ERROR_RULE: &str = "parse-error";
+
+/// Rule name of the file-level error reported when analysis panicked or the input was refused
+pub const PARSER_FAILURE_RULE: &str = "parser-failure";
+
+/// Deepest bracket nesting handed to the parser. Recursive descent on deeper input can exhaust
+/// the stack, which aborts the process whether or not a panic boundary is in place.
+pub const MAX_NESTING_DEPTH: usize = 256;
+
+/// Result of linting an in-memory buffer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BufferLintResult {
+    pub virtual_path: String,
+    pub language: SupportedLanguage,
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub fixed_code: Option<String>, // Buffer with applicable fixes applied, if any fix applied
+    pub applied_fixes: Vec<AppliedFix>, // Each change in `fixed_code` and the rule that made it
+    pub notices: Vec<String>,
+    pub fix_stats: FixStats, // Per-rule attempted/applied/rejected fixes
+    pub fix_convergence: FixConvergence,
+    pub complexity_delta: Option<ComplexityDelta>, // Complexity change from the buffer to `fixed_code`
+    pub profile: Option<RuleProfile>, // Per-rule timings of the lint when `linting.profile` is set
+}
+
+impl BufferLintResult {
+    /// A result that reports `diagnostics` without attempting any fix
+    fn unfixed(virtual_path: &str, language: SupportedLanguage, diagnostics: Vec<LintDiagnostic>, notices: Vec<String>) -> Self {
+        Self {
+            virtual_path: virtual_path.to_string(),
+            language,
+            diagnostics,
+            fixed_code: None,
+            applied_fixes: Vec::new(),
+            notices,
+            fix_stats: FixStats::default(),
+            fix_convergence: FixConvergence {
+                iterations: 0,
+                converged: true,
+            },
+            complexity_delta: None,
+            profile: None,
+        }
+    }
+}
+
+/// Lint `content` with every enabled rule from the rule registry.
+///
+/// @param content The buffer to lint.
+/// @param virtual_path Path used for source-type detection and diagnostics only.
+/// @param config Configuration; `linting.apply_suggestions` controls which fixes are applied.
+/// @returns Diagnostics for the buffer, labelled with `virtual_path`.
+///
+/// @category analysis
+/// @safe team
+/// @mvp core
+/// @complexity low
+/// @since 2.0.0
+pub fn lint_buffer(content: &str, virtual_path: &str, config: &MoonShineConfig) -> Result<BufferLintResult> {
+    let registry = RuleRegistry::new()?;
+    lint_buffer_with_rules(content, virtual_path, config, &configured_rules(&registry, config))
+}
+
+/// [`lint_buffer`] for a run in `mode`, such as the mode the CLI resolved from `--mode`, instead of `config.operation_mode`.
+///
+/// In [`OperationMode::DryRun`] each fixed line of `fixed_code` names the rule that changed it.
+pub fn lint_buffer_in_mode(content: &str, virtual_path: &str, config: &MoonShineConfig, mode: OperationMode) -> Result<BufferLintResult> {
+    let registry = RuleRegistry::new()?;
+    lint_source(content, virtual_path, config, &configured_rules(&registry, config), None, mode)
+}
+
+/// Lint `content` with an explicit rule set; see [`lint_buffer`].
+pub fn lint_buffer_with_rules(content: &str, virtual_path: &str, config: &MoonShineConfig, rules: &[RuleMetadata]) -> Result<BufferLintResult> {
+    lint_source(content, virtual_path, config, rules, None, config.resolved_operation_mode())
+}
+
+/// Lint the selection `byte_range` of `content` with every enabled rule.
+///
+/// @param content The whole buffer; it is parsed in full for context.
+/// @param virtual_path Path used for source-type detection and diagnostics only.
+/// @param byte_range Selected byte range within `content`.
+/// @param config Configuration; `linting.apply_suggestions` controls which fixes are applied.
+/// @returns Diagnostics intersecting the selection; `fixed_code` only changes the enclosing function.
+///
+/// @category analysis
+/// @safe team
+/// @mvp core
+/// @complexity medium
+/// @since 2.0.0
+pub fn lint_range(content: &str, virtual_path: &str, byte_range: Range<usize>, config: &MoonShineConfig) -> Result<BufferLintResult> {
+    let registry = RuleRegistry::new()?;
+    lint_range_with_rules(content, virtual_path, byte_range, config, &configured_rules(&registry, config))
+}
+
+/// Lint a selection with an explicit rule set; see [`lint_range`].
+pub fn lint_range_with_rules(
+    content: &str,
+    virtual_path: &str,
+    byte_range: Range<usize>,
+    config: &MoonShineConfig,
+    rules: &[RuleMetadata],
+) -> Result<BufferLintResult> {
+    let in_bounds = byte_range.start <= byte_range.end && byte_range.end <= content.len();
+    if !in_bounds || !content.is_char_boundary(byte_range.start) || !content.is_char_boundary(byte_range.end) {
+        return Err(Error::Validation {
+            field: "byte_range".to_string(),
+            expected: format!("a range on character boundaries within 0..{}", content.len()),
+            actual: format!("{:?}", byte_range),
+        });
+    }
+
+    lint_source(content, virtual_path, config, rules, Some(byte_range), config.resolved_operation_mode())
+}
+
+fn lint_source(
+    content: &str,
+    virtual_path: &str,
+    config: &MoonShineConfig,
+    rules: &[RuleMetadata],
+    selection: Option<Range<usize>>,
+    mode: OperationMode,
+) -> Result<BufferLintResult> {
+    let language = MultiLanguageAnalyzer::new(LanguageConfig::default()).detect_language(virtual_path, content);
+    if language == SupportedLanguage::Unknown {
+        return Err(Error::Validation {
+            field: "virtual_path".to_string(),
+            expected: "a TypeScript or JavaScript file path".to_string(),
+            actual: virtual_path.to_string(),
+        });
+    }
+
+    let generated = GeneratedFiles::from_config(&config.linting).policy_for(virtual_path, content);
+    if generated == Some(GeneratedFilePolicy::Skip) {
+        let notice = format!("Generated file {} not analyzed (linting.generated_files = skip)", virtual_path);
+        return Ok(BufferLintResult::unfixed(virtual_path, language, Vec::new(), vec![notice]));
+    }
+
+    if let Err(message) = check_nesting(content) {
+        moon_warn!("{}: {}", virtual_path, message);
+        return Ok(BufferLintResult::unfixed(virtual_path, language, vec![parser_failure_diagnostic(virtual_path, message)], Vec::new()));
+    }
+
+    // A panic while analyzing one file becomes that file's error instead of ending the run
+    match catch_panic(|| lint_parsed(content, virtual_path, config, rules, selection, mode, language, generated)) {
+        Ok(result) => result,
+        Err(panic) => {
+            moon_error!("Analysis panicked on {}: {}", virtual_path, panic);
+            let message = format!("File not analyzed: analysis panicked ({})", panic);
+            Ok(BufferLintResult::unfixed(virtual_path, language, vec![parser_failure_diagnostic(virtual_path, message)], Vec::new()))
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn lint_parsed(
+    content: &str,
+    virtual_path: &str,
+    config: &MoonShineConfig,
+    rules: &[RuleMetadata],
+    selection: Option<Range<usize>>,
+    mode: OperationMode,
+    language: SupportedLanguage,
+    generated: Option<GeneratedFilePolicy>,
+) -> Result<BufferLintResult> {
+    let source_type = SourceType::from_path(Path::new(virtual_path)).unwrap_or_default();
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, content, source_type).parse();
+    if !parsed.errors.is_empty() {
+        let diagnostics = parse_error_diagnostics(&parsed.errors, content, virtual_path)
+            .into_iter()
+            .filter(|diagnostic| in_selection(content, diagnostic, selection.as_ref()))
+            .collect();
+        return Ok(BufferLintResult::unfixed(virtual_path, language, diagnostics, Vec::new()));
+    }
+
+    let ctx = RuleExecutionContext {
+        code: content,
+        file_path: virtual_path,
+        source_type,
+        program: Some(&parsed.program),
+    };
+    let rules = with_rule_scopes(rules, &config.linting.rule_scopes);
+    // A selection also needs the model to find its enclosing function, so the rules share it
+    let semantic = selection.is_some().then(|| SemanticBuilder::new().build(&parsed.program).semantic);
+    let outcome = rule_executor(config).evaluate_with_semantic(&rules, &ctx, semantic.as_ref());
+    let aliases = RuleAliases::from_config(&config.linting);
+    let suppression = apply_suppressions(
+        content,
+        virtual_path,
+        canonical_diagnostics(outcome.diagnostics, &aliases),
+        config.linting.report_unused_disables,
+        &aliases,
+    );
+    let mut diagnostics = ignore_messages(suppression.diagnostics, &config.linting.ignore_messages, &aliases);
+    diagnostics.extend(suppression.unused);
+    if config.ai.suggest_only {
+        propose_ai_fixes(content, &mut diagnostics);
+    }
+
+    // Suggestions over the limit are neither reported nor applied
+    let limited = limit_ai_suggestions(diagnostics, config.linting.max_suggestions);
+    let (reported, fixable) = match (&selection, &semantic) {
+        (Some(selection), Some(semantic)) => {
+            let fix_scope = enclosing_function(semantic, selection).unwrap_or_else(|| selection.clone());
+            let fixable: Vec<LintDiagnostic> = limited
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| in_selection(content, diagnostic, Some(&fix_scope)))
+                .cloned()
+                .collect();
+            let reported: Vec<LintDiagnostic> = limited
+                .diagnostics
+                .into_iter()
+                .filter(|diagnostic| in_selection(content, diagnostic, Some(selection)))
+                .collect();
+            (reported, fixable)
+        }
+        _ => (limited.diagnostics.clone(), limited.diagnostics),
+    };
+    let fix_options = FixOptions::from_config(config).with_syntax_check(source_type);
+    let fix_safety = rule_fix_safety(&rules, &aliases);
+    // Later passes re-lint the whole fixed buffer, so a selection gets a single pass
+    let max_fix_iterations = if selection.is_some() { 1 } else { config.linting.max_fix_iterations };
+    // Generated files under `no-fix` are linted but never rewritten
+    let mut first_pass = Some(if generated == Some(GeneratedFilePolicy::NoFix) { Vec::new() } else { fixable });
+    let (fixes, fix_convergence) = fix_until_converged(content, max_fix_iterations, |code| {
+        let diagnostics = first_pass
+            .take()
+            .unwrap_or_else(|| fixable_diagnostics(code, virtual_path, source_type, config, &rules));
+        apply_fixes(code, &diagnostics, fix_options, |rule_name| {
+            fix_safety.get(rule_name).copied().unwrap_or(FixSafety::Suggestion)
+        })
+    });
+    let mut applied_fixes = fixes.applied;
+    let mut fixed_code = (!applied_fixes.is_empty()).then_some(fixes.code);
+    let complexity_delta = fixed_code.as_deref().and_then(|fixed| measure_fix(content, fixed, source_type));
+
+    let mut diagnostics = reported;
+    if config.linting.explain {
+        let ai_enabled = generated.is_none()
+            && !AiExcludes::from_config(config).is_excluded(virtual_path)
+            && !config.ai_offline()
+            && crate::provider_router::ai_available();
+        explain_diagnostics(&mut diagnostics, &rules, ai_enabled, |diagnostic, documented| {
+            ai_explanation(diagnostic, documented, content)
+        });
+    }
+
+    let mut notices = outcome.notices;
+    notices.extend(limited.notes);
+    if let (Some(delta), Some(max_increase)) = (complexity_delta, config.complexity.max_fix_complexity_increase) {
+        if delta.exceeds(max_increase) {
+            fixed_code = None;
+            applied_fixes.clear();
+            notices.push(format!(
+                "Fixes rejected: cyclomatic complexity rose {:.0}% (complexity.max_fix_complexity_increase = {})",
+                -delta.complexity_improvement,
+                max_increase
+            ));
+        }
+    }
+
+    // Dry runs only show the fixed code, so each change can carry the rule that made it
+    if mode == OperationMode::DryRun {
+        fixed_code = fixed_code.map(|code| annotate_provenance(&code, &applied_fixes));
+    }
+
+    Ok(BufferLintResult {
+        virtual_path: virtual_path.to_string(),
+        language,
+        diagnostics,
+        fixed_code,
+        applied_fixes,
+        notices,
+        fix_stats: fixes.stats,
+        fix_convergence,
+        complexity_delta,
+        profile: outcome.profile,
+    })
+}
+
+/// Fix safety of each rule name `rules` report diagnostics under, from their metadata.
+///
+/// Where two rules report under one name the stricter level wins; diagnostics of rules not
+/// in `rules`, AI rules' among them, are suggestions.
+fn rule_fix_safety(rules: &[RuleMetadata], aliases: &RuleAliases) -> HashMap<String, FixSafety> {
+    let mut safety: HashMap<String, FixSafety> = HashMap::new();
+    for rule in rules {
+        let reported_as = match &rule.implementation {
+            RuleImplementation::OxcStatic { rule_name } => Some(rule_name),
+            RuleImplementation::Hybrid { oxc_rule, .. } => Some(oxc_rule),
+            _ => None,
+        };
+        for name in std::iter::once(&rule.id).chain(reported_as) {
+            safety
+                .entry(aliases.canonicalize(name))
+                .and_modify(|level| {
+                    if rule.fix_safety == FixSafety::Suggestion {
+                        *level = FixSafety::Suggestion;
+                    }
+                })
+                .or_insert(rule.fix_safety);
+        }
+    }
+    safety
+}
+
+/// Enabled registry rules narrowed by the preset, category, tag and per-rule settings
+pub(crate) fn configured_rules(registry: &RuleRegistry, config: &MoonShineConfig) -> Vec<RuleMetadata> {
+    let rules = registry.get_enabled_rules();
+    ActiveRuleSet::resolve(&rules, &config.linting).filter_rules(&rules)
+}
+
+/// Rule executor carrying the rule settings of `config`
+pub(crate) fn rule_executor(config: &MoonShineConfig) -> RuleExecutor {
+    let overrides = severity_overrides(&config.linting);
+    let executor = if overrides.is_empty() {
+        RuleExecutor::new()
+    } else {
+        let table = overrides
+            .into_iter()
+            .fold(SeverityTable::global().clone(), |table, (rule_id, severity)| table.with_override(rule_id, severity));
+        RuleExecutor::new().with_severity_table(table)
+    };
+    let executor = executor
+        .with_cost_budget(config.linting.max_total_rule_cost)
+        .with_complexity_limits(config.complexity.clone())
+        .with_tsdoc_required_tags(config.linting.tsdoc_required_tags.clone())
+        .with_line_endings(config.linting.line_endings)
+        .with_async_error_handling(config.linting.async_error_handling)
+        .with_import_order(config.linting.import_order.clone())
+        .with_unsafe_assertions(config.linting.unsafe_assertions.clone())
+        .with_sampling_seed(config.linting.sampling_seed)
+        .with_ai_disabled(config.ai_offline())
+        .with_generated_files(GeneratedFiles::from_config(&config.linting))
+        .with_ai_excludes(AiExcludes::from_config(config))
+        .with_env_levels(EnvLevels::from_config(&config.linting))
+        .with_profiling(config.linting.profile);
+    // Provider calls for behavioral patterns are opt-in; otherwise AI rules use the heuristics only
+    if config.ai.enable_pattern_detection {
+        executor.with_ai_client(Box::new(crate::provider_router::get_ai_router()))
+    } else {
+        executor
+    }
+}
+
+/// Syntax errors as [`PARSE_ERROR_RULE`] diagnostics, each at the span of its primary label.
+///
+/// Every pipeline that parses a file reports parse failures through here, so users see
+/// where the syntax broke instead of an opaque failure.
+pub fn parse_error_diagnostics(errors: &[OxcDiagnostic], source: &str, file_path: &str) -> Vec<LintDiagnostic> {
+    errors
+        .iter()
+        .map(|error| {
+            let span = error
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.first())
+                .map(|label| Span::new(label.offset() as u32, (label.offset() + label.len()) as u32))
+                .unwrap_or_default();
+            static_rules::span_diagnostic(
+                PARSE_ERROR_RULE,
+                error.message.to_string(),
+                source,
+                file_path,
+                span,
+                severities::default_diagnostic_severity(PARSE_ERROR_RULE),
+                None,
+            )
+        })
+        .collect()
+}
+
+/// Parse `source` behind the same guards as [`lint_buffer`]: input nested deeper than
+/// [`MAX_NESTING_DEPTH`] is refused and a parser panic becomes an error. Every pipeline that
+/// parses user code outside [`lint_buffer`] goes through here.
+pub(crate) fn parse_guarded<'a>(allocator: &'a Allocator, source: &'a str, source_type: SourceType) -> std::result::Result<ParserReturn<'a>, String> {
+    check_nesting(source)?;
+    catch_panic(|| Parser::new(allocator, source, source_type).parse()).map_err(|panic| format!("File not parsed: the parser panicked ({})", panic))
+}
+
+/// Refuse `source` when its brackets nest deeper than [`MAX_NESTING_DEPTH`]
+pub(crate) fn check_nesting(source: &str) -> std::result::Result<(), String> {
+    let depth = nesting_depth(source);
+    if depth > MAX_NESTING_DEPTH {
+        return Err(format!("File not parsed: brackets nested {} deep exceed the limit of {}", depth, MAX_NESTING_DEPTH));
+    }
+    Ok(())
+}
+
+/// File-level [`PARSER_FAILURE_RULE`] error at the start of `file_path`
+pub(crate) fn parser_failure_diagnostic(file_path: &str, message: String) -> LintDiagnostic {
+    LintDiagnostic {
+        rule_name: PARSER_FAILURE_RULE.to_string(),
+        message,
+        file_path: file_path.to_string(),
+        line: 1,
+        column: 1,
+        end_line: 1,
+        end_column: 1,
+        severity: severities::default_diagnostic_severity(PARSER_FAILURE_RULE),
+        fix_available: false,
+        suggested_fix: None,
+        explanation: None,
+        patch: None,
+        ai_confidence: None,
+    }
+}
+
+/// Deepest nesting of `(`, `[` and `{` in the code of `source`. Brackets in comments, string
+/// literals and template text do not count; a `${` substitution counts as one level.
+fn nesting_depth(source: &str) -> usize {
+    let bytes = source.as_bytes();
+    let (mut depth, mut deepest) = (0usize, 0usize);
+    // Depth just inside each open `${`, so its `}` resumes the template text
+    let mut substitutions: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i += 1;
+        let resumes_template = match byte {
+            b'/' if bytes.get(i) == Some(&b'/') => {
+                i = find(bytes, i, b"\n");
+                false
+            }
+            b'/' if bytes.get(i) == Some(&b'*') => {
+                i = find(bytes, i + 1, b"*/") + 2;
+                false
+            }
+            b'\'' | b'"' => {
+                i = string_end(bytes, i, byte);
+                false
+            }
+            b'`' => true,
+            b'}' if substitutions.last() == Some(&depth) => {
+                substitutions.pop();
+                depth -= 1;
+                true
+            }
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                deepest = deepest.max(depth);
+                false
+            }
+            b')' | b']' | b'}' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            _ => false,
+        };
+        if resumes_template {
+            let (end, substitution) = template_text_end(bytes, i);
+            i = end;
+            if substitution {
+                depth += 1;
+                deepest = deepest.max(depth);
+                substitutions.push(depth);
+            }
+        }
+    }
+    deepest
+}
+
+/// Index of the first `pattern` at or after `start`, or the end of `bytes`
+fn find(bytes: &[u8], start: usize, pattern: &[u8]) -> usize {
+    let start = start.min(bytes.len());
+    bytes[start..]
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+        .map_or(bytes.len(), |offset| start + offset)
+}
+
+/// Index just past the `quote` closing a string literal whose text starts at `start`; an
+/// unterminated string ends at the line break
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' => return i,
+            byte if byte == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Index just past the template text starting at `start`, and whether it ended at a `${`
+/// rather than the closing backtick
+fn template_text_end(bytes: &[u8], start: usize) -> (usize, bool) {
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => return (i + 1, false),
+            b'$' if bytes.get(i + 1) == Some(&b'{') => return (i + 2, true),
+            _ => i += 1,
+        }
+    }
+    (bytes.len(), false)
+}
+
+/// Run `f`, returning the panic message if it panics.
+///
+/// WASM builds abort on panic, so there only the [`MAX_NESTING_DEPTH`] guard protects the run.
+fn catch_panic<T>(f: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    #[cfg(not(feature = "wasm"))]
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| {
+            payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string())
+        })
+    }
+    #[cfg(feature = "wasm")]
+    {
+        Ok(f())
+    }
+}
+
+/// `diagnostics` reported under their canonical rule IDs
+pub(crate) fn canonical_diagnostics(mut diagnostics: Vec<LintDiagnostic>, aliases: &RuleAliases) -> Vec<LintDiagnostic> {
+    for diagnostic in &mut diagnostics {
+        diagnostic.rule_name = aliases.canonicalize(&diagnostic.rule_name);
+    }
+    diagnostics
+}
+
+/// Unsuppressed diagnostics of already-fixed `code`, for the next fix pass
+fn fixable_diagnostics(code: &str, virtual_path: &str, source_type: SourceType, config: &MoonShineConfig, rules: &[RuleMetadata]) -> Vec<LintDiagnostic> {
+    let allocator = Allocator::default();
+    let Ok(parsed) = parse_guarded(&allocator, code, source_type) else {
+        return Vec::new();
+    };
+    if !parsed.errors.is_empty() {
+        return Vec::new();
+    }
+
+    let ctx = RuleExecutionContext {
+        code,
+        file_path: virtual_path,
+        source_type,
+        program: Some(&parsed.program),
+    };
+    let outcome = rule_executor(config).evaluate(rules, &ctx);
+    let aliases = RuleAliases::from_config(&config.linting);
+    let mut diagnostics = apply_suppressions(code, virtual_path, canonical_diagnostics(outcome.diagnostics, &aliases), false, &aliases).diagnostics;
+    if config.ai.suggest_only {
+        propose_ai_fixes(code, &mut diagnostics);
+    }
+    diagnostics
+}
+
+/// Whether the diagnostic's span intersects `selection`; everything is in an absent selection
+fn in_selection(content: &str, diagnostic: &LintDiagnostic, selection: Option<&Range<usize>>) -> bool {
+    let Some(selection) = selection else {
+        return true;
+    };
+
+    let start = static_rules::line_column_to_offset(content, diagnostic.line, diagnostic.column).unwrap_or(0);
+    let end = static_rules::line_column_to_offset(content, diagnostic.end_line, diagnostic.end_column)
+        .unwrap_or(start)
+        .max(start + 1);
+    start < selection.end.max(selection.start + 1) && selection.start < end
+}
+
+/// Span of the innermost function containing the whole selection
+fn enclosing_function(semantic: &Semantic, selection: &Range<usize>) -> Option<Range<usize>> {
+    semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::Function(_) | AstKind::ArrowFunctionExpression(_) => {
+                let span = node.kind().span();
+                Some(span.start as usize..span.end as usize)
+            }
+            _ => None,
+        })
+        .filter(|span| span.start <= selection.start && selection.end <= span.end)
+        .min_by_key(|span| span.end - span.start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rulebase::testing::static_rule;
+    use crate::types::DiagnosticSeverity;
+
+    const VIRTUAL_PATH: &str = "untitled/does-not-exist/buffer.ts";
+
+    #[test]
+    fn test_in_memory_ts_buffer_reports_virtual_path() {
+        let content = "let total: number = 1;\nif (total == 2) {}\n";
+        let rules = vec![static_rule("prefer-const"), static_rule("eqeqeq")];
+
+        let result = lint_buffer_with_rules(content, VIRTUAL_PATH, &MoonShineConfig::default(), &rules).unwrap();
+
+        assert_eq!(result.language, SupportedLanguage::TypeScript);
+        let rule_names: Vec<&str> = result.diagnostics.iter().map(|d| d.rule_name.as_str()).collect();
+        assert!(rule_names.contains(&"prefer-const"));
+        assert!(rule_names.contains(&"eqeqeq"));
+        assert!(result.diagnostics.iter().all(|d| d.file_path == VIRTUAL_PATH));
+        assert_eq!(result.fixed_code.as_deref(), Some("const total: number = 1;\nif (total == 2) {}\n"));
+        assert!(!Path::new(VIRTUAL_PATH).exists());
+    }
+
+    #[test]
+    fn test_syntax_errors_reported_against_virtual_path() {
+        let result = lint_buffer_with_rules("const a = {;\n", VIRTUAL_PATH, &MoonShineConfig::default(), &[static_rule("prefer-const")]).unwrap();
+
+        assert!(!result.diagnostics.is_empty());
+        assert!(result
+            .diagnostics
+            .iter()
+            .all(|d| d.rule_name == PARSE_ERROR_RULE && d.file_path == VIRTUAL_PATH));
+        assert_eq!(result.diagnostics[0].line, 1);
+        assert!(result.fixed_code.is_none());
+    }
+
+    #[test]
+    fn test_generated_file_policy_limits_fixing_and_linting() {
+        let content = "// @generated by protoc-gen-ts\nlet total = 1;\nconsole.log(total);\n";
+        let rules = vec![static_rule("prefer-const")];
+        let mut config = MoonShineConfig::default();
+
+        let no_ai = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &rules).unwrap();
+        assert_eq!(no_ai.diagnostics.len(), 1);
+        assert!(no_ai.fixed_code.is_some(), "static fixes still apply by default");
+
+        config.linting.generated_files = GeneratedFilePolicy::NoFix;
+        let no_fix = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &rules).unwrap();
+        assert_eq!(no_fix.diagnostics.len(), 1);
+        assert!(no_fix.fixed_code.is_none());
+
+        config.linting.generated_files = GeneratedFilePolicy::Skip;
+        let skipped = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &rules).unwrap();
+        assert!(skipped.diagnostics.is_empty());
+        assert_eq!(skipped.notices.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_error_is_a_positioned_diagnostic_and_skips_rules() {
+        let content = "let total = 1;\nconst broken = {;\nlet later = total;\n";
+        let rules = vec![static_rule("prefer-const"), static_rule("eqeqeq")];
+
+        let result = lint_buffer_with_rules(content, VIRTUAL_PATH, &MoonShineConfig::default(), &rules).unwrap();
+
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.rule_name, PARSE_ERROR_RULE);
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 17), "points at the `;` where the object literal broke");
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert!(result.diagnostics.iter().all(|d| d.rule_name == PARSE_ERROR_RULE), "no rule runs on a broken file");
+    }
+
+    #[test]
+    fn test_range_limits_diagnostics_and_fixes_to_selected_function() {
+        let content = "function first() {\n  let a = 1;\n  return a == 2;\n}\n\nfunction second() {\n  let b = 1;\n  return b == 2;\n}\n";
+        let rules = vec![static_rule("prefer-const"), static_rule("eqeqeq")];
+        let selection_start = content.find("let b").unwrap();
+        let selection = selection_start..selection_start + "let b = 1;".len();
+
+        let result = lint_range_with_rules(content, VIRTUAL_PATH, selection, &MoonShineConfig::default(), &rules).unwrap();
+
+        assert!(!result.diagnostics.is_empty());
+        assert!(result.diagnostics.iter().all(|d| d.line == 7));
+        assert_eq!(
+            result.fixed_code.as_deref(),
+            Some("function first() {\n  let a = 1;\n  return a == 2;\n}\n\nfunction second() {\n  const b = 1;\n  return b == 2;\n}\n")
+        );
+    }
+
+    #[test]
+    fn test_unused_disable_directives_reported_when_enabled() {
+        let content = "// eslint-disable-next-line eqeqeq\nif (a == b) {}\n// eslint-disable-next-line eqeqeq\nif (a === b) {}\n";
+        let mut config = MoonShineConfig::default();
+        config.linting.report_unused_disables = true;
+
+        let result = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[static_rule("eqeqeq")]).unwrap();
+
+        let reported: Vec<(&str, u32)> = result.diagnostics.iter().map(|d| (d.rule_name.as_str(), d.line)).collect();
+        assert_eq!(reported, vec![("unused-disable-directive", 3)]);
+
+        let quiet = lint_buffer_with_rules(content, VIRTUAL_PATH, &MoonShineConfig::default(), &[static_rule("eqeqeq")]).unwrap();
+        assert!(quiet.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_explain_mode_attaches_rule_doc_rationale() {
+        let content = "if (a == b) {}\n";
+        let mut config = MoonShineConfig::default();
+        config.linting.explain = true;
+
+        let explained = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[static_rule("eqeqeq")]).unwrap();
+
+        let explanation = explained.diagnostics[0].explanation.as_deref().unwrap();
+        assert!(explanation.contains("Recommended fix"));
+
+        let terse = lint_buffer_with_rules(content, VIRTUAL_PATH, &MoonShineConfig::default(), &[static_rule("eqeqeq")]).unwrap();
+        assert_eq!(terse.diagnostics[0].explanation, None);
+    }
+
+    #[test]
+    fn test_offline_provider_lints_statically_without_ai_notice() {
+        let mut config = MoonShineConfig::default();
+        config.ai.providers = vec![crate::provider_router::OFFLINE_PROVIDER.to_string()];
+        let mut hybrid_rule = static_rule("hybrid-eqeqeq");
+        hybrid_rule.implementation = RuleImplementation::Hybrid {
+            oxc_rule: "eqeqeq".to_string(),
+            ai_pattern: "equality-intent".to_string(),
+        };
+
+        let result = lint_buffer_with_rules("if (a == b) {}\n", VIRTUAL_PATH, &config, &[hybrid_rule]).unwrap();
+
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].rule_name, "eqeqeq");
+        assert!(result.notices.iter().all(|notice| !notice.contains("AI unavailable")));
+    }
+
+    #[test]
+    fn test_fix_convergence_reported_for_whole_buffer_fixes() {
+        let result = lint_buffer_with_rules("let a = 1;\nlet b = a;\nconsole.log(b);\n", VIRTUAL_PATH, &MoonShineConfig::default(), &[static_rule("prefer-const")]).unwrap();
+
+        assert_eq!(result.fixed_code.as_deref(), Some("const a = 1;\nconst b = a;\nconsole.log(b);\n"));
+        assert_eq!(result.fix_convergence, FixConvergence { iterations: 2, converged: true });
+    }
+
+    #[test]
+    fn test_fix_safety_comes_from_rule_metadata() {
+        let content = "let total = 1;\nconsole.log(total);\n";
+        let mut rule = static_rule("prefer-const");
+        rule.fix_safety = FixSafety::Suggestion;
+        let mut config = MoonShineConfig::default();
+
+        let held_back = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, std::slice::from_ref(&rule)).unwrap();
+        assert_eq!(held_back.diagnostics.len(), 1);
+        assert!(held_back.fixed_code.is_none(), "the metadata, not the rule name, sets the level");
+
+        config.linting.apply_suggestions = true;
+        let applied = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[rule]).unwrap();
+        assert_eq!(applied.fixed_code.as_deref(), Some("const total = 1;\nconsole.log(total);\n"));
+        assert_eq!(applied.applied_fixes[0].safety, FixSafety::Suggestion);
+        assert_eq!(static_rules::fix_safety("no-debugger"), FixSafety::Suggestion);
+    }
+
+    #[test]
+    fn test_dry_run_provenance_follows_the_resolved_mode() {
+        let content = "let total = 1;\nconsole.log(total);\n";
+        let config = MoonShineConfig::default();
+
+        let fixed = lint_buffer_in_mode(content, VIRTUAL_PATH, &config, OperationMode::Fix).unwrap();
+        assert!(!fixed.fixed_code.unwrap().contains("// fixed by"));
+
+        // The config still names the default mode; the mode passed in decides
+        let dry_run = lint_buffer_in_mode(content, VIRTUAL_PATH, &config, OperationMode::DryRun).unwrap();
+        assert!(dry_run.fixed_code.unwrap().starts_with("const total = 1; // fixed by "));
+    }
+
+    #[test]
+    fn test_complexity_limits_come_from_config() {
+        let content = "export function pick(a: number) {\n  if (a > 1) {}\n  if (a > 2) {}\n  return a;\n}\n";
+        let mut config = MoonShineConfig::default();
+        config.complexity.max_cyclomatic_per_function = 2;
+
+        let strict = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[static_rule("complexity")]).unwrap();
+        assert_eq!(strict.diagnostics.len(), 1);
+        assert!(strict.diagnostics[0].message.contains("cyclomatic complexity 3 (maximum 2)"));
+
+        config.complexity.max_cyclomatic_per_function = 3;
+        let relaxed = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[static_rule("complexity")]).unwrap();
+        assert!(relaxed.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_tsdoc_required_tags_come_from_config() {
+        let content = "/**\n * Greets.\n * @since 1.0.0\n */\nexport function greet() {}\n";
+        let mut config = MoonShineConfig::default();
+
+        let defaults = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[static_rule("tsdoc-required-tags")]).unwrap();
+        assert_eq!(defaults.diagnostics.len(), 1);
+        assert!(defaults.diagnostics[0].message.contains("@category, @safe, @mvp, @complexity"));
+
+        config.linting.tsdoc_required_tags = vec!["@since".to_string()];
+        let relaxed = lint_buffer_with_rules(content, VIRTUAL_PATH, &config, &[static_rule("tsdoc-required-tags")]).unwrap();
+        assert!(relaxed.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_range_outside_buffer_is_rejected() {
+        let result = lint_range_with_rules("let a = 1;\n", VIRTUAL_PATH, 4..40, &MoonShineConfig::default(), &[]);
+        assert!(matches!(result, Err(Error::Validation { .. })));
+    }
+
+    #[test]
+    fn test_deeply_nested_input_reports_file_error_and_run_continues() {
+        // Unclosed brackets nested far enough to overflow a recursive-descent parser
+        let pathological = format!("const x = {};\n", "[".repeat(100_000));
+        let rules = vec![static_rule("prefer-const")];
+
+        let refused = lint_buffer_with_rules(&pathological, "src/deep.ts", &MoonShineConfig::default(), &rules).unwrap();
+        assert_eq!(refused.diagnostics.len(), 1);
+        let diagnostic = &refused.diagnostics[0];
+        assert_eq!((diagnostic.rule_name.as_str(), diagnostic.line, diagnostic.severity.clone()), (PARSER_FAILURE_RULE, 1, DiagnosticSeverity::Error));
+        assert!(diagnostic.message.contains("nested 100000 deep"));
+        assert!(refused.fixed_code.is_none());
+
+        // The next file is linted as usual
+        let next = lint_buffer_with_rules("let total = 1;\nconsole.log(total);\n", "src/next.ts", &MoonShineConfig::default(), &rules).unwrap();
+        assert_eq!(next.diagnostics[0].rule_name, "prefer-const");
+
+        // Ordinary nesting is still parsed
+        let nested = format!("const x = {}{};\n", "[".repeat(64), "]".repeat(64));
+        let parsed = lint_buffer_with_rules(&nested, "src/nested.ts", &MoonShineConfig::default(), &rules).unwrap();
+        assert!(parsed.diagnostics.iter().all(|diagnostic| diagnostic.rule_name != PARSER_FAILURE_RULE));
+    }
+
+    #[test]
+    fn test_brackets_in_strings_templates_and_comments_do_not_nest() {
+        let deep = "[".repeat(MAX_NESTING_DEPTH * 2);
+        let source = format!("// {deep}\n/* {deep} */\nconst a = '{deep}';\nconst b = \"{deep}\";\nconst c = `{deep}`;\n");
+        assert_eq!(nesting_depth(&source), 0);
+
+        let substitution = "const t = `text ${ [a, (b)] } more ${ `inner ${ {} }` } (`;\nconst after = [1];\n";
+        assert_eq!(nesting_depth(substitution), 3);
+        assert!(check_nesting(&format!("const x = {};\n", "[".repeat(MAX_NESTING_DEPTH + 1))).is_err());
+    }
+
+    #[test]
+    fn test_guarded_parse_refuses_deep_nesting() {
+        let allocator = Allocator::default();
+        let pathological = format!("const x = {};\n", "(".repeat(100_000));
+
+        let refused = parse_guarded(&allocator, &pathological, SourceType::ts()).unwrap_err();
+        assert!(refused.contains("nested 100000 deep"));
+        assert!(parse_guarded(&allocator, "const x = [[1]];\n", SourceType::ts()).unwrap().errors.is_empty());
+        assert!(fixable_diagnostics(&pathological, "src/deep.ts", SourceType::ts(), &MoonShineConfig::default(), &[static_rule("prefer-const")]).is_empty());
+    }
+
+    #[test]
+    fn test_panic_is_captured_with_its_message() {
+        assert_eq!(catch_panic(|| 7), Ok(7));
+        assert_eq!(catch_panic(|| -> u32 { panic!("unexpected token in parser state") }), Err("unexpected token in parser state".to_string()));
+        let index = 3;
+        assert_eq!(catch_panic(|| -> u32 { panic!("span {} out of bounds", index) }), Err("span 3 out of bounds".to_string()));
+    }
+
+    #[test]
+    fn test_unsupported_virtual_path_is_rejected() {
+        let result = lint_buffer_with_rules("body {}", "styles.css", &MoonShineConfig::default(), &[]);
+        assert!(matches!(result, Err(Error::Validation { .. })));
+    }
+}
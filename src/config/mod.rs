@@ -6,13 +6,20 @@
 
 use crate::error::{Error, Result};
 use crate::moon_pdk_interface::get_moon_config_safe;
+use crate::reporting::ReportFormat;
+use crate::rulebase::active_rules::parse_rule_level;
 use moon_pdk::get_extension_config;
 use serde::{Deserialize, Serialize};
 // Removed unused imports: HashMap, PathBuf
 
 pub use adaptive::{AdaptiveConfig, PatternTrackingConfig, RuleGenerationConfig, StarcoderConfig};
 pub use ai::AiConfig;
-pub use linting::LintingConfig;
+pub use complexity::ComplexityConfig;
+pub use debug::DebugConfig;
+pub use linting::{
+    AsyncErrorGranularity, FailOnConfig, GeneratedFilePolicy, IgnoreMessage, ImportGroup, ImportOrderConfig, LineEndingPolicy, LintingConfig,
+    UnsafeAssertionsConfig,
+};
 
 /// Create a JSON schema for the Moon Shine configuration
 pub fn create_config_schema() -> String {
@@ -71,11 +78,118 @@ pub fn create_config_schema() -> String {
     .to_string()
 }
 
+/// Operation modes accepted by `--mode` and `operation_mode` in `moon.yml`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OperationMode {
+    /// Analyze and write fixes back to disk
+    #[default]
+    Fix,
+    /// Run static checks only, no AI fixing
+    LintOnly,
+    /// CI-friendly reporting: prints every diagnostic, never writes fixes or session state,
+    /// and fails only on `linting.fail_on` thresholds
+    ReportingOnly,
+    /// Full multi-phase workflow including AI enhancement
+    Comprehensive,
+    /// Run the full workflow but never write files
+    DryRun,
+    /// Single-threaded parallel-lint metrics mode
+    ParallelLint,
+}
+
+impl OperationMode {
+    /// All modes, in the order they are listed in error messages
+    pub const ALL: [OperationMode; 6] = [
+        OperationMode::Fix,
+        OperationMode::LintOnly,
+        OperationMode::ReportingOnly,
+        OperationMode::Comprehensive,
+        OperationMode::DryRun,
+        OperationMode::ParallelLint,
+    ];
+
+    /// Canonical kebab-case name used on the CLI and in config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationMode::Fix => "fix",
+            OperationMode::LintOnly => "lint-only",
+            OperationMode::ReportingOnly => "reporting-only",
+            OperationMode::Comprehensive => "comprehensive",
+            OperationMode::DryRun => "dry-run",
+            OperationMode::ParallelLint => "parallel-lint",
+        }
+    }
+
+    /// Comma-separated list of valid mode names for diagnostics
+    pub fn valid_modes() -> String {
+        Self::ALL.iter().map(OperationMode::as_str).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Whether this mode is allowed to write fixed code back to disk
+    pub fn writes_files(&self) -> bool {
+        matches!(self, OperationMode::Fix | OperationMode::Comprehensive)
+    }
+
+    /// Whether this mode prints the report even when no `--format` is given
+    pub fn reports_all_diagnostics(&self) -> bool {
+        *self == OperationMode::ReportingOnly
+    }
+
+    /// Whether this mode may create sessions, prune old ones, or set up the cache directory
+    pub fn writes_session_artifacts(&self) -> bool {
+        *self != OperationMode::ReportingOnly
+    }
+}
+
+impl std::fmt::Display for OperationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Mode names accepted by earlier releases, each with the mode that replaces it
+const RETIRED_MODES: [(&str, OperationMode); 6] = [
+    ("static-analysis", OperationMode::LintOnly),
+    ("typescript-only", OperationMode::LintOnly),
+    ("eslint-only", OperationMode::LintOnly),
+    ("prettier-only", OperationMode::Fix),
+    ("agent-based", OperationMode::Comprehensive),
+    ("tsdoc-only", OperationMode::Comprehensive),
+];
+
+impl std::str::FromStr for OperationMode {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let normalized = value.trim().to_ascii_lowercase().replace('_', "-");
+        if let Some((_, replacement)) = RETIRED_MODES.iter().find(|(retired, _)| *retired == normalized) {
+            return Err(Error::config_field(
+                format!(
+                    "Operation mode '{}' was removed; use '{}' instead. Valid modes: {}",
+                    value,
+                    replacement,
+                    Self::valid_modes()
+                ),
+                "operation_mode",
+                Some(value),
+            ));
+        }
+        Self::ALL.iter().copied().find(|mode| mode.as_str() == normalized).ok_or_else(|| {
+            Error::config_field(
+                format!("Unknown operation mode '{}'. Valid modes: {}", value, Self::valid_modes()),
+                "operation_mode",
+                Some(value),
+            )
+        })
+    }
+}
+
 /// CLI arguments structure for Moon PDK integration
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MoonShineArgs {
-    /// Operation mode: fix, lint-only, or comprehensive
-    pub mode: Option<String>,
+    /// Operation mode selected with `--mode`
+    pub mode: Option<OperationMode>,
 
     /// Only report issues without fixing
     pub lint_only: bool,
@@ -83,12 +197,66 @@ pub struct MoonShineArgs {
     /// CI-friendly reporting mode (no interactive fixes)
     pub reporting_only: bool,
 
+    /// Audit untrusted code: report diagnostics without writing any file or executing anything
+    pub safe_mode: bool,
+
     /// Force initialization of configuration files
     pub force_init: bool,
 
     /// Install default prompts and configuration
     pub install_prompts: bool,
 
+    /// Run the self-check and print a health report
+    pub doctor: bool,
+
+    /// Consolidate legacy `moonshine.prompts.*` keys into prompts.json and print what was migrated
+    pub migrate_prompts: bool,
+
+    /// Apply suggestion-level fixes in addition to safe fixes
+    pub apply_suggestions: bool,
+
+    /// Stage all fixed files and write them only if every one re-parses
+    pub atomic: bool,
+
+    /// Print the fixes the run would apply as a JSON plan instead of applying them
+    pub fix_plan: bool,
+
+    /// Collect per-rule timings and include them in the run metrics
+    pub profile: bool,
+
+    /// `node_modules` paths to analyze despite the default exclude
+    pub include_node_modules: Vec<String>,
+
+    /// Report suppression directives that suppressed nothing
+    pub report_unused_disables: bool,
+
+    /// Attach an explanation of why each rule fired to every diagnostic
+    pub explain: bool,
+
+    /// Unified diff whose changed lines limit the reported diagnostics
+    pub diff: Option<String>,
+
+    /// Print the effective rule set with each rule's source and severity, then exit
+    pub print_active_rules: bool,
+
+    /// Print the effective prompt for this rule, filled in from the first file, then exit
+    pub print_prompt: Option<String>,
+
+    /// Lint and fix TypeScript/JavaScript code fences in markdown files
+    pub markdown: bool,
+
+    /// Times to re-run after a transient host failure (`--host-retries`)
+    pub host_retries: Option<u32>,
+
+    /// Cap on files processed, overriding `max_files` (`--max-files`)
+    pub max_files: Option<u32>,
+
+    /// Report format selected with `--format`
+    pub format: Option<ReportFormat>,
+
+    /// Also write the report to this path (`-` for stdout)
+    pub output: Option<String>,
+
     /// Files to process (supports glob patterns)
     pub files: Vec<String>,
 }
@@ -99,6 +267,7 @@ pub type AdaptiveRuleSystemConfig = AdaptiveConfig;
 mod ai {
     use super::defaults;
     use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct AiConfig {
@@ -112,10 +281,53 @@ mod ai {
         pub max_concurrent_requests: u32,
         #[serde(default = "defaults::ai_batch_size")]
         pub batch_size: u32,
+        /// Examples scored per batch when evaluating DSPy modules
+        #[serde(default = "defaults::ai_eval_batch_size")]
+        pub eval_batch_size: u32,
         #[serde(default)]
         pub enable_copro_optimization: bool,
+        /// Send the AI step of AI-enhanced rules to the configured providers; when unset those
+        /// rules report the local behavioral heuristics only
         #[serde(default)]
         pub enable_pattern_detection: bool,
+        /// Project context prepended to every AI request as a system message
+        #[serde(default)]
+        pub system_prompt: Option<String>,
+        /// File holding the system prompt; ignored when `system_prompt` is set
+        #[serde(default)]
+        pub system_prompt_file: Option<String>,
+        /// Model by rule ID, for rules worth a stronger (or cheaper) model than `model`
+        #[serde(default)]
+        pub rule_model_overrides: HashMap<String, String>,
+        /// Attach AI fixes to their diagnostics as unified-diff patches instead of applying them;
+        /// static fixes still apply
+        #[serde(default)]
+        pub suggest_only: bool,
+        /// Globs of files that get static rules and fixes but no AI passes, e.g. `fixtures/**`;
+        /// files excluded by `linting.exclude_patterns` are not analyzed at all
+        #[serde(default)]
+        pub ai_exclude_patterns: Vec<String>,
+        /// Tokens a provider may generate per request when `max_output_tokens_by_task` sets no cap
+        #[serde(default = "defaults::ai_max_output_tokens")]
+        pub max_output_tokens: u32,
+        /// Output token caps by task kind (`code_fix`, `code_generation`, `code_analysis`,
+        /// `dspy_optimization`, `general`), e.g. small for type fixes, large for rewrites
+        #[serde(default)]
+        pub max_output_tokens_by_task: HashMap<String, u32>,
+        /// Add exported declarations of directly imported files to AI fix prompts as read-only context
+        #[serde(default)]
+        pub cross_file_context: bool,
+        /// Prompt tokens the imported-file context may take per request
+        #[serde(default = "defaults::ai_cross_file_context_tokens")]
+        pub cross_file_context_tokens: u32,
+        /// Check each provider's connectivity once, in parallel, at run start and reuse the
+        /// result for every call instead of paying a cold start per call
+        #[serde(default)]
+        pub provider_warmup: bool,
+        /// Send each AI fix to this many of the best-ranked providers and keep the response that
+        /// parses and removes the most diagnostics; 0 or 1 uses a single provider
+        #[serde(default)]
+        pub ensemble_providers: u32,
     }
 
     impl Default for AiConfig {
@@ -126,16 +338,101 @@ mod ai {
                 providers: defaults::ai_providers(),
                 max_concurrent_requests: defaults::ai_max_concurrent_requests(),
                 batch_size: defaults::ai_batch_size(),
+                eval_batch_size: defaults::ai_eval_batch_size(),
                 enable_copro_optimization: false,
                 enable_pattern_detection: false,
+                system_prompt: None,
+                system_prompt_file: None,
+                rule_model_overrides: HashMap::new(),
+                suggest_only: false,
+                ai_exclude_patterns: Vec::new(),
+                max_output_tokens: defaults::ai_max_output_tokens(),
+                max_output_tokens_by_task: HashMap::new(),
+                cross_file_context: false,
+                cross_file_context_tokens: defaults::ai_cross_file_context_tokens(),
+                provider_warmup: false,
+                ensemble_providers: 0,
+            }
+        }
+    }
+
+    impl AiConfig {
+        /// Output token cap for `task_type`: its `max_output_tokens_by_task` entry, else `max_output_tokens`
+        pub fn max_output_tokens_for(&self, task_type: &str) -> u32 {
+            self.max_output_tokens_by_task.get(task_type).copied().unwrap_or(self.max_output_tokens)
+        }
+
+        /// Model the AI calls of `rule_id` run on: its override, else `model`
+        pub fn model_for_rule(&self, rule_id: &str) -> &str {
+            self.rule_model_overrides.get(rule_id).unwrap_or(&self.model)
+        }
+    }
+}
+
+mod complexity {
+    use super::defaults;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    /// Complexity limits for the `complexity` rule and the `complexity_analysis` prompt
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct ComplexityConfig {
+        #[serde(default = "defaults::max_cyclomatic_per_function")]
+        pub max_cyclomatic_per_function: u32,
+        #[serde(default = "defaults::max_cognitive_per_function")]
+        pub max_cognitive_per_function: u32,
+        /// Per-function Halstead difficulty limit
+        #[serde(default = "defaults::max_halstead_difficulty")]
+        pub max_halstead_difficulty: f64,
+        /// Per-file line count limit
+        #[serde(default = "defaults::max_file_loc")]
+        pub max_file_loc: u32,
+        /// Reject a file's fixes when they raise its cyclomatic complexity by more than this percentage
+        #[serde(default)]
+        pub max_fix_complexity_increase: Option<f64>,
+    }
+
+    impl Default for ComplexityConfig {
+        fn default() -> Self {
+            Self {
+                max_cyclomatic_per_function: defaults::max_cyclomatic_per_function(),
+                max_cognitive_per_function: defaults::max_cognitive_per_function(),
+                max_halstead_difficulty: defaults::max_halstead_difficulty(),
+                max_file_loc: defaults::max_file_loc(),
+                max_fix_complexity_increase: None,
             }
         }
     }
+
+    impl ComplexityConfig {
+        /// Threshold variables for prompt templates, keyed by field name
+        pub fn prompt_variables(&self) -> HashMap<String, String> {
+            HashMap::from([
+                ("max_cyclomatic_per_function".to_string(), self.max_cyclomatic_per_function.to_string()),
+                ("max_cognitive_per_function".to_string(), self.max_cognitive_per_function.to_string()),
+                ("max_halstead_difficulty".to_string(), self.max_halstead_difficulty.to_string()),
+                ("max_file_loc".to_string(), self.max_file_loc.to_string()),
+            ])
+        }
+    }
+}
+
+mod debug {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    pub struct DebugConfig {
+        /// Keep debug sessions for the extended retention window; unset falls back to the
+        /// deprecated `"debug"` entry in `ai.providers`
+        #[serde(default)]
+        pub keep_sessions: Option<bool>,
+    }
 }
 
 mod linting {
     use super::defaults;
     use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct LintingConfig {
@@ -147,6 +444,88 @@ mod linting {
         pub max_suggestions: u32,
         #[serde(default)]
         pub enable_auto_fix: bool,
+        /// Also apply suggestion-level (potentially behavior-changing) fixes
+        #[serde(default)]
+        pub apply_suggestions: bool,
+        /// `node_modules` paths to analyze despite the default exclude (e.g. `node_modules/pkg`)
+        #[serde(default)]
+        pub include_node_modules: Vec<String>,
+        /// Max cumulative `cost` of AI-enhanced rules per file; unset means unbounded
+        #[serde(default)]
+        pub max_total_rule_cost: Option<u32>,
+        /// Per-rule file globs by rule ID, replacing the rule's own `applies_to`
+        #[serde(default)]
+        pub rule_scopes: HashMap<String, Vec<String>>,
+        /// Report `eslint-disable` directives that suppressed no diagnostic
+        #[serde(default)]
+        pub report_unused_disables: bool,
+        /// Max lint-and-fix passes per file before giving up on reaching a fixpoint
+        #[serde(default = "defaults::lint_max_fix_iterations")]
+        pub max_fix_iterations: u32,
+        /// Decode non-UTF-8 source files as latin-1 instead of skipping them
+        #[serde(default)]
+        pub latin1_fallback: bool,
+        /// TSDoc tags the `tsdoc-required-tags` rule requires on every exported declaration
+        #[serde(default = "defaults::lint_tsdoc_required_tags")]
+        pub tsdoc_required_tags: Vec<String>,
+        /// Run-level seed for rules and optimizers that sample; equal seeds reproduce equal samples
+        #[serde(default)]
+        pub sampling_seed: u64,
+        /// Attach an explanation of why each rule fired; AI-enhanced rules ask the AI, costing tokens
+        #[serde(default)]
+        pub explain: bool,
+        /// Record per-rule wall time on every lint (`--profile`)
+        #[serde(default)]
+        pub profile: bool,
+        /// Thresholds that fail the run, checked against the diagnostics found before fixing
+        #[serde(default)]
+        pub fail_on: FailOnConfig,
+        /// Named rule preset applied before the other rule settings
+        #[serde(default)]
+        pub preset: Option<String>,
+        /// Rule categories to turn off, e.g. `style`
+        #[serde(default)]
+        pub disabled_categories: Vec<String>,
+        /// Rule tags to turn off
+        #[serde(default)]
+        pub disabled_tags: Vec<String>,
+        /// Per-rule levels by rule ID (`off`, `warn`, `error`, `info`, `hint`), applied last
+        #[serde(default)]
+        pub rules: HashMap<String, String>,
+        /// Extra rule IDs mapped to the canonical ID they stand for, e.g. `legacy:no-log` -> `no-console`
+        #[serde(default)]
+        pub rule_aliases: HashMap<String, String>,
+        /// Diagnostics to drop by rule and message, muting one noisy variant of a rule
+        #[serde(default)]
+        pub ignore_messages: Vec<IgnoreMessage>,
+        /// Lint TypeScript/JavaScript code fences in markdown files
+        #[serde(default)]
+        pub markdown: bool,
+        /// Globs of generated files, in addition to those with an `@generated` or `Code generated` header
+        #[serde(default = "defaults::lint_generated_file_patterns")]
+        pub generated_file_patterns: Vec<String>,
+        /// What runs on generated files; AI passes never do
+        #[serde(default)]
+        pub generated_files: GeneratedFilePolicy,
+        /// Line ending the `line-endings` rule normalizes mixed files to; off unless set
+        #[serde(default)]
+        pub line_endings: LineEndingPolicy,
+        /// Levels for `no-console` and `no-debugger` by file glob, so tests and dev code can log
+        /// while production code keeps the default; the most relaxed matching glob wins
+        #[serde(default = "defaults::lint_env_globs")]
+        pub env_globs: HashMap<String, String>,
+        /// Weight of each rule category (lowercase) in the summary's quality score; unlisted categories weigh 1
+        #[serde(default = "defaults::lint_category_weights")]
+        pub category_weights: HashMap<String, f64>,
+        /// Whether `async-error-handling` reports each unguarded `await` or each async function with one
+        #[serde(default)]
+        pub async_error_handling: AsyncErrorGranularity,
+        /// Group order and sorting of the `import-order` rule
+        #[serde(default)]
+        pub import_order: ImportOrderConfig,
+        /// Where the `unsafe-assertions` rule reports non-null assertions and unsafe casts
+        #[serde(default)]
+        pub unsafe_assertions: UnsafeAssertionsConfig,
     }
 
     impl Default for LintingConfig {
@@ -156,9 +535,178 @@ mod linting {
                 exclude_patterns: defaults::lint_exclude_patterns(),
                 max_suggestions: defaults::lint_max_suggestions(),
                 enable_auto_fix: false,
+                apply_suggestions: false,
+                include_node_modules: Vec::new(),
+                max_total_rule_cost: None,
+                rule_scopes: HashMap::new(),
+                report_unused_disables: false,
+                max_fix_iterations: defaults::lint_max_fix_iterations(),
+                latin1_fallback: false,
+                tsdoc_required_tags: defaults::lint_tsdoc_required_tags(),
+                sampling_seed: 0,
+                explain: false,
+                profile: false,
+                fail_on: FailOnConfig::default(),
+                preset: None,
+                disabled_categories: Vec::new(),
+                disabled_tags: Vec::new(),
+                rules: HashMap::new(),
+                rule_aliases: HashMap::new(),
+                ignore_messages: Vec::new(),
+                markdown: false,
+                generated_file_patterns: defaults::lint_generated_file_patterns(),
+                generated_files: GeneratedFilePolicy::default(),
+                line_endings: LineEndingPolicy::default(),
+                env_globs: defaults::lint_env_globs(),
+                category_weights: defaults::lint_category_weights(),
+                async_error_handling: AsyncErrorGranularity::default(),
+                import_order: ImportOrderConfig::default(),
+                unsafe_assertions: UnsafeAssertionsConfig::default(),
+            }
+        }
+    }
+
+    /// How generated files are handled (`linting.generated_files`)
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum GeneratedFilePolicy {
+        /// Static rules and their fixes run; AI passes are skipped
+        #[default]
+        NoAi,
+        /// Static rules run, but nothing is fixed
+        NoFix,
+        /// Not analyzed at all
+        Skip,
+    }
+
+    /// Line endings the `line-endings` rule enforces (`linting.line_endings`)
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum LineEndingPolicy {
+        /// Keep each file's line endings as they are; the rule reports nothing
+        #[default]
+        Preserve,
+        /// Normalize mixed files to the ending most of their lines already use
+        Dominant,
+        /// Normalize mixed files to `\n`
+        Lf,
+        /// Normalize mixed files to `\r\n`
+        Crlf,
+    }
+
+    /// Where the `async-error-handling` rule requires try/catch (`linting.async_error_handling`)
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum AsyncErrorGranularity {
+        /// One diagnostic per async function with unguarded awaits
+        #[default]
+        Function,
+        /// One diagnostic per unguarded await
+        Call,
+    }
+
+    /// How the `import-order` rule groups and sorts the leading imports (`linting.import_order`)
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct ImportOrderConfig {
+        /// Groups in the order they appear; unlisted groups go last
+        #[serde(default = "defaults::lint_import_groups")]
+        pub groups: Vec<ImportGroup>,
+        /// Sort specifiers case-sensitively (`Zod` before `axios`); otherwise case is ignored
+        #[serde(default)]
+        pub case_sensitive: bool,
+        /// Specifier prefixes of internal path aliases, e.g. `@/` or `~/`
+        #[serde(default = "defaults::lint_import_internal_prefixes")]
+        pub internal_prefixes: Vec<String>,
+    }
+
+    impl Default for ImportOrderConfig {
+        fn default() -> Self {
+            Self {
+                groups: defaults::lint_import_groups(),
+                case_sensitive: false,
+                internal_prefixes: defaults::lint_import_internal_prefixes(),
+            }
+        }
+    }
+
+    /// Files the `unsafe-assertions` rule skips (`linting.unsafe_assertions`)
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct UnsafeAssertionsConfig {
+        /// Allow non-null assertions and unsafe casts in files matching `test_patterns`
+        #[serde(default)]
+        pub allow_in_tests: bool,
+        /// Globs of test files
+        #[serde(default = "defaults::lint_test_file_patterns")]
+        pub test_patterns: Vec<String>,
+    }
+
+    impl Default for UnsafeAssertionsConfig {
+        fn default() -> Self {
+            Self {
+                allow_in_tests: false,
+                test_patterns: defaults::lint_test_file_patterns(),
+            }
+        }
+    }
+
+    /// Kind of module an import specifier names
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum ImportGroup {
+        /// Node.js built-in modules, e.g. `fs` or `node:path`
+        Builtin,
+        /// Packages, e.g. `react` or `@scope/pkg`
+        External,
+        /// Internal path aliases matching `internal_prefixes`
+        Internal,
+        /// `./` and `../` paths
+        Relative,
+    }
+
+    impl ImportGroup {
+        pub fn as_str(self) -> &'static str {
+            match self {
+                ImportGroup::Builtin => "builtin",
+                ImportGroup::External => "external",
+                ImportGroup::Internal => "internal",
+                ImportGroup::Relative => "relative",
             }
         }
     }
+
+    /// A `linting.ignore_messages` entry: diagnostics of `rule_id` whose message matches `message_regex`
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct IgnoreMessage {
+        pub rule_id: String,
+        pub message_regex: String,
+    }
+
+    impl IgnoreMessage {
+        pub fn regex(&self) -> Result<regex::Regex, regex::Error> {
+            regex::Regex::new(&self.message_regex)
+        }
+    }
+
+    /// When a run fails; unset limits never fail it
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct FailOnConfig {
+        /// Fail when more errors than this are found
+        #[serde(default)]
+        pub max_errors: Option<u32>,
+        /// Fail when more warnings than this are found
+        #[serde(default)]
+        pub max_warnings: Option<u32>,
+        /// Fail when any diagnostic has a fix available
+        #[serde(default)]
+        pub fail_on_fixable: bool,
+    }
+
+    impl FailOnConfig {
+        /// Whether any threshold is set, so diagnostics must be collected
+        pub fn is_active(&self) -> bool {
+            self.max_errors.is_some() || self.max_warnings.is_some() || self.fail_on_fixable
+        }
+    }
 }
 
 mod adaptive {
@@ -270,6 +818,10 @@ pub struct MoonShineConfig {
     #[serde(default)]
     pub adaptive: AdaptiveConfig,
     #[serde(default)]
+    pub debug: DebugConfig,
+    #[serde(default)]
+    pub complexity: ComplexityConfig,
+    #[serde(default)]
     pub enable_relationship_analysis: Option<bool>,
     #[serde(default)]
     pub ai_model: Option<String>,
@@ -279,6 +831,9 @@ pub struct MoonShineConfig {
     pub tsdoc_coverage_target: Option<f64>,
     #[serde(default)]
     pub operation_mode: Option<String>,
+    /// Report-only static analysis that writes no file, session or prompt and runs no host command
+    #[serde(default)]
+    pub safe_mode: bool,
     #[serde(default)]
     pub custom_prompts: Option<std::collections::HashMap<String, String>>,
     #[serde(default)]
@@ -301,6 +856,24 @@ pub struct MoonShineConfig {
     pub workflow_parallel_processing: Option<bool>,
     #[serde(default)]
     pub workflow_timeout_seconds: Option<u64>,
+    /// Report format used when `--format` is not given (`terminal`, `json` or `sarif`)
+    #[serde(default)]
+    pub output_format: Option<String>,
+    /// Path the report is archived to when `--output` is not given (`-` for stdout)
+    #[serde(default)]
+    pub output_file: Option<String>,
+    /// Directory for caches that outlive sessions; defaults to `<moonshine_dir>/cache`
+    #[serde(default)]
+    pub cache_directory: Option<String>,
+    /// Most files one run processes, taken in sorted path order; `0` removes the cap
+    #[serde(default = "defaults::max_files")]
+    pub max_files: Option<u32>,
+    /// Longest a delegated host task (TSC, ESLint, formatter) may run, in milliseconds
+    #[serde(default = "defaults::host_task_timeout_ms")]
+    pub host_task_timeout_ms: u64,
+    /// Most combined stdout and stderr bytes a delegated host task may return
+    #[serde(default = "defaults::host_task_max_output_bytes")]
+    pub host_task_max_output_bytes: u64,
 }
 
 impl Default for MoonShineConfig {
@@ -309,11 +882,14 @@ impl Default for MoonShineConfig {
             ai: AiConfig::default(),
             linting: LintingConfig::default(),
             adaptive: AdaptiveConfig::default(),
+            debug: DebugConfig::default(),
+            complexity: ComplexityConfig::default(),
             enable_relationship_analysis: Some(false),
             ai_model: Some("sonnet".to_string()),
             enable_ai_tsdoc: Some(true),
             tsdoc_coverage_target: Some(90.0),
             operation_mode: Some("fix".to_string()),
+            safe_mode: false,
             custom_prompts: None,
             enable_copro_optimization: Some(true),
             copro_breadth: Some(5),
@@ -325,6 +901,12 @@ impl Default for MoonShineConfig {
             workflow_enabled: Some(true),
             workflow_parallel_processing: Some(true),
             workflow_timeout_seconds: Some(300),
+            output_format: None,
+            output_file: None,
+            cache_directory: None,
+            max_files: defaults::max_files(),
+            host_task_timeout_ms: defaults::host_task_timeout_ms(),
+            host_task_max_output_bytes: defaults::host_task_max_output_bytes(),
         }
     }
 }
@@ -341,14 +923,103 @@ impl MoonShineConfig {
         if !(0.0..=2.0).contains(&self.ai.temperature) {
             return Err(Error::config("ai.temperature must be between 0.0 and 2.0"));
         }
+        if let Some((rule_id, model)) = self.ai.rule_model_overrides.iter().find(|(_, model)| model.trim().is_empty()) {
+            let field = format!("ai.rule_model_overrides.{}", rule_id);
+            return Err(Error::config_field(format!("{} must name a model", field), field, Some(model.as_str())));
+        }
 
         if self.adaptive.rule_generation.quality_threshold < 0.0 || self.adaptive.rule_generation.quality_threshold > 1.0 {
             return Err(Error::config("adaptive.ruleGeneration.qualityThreshold must be between 0.0 and 1.0"));
         }
 
+        if let Some(mode) = self.operation_mode.as_deref() {
+            mode.parse::<OperationMode>()?;
+        }
+
+        let complexity = &self.complexity;
+        for (field, value) in [
+            ("complexity.max_cyclomatic_per_function", f64::from(complexity.max_cyclomatic_per_function)),
+            ("complexity.max_cognitive_per_function", f64::from(complexity.max_cognitive_per_function)),
+            ("complexity.max_halstead_difficulty", complexity.max_halstead_difficulty),
+            ("complexity.max_file_loc", f64::from(complexity.max_file_loc)),
+        ] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(Error::config_field(format!("{} must be positive", field), field, Some(value.to_string())));
+            }
+        }
+        if let Some(max_increase) = complexity.max_fix_complexity_increase.filter(|value| !(value.is_finite() && *value >= 0.0)) {
+            let field = "complexity.max_fix_complexity_increase";
+            return Err(Error::config_field(format!("{} must not be negative", field), field, Some(max_increase.to_string())));
+        }
+
+        if let Some(format) = self.output_format.as_deref() {
+            format.parse::<ReportFormat>()?;
+        }
+
+        if self.ai.eval_batch_size == 0 {
+            return Err(Error::config_field(
+                "ai.eval_batch_size must be at least 1",
+                "ai.eval_batch_size",
+                Some("0".to_string()),
+            ));
+        }
+
+        if self.linting.max_fix_iterations == 0 {
+            return Err(Error::config_field(
+                "linting.max_fix_iterations must be at least 1",
+                "linting.max_fix_iterations",
+                Some("0".to_string()),
+            ));
+        }
+
+        if let Some(preset) = self.linting.preset.as_deref().filter(|preset| !crate::rulebase::has_preset(preset)) {
+            return Err(Error::config_field(
+                format!("Unknown rule preset '{}'. Valid presets: {}", preset, crate::rulebase::available_presets().join(", ")),
+                "linting.preset",
+                Some(preset),
+            ));
+        }
+
+        if let Some((rule_id, level)) = self.linting.rules.iter().find(|(_, level)| parse_rule_level(level).is_none()) {
+            return Err(Error::config_field(
+                format!("Invalid level '{}' for rule '{}'; expected off, warn, error, info or hint", level, rule_id),
+                "linting.rules",
+                Some(level),
+            ));
+        }
+
+        for (field, value) in [
+            ("host_task_timeout_ms", self.host_task_timeout_ms),
+            ("host_task_max_output_bytes", self.host_task_max_output_bytes),
+        ] {
+            if value == 0 {
+                return Err(Error::config_field(format!("{} must be at least 1", field), field, Some("0".to_string())));
+            }
+        }
+
+        for ignore in &self.linting.ignore_messages {
+            if let Err(e) = ignore.regex() {
+                return Err(Error::config_field(
+                    format!("Invalid message_regex for rule '{}': {}", ignore.rule_id, e),
+                    "linting.ignore_messages",
+                    Some(&ignore.message_regex),
+                ));
+            }
+        }
+
         Ok(())
     }
 
+    /// File cap for a run, `None` when uncapped
+    pub fn resolved_max_files(&self) -> Option<u32> {
+        self.max_files.filter(|max| *max > 0)
+    }
+
+    /// Configured operation mode, defaulting to `fix` when unset or invalid (see `validate`)
+    pub fn resolved_operation_mode(&self) -> OperationMode {
+        self.operation_mode.as_deref().and_then(|mode| mode.parse().ok()).unwrap_or_default()
+    }
+
     /// Directory where Moon stores moon-shine data (prompts, sessions, cache).
     pub fn moonshine_directory() -> String {
         get_moon_config_safe("moonshine_directory")
@@ -357,6 +1028,13 @@ impl MoonShineConfig {
             .unwrap_or_else(|| ".moon/moonshine".to_string())
     }
 
+    /// Directory for the incremental and response caches.
+    ///
+    /// Kept apart from `<moonshine_dir>/sessions` so session pruning never evicts caches.
+    pub fn resolved_cache_directory(&self) -> String {
+        self.cache_directory.clone().unwrap_or_else(|| format!("{}/cache", Self::moonshine_directory()))
+    }
+
     /// Get debug session retention hours
     pub fn debug_session_retention_hours(&self) -> u32 {
         // Default to 12 hours for debug sessions
@@ -369,8 +1047,23 @@ impl MoonShineConfig {
         48
     }
 
+    /// Whether debug sessions get the extended retention window.
+    ///
+    /// `debug.keep_sessions` wins when set; otherwise the deprecated `"debug"` entry in
+    /// `ai.providers` is still honored.
     pub fn keep_debug_sessions(&self) -> bool {
-        self.ai.providers.iter().any(|p| p == "debug")
+        self.debug.keep_sessions.unwrap_or_else(|| {
+            let legacy = self.ai.providers.iter().any(|p| p == "debug");
+            if legacy {
+                moon_warn!("Listing \"debug\" in ai.providers is deprecated; set debug.keep_sessions instead");
+            }
+            legacy
+        })
+    }
+
+    /// Whether `ai.providers` lists the offline `"none"` provider, disabling AI for static-only runs
+    pub fn ai_offline(&self) -> bool {
+        self.ai.providers.iter().any(|p| p == crate::provider_router::OFFLINE_PROVIDER)
     }
 
     /// Get default language for analysis
@@ -390,6 +1083,7 @@ impl MoonShineConfig {
 }
 
 pub mod defaults {
+    use super::ImportGroup;
     use std::path::PathBuf;
 
     // AI defaults
@@ -408,6 +1102,15 @@ pub mod defaults {
     pub fn ai_batch_size() -> u32 {
         5
     }
+    pub fn ai_eval_batch_size() -> u32 {
+        32
+    }
+    pub fn ai_max_output_tokens() -> u32 {
+        8192
+    }
+    pub fn ai_cross_file_context_tokens() -> u32 {
+        2000
+    }
 
     // Linting defaults
     pub fn lint_include_patterns() -> Vec<String> {
@@ -419,6 +1122,56 @@ pub mod defaults {
     pub fn lint_max_suggestions() -> u32 {
         50
     }
+    pub fn lint_max_fix_iterations() -> u32 {
+        10
+    }
+    pub fn lint_tsdoc_required_tags() -> Vec<String> {
+        vec!["@category".into(), "@safe".into(), "@mvp".into(), "@complexity".into(), "@since".into()]
+    }
+    pub fn lint_import_groups() -> Vec<ImportGroup> {
+        vec![ImportGroup::Builtin, ImportGroup::External, ImportGroup::Internal, ImportGroup::Relative]
+    }
+    pub fn lint_import_internal_prefixes() -> Vec<String> {
+        vec!["@/".into(), "~/".into(), "#".into()]
+    }
+    pub fn lint_generated_file_patterns() -> Vec<String> {
+        vec!["**/*.pb.ts".into(), "**/*_pb.ts".into(), "**/*_pb.js".into(), "**/*.generated.ts".into()]
+    }
+    pub fn lint_test_file_patterns() -> Vec<String> {
+        vec!["**/*.test.{ts,tsx,js,jsx}".into(), "**/*.spec.{ts,tsx,js,jsx}".into(), "**/__tests__/**".into()]
+    }
+    pub fn lint_env_globs() -> std::collections::HashMap<String, String> {
+        lint_test_file_patterns().into_iter().map(|glob| (glob, "warn".to_string())).collect()
+    }
+    pub fn lint_category_weights() -> std::collections::HashMap<String, f64> {
+        [("security", 4.0), ("correctness", 3.0), ("complexity", 2.0), ("style", 1.0)]
+            .into_iter()
+            .map(|(category, weight)| (category.to_string(), weight))
+            .collect()
+    }
+    pub fn max_files() -> Option<u32> {
+        Some(1000)
+    }
+    pub fn host_task_timeout_ms() -> u64 {
+        120_000
+    }
+    pub fn host_task_max_output_bytes() -> u64 {
+        16 * 1024 * 1024
+    }
+
+    // Complexity defaults
+    pub fn max_cyclomatic_per_function() -> u32 {
+        10
+    }
+    pub fn max_cognitive_per_function() -> u32 {
+        15
+    }
+    pub fn max_halstead_difficulty() -> f64 {
+        20.0
+    }
+    pub fn max_file_loc() -> u32 {
+        500
+    }
 
     // Adaptive defaults
     pub fn adaptive_enabled() -> bool {
@@ -464,3 +1217,135 @@ pub mod defaults {
         Some(PathBuf::from("models/starcoder-1b").to_string_lossy().to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_mode_parses_each_mode() {
+        for mode in OperationMode::ALL {
+            assert_eq!(mode.as_str().parse::<OperationMode>().unwrap(), mode);
+            assert_eq!(mode.to_string(), mode.as_str());
+        }
+
+        assert_eq!("LINT_ONLY".parse::<OperationMode>().unwrap(), OperationMode::LintOnly);
+        assert_eq!(" dry-run ".parse::<OperationMode>().unwrap(), OperationMode::DryRun);
+    }
+
+    #[test]
+    fn test_operation_mode_rejects_unknown_mode() {
+        let error = "frobnicate".parse::<OperationMode>().unwrap_err();
+
+        match &error {
+            Error::Config { message, field, value } => {
+                assert!(message.contains("frobnicate"));
+                for mode in OperationMode::ALL {
+                    assert!(message.contains(mode.as_str()), "error should list '{}'", mode);
+                }
+                assert_eq!(field.as_deref(), Some("operation_mode"));
+                assert_eq!(value.as_deref(), Some("frobnicate"));
+            }
+            other => panic!("expected config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_operation_mode_names_replacement_for_retired_mode() {
+        let error = "ESLINT_ONLY".parse::<OperationMode>().unwrap_err();
+
+        match &error {
+            Error::Config { message, field, .. } => {
+                assert!(message.contains("'ESLINT_ONLY' was removed; use 'lint-only' instead"), "{}", message);
+                assert_eq!(field.as_deref(), Some("operation_mode"));
+            }
+            other => panic!("expected config error, got {:?}", other),
+        }
+        assert!("agent-based".parse::<OperationMode>().unwrap_err().to_string().contains("use 'comprehensive'"));
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_operation_mode() {
+        let mut config = MoonShineConfig {
+            operation_mode: Some("frobnicate".to_string()),
+            ..MoonShineConfig::default()
+        };
+
+        assert!(matches!(config.validate(), Err(Error::Config { .. })));
+        assert_eq!(config.resolved_operation_mode(), OperationMode::Fix);
+
+        config.operation_mode = Some("comprehensive".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.resolved_operation_mode(), OperationMode::Comprehensive);
+    }
+
+    #[test]
+    fn test_keep_debug_sessions_explicit_field() {
+        let mut config = MoonShineConfig::default();
+
+        config.debug.keep_sessions = Some(true);
+        assert!(config.keep_debug_sessions());
+
+        // The explicit field overrides the legacy providers entry
+        config.ai.providers.push("debug".to_string());
+        config.debug.keep_sessions = Some(false);
+        assert!(!config.keep_debug_sessions());
+    }
+
+    #[test]
+    fn test_keep_debug_sessions_legacy_providers_fallback() {
+        let mut config = MoonShineConfig::default();
+        assert!(config.debug.keep_sessions.is_none());
+        assert!(!config.keep_debug_sessions());
+
+        config.ai.providers.push("debug".to_string());
+        assert!(config.keep_debug_sessions());
+    }
+
+    #[test]
+    fn test_debug_config_deserializes() {
+        let config: MoonShineConfig = serde_json::from_str(r#"{ "debug": { "keep_sessions": true } }"#).unwrap();
+        assert_eq!(config.debug.keep_sessions, Some(true));
+
+        let config: MoonShineConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.debug.keep_sessions, None);
+    }
+
+    #[test]
+    fn test_rule_model_overrides_fall_back_to_ai_model() {
+        let mut config: MoonShineConfig =
+            serde_json::from_str(r#"{ "ai": { "model": "sonnet", "rule_model_overrides": { "security-review": "opus" } } }"#).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.ai.model_for_rule("security-review"), "opus");
+        assert_eq!(config.ai.model_for_rule("prefer-const"), "sonnet");
+
+        config.ai.rule_model_overrides.insert("prefer-const".to_string(), " ".to_string());
+        assert!(matches!(config.validate(), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_validate_rejects_non_positive_complexity_limits() {
+        let mut config = MoonShineConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.complexity.max_cyclomatic_per_function = 0;
+        assert!(matches!(config.validate(), Err(Error::Config { .. })));
+
+        config.complexity = ComplexityConfig {
+            max_halstead_difficulty: -1.0,
+            ..ComplexityConfig::default()
+        };
+        assert!(matches!(config.validate(), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_ignore_message_regex() {
+        let mut config = MoonShineConfig::default();
+        config.linting.ignore_messages = vec![IgnoreMessage {
+            rule_id: "no-console".to_string(),
+            message_regex: "console\\.(log".to_string(),
+        }];
+
+        assert!(matches!(config.validate(), Err(Error::Config { .. })));
+    }
+}
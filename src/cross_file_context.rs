@@ -0,0 +1,217 @@
+//! # Cross-File AI Context
+//!
+//! Type errors often span modules: the fix for a call site depends on an interface or
+//! function signature declared in another file. When `ai.cross_file_context` is on, AI fix
+//! prompts carry the exported declarations of the target's directly imported files that
+//! the target actually mentions, resolved through the project [`DependencyGraph`]. The
+//! snippets are marked read-only and bounded by `ai.cross_file_context_tokens`, so they
+//! never crowd out the code being fixed.
+//!
+//! @category ai
+//! @safe team
+//! @mvp enhanced
+//! @complexity medium
+//! @since 2.0.0
+
+use crate::rulebase::import_cycles::{import_graph, normalize};
+use crate::types::DependencyGraph;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{Declaration, Statement};
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use std::path::Path;
+
+/// Rough characters per token, matching the token estimates used elsewhere
+const CHARS_PER_TOKEN: usize = 4;
+
+const CONTEXT_HEADER: &str = "Read-only context from imported files (do not modify or return this code):\n";
+
+/// An exported declaration of an imported file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextSnippet {
+    pub file_path: String,
+    pub name: String,
+    pub code: String,
+}
+
+impl ContextSnippet {
+    fn render(&self) -> String {
+        format!("\n// {}\n{}\n", self.file_path, self.code)
+    }
+}
+
+/// Snippets selected for one target file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CrossFileContext {
+    pub snippets: Vec<ContextSnippet>,
+    pub omitted: usize, // Relevant snippets left out to stay within the token budget
+}
+
+impl CrossFileContext {
+    pub fn is_empty(&self) -> bool {
+        self.snippets.is_empty()
+    }
+
+    /// Prompt section holding the snippets; empty when there are none
+    pub fn render(&self) -> String {
+        if self.snippets.is_empty() {
+            return String::new();
+        }
+        std::iter::once(CONTEXT_HEADER.to_string()).chain(self.snippets.iter().map(ContextSnippet::render)).collect()
+    }
+}
+
+/// Context for `target` from `(path, source)` project files, within `max_tokens`.
+///
+/// Snippets are taken in import order; one that does not fit the remaining budget is
+/// skipped so smaller declarations after it can still be included.
+pub fn gather_context(target: &str, files: &[(String, String)], max_tokens: u32) -> CrossFileContext {
+    let graph = import_graph(files);
+    let target_path = normalize(Path::new(target));
+    let Some(target_index) = graph.nodes.iter().position(|path| *path == target_path) else {
+        return CrossFileContext::default();
+    };
+    let target_source = &files[target_index].1;
+
+    let budget = max_tokens as usize * CHARS_PER_TOKEN;
+    let mut used = CONTEXT_HEADER.len();
+    let mut context = CrossFileContext::default();
+    for imported in direct_imports(&graph, target_index) {
+        for snippet in exported_declarations(&graph.nodes[imported], &files[imported].1) {
+            if !mentions(target_source, &snippet.name) {
+                continue;
+            }
+            let size = snippet.render().len();
+            if used + size > budget {
+                context.omitted += 1;
+                continue;
+            }
+            used += size;
+            context.snippets.push(snippet);
+        }
+    }
+
+    if context.omitted > 0 {
+        moon_debug!(
+            "Cross-file context for {}: {} snippet(s) included, {} over the {} token budget",
+            target,
+            context.snippets.len(),
+            context.omitted,
+            max_tokens
+        );
+    }
+    context
+}
+
+/// Files `target` imports directly, in graph order
+fn direct_imports(graph: &DependencyGraph, target: usize) -> impl Iterator<Item = usize> + '_ {
+    graph.edges.iter().filter(move |&&(from, to)| from == target && to != target).map(|&(_, to)| to)
+}
+
+/// Source text of each named top-level export of `source`
+fn exported_declarations(file_path: &str, source: &str) -> Vec<ContextSnippet> {
+    let source_type = SourceType::from_path(Path::new(file_path)).unwrap_or_default();
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source, source_type).parse();
+    if !ret.errors.is_empty() {
+        moon_debug!("Skipping cross-file context from {}: parse errors", file_path);
+        return Vec::new();
+    }
+
+    ret.program
+        .body
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::ExportNamedDeclaration(export) => Some((export.declaration.as_ref()?, export.span)),
+            _ => None,
+        })
+        .flat_map(|(declaration, span)| {
+            let code = source[span.start as usize..span.end as usize].to_string();
+            declared_names(declaration).into_iter().map(move |name| ContextSnippet {
+                file_path: file_path.to_string(),
+                name,
+                code: code.clone(),
+            })
+        })
+        .collect()
+}
+
+fn declared_names(declaration: &Declaration) -> Vec<String> {
+    match declaration {
+        Declaration::VariableDeclaration(variable) => variable
+            .declarations
+            .iter()
+            .filter_map(|declarator| declarator.id.get_binding_identifier())
+            .map(|id| id.name.to_string())
+            .collect(),
+        Declaration::FunctionDeclaration(function) => function.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::ClassDeclaration(class) => class.id.iter().map(|id| id.name.to_string()).collect(),
+        Declaration::TSTypeAliasDeclaration(alias) => vec![alias.id.name.to_string()],
+        Declaration::TSInterfaceDeclaration(interface) => vec![interface.id.name.to_string()],
+        Declaration::TSEnumDeclaration(enumeration) => vec![enumeration.id.name.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Whether `name` occurs in `source` as a whole identifier
+fn mentions(source: &str, name: &str) -> bool {
+    let is_identifier = |ch: char| ch.is_alphanumeric() || ch == '_' || ch == '$';
+    source.match_indices(name).any(|(start, _)| {
+        let before = source[..start].chars().next_back();
+        let after = source[start + name.len()..].chars().next();
+        !before.is_some_and(is_identifier) && !after.is_some_and(is_identifier)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> Vec<(String, String)> {
+        [
+            (
+                "src/service.ts",
+                "import type { User } from './models';\nimport { formatName } from './format';\n\nexport function greet(user: User): string {\n  return formatName(user.id);\n}\n",
+            ),
+            (
+                "src/models.ts",
+                "export interface User {\n  id: number;\n  name: string;\n}\n\nexport interface Account {\n  owner: string;\n}\n",
+            ),
+            ("src/format.ts", "export function formatName(name: string): string {\n  return name.trim();\n}\n"),
+            ("src/unrelated.ts", "export interface Order {\n  total: number;\n}\n"),
+        ]
+        .into_iter()
+        .map(|(path, source)| (path.to_string(), source.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_imported_type_definition_included_in_context() {
+        let context = gather_context("./src/service.ts", &project(), 2000);
+
+        let names: Vec<&str> = context.snippets.iter().map(|snippet| snippet.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "formatName"], "only mentioned exports of direct imports");
+        assert_eq!(context.omitted, 0);
+
+        let rendered = context.render();
+        assert!(rendered.starts_with(CONTEXT_HEADER));
+        assert!(rendered.contains("// src/models.ts\nexport interface User {\n  id: number;\n  name: string;\n}\n"));
+        assert!(!rendered.contains("Account") && !rendered.contains("Order"));
+    }
+
+    #[test]
+    fn test_context_trimmed_to_token_budget() {
+        let full = gather_context("src/service.ts", &project(), 2000);
+        let full_tokens = (full.render().len() / CHARS_PER_TOKEN) as u32;
+
+        // Room for the header and the `User` interface, not the `formatName` function after it
+        let trimmed = gather_context("src/service.ts", &project(), full_tokens - 5);
+        assert!(trimmed.render().len() <= (full_tokens - 5) as usize * CHARS_PER_TOKEN);
+        assert_eq!(trimmed.snippets.len() + trimmed.omitted, full.snippets.len());
+        assert_eq!(trimmed.omitted, 1);
+        assert_eq!(trimmed.snippets[0].name, "User");
+
+        assert!(gather_context("src/service.ts", &project(), 10).render().is_empty());
+        assert!(gather_context("src/missing.ts", &project(), 2000).is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! # TypeScript Declaration Files
+//!
+//! Declaration files describe types and carry no runtime code, so rules about runtime
+//! behavior only produce false positives on their ambient declarations. A file is a
+//! declaration file when its extension is `.d.ts` (or `.d.mts` / `.d.cts`), or when every
+//! top-level statement is ambient: `declare` declarations, interfaces, type aliases and
+//! type-only imports and exports. On such files only type and documentation rules run.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::rule_types::{RuleCategory, RuleMetadata};
+use crate::rulebase::import_order::IMPORT_ORDER_RULE;
+use crate::rulebase::static_rules::{LINE_ENDINGS_RULE, TSDOC_REQUIRED_TAGS_RULE};
+use crate::rulebase::RuleImplementation;
+use oxc_ast::ast::{Declaration, Program, Statement};
+
+/// Extensions of TypeScript declaration files
+const DECLARATION_EXTENSIONS: &[&str] = &[".d.ts", ".d.mts", ".d.cts"];
+
+/// Built-in rules that check declarations themselves rather than runtime behavior
+pub const DECLARATION_RULES: &[&str] = &[TSDOC_REQUIRED_TAGS_RULE, LINE_ENDINGS_RULE, IMPORT_ORDER_RULE];
+
+/// Rule tags marking a rule as a type or documentation check
+const DECLARATION_TAGS: &[&str] = &["types", "typescript", "documentation"];
+
+/// Whether `file_path`, parsed as `program` when it parsed, is a declaration file
+pub fn is_declaration_file(file_path: &str, program: Option<&Program>) -> bool {
+    DECLARATION_EXTENSIONS.iter().any(|extension| file_path.ends_with(extension)) || program.is_some_and(is_ambient_only)
+}
+
+/// Whether `program` has statements and all of them are ambient
+pub fn is_ambient_only(program: &Program) -> bool {
+    !program.body.is_empty() && program.body.iter().all(is_ambient_statement)
+}
+
+/// Whether `rule` runs on declaration files: type and documentation rules do, runtime and AI behavioral rules do not
+pub fn applies_to_declarations(rule: &RuleMetadata) -> bool {
+    if matches!(rule.implementation, RuleImplementation::AiBehavioral { .. } | RuleImplementation::Hybrid { .. }) {
+        return false;
+    }
+    DECLARATION_RULES.contains(&rule.id.as_str())
+        || rule.category == RuleCategory::Documentation
+        || rule.tags.iter().any(|tag| DECLARATION_TAGS.contains(&tag.as_str()))
+}
+
+fn is_ambient_statement(statement: &Statement) -> bool {
+    match statement {
+        Statement::ImportDeclaration(import) => import.import_kind.is_type(),
+        Statement::ExportNamedDeclaration(export) => export.export_kind.is_type() || export.declaration.as_ref().is_some_and(is_ambient_declaration),
+        Statement::VariableDeclaration(declaration) => declaration.declare,
+        Statement::FunctionDeclaration(function) => function.declare,
+        Statement::ClassDeclaration(class) => class.declare,
+        Statement::TSEnumDeclaration(enumeration) => enumeration.declare,
+        Statement::TSModuleDeclaration(module) => module.declare,
+        Statement::TSTypeAliasDeclaration(_) | Statement::TSInterfaceDeclaration(_) => true,
+        _ => false,
+    }
+}
+
+fn is_ambient_declaration(declaration: &Declaration) -> bool {
+    match declaration {
+        Declaration::VariableDeclaration(declaration) => declaration.declare,
+        Declaration::FunctionDeclaration(function) => function.declare,
+        Declaration::ClassDeclaration(class) => class.declare,
+        Declaration::TSEnumDeclaration(enumeration) => enumeration.declare,
+        Declaration::TSModuleDeclaration(module) => module.declare,
+        Declaration::TSTypeAliasDeclaration(_) | Declaration::TSInterfaceDeclaration(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    fn declares_only(source: &str) -> bool {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        assert!(ret.errors.is_empty(), "fixture should parse");
+        is_declaration_file("src/globals.ts", Some(&ret.program))
+    }
+
+    #[test]
+    fn test_declaration_files_detected_by_extension_and_declare_context() {
+        assert!(is_declaration_file("types/env.d.ts", None));
+        assert!(is_declaration_file("types/env.d.mts", None));
+        assert!(!is_declaration_file("src/env.ts", None));
+
+        let ambient = "import type { User } from './user';\n\
+            declare module 'legacy' {\n  export const version: string;\n}\n\
+            export declare function load(user: User): void;\n\
+            export interface Options {\n  debug: boolean;\n}\n";
+        assert!(declares_only(ambient));
+        assert!(!declares_only("export interface Options {\n  debug: boolean;\n}\nexport const defaults: Options = { debug: false };\n"));
+    }
+}
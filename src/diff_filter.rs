@@ -0,0 +1,194 @@
+//! # Changed-Line Filtering for PR Checks
+//!
+//! Linting a large legacy file in a pull request reports every old problem along with
+//! the new ones. With `--diff <file>` the unified diff of the change is read, the lines
+//! it adds or modifies are collected per file, and only diagnostics intersecting those
+//! lines are reported. Files are still parsed and linted whole, so rules keep their full
+//! context; the diff only decides what is shown. [`ChangedLines::from_file_pair`] builds
+//! the same information from base and head contents when no diff is at hand.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::buffer_lint::PARSE_ERROR_RULE;
+use crate::types::LintDiagnostic;
+use similar::{DiffTag, TextDiff};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+/// Added or modified lines of the new version of each file, 1-based
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    pub files: BTreeMap<String, Vec<RangeInclusive<u32>>>,
+}
+
+impl ChangedLines {
+    /// Changed lines from a unified diff (`git diff` output); deleted files are ignored
+    pub fn from_unified_diff(diff: &str) -> Self {
+        let mut changed = Self::default();
+        let mut current: Option<String> = None;
+        let mut new_line = 0u32;
+        // Lines left in the current hunk on the old and new side; headers only appear outside hunks
+        let (mut old_left, mut new_left) = (0u32, 0u32);
+
+        for line in diff.lines() {
+            if old_left == 0 && new_left == 0 {
+                if let Some(path) = line.strip_prefix("+++ ") {
+                    let path = path.split('\t').next().unwrap_or(path).trim();
+                    current = (path != "/dev/null").then(|| normalize_path(path.strip_prefix("b/").unwrap_or(path)));
+                } else if let Some((old, new)) = line.strip_prefix("@@ ").and_then(hunk_header) {
+                    (old_left, new_left) = (old.1, new.1);
+                    new_line = new.0;
+                }
+                continue;
+            }
+
+            match line.chars().next() {
+                Some('+') => {
+                    if let Some(path) = &current {
+                        changed.add(path, new_line, new_line);
+                    }
+                    new_line += 1;
+                    new_left = new_left.saturating_sub(1);
+                }
+                Some('-') => old_left = old_left.saturating_sub(1),
+                Some('\\') => {}
+                _ => {
+                    new_line += 1;
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Changed lines of `path` between its `base` and `head` contents
+    pub fn from_file_pair(path: &str, base: &str, head: &str) -> Self {
+        let mut changed = Self::default();
+        let path = normalize_path(path);
+        for op in TextDiff::from_lines(base, head).ops() {
+            if matches!(op.tag(), DiffTag::Insert | DiffTag::Replace) {
+                let range = op.new_range();
+                changed.add(&path, range.start as u32 + 1, range.end as u32);
+            }
+        }
+        changed
+    }
+
+    /// Combine with the changes of other files
+    pub fn merge(&mut self, other: ChangedLines) {
+        for (path, ranges) in other.files {
+            for range in ranges {
+                self.add(&path, *range.start(), *range.end());
+            }
+        }
+    }
+
+    /// Changed ranges of the file at `file_path`, which may be absolute or differently rooted
+    pub fn ranges_for(&self, file_path: &str) -> Option<&[RangeInclusive<u32>]> {
+        let file_path = normalize_path(file_path);
+        self.files
+            .iter()
+            .find(|(path, _)| file_path == **path || file_path.ends_with(&format!("/{}", path)))
+            .map(|(_, ranges)| ranges.as_slice())
+    }
+
+    /// Whether `diagnostic` should be reported: its lines intersect a changed range.
+    ///
+    /// Parse errors in a changed file are always kept, since they stop the rest of the
+    /// file from being linted.
+    pub fn intersects(&self, diagnostic: &LintDiagnostic) -> bool {
+        let Some(ranges) = self.ranges_for(&diagnostic.file_path) else {
+            return false;
+        };
+        let end_line = diagnostic.end_line.max(diagnostic.line);
+        diagnostic.rule_name == PARSE_ERROR_RULE || ranges.iter().any(|range| diagnostic.line <= *range.end() && *range.start() <= end_line)
+    }
+
+    /// Files the diff touches, as written in the diff
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    fn add(&mut self, path: &str, start: u32, end: u32) {
+        let ranges = self.files.entry(path.to_string()).or_default();
+        match ranges.last_mut() {
+            // Consecutive lines extend the previous range
+            Some(last) if *last.end() + 1 >= start && *last.start() <= start => *last = *last.start()..=end.max(*last.end()),
+            _ => ranges.push(start..=end),
+        }
+    }
+}
+
+/// Keep only diagnostics on changed lines; see [`ChangedLines::intersects`]
+pub fn filter_to_changed_lines(diagnostics: Vec<LintDiagnostic>, changed: &ChangedLines) -> Vec<LintDiagnostic> {
+    diagnostics.into_iter().filter(|diagnostic| changed.intersects(diagnostic)).collect()
+}
+
+/// `(start, length)` of the old and new side from a hunk header body such as `-10,7 +12,9 @@ fn name`
+fn hunk_header(header: &str) -> Option<((u32, u32), (u32, u32))> {
+    let mut sides = header.split_whitespace();
+    let old = side_range(sides.next()?.strip_prefix('-')?)?;
+    let new = side_range(sides.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+/// `12,9` into `(12, 9)`; the length defaults to 1 when omitted
+fn side_range(range: &str) -> Option<(u32, u32)> {
+    match range.split_once(',') {
+        Some((start, length)) => Some((start.parse().ok()?, length.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_lint::lint_buffer_with_rules;
+    use crate::config::MoonShineConfig;
+    use crate::rulebase::testing::static_rule;
+
+    const HEAD: &str = "function legacy(a, b) {\n  if (a == b) {\n    return 1;\n  }\n  return 0;\n}\n\nfunction touched(x, y) {\n  if (x == y) {\n    return 'same';\n  }\n  return x != y ? 'different' : 'unknown';\n}\n";
+
+    const DIFF: &str = "diff --git a/src/compare.ts b/src/compare.ts\nindex 1111111..2222222 100644\n--- a/src/compare.ts\n+++ b/src/compare.ts\n@@ -8,6 +8,6 @@ function legacy(a, b) {\n function touched(x, y) {\n-  if (x === y) {\n+  if (x == y) {\n     return 'same';\n   }\n-  return 'different';\n+  return x != y ? 'different' : 'unknown';\n }\n";
+
+    #[test]
+    fn test_unified_diff_yields_added_lines_of_new_file() {
+        let changed = ChangedLines::from_unified_diff(DIFF);
+
+        assert_eq!(changed.paths().collect::<Vec<_>>(), vec!["src/compare.ts"]);
+        assert_eq!(changed.ranges_for("/repo/src/compare.ts"), Some(&[9..=9, 12..=12][..]));
+        assert_eq!(changed.ranges_for("other/compare.ts"), None);
+    }
+
+    #[test]
+    fn test_only_diagnostics_on_changed_lines_are_reported() {
+        let result = lint_buffer_with_rules(HEAD, "src/compare.ts", &MoonShineConfig::default(), &[static_rule("eqeqeq")]).unwrap();
+        let all_lines: Vec<u32> = result.diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(all_lines, vec![2, 9, 12], "the whole file is still linted");
+
+        let reported = filter_to_changed_lines(result.diagnostics, &ChangedLines::from_unified_diff(DIFF));
+
+        let reported_lines: Vec<u32> = reported.iter().map(|d| d.line).collect();
+        assert_eq!(reported_lines, vec![9, 12]);
+    }
+
+    #[test]
+    fn test_file_pair_matches_unified_diff() {
+        let base = HEAD.replace("  if (x == y) {", "  if (x === y) {").replace("  return x != y ? 'different' : 'unknown';", "  return 'different';");
+
+        let changed = ChangedLines::from_file_pair("./src/compare.ts", &base, HEAD);
+
+        assert_eq!(changed, ChangedLines::from_unified_diff(DIFF));
+    }
+}
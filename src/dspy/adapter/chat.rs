@@ -484,7 +484,9 @@ impl Adapter for ConversationHistoryAdapter {
     /// Makes an asynchronous call to the AI model and returns a `Prediction`.
     ///
     /// This method orchestrates the communication with the underlying AI model,
-    /// including sending the formatted prompt and parsing the response.
+    /// including sending the formatted prompt and parsing the response. JSON-capable
+    /// providers are asked for a validated JSON object; other providers fall back to
+    /// the `[[ ## field ## ]]` delimiter parser.
     ///
     /// @param lm A mutable reference to the `LM` (Language Model) instance.
     /// @param signature The metadata signature of the AI model.
@@ -498,6 +500,17 @@ impl Adapter for ConversationHistoryAdapter {
     /// @since 1.0.0
     async fn call(&self, lm: &mut LM, signature: &dyn MetaSignature, inputs: Example) -> Result<Prediction> {
         let messages = self.format(signature, inputs);
+        let output_fields = signature.output_fields();
+
+        if let Some((payload, usage)) = lm.call_json(messages.clone(), "predict", &output_fields).await? {
+            let output = payload
+                .as_object()
+                .map(|fields| fields.iter().map(|(name, value)| (name.clone(), value.clone())).collect())
+                .unwrap_or_default();
+
+            return Ok(Prediction { data: output, lm_usage: usage });
+        }
+
         let (response, usage) = lm.call(messages, "predict").await?;
         let output = self.parse_response(signature, response);
 
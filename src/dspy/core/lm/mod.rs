@@ -20,13 +20,14 @@
 pub use crate::{
     config::MoonShineConfig,
     message_types::{ConversationHistory, Message},
-    provider_router::{execute_ai_prompt, AIContext},
+    provider_router::{execute_ai_prompt, get_ai_router, AIContext, AIRequest, AITaskKind},
     token_usage::LanguageModelUsageMetrics,
 };
 
 use anyhow::Result;
 use bon::Builder;
 use secrecy::SecretString;
+use serde_json::Value;
 
 /// Represents a direct AI Language Model (LM) for DSPy, routing requests through `moon-shine`'s AI provider.
 ///
@@ -116,6 +117,72 @@ impl DirectAILM {
         Ok((message, usage))
     }
 
+    /// Makes a JSON-mode call to the AI provider for structured signatures.
+    ///
+    /// When the routed provider supports JSON mode, the response is validated against
+    /// `output_fields` (retrying once with a "return valid JSON" nudge) and returned as a
+    /// parsed JSON object. Returns `Ok(None)` when the provider has no JSON mode so callers
+    /// can fall back to delimiter parsing via [`DirectAILM::call`].
+    ///
+    /// @param messages The `ConversationHistory` object containing the conversation history and prompt.
+    /// @param signature A string representing the signature or task for the AI (used in prompt formatting).
+    /// @param output_fields The signature's output field definitions used for validation.
+    /// @returns A `Result` containing the validated payload and usage metrics, or `None` without JSON mode.
+    ///
+    /// @category dspy-method
+    /// @safe team
+    /// @mvp core
+    /// @complexity medium
+    /// @since 2.0.0
+    pub async fn call_json(
+        &mut self,
+        messages: ConversationHistory,
+        signature: &str,
+        output_fields: &Value,
+    ) -> Result<Option<(Value, LanguageModelUsageMetrics)>> {
+        let field_names = output_fields
+            .as_object()
+            .map(|fields| fields.keys().map(|k| format!("`{k}`")).collect::<Vec<_>>().join(", "))
+            .unwrap_or_default();
+        let prompt = format!(
+            "{}\n\nRespond with a single JSON object whose keys are the output fields {} instead of field markers.",
+            self.convert_chat_to_prompt(&messages, signature),
+            field_names
+        );
+
+        let request = AIRequest::new(prompt.clone(), AIContext::new(self.session_id.clone(), AITaskKind::General));
+
+        let Some((response, payload)) = get_ai_router()
+            .execute_json(request, output_fields)
+            .await
+            .map_err(|e| anyhow::anyhow!("AI provider error: {}", e))?
+        else {
+            return Ok(None);
+        };
+
+        let message = Message::Assistant {
+            content: response.content.clone(),
+        };
+
+        let usage = LanguageModelUsageMetrics {
+            input_tokens: (prompt.len() / 4) as u32,
+            output_tokens: (response.content.len() / 4) as u32,
+            total_tokens: 0,
+            reasoning_tokens: None,
+            provider_used: Some(response.provider_used.clone()),
+            execution_time_ms: Some(response.execution_time_ms),
+        };
+
+        self.history.push(LMResponse {
+            chat: messages,
+            output: message,
+            config: self.config.clone(),
+            signature: signature.to_string(),
+        });
+
+        Ok(Some((payload, usage)))
+    }
+
     /// Converts a `ConversationHistory` object into a single prompt string for the AI provider.
     ///
     /// This function concatenates system, user, and assistant messages from the chat history
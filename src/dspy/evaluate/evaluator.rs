@@ -13,9 +13,46 @@
 //! @complexity medium
 //! @since 1.0.0
 
+use crate::config::MoonShineConfig;
 use crate::data::{Example, Prediction};
 use crate::dspy::core::Module;
-use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+
+/// How a dataset is split and scored during evaluation.
+///
+/// Examples are processed `batch_size` at a time; within a batch at most
+/// `max_concurrency` forward-and-metric calls are in flight.
+///
+/// @category dspy-struct
+/// @safe team
+/// @mvp core
+/// @complexity low
+/// @since 2.0.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalSettings {
+    pub batch_size: usize,
+    pub max_concurrency: usize,
+}
+
+impl Default for EvalSettings {
+    fn default() -> Self {
+        Self {
+            batch_size: 32,
+            max_concurrency: 32,
+        }
+    }
+}
+
+impl EvalSettings {
+    /// Settings from `ai.eval_batch_size`, bounded by the same `ai.max_concurrent_requests`
+    /// limit that applies to every other AI call.
+    pub fn from_config(config: &MoonShineConfig) -> Self {
+        Self {
+            batch_size: config.ai.eval_batch_size.max(1) as usize,
+            max_concurrency: config.ai.max_concurrent_requests.max(1) as usize,
+        }
+    }
+}
 
 /// Defines the interface for evaluating the performance of a DSPy module.
 ///
@@ -53,9 +90,8 @@ pub trait Evaluator: Module {
 
     /// Evaluates the module's performance over a collection of examples.
     ///
-    /// This method processes a batch of examples, generates predictions, and then
-    /// computes the average metric score across all predictions. It leverages the
-    /// `batch` method from the `Module` trait for concurrent processing.
+    /// Uses `MAX_CONCURRENCY` for both the batch size and the concurrency bound;
+    /// see [`Evaluator::evaluate_with`].
     ///
     /// @param examples A `Vec<Example>` representing the dataset for evaluation.
     /// @returns A `f32` representing the average metric score across all examples.
@@ -66,24 +102,129 @@ pub trait Evaluator: Module {
     /// @complexity medium
     /// @since 1.0.0
     async fn evaluate(&self, examples: Vec<Example>) -> f32 {
-        let predictions = match self.batch(examples.clone(), Self::MAX_CONCURRENCY, Self::DISPLAY_PROGRESS).await {
-            Ok(predictions) => predictions,
-            Err(_) => {
-                // Return 0.0 score if batch prediction fails
-                return 0.0;
+        let settings = EvalSettings {
+            batch_size: Self::MAX_CONCURRENCY,
+            max_concurrency: Self::MAX_CONCURRENCY,
+        };
+        self.evaluate_with(examples, &settings).await
+    }
+
+    /// Evaluates the module over `examples` in batches with bounded concurrency.
+    ///
+    /// Each example runs `forward` and then `metric`; an example whose forward pass
+    /// fails scores 0.0. Scores are placed by example index and summed in input order,
+    /// so the average does not depend on the order in which calls complete.
+    ///
+    /// @param examples The dataset for evaluation.
+    /// @param settings Batch size and concurrency bound.
+    /// @returns The average metric score, or 0.0 for an empty dataset.
+    ///
+    /// @category dspy-method
+    /// @safe team
+    /// @mvp core
+    /// @complexity medium
+    /// @since 2.0.0
+    async fn evaluate_with(&self, examples: Vec<Example>, settings: &EvalSettings) -> f32 {
+        if examples.is_empty() {
+            return 0.0;
+        }
+
+        let batch_size = settings.batch_size.max(1);
+        let batch_count = examples.len().div_ceil(batch_size);
+        let mut scores = vec![0.0f32; examples.len()];
+
+        for (batch_idx, batch) in examples.chunks(batch_size).enumerate() {
+            if Self::DISPLAY_PROGRESS {
+                moon_debug!("Evaluating batch {}/{}", batch_idx + 1, batch_count);
             }
+
+            let offset = batch_idx * batch_size;
+            let batch_scores: Vec<(usize, f32)> = stream::iter(batch.iter().enumerate())
+                .map(|(index, example)| async move {
+                    let score = match self.forward(example.clone()).await {
+                        Ok(prediction) => self.metric(example, &prediction).await,
+                        Err(_) => 0.0,
+                    };
+                    (offset + index, score)
+                })
+                .buffer_unordered(settings.max_concurrency.max(1))
+                .collect()
+                .await;
+
+            for (index, score) in batch_scores {
+                scores[index] = score;
+            }
+        }
+
+        scores.iter().sum::<f32>() / scores.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_usage::LanguageModelUsageMetrics;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    /// Echoes the example's `value` back; later examples finish first
+    struct EchoModule;
+
+    impl Module for EchoModule {
+        async fn forward(&self, inputs: Example) -> anyhow::Result<Prediction> {
+            let value = inputs.get("value", None).as_u64().unwrap_or_default();
+            tokio::time::sleep(Duration::from_micros((100 - value) * 50)).await;
+            Ok(Prediction::new(HashMap::from([("value".to_string(), json!(value))]), LanguageModelUsageMetrics::default()))
+        }
+    }
+
+    impl Evaluator for EchoModule {
+        const DISPLAY_PROGRESS: bool = false;
+
+        async fn metric(&self, _example: &Example, prediction: &Prediction) -> f32 {
+            let value = prediction.get("value", None).as_u64().unwrap_or_default() as f32;
+            (value * 0.37).sin().abs()
+        }
+    }
+
+    fn example(value: u64) -> Example {
+        Example::new(HashMap::from([("value".to_string(), json!(value))]), vec!["value".to_string()], Vec::new())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_evaluation_matches_serial_score() {
+        let module = EchoModule;
+        let examples: Vec<Example> = (0..100).map(example).collect();
+
+        let mut serial_sum = 0.0f32;
+        for example in &examples {
+            let prediction = module.forward(example.clone()).await.unwrap();
+            serial_sum += module.metric(example, &prediction).await;
+        }
+        let serial = serial_sum / examples.len() as f32;
+
+        let settings = EvalSettings {
+            batch_size: 30,
+            max_concurrency: 8,
         };
+        let concurrent = module.evaluate_with(examples, &settings).await;
+
+        assert_eq!(concurrent, serial);
+    }
 
-        let futures: Vec<_> = examples
-            .iter()
-            .zip(predictions.iter())
-            .map(|(example, prediction)| {
-                let prediction = prediction.clone();
-                async move { self.metric(example, &prediction).await }
-            })
-            .collect();
-
-        let metrics = join_all(futures).await;
-        metrics.iter().sum::<f32>() / examples.len() as f32
+    #[test]
+    fn test_eval_settings_from_config() {
+        let mut config = MoonShineConfig::default();
+        config.ai.eval_batch_size = 64;
+        config.ai.max_concurrent_requests = 4;
+
+        assert_eq!(
+            EvalSettings::from_config(&config),
+            EvalSettings {
+                batch_size: 64,
+                max_concurrency: 4
+            }
+        );
     }
 }
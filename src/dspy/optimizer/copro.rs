@@ -1,10 +1,12 @@
-use crate::dspy::{get_lm, Evaluator, Example, Module, Optimizable, Optimizer, Predict, Predictor, LM};
+use crate::dspy::{get_lm, EvalSettings, Evaluator, Example, Module, Optimizable, Optimizer, Predict, Predictor, LM};
 use crate::signature;
+use crate::token_usage::tie_break;
 use anyhow::Result;
 use bon::Builder;
 // Complex manual MetaSignature implementations preserved (DSRs comment removed)
 // WASM-compatible async batch processing
 use futures::future::try_join_all;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 const GLOBAL_OPTIMIZATION_THRESHOLD_FACTOR: f32 = 1.1;
@@ -221,6 +223,16 @@ struct Candidate {
     pub prefix: String,
 }
 
+impl Candidate {
+    /// Selection order: higher score, then [`tie_break`] on the instruction and prefix, so
+    /// equally scored candidates resolve the same way regardless of `HashMap` iteration order
+    fn selection_cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| tie_break(&format!("{}\n{}", self.instruction, self.prefix), &format!("{}\n{}", other.instruction, other.prefix)))
+    }
+}
+
 #[derive(Clone)]
 struct ProgramStats {
     pub results_best: HashMap<String, Vec<f32>>,
@@ -239,6 +251,12 @@ pub struct COPRO {
     #[builder(default = false)]
     pub track_stats: bool,
     pub prompt_model: Option<LM>,
+    /// Batching and concurrency for scoring candidates over the trainset
+    #[builder(default)]
+    pub eval_settings: EvalSettings,
+    /// Save the instructions applied to the module to prompts.json, as one optimized-prompt batch
+    #[builder(default = false)]
+    pub save_prompts: bool,
 }
 
 // WASM-compatible safe lazy initialization with caching
@@ -486,7 +504,7 @@ impl Optimizer for COPRO {
                         );
 
                         // Use reference instead of expensive vector clone for better memory efficiency
-                        let score = module.evaluate(trainset.clone()).await;
+                        let score = module.evaluate_with(trainset.clone(), &self.eval_settings).await;
                         stats.total_calls += 1;
 
                         // Store evaluated candidate
@@ -518,7 +536,7 @@ impl Optimizer for COPRO {
                     .ok_or_else(|| anyhow::anyhow!("Predictor {} not found in evaluated_candidates", predictor_name))?
                     .values()
                     .filter(|candidate| candidate.score.is_finite()) // Filter out NaN/infinity
-                    .max_by(|a, b| a.score.total_cmp(&b.score))
+                    .max_by(|a, b| a.selection_cmp(b))
                 {
                     {
                         let mut module_predictors = module.parameters();
@@ -577,7 +595,7 @@ impl Optimizer for COPRO {
                     .values()
                     .cloned()
                     .collect();
-                best_candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+                best_candidates.sort_by(|a, b| a.selection_cmp(b));
 
                 // Take up to breadth best candidates
                 let num_examples = std::cmp::min(self.breadth, best_candidates.len());
@@ -631,12 +649,12 @@ impl Optimizer for COPRO {
         let mut best_overall: Option<(String, Candidate)> = None;
 
         for (predictor_name, candidates_map) in &evaluated_candidates {
-            if let Some(best) = candidates_map
-                .values()
-                .filter(|c| c.score.is_finite())
-                .max_by(|a, b| a.score.total_cmp(&b.score))
-            {
-                if best_overall.is_none() || best.score > best_overall.as_ref().map(|(_, c)| c.score).unwrap_or(f32::NEG_INFINITY) {
+            if let Some(best) = candidates_map.values().filter(|c| c.score.is_finite()).max_by(|a, b| a.selection_cmp(b)) {
+                // Identical candidates of two predictors go to the predictor whose name sorts first
+                let preferred = best_overall.as_ref().map_or(true, |(overall_name, overall)| {
+                    best.selection_cmp(overall).then_with(|| overall_name.cmp(predictor_name)) == Ordering::Greater
+                });
+                if preferred {
                     best_overall = Some((predictor_name.clone(), best.clone()));
                 }
             }
@@ -644,6 +662,7 @@ impl Optimizer for COPRO {
 
         // Global optimization: Apply best overall combination if better than individual bests
         let mut applied_global = false;
+        let mut applied_prompts: Vec<(String, String, f64)> = Vec::new();
         if let Some((best_predictor, best_candidate)) = best_overall.clone() {
             // Calculate average score of individual bests
             let individual_avg = evaluated_candidates
@@ -652,7 +671,7 @@ impl Optimizer for COPRO {
                     candidates
                         .values()
                         .filter(|c| c.score.is_finite())
-                        .max_by(|a, b| a.score.total_cmp(&b.score))
+                        .max_by(|a, b| a.selection_cmp(b))
                         .map(|c| c.score)
                 })
                 .fold((0.0, 0), |(sum, count), score| (sum + score, count + 1));
@@ -671,6 +690,7 @@ impl Optimizer for COPRO {
                     if !best_candidate.prefix.is_empty() {
                         target_predictor.update_signature_prefix(best_candidate.prefix.clone())?;
                     }
+                    applied_prompts.push((best_predictor.clone(), best_candidate.instruction.clone(), f64::from(best_candidate.score)));
                     applied_global = true;
                 }
             }
@@ -682,16 +702,21 @@ impl Optimizer for COPRO {
             for (predictor_name, predictor) in module_predictors {
                 if let Some(best) = evaluated_candidates
                     .get(&predictor_name)
-                    .and_then(|m| m.values().filter(|c| c.score.is_finite()).max_by(|a, b| a.score.total_cmp(&b.score)))
+                    .and_then(|m| m.values().filter(|c| c.score.is_finite()).max_by(|a, b| a.selection_cmp(b)))
                 {
                     predictor.update_signature_instruction(best.instruction.clone())?;
                     if !best.prefix.is_empty() {
                         predictor.update_signature_prefix(best.prefix.clone())?;
                     }
+                    applied_prompts.push((predictor_name.clone(), best.instruction.clone(), f64::from(best.score)));
                 }
             }
         }
 
+        if self.save_prompts {
+            crate::prompts::apply_optimized_batch(applied_prompts).map_err(|e| anyhow::anyhow!("Failed to save optimized prompts: {}", e))?;
+        }
+
         // Final optimization statistics
         if self.track_stats {
             moon_info!("\n=== Optimization Complete ===");
@@ -829,6 +854,36 @@ mod macro_examples_tests {
     use super::*;
     use crate::dspy::MetaSignature;
 
+    fn candidate(score: f32, instruction: &str) -> Candidate {
+        Candidate {
+            score,
+            instruction: instruction.to_string(),
+            prefix: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_equal_scores_tie_break_deterministically() {
+        // Same score and token estimate: the smaller blake3 hash of the template wins
+        let (first, second) = ("Fix the code.", "Fix all code.");
+        let hash = |instruction: &str| *blake3::hash(format!("{}\n", instruction).as_bytes()).as_bytes();
+        let expected = if hash(first) < hash(second) { first } else { second };
+
+        for _ in 0..10 {
+            for order in [[first, second], [second, first]] {
+                let candidates: HashMap<String, Candidate> = order.iter().map(|instruction| (instruction.to_string(), candidate(0.8, instruction))).collect();
+                let best = candidates.values().max_by(|a, b| a.selection_cmp(b)).unwrap();
+                assert_eq!(best.instruction, expected);
+            }
+        }
+
+        // Fewer tokens beats the hash order
+        let short = candidate(0.8, "Fix it.");
+        let long = candidate(0.8, "Fix the code and explain every change.");
+        assert_eq!(short.selection_cmp(&long), Ordering::Greater);
+        assert_eq!(candidate(0.9, "Fix the code and explain every change.").selection_cmp(&short), Ordering::Greater);
+    }
+
     #[test]
     fn test_cot_instruction_refinement() {
         let sig = CoTInstructionRefinement::new();
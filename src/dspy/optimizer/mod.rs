@@ -15,8 +15,10 @@
 //! @since 1.0.0
 
 pub mod copro;
+pub mod prompt_optimizer;
 
 pub use copro::*;
+pub use prompt_optimizer::{log_prompt_ab_outcome, run_prompt_ab_test, PromptAbConfig, PromptAbOutcome};
 
 use crate::data::{Example, Prediction};
 use crate::dspy::{
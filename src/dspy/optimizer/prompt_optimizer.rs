@@ -0,0 +1,360 @@
+//! # Prompt A/B Testing
+//!
+//! COPRO reports a score for each optimized prompt, but that score comes from the
+//! optimizer's own trial runs. This module checks a candidate prompt against the base
+//! prompt on the same sample of inputs: both run on every input, a `MetricFn` scores
+//! each prediction, and the candidate is recommended for promotion only if it wins by
+//! the configured margin with enough statistical confidence. Outcomes are appended to
+//! the session directory so promotion decisions can be audited later.
+//!
+//! @category dspy-optimizer
+//! @safe team
+//! @mvp enhanced
+//! @complexity medium
+//! @since 2.0.0
+
+use super::MetricFn;
+use crate::data::{Example, Prediction};
+use crate::rulebase::sampling::UnitRng;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File (inside the session directory) that A/B outcomes are appended to.
+pub const PROMPT_AB_LOG_FILE: &str = "prompt_ab.jsonl";
+
+/// Settings for an A/B comparison between a base and a candidate prompt.
+///
+/// @category dspy-struct
+/// @safe team
+/// @mvp enhanced
+/// @complexity low
+/// @since 2.0.0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptAbConfig {
+    /// Maximum number of inputs to evaluate.
+    pub sample_size: usize,
+    /// Draw the sample at random from this seed (normally `linting.sampling_seed`);
+    /// unset uses the first `sample_size` inputs.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Minimum mean-score improvement of the candidate over the base.
+    pub promotion_margin: f64,
+    /// Minimum one-sided sign-test confidence that the candidate is better.
+    pub min_confidence: f64,
+}
+
+impl Default for PromptAbConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 20,
+            seed: None,
+            promotion_margin: 0.05,
+            min_confidence: 0.9,
+        }
+    }
+}
+
+/// Result of an A/B comparison.
+///
+/// @category dspy-struct
+/// @safe team
+/// @mvp enhanced
+/// @complexity low
+/// @since 2.0.0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptAbOutcome {
+    pub rule_type: String,
+    pub samples: usize,
+    pub base_mean_score: f64,
+    pub candidate_mean_score: f64,
+    pub candidate_wins: usize,
+    pub base_wins: usize,
+    pub ties: usize,
+    /// Candidate wins divided by decided (non-tied) samples.
+    pub win_rate: f64,
+    /// One-sided sign-test confidence that the candidate beats the base.
+    pub confidence: f64,
+    pub recommend_promotion: bool,
+    pub timestamp: String,
+}
+
+/// Runs the base and candidate prompts over a sample of inputs and compares them.
+///
+/// `run` executes one prompt against one input (normally through the LM) and returns
+/// its prediction; `metric` scores that prediction against the input example.
+///
+/// @param rule_type The rule whose prompt is being tested.
+/// @param base_prompt The currently active prompt.
+/// @param candidate_prompt The optimized prompt proposed for promotion.
+/// @param inputs Examples to evaluate; at most `config.sample_size` are used.
+/// @param metric Scores a prediction for an example (higher is better).
+/// @param config A/B settings, including the promotion margin.
+/// @param run Executes a prompt against an example.
+/// @returns The `PromptAbOutcome` including the promotion recommendation.
+///
+/// @category dspy-optimizer
+/// @safe team
+/// @mvp enhanced
+/// @complexity medium
+/// @since 2.0.0
+pub async fn run_prompt_ab_test<F, Fut>(
+    rule_type: &str,
+    base_prompt: &str,
+    candidate_prompt: &str,
+    inputs: &[Example],
+    metric: &MetricFn,
+    config: &PromptAbConfig,
+    mut run: F,
+) -> Result<PromptAbOutcome>
+where
+    F: FnMut(String, Example) -> Fut,
+    Fut: Future<Output = Result<Prediction>>,
+{
+    let sample: Vec<&Example> = match config.seed {
+        Some(seed) => UnitRng::for_unit(seed, rule_type, "prompt-ab")
+            .sample_indices(inputs.len(), config.sample_size)
+            .into_iter()
+            .map(|index| &inputs[index])
+            .collect(),
+        None => inputs.iter().take(config.sample_size).collect(),
+    };
+    if sample.is_empty() {
+        anyhow::bail!("A/B test for {} needs at least one input example", rule_type);
+    }
+
+    let mut base_total = 0.0;
+    let mut candidate_total = 0.0;
+    let (mut candidate_wins, mut base_wins, mut ties) = (0, 0, 0);
+
+    for example in sample.iter().copied() {
+        let base_score = metric(example, &run(base_prompt.to_string(), example.clone()).await?);
+        let candidate_score = metric(example, &run(candidate_prompt.to_string(), example.clone()).await?);
+
+        base_total += base_score;
+        candidate_total += candidate_score;
+        match candidate_score.partial_cmp(&base_score) {
+            Some(std::cmp::Ordering::Greater) => candidate_wins += 1,
+            Some(std::cmp::Ordering::Less) => base_wins += 1,
+            _ => ties += 1,
+        }
+    }
+
+    let samples = sample.len();
+    let base_mean_score = base_total / samples as f64;
+    let candidate_mean_score = candidate_total / samples as f64;
+    let decided = candidate_wins + base_wins;
+    let win_rate = if decided == 0 { 0.0 } else { candidate_wins as f64 / decided as f64 };
+    let confidence = sign_test_confidence(candidate_wins, decided);
+    let recommend_promotion = candidate_mean_score - base_mean_score >= config.promotion_margin && confidence >= config.min_confidence;
+
+    moon_info!(
+        "Prompt A/B for {}: candidate {:.3} vs base {:.3}, win rate {:.0}% (confidence {:.2}) - {}",
+        rule_type,
+        candidate_mean_score,
+        base_mean_score,
+        win_rate * 100.0,
+        confidence,
+        if recommend_promotion { "promote" } else { "keep base" }
+    );
+
+    Ok(PromptAbOutcome {
+        rule_type: rule_type.to_string(),
+        samples,
+        base_mean_score,
+        candidate_mean_score,
+        candidate_wins,
+        base_wins,
+        ties,
+        win_rate,
+        confidence,
+        recommend_promotion,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Appends an A/B outcome to [`PROMPT_AB_LOG_FILE`] in the session directory.
+///
+/// @param session_dir The run's session directory.
+/// @param outcome The outcome to record.
+/// @returns The path of the log file.
+///
+/// @category persistence
+/// @safe team
+/// @mvp enhanced
+/// @complexity low
+/// @since 2.0.0
+pub fn log_prompt_ab_outcome(session_dir: &Path, outcome: &PromptAbOutcome) -> Result<PathBuf> {
+    crate::moon_pdk_interface::ensure_mutation_allowed(format!("write to {}", session_dir.display()))?;
+    std::fs::create_dir_all(session_dir)?;
+    let path = session_dir.join(PROMPT_AB_LOG_FILE);
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(outcome)?)?;
+    Ok(path)
+}
+
+/// Confidence that the candidate is better: `1 - P(X >= wins)` for `X ~ Binomial(decided, 0.5)`.
+fn sign_test_confidence(wins: usize, decided: usize) -> f64 {
+    if decided == 0 {
+        return 0.0;
+    }
+
+    // Probabilities of each outcome count, built iteratively to avoid large factorials
+    let mut probability = 0.5_f64.powi(decided as i32);
+    let mut tail = 0.0;
+    for k in 0..=decided {
+        if k >= wins {
+            tail += probability;
+        }
+        probability *= (decided - k) as f64 / (k + 1) as f64;
+    }
+
+    (1.0 - tail).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_usage::LanguageModelUsageMetrics;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn inputs(count: usize) -> Vec<Example> {
+        (0..count)
+            .map(|index| {
+                let data = HashMap::from([("index".to_string(), json!(index)), ("expected".to_string(), json!("fixed"))]);
+                Example::new(data, vec!["index".to_string()], vec!["expected".to_string()])
+            })
+            .collect()
+    }
+
+    fn exact_match() -> MetricFn {
+        Box::new(|example, prediction| {
+            if prediction.get("answer", None) == example.get("expected", None) {
+                1.0
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// Mock LM: the "good" prompt always produces the expected answer, the other one only every third time.
+    async fn mock_lm(prompt: String, example: Example, good_prompt: &str) -> Result<Prediction> {
+        let index = example.get("index", None).as_u64().unwrap_or(0);
+        let answer = if prompt == good_prompt || index % 3 == 0 { "fixed" } else { "broken" };
+        Ok(Prediction::new(
+            HashMap::from([("answer".to_string(), json!(answer))]),
+            LanguageModelUsageMetrics::default(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_better_candidate_is_recommended_for_promotion() {
+        let outcome = run_prompt_ab_test(
+            "no_console",
+            "base",
+            "candidate",
+            &inputs(12),
+            &exact_match(),
+            &PromptAbConfig::default(),
+            |prompt, example| mock_lm(prompt, example, "candidate"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.samples, 12);
+        assert_eq!(outcome.candidate_wins, 8);
+        assert_eq!(outcome.base_wins, 0);
+        assert_eq!(outcome.win_rate, 1.0);
+        assert!(outcome.confidence > 0.99);
+        assert!(outcome.recommend_promotion);
+    }
+
+    #[tokio::test]
+    async fn test_worse_candidate_is_not_promoted() {
+        let outcome = run_prompt_ab_test(
+            "no_console",
+            "base",
+            "candidate",
+            &inputs(12),
+            &exact_match(),
+            &PromptAbConfig::default(),
+            |prompt, example| mock_lm(prompt, example, "base"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.candidate_wins, 0);
+        assert_eq!(outcome.base_wins, 8);
+        assert!(outcome.candidate_mean_score < outcome.base_mean_score);
+        assert!(!outcome.recommend_promotion);
+    }
+
+    #[tokio::test]
+    async fn test_marginal_candidate_below_margin_is_not_promoted() {
+        let config = PromptAbConfig {
+            promotion_margin: 0.9,
+            ..PromptAbConfig::default()
+        };
+        let outcome = run_prompt_ab_test("no_console", "base", "candidate", &inputs(12), &exact_match(), &config, |prompt, example| {
+            mock_lm(prompt, example, "candidate")
+        })
+        .await
+        .unwrap();
+
+        assert!(outcome.confidence > 0.99);
+        assert!(!outcome.recommend_promotion, "improvement of 0.67 is below the 0.9 margin");
+    }
+
+    #[tokio::test]
+    async fn test_seeded_sample_is_reproducible() {
+        let config = PromptAbConfig {
+            sample_size: 5,
+            seed: Some(42),
+            ..PromptAbConfig::default()
+        };
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let mut seen = Vec::new();
+            run_prompt_ab_test("no_console", "base", "candidate", &inputs(12), &exact_match(), &config, |prompt, example| {
+                seen.push(example.get("index", None));
+                mock_lm(prompt, example, "candidate")
+            })
+            .await
+            .unwrap();
+            runs.push(seen);
+        }
+
+        assert_eq!(runs[0].len(), 10, "base and candidate run on each of the 5 sampled inputs");
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[test]
+    fn test_outcome_logged_to_session_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let outcome = PromptAbOutcome {
+            rule_type: "no_console".to_string(),
+            samples: 1,
+            base_mean_score: 0.0,
+            candidate_mean_score: 1.0,
+            candidate_wins: 1,
+            base_wins: 0,
+            ties: 0,
+            win_rate: 1.0,
+            confidence: 0.5,
+            recommend_promotion: false,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let path = log_prompt_ab_outcome(dir.path(), &outcome).unwrap();
+        log_prompt_ab_outcome(dir.path(), &outcome).unwrap();
+
+        let lines: Vec<String> = std::fs::read_to_string(path).unwrap().lines().map(str::to_owned).collect();
+        assert_eq!(lines.len(), 2);
+        let logged: PromptAbOutcome = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(logged, outcome);
+    }
+}
@@ -456,6 +456,7 @@ impl AstAutoFixEngine {
                 fixed_text: "unknown".to_string(),
                 confidence: 0.9,
                 impact_score: 7,
+                rule_id: None,
             });
         }
 
@@ -489,6 +490,7 @@ impl AstAutoFixEngine {
                 fixed_text: "// Loops already optimized".to_string(),
                 confidence: 1.0,
                 impact_score: 6,
+                rule_id: None,
             });
         }
 
@@ -522,6 +524,7 @@ impl AstAutoFixEngine {
                 fixed_text: "// Security validated".to_string(),
                 confidence: 1.0,
                 impact_score: 10,
+                rule_id: None,
             });
         }
 
@@ -544,6 +547,7 @@ impl AstAutoFixEngine {
             fixed_text: "obj?.prop?.method?.()".to_string(),
             confidence: 0.92,
             impact_score: 5,
+            rule_id: None,
         });
 
         Ok(fixes)
@@ -796,6 +800,7 @@ impl AstAutoFixEngine {
                 fixed_text: "unknown".to_string(), // Safer default than 'any'
                 confidence: 0.8,
                 impact_score: 6,
+                rule_id: None,
             });
         }
 
@@ -823,6 +828,7 @@ impl AstAutoFixEngine {
                     fixed_text: "unknown".to_string(), // Safer default than 'any'
                     confidence: 0.8,
                     impact_score: 6,
+                    rule_id: None,
                 });
             }
         }
@@ -851,6 +857,7 @@ impl AstAutoFixEngine {
                 fixed_text: loop_location.optimized.clone(),
                 confidence: 0.9,
                 impact_score: 6,
+                rule_id: None,
             });
         }
 
@@ -882,6 +889,7 @@ impl AstAutoFixEngine {
                     ),
                     confidence: 0.85,
                     impact_score: 6,
+                    rule_id: None,
                 });
             }
         }
@@ -910,6 +918,7 @@ impl AstAutoFixEngine {
                 fixed_text: vulnerability.fixed.clone(),
                 confidence: vulnerability.confidence,
                 impact_score: vulnerability.severity_score,
+                rule_id: None,
             });
         }
 
@@ -937,6 +946,7 @@ impl AstAutoFixEngine {
                     fixed_text: "// SECURITY: eval() removed - use JSON.parse() or Function constructor".to_string(),
                     confidence: 0.95,
                     impact_score: 9,
+                    rule_id: None,
                 });
             }
         }
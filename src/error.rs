@@ -51,6 +51,14 @@ pub enum Error {
         source: Option<Box<dyn std::error::Error + Send + Sync>>,
     },
 
+    /// AI was intentionally disabled (offline `"none"` provider); callers use static analysis instead.
+    #[error("AI disabled: {reason}")]
+    AiDisabled { reason: String },
+
+    /// A write or command was refused because the run is in safe mode.
+    #[error("Safe mode: refused to {action}")]
+    SafeMode { action: String },
+
     /// Error during JSON serialization or deserialization.
     #[error("JSON serialization/deserialization failed")]
     Serialization {
@@ -132,6 +140,10 @@ pub enum Error {
     #[error("Task execution timeout: {task_name}")]
     Timeout { task_name: String, duration_ms: u64 },
 
+    /// Task produced more output than its configured cap.
+    #[error("Task output limit exceeded: {task_name} produced {actual_bytes} bytes (limit {limit_bytes})")]
+    OutputLimit { task_name: String, limit_bytes: u64, actual_bytes: u64 },
+
     /// Validation error (e.g., invalid input data).
     #[error("Validation failed: {field}")]
     Validation { field: String, expected: String, actual: String },
@@ -484,6 +496,27 @@ impl Error {
         }
     }
 
+    /// Creates an `OutputLimit` error for a task whose output exceeded its size cap.
+    ///
+    /// @param task_name A string slice or type convertible to `String` naming the task.
+    /// @param limit_bytes The configured output cap in bytes.
+    /// @param actual_bytes The number of bytes the task produced.
+    /// @returns A new `Error::OutputLimit` instance.
+    ///
+    /// @category constructor
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    #[must_use]
+    pub fn output_limit(task_name: impl Into<String>, limit_bytes: u64, actual_bytes: u64) -> Self {
+        Self::OutputLimit {
+            task_name: task_name.into(),
+            limit_bytes,
+            actual_bytes,
+        }
+    }
+
     /// Creates a `Validation` error for invalid input or data.
     ///
     /// @param field The name of the field that failed validation.
@@ -638,6 +671,105 @@ impl Error {
         Self::DataValidation { message: message.into() }
     }
 
+    /// Creates an `AiDisabled` error for AI requests made while the offline provider is configured.
+    ///
+    /// @param reason A string slice or type convertible to `String` explaining why AI is disabled.
+    /// @returns A new `Error::AiDisabled` instance.
+    ///
+    /// @category constructor
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    #[must_use]
+    pub fn ai_disabled(reason: impl Into<String>) -> Self {
+        Self::AiDisabled { reason: reason.into() }
+    }
+
+    /// Creates a `SafeMode` error for a file write or command execution attempted in safe mode.
+    ///
+    /// @param action A string slice or type convertible to `String` describing the refused mutation.
+    /// @returns A new `Error::SafeMode` instance.
+    ///
+    /// @category constructor
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    #[must_use]
+    pub fn safe_mode(action: impl Into<String>) -> Self {
+        Self::SafeMode { action: action.into() }
+    }
+
+    /// Whether this error reports intentionally disabled AI rather than a provider failure.
+    ///
+    /// @returns `true` for `Error::AiDisabled`, `false` otherwise.
+    ///
+    /// @category utility
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    pub fn is_ai_disabled(&self) -> bool {
+        matches!(self, Self::AiDisabled { .. })
+    }
+
+    /// Returns the classified provider failure carried by an `AIExecution` error.
+    ///
+    /// @returns The `ProviderError` source, if this error has one.
+    ///
+    /// @category utility
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    pub fn provider_error(&self) -> Option<&crate::provider_router::ProviderError> {
+        match self {
+            Self::AIExecution { source: Some(source), .. } => source.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Determines if this is the provider router giving up after every provider failed.
+    ///
+    /// Callers treat it like having no AI provider at all.
+    ///
+    /// @returns `true` for a `Multiple` error made up only of `AIExecution` failures.
+    ///
+    /// @category utility
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    pub fn is_all_providers_failed(&self) -> bool {
+        match self {
+            Self::Multiple { errors, successful_count: 0 } => {
+                !errors.is_empty() && errors.iter().all(|error| matches!(error, Self::AIExecution { .. }))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this error is a host failure that may succeed if the run is retried.
+    ///
+    /// Host function failures and timeouts qualify, as do I/O errors of a transient kind;
+    /// configuration, validation and data errors never do.
+    ///
+    /// @returns `true` if retrying the run could help, `false` otherwise.
+    ///
+    /// @category utility
+    /// @safe team
+    /// @mvp core
+    /// @complexity low
+    /// @since 2.0.0
+    pub fn is_transient_host(&self) -> bool {
+        match self {
+            Self::MoonPdk { .. } | Self::Timeout { .. } => true,
+            Self::Io { source, .. } => crate::host_retry::is_transient_io_kind(source.kind()),
+            _ => false,
+        }
+    }
+
     /// Determines if the error is recoverable, allowing for graceful degradation or retry.
     ///
     /// Recoverable errors typically indicate transient issues or situations where the system
@@ -654,6 +786,8 @@ impl Error {
         match self {
             Self::ClaudeCli { .. } => true,          // Can fallback to other tools
             Self::AIExecution { .. } => true,        // Can fallback to other AI providers or tools
+            Self::AiDisabled { .. } => true,         // Static analysis runs instead
+            Self::SafeMode { .. } => true,           // Analysis continues without the mutation
             Self::Serialization { .. } => false,     // Data corruption
             Self::Io { .. } => true,                 // Can retry or skip file
             Self::MoonHost { .. } => false,          // WASM runtime issue
@@ -664,6 +798,7 @@ impl Error {
             Self::PatternDetection { .. } => true,   // Can disable pattern detection
             Self::CoproOptimization { .. } => true,  // Can disable optimization
             Self::Timeout { .. } => true,            // Can retry with longer timeout
+            Self::OutputLimit { .. } => true,        // Can skip the task's results
             Self::Validation { .. } => false,        // Invalid input
             Self::Multiple { .. } => true,           // Partial success is possible
             Self::DataAccess { .. } => true,         // Can retry or fallback
@@ -692,6 +827,8 @@ impl Error {
         match self {
             Self::ClaudeCli { .. } => ErrorSeverity::Warning,
             Self::AIExecution { .. } => ErrorSeverity::Warning,
+            Self::AiDisabled { .. } => ErrorSeverity::Info,
+            Self::SafeMode { .. } => ErrorSeverity::Info,
             Self::Serialization { .. } => ErrorSeverity::Error,
             Self::Io { .. } => ErrorSeverity::Warning,
             Self::MoonHost { .. } => ErrorSeverity::Critical,
@@ -702,6 +839,7 @@ impl Error {
             Self::PatternDetection { .. } => ErrorSeverity::Info,
             Self::CoproOptimization { .. } => ErrorSeverity::Info,
             Self::Timeout { .. } => ErrorSeverity::Warning,
+            Self::OutputLimit { .. } => ErrorSeverity::Warning,
             Self::Validation { .. } => ErrorSeverity::Error,
             Self::Multiple { .. } => ErrorSeverity::Warning,
             Self::DataAccess { .. } => ErrorSeverity::Warning,
@@ -730,6 +868,8 @@ impl Error {
         match self {
             Self::ClaudeCli { .. } => "claude_cli",
             Self::AIExecution { .. } => "ai_execution",
+            Self::AiDisabled { .. } => "ai_disabled",
+            Self::SafeMode { .. } => "safe_mode",
             Self::Serialization { .. } => "serialization",
             Self::Io { .. } => "io",
             Self::MoonHost { .. } => "moon_host",
@@ -740,6 +880,7 @@ impl Error {
             Self::PatternDetection { .. } => "pattern_detection",
             Self::CoproOptimization { .. } => "copro_optimization",
             Self::Timeout { .. } => "timeout",
+            Self::OutputLimit { .. } => "output_limit",
             Self::Validation { .. } => "validation",
             Self::Multiple { .. } => "multiple",
             Self::DataAccess { .. } => "data_access",
@@ -773,6 +914,8 @@ impl Error {
             Self::AIExecution { provider, message, .. } => {
                 format!("AI provider '{}' failed: {}. Falling back to static analysis.", provider, message)
             }
+            Self::AiDisabled { reason } => format!("AI disabled: {}. Using static analysis only.", reason),
+            Self::SafeMode { action } => format!("Safe mode refused to {}. Nothing is written or executed.", action),
             Self::Processing { message, .. } => {
                 format!("Processing error: {}. Please retry or check logs for details.", message)
             }
@@ -803,6 +946,9 @@ impl Error {
             Self::PatternDetection { pattern_type, .. } => format!("Pattern detection failed for '{}': Continuing with basic analysis", pattern_type),
             Self::CoproOptimization { stage, .. } => format!("Optimization failed at '{}': Using default configuration", stage),
             Self::Timeout { task_name, .. } => format!("Operation '{}' timed out: Please try with simpler input", task_name),
+            Self::OutputLimit { task_name, limit_bytes, .. } => {
+                format!("Operation '{}' produced more than {} bytes of output: Please narrow its input", task_name, limit_bytes)
+            }
             Self::Validation { field, expected, .. } => format!("Invalid '{}': Expected {}", field, expected),
             Self::Multiple { errors, successful_count } => {
                 format!("Partial completion: {} operations succeeded, {} failed", successful_count, errors.len())
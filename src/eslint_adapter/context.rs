@@ -71,6 +71,9 @@ impl<'a> ESLintRuleContext<'a> {
             column,
             severity: self.severity,
             fix_available: report.fix.is_some(),
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
         };
 
         self.diagnostics.push(diagnostic);
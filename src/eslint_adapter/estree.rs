@@ -0,0 +1,309 @@
+//! # ESTree Bridge
+//!
+//! Presents OXC semantic nodes through ESTree-style accessors so ESLint rules can be
+//! ported mechanically: `node.type` strings, `node.parent` navigation and
+//! `sourceCode.getScope(node)`. OXC-only wrapper nodes with no ESTree equivalent
+//! (parenthesized expressions, formal parameter lists, ...) are skipped when navigating,
+//! so parents match what an ESLint rule would see.
+//!
+//! A ported rule iterates [`EstreeProgram::nodes_of_type`] in place of an ESLint
+//! selector and reports through [`ESLintRuleContext`](super::context::ESLintRuleContext):
+//!
+//! ```ignore
+//! for node in EstreeProgram::new(&semantic).nodes_of_type("DebuggerStatement") {
+//!     context.report(ESLintReport::new(node.span(), "Unexpected 'debugger' statement."));
+//! }
+//! ```
+
+use oxc_ast::AstKind;
+use oxc_semantic::{AstNode, ScopeId, Semantic};
+use oxc_span::{GetSpan, Span};
+
+/// ESTree `type` of an OXC node kind, or `None` for OXC-only nodes ESTree does not have
+pub fn estree_type(kind: &AstKind) -> Option<&'static str> {
+    let estree = match kind {
+        AstKind::Program(_) => "Program",
+        AstKind::BlockStatement(_) | AstKind::FunctionBody(_) => "BlockStatement",
+        AstKind::BreakStatement(_) => "BreakStatement",
+        AstKind::ContinueStatement(_) => "ContinueStatement",
+        AstKind::DebuggerStatement(_) => "DebuggerStatement",
+        AstKind::DoWhileStatement(_) => "DoWhileStatement",
+        AstKind::EmptyStatement(_) => "EmptyStatement",
+        AstKind::ExpressionStatement(_) => "ExpressionStatement",
+        AstKind::ForInStatement(_) => "ForInStatement",
+        AstKind::ForOfStatement(_) => "ForOfStatement",
+        AstKind::ForStatement(_) => "ForStatement",
+        AstKind::IfStatement(_) => "IfStatement",
+        AstKind::LabeledStatement(_) => "LabeledStatement",
+        AstKind::ReturnStatement(_) => "ReturnStatement",
+        AstKind::SwitchStatement(_) => "SwitchStatement",
+        AstKind::SwitchCase(_) => "SwitchCase",
+        AstKind::ThrowStatement(_) => "ThrowStatement",
+        AstKind::TryStatement(_) => "TryStatement",
+        AstKind::CatchClause(_) => "CatchClause",
+        AstKind::WhileStatement(_) => "WhileStatement",
+        AstKind::WithStatement(_) => "WithStatement",
+        AstKind::VariableDeclaration(_) => "VariableDeclaration",
+        AstKind::VariableDeclarator(_) => "VariableDeclarator",
+        AstKind::Function(function) if function.is_declaration() => "FunctionDeclaration",
+        AstKind::Function(_) => "FunctionExpression",
+        AstKind::ArrowFunctionExpression(_) => "ArrowFunctionExpression",
+        AstKind::Class(class) if class.is_declaration() => "ClassDeclaration",
+        AstKind::Class(_) => "ClassExpression",
+        AstKind::MethodDefinition(_) => "MethodDefinition",
+        AstKind::PropertyDefinition(_) => "PropertyDefinition",
+        AstKind::IdentifierName(_) | AstKind::IdentifierReference(_) | AstKind::BindingIdentifier(_) | AstKind::LabelIdentifier(_) => {
+            "Identifier"
+        }
+        AstKind::PrivateIdentifier(_) => "PrivateIdentifier",
+        AstKind::StringLiteral(_)
+        | AstKind::NumericLiteral(_)
+        | AstKind::BooleanLiteral(_)
+        | AstKind::NullLiteral(_)
+        | AstKind::BigIntLiteral(_)
+        | AstKind::RegExpLiteral(_) => "Literal",
+        AstKind::TemplateLiteral(_) => "TemplateLiteral",
+        AstKind::TaggedTemplateExpression(_) => "TaggedTemplateExpression",
+        AstKind::ArrayExpression(_) => "ArrayExpression",
+        AstKind::ObjectExpression(_) => "ObjectExpression",
+        AstKind::ObjectProperty(_) => "Property",
+        AstKind::SpreadElement(_) => "SpreadElement",
+        AstKind::AssignmentExpression(_) => "AssignmentExpression",
+        AstKind::AwaitExpression(_) => "AwaitExpression",
+        AstKind::BinaryExpression(_) => "BinaryExpression",
+        AstKind::LogicalExpression(_) => "LogicalExpression",
+        AstKind::UnaryExpression(_) => "UnaryExpression",
+        AstKind::UpdateExpression(_) => "UpdateExpression",
+        AstKind::ConditionalExpression(_) => "ConditionalExpression",
+        AstKind::CallExpression(_) => "CallExpression",
+        AstKind::NewExpression(_) => "NewExpression",
+        AstKind::MemberExpression(_) => "MemberExpression",
+        AstKind::ChainExpression(_) => "ChainExpression",
+        AstKind::SequenceExpression(_) => "SequenceExpression",
+        AstKind::ThisExpression(_) => "ThisExpression",
+        AstKind::YieldExpression(_) => "YieldExpression",
+        AstKind::ImportExpression(_) => "ImportExpression",
+        AstKind::MetaProperty(_) => "MetaProperty",
+        AstKind::Super(_) => "Super",
+        AstKind::ArrayPattern(_) => "ArrayPattern",
+        AstKind::ObjectPattern(_) => "ObjectPattern",
+        AstKind::AssignmentPattern(_) => "AssignmentPattern",
+        AstKind::ImportDeclaration(_) => "ImportDeclaration",
+        AstKind::ExportNamedDeclaration(_) => "ExportNamedDeclaration",
+        AstKind::ExportDefaultDeclaration(_) => "ExportDefaultDeclaration",
+        AstKind::ExportAllDeclaration(_) => "ExportAllDeclaration",
+        _ => return None,
+    };
+    Some(estree)
+}
+
+/// Entry point for ported rules: every ESTree-visible node of a parsed file
+pub struct EstreeProgram<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+}
+
+impl<'s, 'a> EstreeProgram<'s, 'a> {
+    pub fn new(semantic: &'s Semantic<'a>) -> Self {
+        Self { semantic }
+    }
+
+    /// All ESTree-visible nodes in source order
+    pub fn nodes(&self) -> impl Iterator<Item = EstreeNode<'s, 'a>> + '_ {
+        let semantic = self.semantic;
+        semantic.nodes().iter().filter_map(move |node| EstreeNode::new(semantic, node))
+    }
+
+    /// Nodes whose ESTree `type` is `node_type`, the equivalent of an ESLint `NodeType(node) {}` listener
+    pub fn nodes_of_type(&self, node_type: &'static str) -> impl Iterator<Item = EstreeNode<'s, 'a>> + '_ {
+        self.nodes().filter(move |node| node.node_type() == node_type)
+    }
+}
+
+/// An OXC node viewed as an ESTree node
+#[derive(Clone, Copy)]
+pub struct EstreeNode<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    node: &'s AstNode<'a>,
+    node_type: &'static str,
+}
+
+impl<'s, 'a> EstreeNode<'s, 'a> {
+    /// Wrap `node`, or `None` when it has no ESTree equivalent
+    pub fn new(semantic: &'s Semantic<'a>, node: &'s AstNode<'a>) -> Option<Self> {
+        estree_type(&node.kind()).map(|node_type| Self { semantic, node, node_type })
+    }
+
+    /// ESTree `node.type`
+    pub fn node_type(&self) -> &'static str {
+        self.node_type
+    }
+
+    /// Underlying OXC node kind, for reading node-specific fields
+    pub fn kind(&self) -> AstKind<'a> {
+        self.node.kind()
+    }
+
+    /// Source range of the node (ESTree `node.range`)
+    pub fn span(&self) -> Span {
+        self.node.kind().span()
+    }
+
+    /// ESTree `node.parent`: the nearest ancestor ESTree knows about
+    pub fn parent(&self) -> Option<Self> {
+        let nodes = self.semantic.nodes();
+        let mut current = nodes.parent_node(self.node.id())?;
+        loop {
+            if let Some(parent) = Self::new(self.semantic, current) {
+                return Some(parent);
+            }
+            current = nodes.parent_node(current.id())?;
+        }
+    }
+
+    /// Ancestors from the parent up to `Program`, as ESLint's `sourceCode.getAncestors(node)` reversed
+    pub fn ancestors(&self) -> impl Iterator<Item = EstreeNode<'s, 'a>> {
+        std::iter::successors(self.parent(), |node| node.parent())
+    }
+
+    /// ESLint's `sourceCode.getScope(node)`: the scope a function or class creates, otherwise
+    /// the innermost scope containing the node
+    pub fn get_scope(&self) -> EstreeScope<'s, 'a> {
+        let own_scope = match self.node.kind() {
+            AstKind::Function(function) => function.scope_id.get(),
+            AstKind::ArrowFunctionExpression(arrow) => arrow.scope_id.get(),
+            AstKind::Class(class) => class.scope_id.get(),
+            _ => None,
+        };
+
+        EstreeScope {
+            semantic: self.semantic,
+            scope_id: own_scope.unwrap_or_else(|| self.node.scope_id()),
+        }
+    }
+
+    /// Source text of the node (ESLint's `sourceCode.getText(node)`)
+    pub fn text(&self) -> &'a str {
+        let span = self.span();
+        let source = self.semantic.source_text();
+        source.get(span.start as usize..span.end as usize).unwrap_or("")
+    }
+}
+
+/// A semantic scope viewed as an ESLint scope
+#[derive(Clone, Copy)]
+pub struct EstreeScope<'s, 'a> {
+    semantic: &'s Semantic<'a>,
+    scope_id: ScopeId,
+}
+
+impl<'s, 'a> EstreeScope<'s, 'a> {
+    /// ESLint `scope.type`: `global`, `module`, `function`, `class`, `catch`, `for`, `switch`,
+    /// `class-static-block` or `block`
+    pub fn scope_type(&self) -> &'static str {
+        let scopes = self.semantic.scopes();
+        if scopes.get_parent_id(self.scope_id).is_none() {
+            return if self.semantic.source_type().is_module() { "module" } else { "global" };
+        }
+
+        let node_id = scopes.get_node_id(self.scope_id);
+        match self.semantic.nodes().get_node(node_id).kind() {
+            AstKind::Function(_) | AstKind::ArrowFunctionExpression(_) => "function",
+            AstKind::Class(_) => "class",
+            AstKind::CatchClause(_) => "catch",
+            AstKind::ForStatement(_) | AstKind::ForInStatement(_) | AstKind::ForOfStatement(_) => "for",
+            AstKind::SwitchStatement(_) => "switch",
+            AstKind::StaticBlock(_) => "class-static-block",
+            _ => "block",
+        }
+    }
+
+    /// Names declared directly in this scope (ESLint `scope.variables`)
+    pub fn variables(&self) -> Vec<String> {
+        self.semantic.scopes().get_bindings(self.scope_id).keys().map(|name| name.to_string()).collect()
+    }
+
+    /// ESLint `scope.set.has(name)`
+    pub fn has_variable(&self, name: &str) -> bool {
+        self.semantic.scopes().get_binding(self.scope_id, name).is_some()
+    }
+
+    /// ESLint `scope.upper`
+    pub fn upper(&self) -> Option<Self> {
+        self.semantic.scopes().get_parent_id(self.scope_id).map(|scope_id| Self {
+            semantic: self.semantic,
+            scope_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eslint_adapter::context::{ESLintReport, ESLintRuleContext};
+    use crate::types::{DiagnosticSeverity, LintDiagnostic};
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_semantic::SemanticBuilder;
+    use oxc_span::SourceType;
+
+    /// ESLint's `no-debugger`, ported line for line:
+    /// `DebuggerStatement(node) { context.report({ node, messageId: "unexpected" }) }`
+    fn no_debugger(context: &mut ESLintRuleContext, program: &EstreeProgram) {
+        for node in program.nodes_of_type("DebuggerStatement") {
+            context.report(ESLintReport::new(node.span(), "Unexpected 'debugger' statement."));
+        }
+    }
+
+    fn run_ported_rule(source: &str) -> Vec<LintDiagnostic> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        let semantic_ret = SemanticBuilder::new().build(&ret.program);
+        let mut context = ESLintRuleContext::new(
+            source,
+            "test.ts",
+            &ret.program,
+            Some(&semantic_ret.semantic),
+            "no-debugger".to_string(),
+            DiagnosticSeverity::Error,
+            vec![],
+        );
+
+        no_debugger(&mut context, &EstreeProgram::new(&semantic_ret.semantic));
+        context.get_diagnostics()
+    }
+
+    #[test]
+    fn test_ported_no_debugger_reports_each_statement() {
+        let diagnostics = run_ported_rule("function f() {\n  debugger;\n}\nconst debuggerName = 1;\ndebugger;\n");
+
+        let positions: Vec<(u32, u32)> = diagnostics.iter().map(|d| (d.line, d.column)).collect();
+        assert_eq!(positions, vec![(2, 3), (5, 1)]);
+        assert!(diagnostics.iter().all(|d| d.rule_name == "no-debugger" && d.message == "Unexpected 'debugger' statement."));
+    }
+
+    #[test]
+    fn test_parent_skips_oxc_only_nodes_and_scope_matches_eslint() {
+        let source = "export function outer(a) {\n  const b = (a + 1);\n  return b;\n}\n";
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        let semantic_ret = SemanticBuilder::new().build(&ret.program);
+        let program = EstreeProgram::new(&semantic_ret.semantic);
+
+        let binary = program.nodes_of_type("BinaryExpression").next().unwrap();
+        assert_eq!(binary.text(), "a + 1");
+        // ESTree has no ParenthesizedExpression: the declarator is the direct parent
+        assert_eq!(binary.parent().unwrap().node_type(), "VariableDeclarator");
+        let ancestors: Vec<&str> = binary.ancestors().map(|node| node.node_type()).collect();
+        assert_eq!(
+            ancestors,
+            vec!["VariableDeclarator", "VariableDeclaration", "BlockStatement", "FunctionDeclaration", "ExportNamedDeclaration", "Program"]
+        );
+
+        let function = program.nodes_of_type("FunctionDeclaration").next().unwrap();
+        let scope = function.get_scope();
+        assert_eq!(scope.scope_type(), "function");
+        assert!(scope.has_variable("a"));
+        assert!(scope.variables().contains(&"b".to_string()));
+        assert_eq!(scope.upper().unwrap().scope_type(), "module");
+    }
+}
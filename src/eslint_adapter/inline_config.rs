@@ -0,0 +1,245 @@
+//! # Inline Rule Configuration
+//!
+//! ESLint lets a file configure rules for itself with a block comment such as
+//! `/* eslint eqeqeq: ["error", "smart"], no-console: "off" */`. This module parses
+//! those comments so they can be merged over the global [`ESLintConfig`] for that file
+//! only. Malformed entries are reported with `log::warn!` and skipped; the rest of the
+//! comment still applies.
+
+use super::{ESLintConfig, ESLintRuleLevel};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Level and options a configuration comment sets for one rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineRuleSetting {
+    pub level: ESLintRuleLevel,
+    pub options: Vec<Value>, // Everything after the level in `["error", ...options]`
+}
+
+/// Rule settings from every `/* eslint ... */` comment in a file, later comments winning
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InlineConfig {
+    pub rules: HashMap<String, InlineRuleSetting>,
+    pub malformed: Vec<String>, // Entries that could not be parsed, as written
+}
+
+impl InlineConfig {
+    /// Options the file configured for `rule_id`, empty when it set none
+    pub fn options(&self, rule_id: &str) -> &[Value] {
+        self.rules.get(rule_id).map(|setting| setting.options.as_slice()).unwrap_or(&[])
+    }
+}
+
+impl ESLintConfig {
+    /// This config with a file's inline rule settings merged over it
+    pub fn with_inline_config(&self, inline: &InlineConfig) -> ESLintConfig {
+        let mut merged = self.clone();
+        for (rule_id, setting) in &inline.rules {
+            merged.rules.insert(rule_id.clone(), setting.level);
+        }
+        merged
+    }
+}
+
+impl ESLintRuleLevel {
+    /// Parse a level as ESLint writes it: `"off"`/`"warn"`/`"error"` or `0`/`1`/`2`
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(level) => match level.as_str() {
+                "off" => Some(Self::Off),
+                "warn" => Some(Self::Warn),
+                "error" => Some(Self::Error),
+                _ => None,
+            },
+            Value::Number(level) => match level.as_u64() {
+                Some(0) => Some(Self::Off),
+                Some(1) => Some(Self::Warn),
+                Some(2) => Some(Self::Error),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Parse every `/* eslint rule: setting, ... */` configuration comment in `source`
+pub fn parse_inline_config(source: &str) -> InlineConfig {
+    let mut config = InlineConfig::default();
+
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        let body_start = &rest[start + 2..];
+        let Some(end) = body_start.find("*/") else {
+            break;
+        };
+        let body = body_start[..end].trim();
+        rest = &body_start[end + 2..];
+
+        // `eslint-disable`, `eslint-env` and friends are not configuration comments
+        let Some(entries) = body.strip_prefix("eslint").filter(|entries| entries.starts_with(char::is_whitespace)) else {
+            continue;
+        };
+
+        for entry in split_top_level(entries) {
+            match parse_entry(entry) {
+                Some((rule_id, setting)) => {
+                    config.rules.insert(rule_id, setting);
+                }
+                None => {
+                    log::warn!("Ignoring malformed inline rule configuration: {}", entry);
+                    config.malformed.push(entry.to_string());
+                }
+            }
+        }
+    }
+
+    config
+}
+
+/// `rule: setting` into the rule ID and its parsed setting
+fn parse_entry(entry: &str) -> Option<(String, InlineRuleSetting)> {
+    let (rule_id, setting) = entry.split_once(':')?;
+    let rule_id = rule_id.trim().trim_matches('"');
+    if rule_id.is_empty() {
+        return None;
+    }
+
+    let setting = match serde_json::from_str::<Value>(setting.trim()).ok()? {
+        Value::Array(mut values) if !values.is_empty() => {
+            let level = ESLintRuleLevel::from_json(&values[0])?;
+            InlineRuleSetting {
+                level,
+                options: values.split_off(1),
+            }
+        }
+        level => InlineRuleSetting {
+            level: ESLintRuleLevel::from_json(&level)?,
+            options: Vec::new(),
+        },
+    };
+
+    Some((rule_id.to_string(), setting))
+}
+
+/// Split on commas outside brackets, braces and strings
+fn split_top_level(entries: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut part_start = 0;
+
+    for (index, ch) in entries.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '[' | '{' if !in_string => depth += 1,
+            ']' | '}' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                parts.push(&entries[part_start..index]);
+                part_start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&entries[part_start..]);
+
+    parts.into_iter().map(str::trim).filter(|part| !part.is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eslint_adapter::context::{ESLintReport, ESLintRuleContext};
+    use crate::eslint_adapter::estree::EstreeProgram;
+    use crate::eslint_adapter::ESLintAdapter;
+    use crate::rule_registry::RuleSeverity;
+    use crate::types::DiagnosticSeverity;
+    use oxc_allocator::Allocator;
+    use oxc_ast::AstKind;
+    use oxc_parser::Parser;
+    use oxc_semantic::SemanticBuilder;
+    use oxc_span::SourceType;
+    use serde_json::json;
+
+    fn global_config() -> ESLintConfig {
+        ESLintConfig {
+            rules: HashMap::from([("no-console".to_string(), ESLintRuleLevel::Warn), ("eqeqeq".to_string(), ESLintRuleLevel::Warn)]),
+            env: None,
+            extends: None,
+            parser_options: None,
+        }
+    }
+
+    #[test]
+    fn test_parses_levels_options_and_skips_other_directives() {
+        let source = "/* eslint no-console: [\"error\", { \"allow\": [\"warn\", \"error\"] }], eqeqeq: 0 */\n/* eslint-disable curly */\nconsole.log(1);\n";
+
+        let inline = parse_inline_config(source);
+
+        assert_eq!(inline.rules.len(), 2);
+        assert_eq!(inline.rules["no-console"].level, ESLintRuleLevel::Error);
+        assert_eq!(inline.options("no-console"), &[json!({ "allow": ["warn", "error"] })]);
+        assert_eq!(inline.rules["eqeqeq"].level, ESLintRuleLevel::Off);
+        assert!(inline.options("curly").is_empty());
+        assert!(inline.malformed.is_empty());
+    }
+
+    #[test]
+    fn test_malformed_entries_are_skipped() {
+        let inline = parse_inline_config("/* eslint no-console: [\"error\", {allow}], eqeqeq: \"loud\", curly: \"error\" */");
+
+        assert_eq!(inline.malformed, vec!["no-console: [\"error\", {allow}]", "eqeqeq: \"loud\""]);
+        assert_eq!(inline.rules.keys().collect::<Vec<_>>(), vec!["curly"]);
+    }
+
+    #[test]
+    fn test_file_top_comment_raises_rule_to_error_with_options() {
+        let source = "/* eslint no-console: [\"error\", { \"allow\": [\"warn\"] }] */\nconsole.log(1);\nconsole.warn(2);\n";
+        let inline = parse_inline_config(source);
+        let file_config = global_config().with_inline_config(&inline);
+
+        let converted = ESLintAdapter::new().convert_eslint_config(&file_config);
+        let no_console = converted.iter().find(|rule| rule.name == "no-console").unwrap();
+        assert_eq!(no_console.severity, RuleSeverity::Error);
+        assert_eq!(global_config().rules["no-console"], ESLintRuleLevel::Warn, "global config is untouched");
+
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        let semantic_ret = SemanticBuilder::new().build(&ret.program);
+        let mut context = ESLintRuleContext::new(
+            source,
+            "test.ts",
+            &ret.program,
+            Some(&semantic_ret.semantic),
+            "no-console".to_string(),
+            DiagnosticSeverity::Error,
+            inline.options("no-console").to_vec(),
+        );
+
+        // ESLint's `no-console` with its `allow` option
+        let allowed: Vec<String> = context
+            .get_options()
+            .first()
+            .and_then(|options| options["allow"].as_array())
+            .map(|methods| methods.iter().filter_map(|method| method.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        for node in EstreeProgram::new(&semantic_ret.semantic).nodes_of_type("MemberExpression") {
+            let AstKind::MemberExpression(member) = node.kind() else {
+                continue;
+            };
+            let is_console = matches!(member.object(), oxc_ast::ast::Expression::Identifier(object) if object.name.as_str() == "console");
+            let method = member.static_property_name().unwrap_or_default();
+            if is_console && !allowed.iter().any(|allowed| allowed == method) {
+                context.report(ESLintReport::new(node.span(), "Unexpected console statement."));
+            }
+        }
+
+        let diagnostics = context.get_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+}
@@ -8,7 +8,7 @@
 //! - **Fast Path**: ESLint rules compiled to Rust at build time
 //! - **Fallback Path**: External ESLint execution via Moon tasks
 //! - **Context Mapping**: ESLint's context API mapped to our diagnostic system
-//! - **AST Compatibility**: ESTree nodes mapped to OXC AST structures
+//! - **AST Compatibility**: ESTree nodes mapped to OXC AST structures (see [`estree`])
 //!
 //! ## Performance Benefits
 //! - Zero runtime parsing for compiled rules
@@ -17,6 +17,8 @@
 //! - Leverages existing OXC parser infrastructure
 
 pub mod context;
+pub mod estree;
+pub mod inline_config;
 pub mod visitor;
 pub mod utils;
 pub mod rule_mapping;
@@ -218,7 +220,7 @@ pub struct ESLintConfig {
 }
 
 /// ESLint rule level (off/warn/error)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ESLintRuleLevel {
     #[serde(rename = "off")]
     Off,
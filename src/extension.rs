@@ -15,14 +15,34 @@
 //! @complexity high
 //! @since 1.0.0
 
-use crate::config::{MoonShineArgs, MoonShineConfig};
-use crate::installation::{check_moonshine_installed, install_moonshine_extension, load_prompt_from_storage};
+use crate::ai_code_fixer::ClaudeFixer;
+use crate::config::{GeneratedFilePolicy, MoonShineArgs, MoonShineConfig, OperationMode};
+use crate::diff_filter::{filter_to_changed_lines, ChangedLines};
+use crate::error::Error;
+use crate::file_filter::{select_files, AiExcludes, FileFilter};
+use crate::fix_plan::{plan_buffer, FixPlan};
+use crate::generated_files::GeneratedFiles;
+use crate::host_retry::{host_backoff, is_transient_io_kind, is_transient_message, retry_transient, HostRetryPolicy, DEFAULT_HOST_RETRIES};
+use crate::installation::{check_moonshine_installed, install_moonshine_extension, load_prompt_from_storage, run_doctor};
+use crate::markdown_fences::{is_markdown_path, lint_markdown};
 use crate::multi_language_analyzer::{LanguageConfig as MultiLanguageConfig, MultiLanguageAnalyzer, SupportedLanguage};
 use crate::prompts;
+use crate::reporting::{
+    evaluate_run_status, quality_score, quality_summary, render_report, write_report, ReportFormat, RunStatus, TerminalReportOptions, STDOUT_OUTPUT,
+};
+use crate::rule_registry::RuleRegistry;
+use crate::rulebase::active_rules::ActiveRuleSet;
+use crate::rulebase::import_cycles::{configured_import_cycles, import_cycle_rule};
+use crate::rulebase::profile::RuleProfile;
+use crate::session::{cleanup_sessions, ensure_cache_directory, SessionGuard, SessionManager};
+use crate::source_encoding::{read_source_file, skipped_file_diagnostic, DecodedSource, SkipReason};
+use crate::storage::{AnalysisSummary, AstCache, PathScopes};
+use crate::write_transaction::WriteTransaction;
 // use crate::storage::HybridStorage; // Reserved for future integration
 // use crate::parallel_lint_runner::{run_parallel_lint, ParallelLintConfig}; // Module doesn't exist yet
 use crate::moon_host::{FnResult, Json, PluginError, WithReturnCode};
 use moon_pdk::*;
+use oxc_span::SourceType;
 use serde::{Deserialize, Serialize};
 
 /// Represents the input payload for the `execute_extension` function.
@@ -106,8 +126,26 @@ fn parse_moon_args(args: &[String]) -> Result<MoonShineArgs, String> {
         mode: None,
         lint_only: false,
         reporting_only: false,
+        safe_mode: false,
         force_init: false,
         install_prompts: false,
+        doctor: false,
+        migrate_prompts: false,
+        apply_suggestions: false,
+        atomic: false,
+        fix_plan: false,
+        profile: false,
+        include_node_modules: Vec::new(),
+        report_unused_disables: false,
+        explain: false,
+        diff: None,
+        print_active_rules: false,
+        print_prompt: None,
+        markdown: false,
+        host_retries: None,
+        max_files: None,
+        format: None,
+        output: None,
         files: Vec::new(),
     };
 
@@ -116,7 +154,8 @@ fn parse_moon_args(args: &[String]) -> Result<MoonShineArgs, String> {
         match args[i].as_str() {
             "--mode" => {
                 if i + 1 < args.len() {
-                    parsed_args.mode = Some(args[i + 1].clone());
+                    let mode = args[i + 1].parse::<OperationMode>().map_err(|e| e.to_string())?;
+                    parsed_args.mode = Some(mode);
                     i += 2;
                 } else {
                     return Err("--mode requires a value".to_string());
@@ -138,6 +177,109 @@ fn parse_moon_args(args: &[String]) -> Result<MoonShineArgs, String> {
                 parsed_args.install_prompts = true;
                 i += 1;
             }
+            "--doctor" => {
+                parsed_args.doctor = true;
+                i += 1;
+            }
+            "--migrate-prompts" => {
+                parsed_args.migrate_prompts = true;
+                i += 1;
+            }
+            "--apply-suggestions" => {
+                parsed_args.apply_suggestions = true;
+                i += 1;
+            }
+            "--atomic" => {
+                parsed_args.atomic = true;
+                i += 1;
+            }
+            "--safe-mode" => {
+                parsed_args.safe_mode = true;
+                i += 1;
+            }
+            "--profile" => {
+                parsed_args.profile = true;
+                i += 1;
+            }
+            "--report-unused-disables" => {
+                parsed_args.report_unused_disables = true;
+                i += 1;
+            }
+            "--explain" => {
+                parsed_args.explain = true;
+                i += 1;
+            }
+            "--print-active-rules" => {
+                parsed_args.print_active_rules = true;
+                i += 1;
+            }
+            "--print-prompt" => {
+                if i + 1 < args.len() {
+                    parsed_args.print_prompt = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--print-prompt requires a rule".to_string());
+                }
+            }
+            "--markdown" => {
+                parsed_args.markdown = true;
+                i += 1;
+            }
+            "--fix-plan" => {
+                parsed_args.fix_plan = true;
+                i += 1;
+            }
+            "--include-node-modules" => {
+                if i + 1 < args.len() {
+                    parsed_args.include_node_modules.push(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--include-node-modules requires a path".to_string());
+                }
+            }
+            "--format" => {
+                if i + 1 < args.len() {
+                    let format = args[i + 1].parse::<ReportFormat>().map_err(|e| e.to_string())?;
+                    parsed_args.format = Some(format);
+                    i += 2;
+                } else {
+                    return Err("--format requires a value".to_string());
+                }
+            }
+            "--diff" => {
+                if i + 1 < args.len() {
+                    parsed_args.diff = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--diff requires a path to a unified diff".to_string());
+                }
+            }
+            "--host-retries" => {
+                if i + 1 < args.len() {
+                    let retries = args[i + 1].parse::<u32>().map_err(|_| format!("--host-retries expects a count, got {}", args[i + 1]))?;
+                    parsed_args.host_retries = Some(retries);
+                    i += 2;
+                } else {
+                    return Err("--host-retries requires a count".to_string());
+                }
+            }
+            "--max-files" => {
+                if i + 1 < args.len() {
+                    let max_files = args[i + 1].parse::<u32>().map_err(|_| format!("--max-files expects a count, got {}", args[i + 1]))?;
+                    parsed_args.max_files = Some(max_files);
+                    i += 2;
+                } else {
+                    return Err("--max-files requires a count".to_string());
+                }
+            }
+            "--output" => {
+                if i + 1 < args.len() {
+                    parsed_args.output = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    return Err("--output requires a path".to_string());
+                }
+            }
             arg if !arg.starts_with("--") => {
                 parsed_args.files.push(arg.to_string());
                 i += 1;
@@ -180,28 +322,228 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
         moon_error!("Failed to parse arguments: {}", e);
         WithReturnCode::new(create_extension_error("Invalid arguments provided"), 1)
     })?;
+
+    // Transient host failures happen before anything is written, so the whole run can be repeated
+    let policy = HostRetryPolicy::new(args.host_retries.unwrap_or(DEFAULT_HOST_RETRIES));
+    retry_transient(&policy, host_backoff, RunFailure::is_transient, || run_extension(args.clone())).map_err(RunFailure::into_exit)
+}
+
+/// Why a single run of the extension stopped early
+enum RunFailure {
+    /// A host read failed in a way a retry may fix
+    Transient(Error),
+    /// The run finished with this error and return code
+    Exit(WithReturnCode<PluginError>),
+}
+
+impl From<WithReturnCode<PluginError>> for RunFailure {
+    fn from(exit: WithReturnCode<PluginError>) -> Self {
+        Self::Exit(exit)
+    }
+}
+
+impl RunFailure {
+    fn is_transient(&self) -> bool {
+        matches!(self, Self::Transient(error) if error.is_transient_host())
+    }
+
+    fn into_exit(self) -> WithReturnCode<PluginError> {
+        match self {
+            Self::Transient(error) => {
+                moon_error!("Host still failing after retries: {}", error);
+                WithReturnCode::new(create_extension_error(&error.to_string()), 1)
+            }
+            Self::Exit(exit) => exit,
+        }
+    }
+}
+
+/// Extension config from the host.
+///
+/// Transient read failures are returned so the run can be retried; any other failure,
+/// such as config that does not deserialize, falls back to the defaults.
+fn load_extension_config(read: impl FnOnce() -> Result<MoonShineConfig, PluginError>) -> crate::error::Result<MoonShineConfig> {
+    match read() {
+        Ok(config) => Ok(config),
+        Err(e) if is_transient_host_failure(&e) => Err(Error::moon_pdk(format!("get_extension_config: {}", e))),
+        Err(e) => {
+            moon_warn!("Configuration error, using defaults: {}", e);
+            Ok(MoonShineConfig::default())
+        }
+    }
+}
+
+fn is_transient_host_failure(error: &PluginError) -> bool {
+    error.downcast_ref::<std::io::Error>().is_some_and(|io| is_transient_io_kind(io.kind())) || is_transient_message(&error.to_string())
+}
+
+/// Prompt variables for `--print-prompt`; without a readable file the placeholders stay unfilled
+fn prompt_preview_context(file_path: Option<&str>, config: &MoonShineConfig) -> std::collections::HashMap<String, String> {
+    let mut context = std::collections::HashMap::new();
+    let Some(file_path) = file_path else {
+        return context;
+    };
+
+    let language = match MultiLanguageAnalyzer::new(MultiLanguageConfig::default()).detect_language(file_path, "") {
+        SupportedLanguage::TypeScript => "typescript",
+        SupportedLanguage::JavaScript => "javascript",
+        SupportedLanguage::Unknown => "unknown",
+    };
+    context.insert("file_path".to_string(), file_path.to_string());
+    context.insert("language".to_string(), language.to_string());
+    match read_source_file(file_path, config.linting.latin1_fallback) {
+        Ok(DecodedSource::Utf8(code)) | Ok(DecodedSource::Latin1(code)) => {
+            context.insert("code".to_string(), code);
+        }
+        Ok(DecodedSource::Skipped(reason)) => moon_warn!("Not filling {{code}} from {} - {}", file_path, reason),
+        Err(e) => moon_warn!("Not filling {{code}} from {}: {}", file_path, e),
+    }
+    context
+}
+
+/// One attempt at the run `execute_extension_logic` describes
+fn run_extension(args: MoonShineArgs) -> Result<(), RunFailure> {
     // Load configuration with proper error handling via Moon PDK
-    let config = get_extension_config::<MoonShineConfig>().unwrap_or_else(|e| {
-        moon_warn!("Configuration error, using defaults: {}", e);
-        MoonShineConfig::default()
-    });
+    let mut config = load_extension_config(get_extension_config::<MoonShineConfig>).map_err(RunFailure::Transient)?;
+    // Safe mode is set before anything else runs, so no later step - doctor included - can write or execute
+    let safe_mode = args.safe_mode || config.safe_mode;
+    crate::moon_pdk_interface::set_safe_mode(safe_mode);
+    if safe_mode {
+        moon_info!("Safe mode - static analysis only; no file is written and no command is executed");
+        config.ai.providers = vec![crate::provider_router::OFFLINE_PROVIDER.to_string()];
+    }
+
+    // Doctor reports configuration problems instead of failing on them
+    if args.doctor {
+        let report = run_doctor(&config);
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string()));
+        return if report.healthy {
+            Ok(())
+        } else {
+            Err(WithReturnCode::new(create_extension_error("Health check failed - see report for details"), 1).into())
+        };
+    }
+
+    if args.migrate_prompts {
+        let report = prompts::migrate_legacy_prompts().map_err(|e| {
+            moon_error!("Prompt migration failed: {}", e);
+            WithReturnCode::new(create_extension_error(&e.to_string()), 1)
+        })?;
+        moon_info!(
+            "Migrated {} legacy prompt(s) into {}; legacy keys were left in place",
+            report.migrated.len(),
+            report.path
+        );
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string()));
+        return Ok(());
+    }
+
+    config.validate().map_err(|e| {
+        moon_error!("Invalid configuration: {}", e);
+        WithReturnCode::new(create_extension_error(&e.to_string()), 1)
+    })?;
+    if args.apply_suggestions {
+        config.linting.apply_suggestions = true;
+    }
+    if args.report_unused_disables {
+        config.linting.report_unused_disables = true;
+    }
+    if args.explain {
+        config.linting.explain = true;
+    }
+    if args.profile {
+        config.linting.profile = true;
+    }
+    if args.markdown {
+        config.linting.markdown = true;
+    }
+    if args.max_files.is_some() {
+        config.max_files = args.max_files;
+    }
+    config.linting.include_node_modules.extend(args.include_node_modules.iter().cloned());
+
+    if args.print_active_rules {
+        let registry = RuleRegistry::new().map_err(|e| WithReturnCode::new(create_extension_error(&e.to_string()), 1))?;
+        let mut rules = registry.get_enabled_rules();
+        rules.push(import_cycle_rule());
+        let active_rules = ActiveRuleSet::resolve(&rules, &config.linting);
+        moon_info!("{} of {} rules active", active_rules.active().count(), active_rules.rules.len());
+        println!("{}", serde_json::to_string_pretty(&active_rules).unwrap_or_else(|_| "{}".to_string()));
+        return Ok(());
+    }
+
+    if let Some(rule) = args.print_prompt.as_deref() {
+        println!("{}", prompts::preview_prompt(rule, &prompt_preview_context(args.files.first().map(String::as_str), &config)));
+        return Ok(());
+    }
+
+    let operation_mode = if safe_mode {
+        OperationMode::ReportingOnly
+    } else if args.fix_plan {
+        // A plan computes fixes and stops before applying them
+        OperationMode::DryRun
+    } else {
+        args.mode
+            .or(if args.reporting_only { Some(OperationMode::ReportingOnly) } else { None })
+            .or(if args.lint_only { Some(OperationMode::LintOnly) } else { None })
+            .unwrap_or_else(|| config.resolved_operation_mode())
+    };
+
+    // --format/--output override the configured report settings; an output path alone archives JSON
+    let report_output = args.output.clone().or_else(|| config.output_file.clone());
+    let report_format = args
+        .format
+        .or_else(|| config.output_format.as_deref().and_then(|format| format.parse().ok()))
+        .or(report_output.as_ref().map(|_| ReportFormat::Json));
+    let mut report_diagnostics = Vec::new();
+
+    // --diff reports only diagnostics on changed lines; files are still linted whole
+    let changed_lines = match args.diff.as_deref() {
+        Some(path) => match crate::moon_pdk_interface::read_file_content(path) {
+            Ok(diff) => Some(ChangedLines::from_unified_diff(&diff)),
+            Err(e) if is_transient_message(&e.to_string()) => return Err(RunFailure::Transient(Error::moon_pdk(format!("read --diff {}: {}", path, e)))),
+            Err(e) => {
+                moon_error!("Failed to read diff {}: {}", path, e);
+                return Err(WithReturnCode::new(create_extension_error("Failed to read --diff input"), 1).into());
+            }
+        },
+        None => None,
+    };
+    // Reporting-only prints every diagnostic even without --format
+    let report_format = report_format
+        .or(changed_lines.as_ref().map(|_| ReportFormat::Terminal))
+        .or(operation_mode.reports_all_diagnostics().then_some(ReportFormat::Terminal));
+
+    // Allocate this run's session and prune expired ones; a run that fails removes its unfinished session on return
+    let session = if operation_mode.writes_session_artifacts() {
+        let session_manager = SessionManager::new(MoonShineConfig::moonshine_directory());
+        let session = match session_manager.create_session(chrono::Utc::now()) {
+            Ok(session) => Some(SessionGuard::new(session, config.keep_debug_sessions())),
+            Err(e) => {
+                moon_debug!("Session directory unavailable: {}", e);
+                None
+            }
+        };
+        if let Err(e) = cleanup_sessions(MoonShineConfig::moonshine_directory(), &config, chrono::Utc::now()) {
+            moon_warn!("Session pruning failed: {}", e);
+        }
+        session
+    } else {
+        None
+    };
 
     // --- Parallel Lint Integration ---
-    let operation_mode = args
-        .mode
-        .as_deref()
-        .or(if args.reporting_only { Some("reporting-only") } else { None })
-        .or(if args.lint_only { Some("lint-only") } else { None })
-        .unwrap_or(config.operation_mode.as_deref().unwrap_or("fix"));
-
-    let file_arguments = if args.files.is_empty() {
+    let file_arguments = if let Some(changed_lines) = changed_lines.as_ref().filter(|_| args.files.is_empty()) {
+        // Only the files the diff touches
+        changed_lines.paths().map(str::to_string).collect()
+    } else if args.files.is_empty() {
         // Default file patterns when none specified
         vec!["src".to_string()]
     } else {
         args.files
     };
 
-    if operation_mode == "parallel-lint" {
+    if operation_mode == OperationMode::ParallelLint {
         // Only support first file/dir argument for now
         let mut metrics_file: Option<String> = None;
         let mut concurrency: Option<usize> = None;
@@ -244,6 +586,9 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
                 let _ = serde_json::to_writer_pretty(&mut f, &json);
             }
         }
+        if let Some(session) = session {
+            session.complete();
+        }
         return Ok(());
     }
 
@@ -251,7 +596,7 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
     moon_debug!("Processing files: {:?}", file_arguments);
 
     // Skip installation in reporting-only mode for CI environments
-    if args.reporting_only {
+    if operation_mode == OperationMode::ReportingOnly {
         moon_info!("Reporting mode - skipping installation checks for CI compatibility");
     } else {
         // Check if installation is needed (first run, missing files, or forced)
@@ -273,7 +618,7 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
                 }
                 Err(e) => {
                     moon_error!("Installation failed: {}", e);
-                    return Err(WithReturnCode::new(create_extension_error("Failed to prepare extension installation"), 1));
+                    return Err(WithReturnCode::new(create_extension_error("Failed to prepare extension installation"), 1).into());
                 }
             }
         }
@@ -285,14 +630,14 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
 
     if available_rules.is_empty() {
         moon_error!("No linting rules available - extension malfunction");
-        return Err(WithReturnCode::new(create_extension_error("No linting rules loaded"), 1));
+        return Err(WithReturnCode::new(create_extension_error("No linting rules loaded"), 1).into());
     }
 
     // Validate prompt system with comprehensive error handling
     let test_prompt = load_prompt_from_storage("typescript_strict", Some(&config));
     if test_prompt.is_empty() {
         moon_error!("Prompt system malfunction - no test prompt loaded");
-        return Err(WithReturnCode::new(create_extension_error("Prompt system not working"), 1));
+        return Err(WithReturnCode::new(create_extension_error("Prompt system not working"), 1).into());
     }
 
     moon_debug!("Prompt system validated - {} chars", test_prompt.len());
@@ -313,6 +658,15 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
         moon_info!("Using static prompts (COPRO disabled)");
     }
 
+    if config.ai_offline() || crate::provider_router::ai_disabled() {
+        moon_debug!("AI disabled by the \"none\" provider - running static analysis only");
+    } else if !crate::provider_router::ai_available() {
+        moon_info!("No AI provider available - AI-enhanced rules will report static analysis results only");
+    } else if config.ai.provider_warmup {
+        let ready = crate::provider_router::warm_up_providers();
+        moon_info!("AI providers warmed up: {}", if ready.is_empty() { "none ready".to_string() } else { ready.join(", ") });
+    }
+
     // Initialize Moon-compatible caching for AI results
     let cache_key = format!(
         "moonshine-{}-{}-{}",
@@ -321,6 +675,12 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
         if copro_enabled { "copro" } else { "static" }
     );
     moon_debug!("Cache key: {}", cache_key);
+    if operation_mode.writes_session_artifacts() {
+        match ensure_cache_directory(config.resolved_cache_directory()) {
+            Ok(cache_dir) => moon_debug!("Cache directory: {}", cache_dir.display()),
+            Err(e) => moon_warn!("Cache directory unavailable: {}", e),
+        }
+    }
 
     let workflow_config = get_workflow_config();
     if let Some(phases) = workflow_config.get("phases").and_then(|p| p.as_array()) {
@@ -356,19 +716,105 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
         ..Default::default()
     });
 
+    // Drop excluded and gitignored paths; allowlisted node_modules packages stay in
+    let file_filter = FileFilter::new(".", &config).map_err(|e| {
+        moon_error!("Invalid file filter configuration: {}", e);
+        WithReturnCode::new(create_extension_error(&e.to_string()), 1)
+    })?;
+    let file_arguments: Vec<String> = file_arguments
+        .into_iter()
+        .filter(|file_path| {
+            let excluded = file_filter.is_excluded(file_path);
+            if excluded {
+                moon_debug!("Skipping excluded path {}", file_path);
+            }
+            !excluded
+        })
+        .collect();
+
+    // Cap the run so an accidental repo-wide glob fails safe; skipped files are reported
+    let selection = select_files(file_arguments, config.resolved_max_files());
+    if let Some(limit) = config.resolved_max_files().filter(|_| !selection.skipped.is_empty()) {
+        moon_warn!(
+            "max_files limit of {} reached - processing {} file(s), skipping {}",
+            limit,
+            selection.selected.len(),
+            selection.skipped.len()
+        );
+        report_diagnostics.extend(
+            selection
+                .skipped
+                .iter()
+                .map(|file_path| skipped_file_diagnostic(file_path, SkipReason::MaxFiles { limit })),
+        );
+    }
+    let file_arguments = selection.selected;
+
+    // With --atomic, fixed files are staged and committed together after the loop
+    let mut write_transaction = (args.atomic && operation_mode.writes_files()).then(|| {
+        let staging_dir = match &session {
+            Some(session) => session.staging_dir(),
+            None => std::path::Path::new(&MoonShineConfig::moonshine_directory()).join("staging"),
+        };
+        WriteTransaction::new(staging_dir)
+    });
+
+    // Files with identical content and path-dependent settings are analyzed once; entries persist unless the mode is read-only
+    let path_scopes = RuleRegistry::new()
+        .map(|registry| PathScopes::from_config(&config, &crate::buffer_lint::configured_rules(&registry, &config)))
+        .unwrap_or_default();
+    let mut ast_cache = if operation_mode.writes_session_artifacts() {
+        AstCache::for_config(config.resolved_cache_directory(), &config)
+    } else {
+        AstCache::in_memory()
+    }
+    .with_path_scopes(path_scopes);
+
+    let generated_files = GeneratedFiles::from_config(&config.linting);
+    let ai_excludes = AiExcludes::from_config(&config);
+    let mut fix_plan = args.fix_plan.then(FixPlan::default);
+    let mut rule_profile = RuleProfile::default();
+    // Sources of every linted file, for the import cycle pass across them
+    let mut linted_sources: Vec<(String, String)> = Vec::new();
+    // Statically fixed sources awaiting AI fixes, which run once over the batch after the loop
+    let mut ai_fix_batch: Vec<(String, String)> = Vec::new();
+    let ai_fixes_allowed = operation_mode.writes_files() && !config.ai.suggest_only && crate::provider_router::ai_available();
+
     // Execute workflow for each file
     for file_path in &file_arguments {
         moon_info!("Processing file: {}", file_path);
 
-        // Read file content
-        let file_content = match crate::moon_pdk_interface::read_file_content(file_path) {
-            Ok(content) => content,
+        // Read file content; binary and non-UTF-8 files are skipped unless latin-1 decoding is enabled
+        let file_content = match read_source_file(file_path, config.linting.latin1_fallback) {
+            Ok(DecodedSource::Utf8(content)) => content,
+            Ok(DecodedSource::Latin1(content)) => {
+                moon_info!("Decoded non-UTF-8 file {} as latin-1", file_path);
+                content
+            }
+            Ok(DecodedSource::Skipped(reason)) => {
+                moon_info!("Skipping file {} - {}", file_path, reason);
+                report_diagnostics.push(skipped_file_diagnostic(file_path, reason));
+                continue;
+            }
             Err(e) => {
                 moon_error!("Failed to read file {}: {}", file_path, e);
                 continue;
             }
         };
 
+        // Markdown is not parsed itself; only its code fences are linted and fixed
+        if config.linting.markdown && is_markdown_path(file_path) {
+            match lint_markdown(&file_content, file_path, &config) {
+                Ok(result) => {
+                    moon_info!("Linted {} code fence(s) in {}: {} diagnostic(s)", result.fences, file_path, result.diagnostics.len());
+                    report_diagnostics.extend(result.diagnostics);
+                    write_fix_if_allowed(operation_mode, write_transaction.as_mut(), file_path, result.fixed_markdown)?;
+                }
+                Err(e) => moon_warn!("Failed to lint code fences in {}: {}", file_path, e),
+            }
+            continue;
+        }
+
         let detected_language = multi_language_analyzer.detect_language(file_path, &file_content);
         if detected_language == SupportedLanguage::Unknown {
             moon_warn!(
@@ -378,12 +824,93 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
             continue;
         }
 
-        let workflow_definition = crate::workflow::WorkflowDefinition::from_mode(&operation_mode);
+        // Generated files never get AI passes; the policy decides what else runs
+        let generated = generated_files.policy_for(file_path, &file_content);
+        if generated == Some(GeneratedFilePolicy::Skip) {
+            moon_info!("Skipping generated file {}", file_path);
+            report_diagnostics.push(skipped_file_diagnostic(file_path, SkipReason::Generated));
+            continue;
+        }
 
-        let mut engine = match crate::workflow::WorkflowEngine::new(workflow_definition, file_content, file_path.clone(), config.clone()) {
+        // Input nested too deeply to parse safely is refused once; no later pass parses it
+        if let Err(message) = crate::buffer_lint::check_nesting(&file_content) {
+            moon_warn!("Skipping file {} - {}", file_path, message);
+            report_diagnostics.push(crate::buffer_lint::parser_failure_diagnostic(file_path, message));
+            continue;
+        }
+
+        let collect_diagnostics = report_format.is_some() || config.linting.fail_on.is_active();
+        if collect_diagnostics {
+            linted_sources.push((file_path.clone(), file_content.clone()));
+        }
+        if config.linting.profile {
+            // A cache hit runs no rules, so profiled runs lint every file
+            match crate::buffer_lint::lint_buffer(&file_content, file_path, &config) {
+                Ok(result) => {
+                    rule_profile.merge(result.profile.unwrap_or_default());
+                    if collect_diagnostics {
+                        report_diagnostics.extend(result.diagnostics);
+                    }
+                }
+                Err(e) => moon_warn!("Failed to profile rules on {}: {}", file_path, e),
+            }
+        } else if collect_diagnostics {
+            let source_type = SourceType::from_path(file_path).unwrap_or_default();
+            let summary = ast_cache.get_or_analyze(file_path, &file_content, source_type, || {
+                crate::buffer_lint::lint_buffer(&file_content, file_path, &config).map(|result| AnalysisSummary::new(&file_content, result.diagnostics))
+            });
+            match summary {
+                Ok(summary) => report_diagnostics.extend(summary.diagnostics),
+                Err(e) => moon_warn!("Failed to collect diagnostics for {}: {}", file_path, e),
+            }
+        }
+
+        if let Some(fix_plan) = fix_plan.as_mut() {
+            match plan_buffer(&file_content, file_path, &config) {
+                Ok(file_plan) => fix_plan.push(file_plan),
+                Err(e) => moon_warn!("Failed to plan fixes for {}: {}", file_path, e),
+            }
+            continue;
+        }
+
+        // The workflow runs tools and AI through the host; safe mode stops at the diagnostics
+        if safe_mode {
+            continue;
+        }
+
+        let mut workflow_definition = crate::workflow::WorkflowDefinition::for_operation_mode(operation_mode);
+        if generated.is_some() {
+            moon_info!("{} is generated - skipping AI passes", file_path);
+            workflow_definition = workflow_definition.without_ai();
+        } else if ai_excludes.is_excluded(file_path) {
+            moon_info!("{} matches ai.ai_exclude_patterns - skipping AI passes", file_path);
+            workflow_definition = workflow_definition.without_ai();
+        }
+
+        // Static fixes allowed by each rule's fix safety (suggestions with --apply-suggestions) are the workflow's input;
+        // a dry run shows them with the rule behind each changed line
+        let static_fixed = if operation_mode.writes_files() || operation_mode == OperationMode::DryRun {
+            match crate::buffer_lint::lint_buffer_in_mode(&file_content, file_path, &config, operation_mode) {
+                Ok(result) => result.fixed_code,
+                Err(e) => {
+                    moon_warn!("Failed to apply static fixes to {}: {}", file_path, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        if let Some(code) = static_fixed.as_ref().filter(|_| operation_mode == OperationMode::DryRun) {
+            moon_info!("Dry run - fixes for {} are not written:\n{}", file_path, code);
+        }
+        let workflow_input = static_fixed.clone().unwrap_or(file_content);
+        let ai_fix_step = workflow_definition.has_ai();
+
+        let mut engine = match crate::workflow::WorkflowEngine::new(workflow_definition, workflow_input, file_path.clone(), config.clone()) {
             Ok(engine) => engine,
             Err(e) => {
                 moon_error!("Failed to create workflow engine for {}: {}", file_path, e);
+                write_fix_if_allowed(operation_mode, write_transaction.as_mut(), file_path, static_fixed)?;
                 continue;
             }
         };
@@ -397,24 +924,144 @@ pub fn execute_extension_logic(Json(input): Json<ExecuteExtensionInput>) -> FnRe
                     workflow_result.quality_score
                 );
 
-                // Write results if available
-                if let Some(output_code) = workflow_result.final_code {
-                    match crate::moon_pdk_interface::write_file_to_host(file_path, &output_code) {
-                        Ok(_) => moon_info!("Updated file: {}", file_path),
-                        Err(e) => moon_error!("Failed to write updated file {}: {}", file_path, e),
-                    }
+                // Write results if available and the mode allows mutation
+                let final_code = workflow_result.final_code.filter(|_| generated != Some(GeneratedFilePolicy::NoFix));
+                match final_code {
+                    Some(code) if ai_fixes_allowed && ai_fix_step => ai_fix_batch.push((file_path.clone(), code)),
+                    final_code => write_fix_if_allowed(operation_mode, write_transaction.as_mut(), file_path, final_code)?,
                 }
             }
             Err(e) => {
                 moon_error!("Workflow execution failed for {}: {}", file_path, e);
+                write_fix_if_allowed(operation_mode, write_transaction.as_mut(), file_path, static_fixed)?;
+            }
+        }
+    }
+
+    // One batch lets files with identical code share a provider call; a failed batch keeps the static fixes
+    if !ai_fix_batch.is_empty() {
+        let session_id = session.as_ref().map_or_else(|| "moonshine-fix".to_string(), |session| session.id.clone());
+        let mut fixer = ClaudeFixer::new(config.clone()).with_project_files(ai_fix_batch.clone());
+        let ai_fixed: Vec<Option<String>> = match futures::executor::block_on(fixer.fix_files(&ai_fix_batch, session_id)) {
+            Ok(results) => results.into_iter().map(|result| result.fixed_content.filter(|_| result.success)).collect(),
+            Err(e) => {
+                moon_warn!("AI fixes failed for {} file(s): {}", ai_fix_batch.len(), e);
+                vec![None; ai_fix_batch.len()]
+            }
+        };
+        for ((file_path, code), ai_fixed) in ai_fix_batch.into_iter().zip(ai_fixed) {
+            write_fix_if_allowed(operation_mode, write_transaction.as_mut(), &file_path, Some(ai_fixed.unwrap_or(code)))?;
+        }
+    }
+
+    // Cycles span files, so they are only found once every file has been read
+    if linted_sources.len() > 1 {
+        report_diagnostics.extend(configured_import_cycles(&linted_sources, &config));
+    }
+
+    if let Some(transaction) = write_transaction {
+        if let Err(e) = transaction.commit() {
+            moon_error!("Atomic write rolled back: {}", e);
+            return Err(WithReturnCode::new(create_extension_error("Atomic write failed - no files written"), 1).into());
+        }
+    }
+    // Every file was processed; the session now waits for retention pruning
+    if let Some(session) = session {
+        session.complete();
+    }
+
+    if let Some(fix_plan) = &fix_plan {
+        moon_info!("Planned {} fix(es) in {} file(s); nothing was applied", fix_plan.fix_count(), fix_plan.files.len());
+        println!("{}", serde_json::to_string_pretty(fix_plan).unwrap_or_else(|_| "{}".to_string()));
+    }
+
+    if config.linting.profile {
+        println!("{}", serde_json::to_string_pretty(&profile_metrics(file_arguments.len(), rule_profile)).unwrap_or_else(|_| "{}".to_string()));
+    }
+
+    if let Some(changed_lines) = &changed_lines {
+        let total = report_diagnostics.len();
+        report_diagnostics = filter_to_changed_lines(report_diagnostics, changed_lines);
+        moon_info!("Reporting {} of {} diagnostic(s) on changed lines", report_diagnostics.len(), total);
+    }
+
+    // The report always goes to stdout for Moon; --output additionally archives it without color
+    if let Some(report_format) = report_format {
+        print!("{}", render_report(&report_diagnostics, report_format, TerminalReportOptions::detect()));
+        if report_format == ReportFormat::Terminal && !report_diagnostics.is_empty() {
+            let categories = RuleRegistry::new().map(|registry| registry.rule_categories()).unwrap_or_default();
+            let quality = quality_score(&report_diagnostics, &categories, &config.linting.category_weights);
+            println!("{}", quality_summary(&quality, TerminalReportOptions::detect()));
+        }
+        if let Some(path) = report_output.as_deref().filter(|path| *path != STDOUT_OUTPUT) {
+            if safe_mode {
+                moon_warn!("Safe mode - report not written to {}", path);
+            } else {
+                let report = render_report(&report_diagnostics, report_format, TerminalReportOptions::default());
+                if let Err(e) = write_report(path, &report) {
+                    moon_error!("{}", e);
+                    return Err(WithReturnCode::new(create_extension_error("Failed to write report output"), 1).into());
+                }
+                moon_info!("Wrote {} report to {}", report_format, path);
             }
         }
     }
 
+    // Thresholds apply to what was found, whether or not fixes were applied
+    if let RunStatus::Failed { reasons } = evaluate_run_status(&report_diagnostics, &config.linting.fail_on) {
+        moon_error!("Run failed linting.fail_on thresholds: {}", reasons.join("; "));
+        return Err(WithReturnCode::new(create_extension_error(&reasons.join("; ")), 1).into());
+    }
+
     moon_info!("Moon Shine workflow execution completed for {} files", file_arguments.len());
     Ok(())
 }
 
+/// Run metrics for `--profile`: the per-rule timings of every processed file, slowest rule first
+fn profile_metrics(files_processed: usize, rule_profile: RuleProfile) -> serde_json::Value {
+    serde_json::json!({
+        "metrics": {
+            "files_processed": files_processed,
+            "rule_profile": rule_profile
+        }
+    })
+}
+
+/// Write `fixed_code` to `file_path` when `operation_mode` may mutate files, or stage it when the run is `--atomic`.
+///
+/// Read-only modes such as reporting-only leave the file untouched; the fixes stay on the
+/// reported diagnostics as suggestions. Only a staging failure stops the run.
+fn write_fix_if_allowed(
+    operation_mode: OperationMode,
+    transaction: Option<&mut WriteTransaction>,
+    file_path: &str,
+    fixed_code: Option<String>,
+) -> Result<(), RunFailure> {
+    match fixed_code {
+        Some(_) if !operation_mode.writes_files() => {
+            moon_info!("{} mode - not writing changes to {}", operation_mode, file_path);
+            Ok(())
+        }
+        Some(code) => write_updated_file(transaction, file_path, &code),
+        None => Ok(()),
+    }
+}
+
+fn write_updated_file(transaction: Option<&mut WriteTransaction>, file_path: &str, code: &str) -> Result<(), RunFailure> {
+    if let Some(transaction) = transaction {
+        if let Err(e) = transaction.stage(file_path, code) {
+            moon_error!("Failed to stage updated file {}: {}", file_path, e);
+            return Err(WithReturnCode::new(create_extension_error("Atomic write staging failed - no files written"), 1).into());
+        }
+    } else {
+        match crate::moon_pdk_interface::write_file_to_host(file_path, code) {
+            Ok(_) => moon_info!("Updated file: {}", file_path),
+            Err(e) => moon_error!("Failed to write updated file {}: {}", file_path, e),
+        }
+    }
+    Ok(())
+}
+
 /// Retrieves all available AI prompt rule types.
 ///
 /// This function collects rule types from both embedded defaults and any custom
@@ -495,6 +1142,8 @@ pub fn get_workflow_config() -> serde_json::Value {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rulebase::testing::static_rule;
+    use crate::types::DiagnosticSeverity;
     use serde_json::json;
 
     #[test]
@@ -537,6 +1186,89 @@ mod tests {
         assert!(input.context.is_none());
     }
 
+    #[test]
+    fn test_parse_format_and_output_arguments() {
+        let args: Vec<String> = ["--format", "sarif", "--output", "-", "src/app.ts"].iter().map(|arg| arg.to_string()).collect();
+        let parsed = parse_moon_args(&args).unwrap();
+
+        assert_eq!(parsed.format, Some(ReportFormat::Sarif));
+        assert_eq!(parsed.output.as_deref(), Some(STDOUT_OUTPUT));
+        assert_eq!(parsed.files, vec!["src/app.ts".to_string()]);
+        assert!(parse_moon_args(&["--format".to_string(), "xml".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_migrate_prompts_flag() {
+        let parsed = parse_moon_args(&["--migrate-prompts".to_string()]).unwrap();
+
+        assert!(parsed.migrate_prompts);
+        assert!(parsed.files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_fix_plan_flag() {
+        let parsed = parse_moon_args(&["--fix-plan".to_string(), "src/a.ts".to_string()]).unwrap();
+
+        assert!(parsed.fix_plan);
+        assert_eq!(parsed.files, vec!["src/a.ts"]);
+    }
+
+    #[test]
+    fn test_parse_diff_argument() {
+        let parsed = parse_moon_args(&["--diff".to_string(), "pr.diff".to_string(), "src/a.ts".to_string()]).unwrap();
+
+        assert_eq!(parsed.diff.as_deref(), Some("pr.diff"));
+        assert_eq!(parsed.files, vec!["src/a.ts"]);
+        assert!(parse_moon_args(&["--diff".to_string()]).is_err());
+    }
+
+    /// The config-loading start of `run_extension` over a stubbed host read
+    fn configured_run(read: impl FnOnce() -> Result<MoonShineConfig, PluginError>) -> Result<MoonShineConfig, RunFailure> {
+        let config = load_extension_config(read).map_err(RunFailure::Transient)?;
+        config.validate().map_err(|e| WithReturnCode::new(create_extension_error(&e.to_string()), 1))?;
+        Ok(config)
+    }
+
+    #[test]
+    fn test_transient_config_read_is_retried_until_run_completes() {
+        let mut reads = 0;
+        let mut backoffs = 0;
+
+        let result = retry_transient(&HostRetryPolicy::new(2), |_| backoffs += 1, RunFailure::is_transient, || {
+            reads += 1;
+            let first_read = reads == 1;
+            configured_run(move || {
+                if first_read {
+                    Err(PluginError::msg("host_read_file: Resource temporarily unavailable"))
+                } else {
+                    Ok(MoonShineConfig::default())
+                }
+            })
+        });
+
+        assert!(result.is_ok());
+        assert_eq!((reads, backoffs), (2, 1));
+    }
+
+    #[test]
+    fn test_config_validation_error_is_not_retried() {
+        let mut runs = 0;
+
+        let result = retry_transient(&HostRetryPolicy::new(2), |_| panic!("validation errors never back off"), RunFailure::is_transient, || {
+            runs += 1;
+            configured_run(|| {
+                let mut config = MoonShineConfig::default();
+                config.ai.temperature = 5.0;
+                Ok(config)
+            })
+        });
+
+        assert!(matches!(result, Err(RunFailure::Exit(_))));
+        assert_eq!(runs, 1);
+        // Config the host returns but cannot deserialize is not transient either
+        assert!(load_extension_config(|| Err(PluginError::msg("invalid type: string \"hot\", expected f32"))).is_ok());
+    }
+
     #[test]
     fn test_extension_manifest_creation() {
         let manifest = ExtensionManifest {
@@ -719,4 +1451,138 @@ mod tests {
             assert_eq!(context["max_iterations"], 5);
         }
     }
+
+    const FIXABLE_README: &str = "# Usage\n\n```ts\nlet doubled = 2 * 2;\nconsole.log(doubled);\n```\n";
+
+    #[test]
+    fn test_reporting_only_never_modifies_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("README.md");
+        std::fs::write(&path, FIXABLE_README).unwrap();
+        let path = path.to_str().unwrap();
+        let result =
+            crate::markdown_fences::lint_markdown_with_rules(FIXABLE_README, path, &MoonShineConfig::default(), &[static_rule("prefer-const")]).unwrap();
+        assert!(result.fixed_markdown.is_some());
+
+        let mode = OperationMode::ReportingOnly;
+        assert!(mode.reports_all_diagnostics() && !mode.writes_session_artifacts());
+        write_fix_if_allowed(mode, None, path, result.fixed_markdown.clone()).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), FIXABLE_README);
+        // The fix is still reported, as a suggestion
+        assert_eq!(result.diagnostics[0].suggested_fix.as_deref(), Some("const"));
+
+        write_fix_if_allowed(OperationMode::Fix, None, path, result.fixed_markdown).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), FIXABLE_README.replace("let", "const"));
+    }
+
+    #[test]
+    fn test_profile_metrics_merge_rule_timings_of_every_file() {
+        let mut config = MoonShineConfig::default();
+        config.linting.profile = true;
+        let rules = [static_rule("prefer-const")];
+
+        let mut rule_profile = RuleProfile::default();
+        for (path, source) in [("src/a.ts", "let a = 1;\nconsole.log(a);\n"), ("src/b.ts", "const b = 2;\n")] {
+            let result = crate::buffer_lint::lint_buffer_with_rules(source, path, &config, &rules).unwrap();
+            rule_profile.merge(result.profile.expect("profiling enabled"));
+        }
+        let metrics = profile_metrics(2, rule_profile);
+
+        let entries = metrics["metrics"]["rule_profile"]["entries"].as_array().unwrap();
+        assert!(!entries.is_empty());
+        assert_eq!(entries[0]["rule"], "prefer-const");
+        assert_eq!(entries[0]["files"], 2);
+
+        let unprofiled = crate::buffer_lint::lint_buffer_with_rules("let a = 1;\n", "src/a.ts", &MoonShineConfig::default(), &rules).unwrap();
+        assert!(unprofiled.profile.is_none());
+    }
+
+    /// Every file under `dir` with its content, in path order
+    fn tree_snapshot(dir: &std::path::Path) -> Vec<(std::path::PathBuf, Vec<u8>)> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                files.extend(tree_snapshot(&path));
+            } else {
+                files.push((path.clone(), std::fs::read(&path).unwrap()));
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_safe_mode_writes_and_executes_nothing() {
+        use crate::moon_pdk_interface::{execute_command, mutation_count, set_safe_mode, update_prompts_json, write_file_to_host, ExecCommandInput};
+
+        let dir = tempfile::tempdir().unwrap();
+        let source_path = dir.path().join("index.ts");
+        std::fs::write(&source_path, "let doubled = 2 * 2;\nconsole.log(doubled);\n").unwrap();
+        let source_path = source_path.to_str().unwrap();
+        let moonshine_dir = dir.path().join(".moon/moonshine");
+        let before = tree_snapshot(dir.path());
+        let mutations = mutation_count();
+
+        set_safe_mode(true);
+        let config = MoonShineConfig::default();
+
+        // Analysis still runs and reports, from an on-disk cache that is never written
+        let source = std::fs::read_to_string(source_path).unwrap();
+        let mut ast_cache = AstCache::for_config(moonshine_dir.join("cache"), &config);
+        let result = crate::buffer_lint::lint_buffer_with_rules(&source, source_path, &config, &[static_rule("prefer-const")]).unwrap();
+        let summary = ast_cache
+            .get_or_analyze(source_path, &source, SourceType::ts(), || Ok::<_, Error>(AnalysisSummary::new(&source, result.diagnostics.clone())))
+            .unwrap();
+        assert!(!summary.diagnostics.is_empty());
+        assert!(result.fixed_code.is_some());
+
+        // Every mutation the pipeline can attempt is refused, whatever the operation mode
+        assert!(SessionManager::new(&moonshine_dir).create_session(chrono::Utc::now()).is_err());
+        assert!(SessionManager::new(&moonshine_dir).prune_older_than(0, chrono::Utc::now()).is_err());
+        assert!(ensure_cache_directory(moonshine_dir.join("cache")).is_err());
+        let mut transaction = WriteTransaction::new(moonshine_dir.join("staging"));
+        assert!(transaction.stage(source_path, result.fixed_code.as_deref().unwrap()).is_err());
+        write_fix_if_allowed(OperationMode::Fix, None, source_path, result.fixed_code.clone()).unwrap();
+        assert!(write_report(dir.path().join("report.json").to_str().unwrap(), "{}").is_err());
+        assert!(update_prompts_json(&json!({ "prefer-const": "rewritten" })).is_err());
+        crate::storage::FixHistory::persistent(moonshine_dir.join("fix-history.json"), 5).record_run("run-1", chrono::Utc::now(), &result.fix_stats);
+        let command = ExecCommandInput {
+            command: "touch".to_string(),
+            args: vec![dir.path().join("executed").display().to_string()],
+            env: Default::default(),
+            working_dir: None,
+        };
+        assert!(matches!(execute_command(command).unwrap_err().downcast_ref::<Error>(), Some(Error::SafeMode { .. })));
+
+        assert_eq!(mutation_count(), mutations, "no write or command reached the host");
+        assert_eq!(tree_snapshot(dir.path()), before);
+
+        // The same write goes through once safe mode is off
+        set_safe_mode(false);
+        write_file_to_host(source_path, result.fixed_code.as_deref().unwrap()).unwrap();
+        assert_eq!(mutation_count(), mutations + 1);
+        assert_ne!(tree_snapshot(dir.path()), before);
+    }
+
+    #[test]
+    fn test_reporting_only_status_follows_fail_on_thresholds() {
+        let result =
+            crate::markdown_fences::lint_markdown_with_rules(FIXABLE_README, "README.md", &MoonShineConfig::default(), &[static_rule("prefer-const")]).unwrap();
+        let count = |severity: DiagnosticSeverity| result.diagnostics.iter().filter(|d| d.severity == severity).count() as u32;
+        assert!(!result.diagnostics.is_empty());
+
+        // Findings alone never fail the run
+        assert_eq!(evaluate_run_status(&result.diagnostics, &Default::default()), RunStatus::Passed);
+
+        let mut fail_on = crate::config::FailOnConfig {
+            max_errors: Some(count(DiagnosticSeverity::Error)),
+            max_warnings: Some(count(DiagnosticSeverity::Warning)),
+            fail_on_fixable: false,
+        };
+        assert_eq!(evaluate_run_status(&result.diagnostics, &fail_on), RunStatus::Passed);
+
+        fail_on.fail_on_fixable = true;
+        assert!(matches!(evaluate_run_status(&result.diagnostics, &fail_on), RunStatus::Failed { .. }));
+    }
 }
@@ -0,0 +1,213 @@
+//! # Source File Filtering
+//!
+//! Decides which paths a run analyzes from `linting.include_patterns`,
+//! `linting.exclude_patterns` and the workspace `.gitignore`. Dependencies under
+//! `node_modules` are excluded by default; `linting.include_node_modules` (or
+//! `--include-node-modules`) allowlists specific packages so a single dependency can be
+//! scanned without dropping the global exclude. An allowlisted path overrides both the
+//! exclude patterns and `.gitignore`; every other path is still subject to both.
+//!
+//! `max_files` (or `--max-files`) then caps how many of the remaining files a run
+//! processes, so an accidental repo-wide glob cannot run up an unbounded AI bill. Files
+//! are taken in sorted path order, so a capped run always covers the same files.
+//!
+//! [`AiExcludes`] narrows AI passes rather than analysis: files matching
+//! `ai.ai_exclude_patterns` still get static rules and fixes.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp core
+//! @complexity low
+//! @since 2.0.0
+
+use crate::config::MoonShineConfig;
+use crate::error::{Error, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/// Path filter built from the linting configuration and `.gitignore`
+#[derive(Debug)]
+pub struct FileFilter {
+    root: PathBuf,
+    include: GlobSet,
+    exclude: GlobSet,
+    allowlist: GlobSet,
+    gitignore: Gitignore,
+}
+
+impl FileFilter {
+    /// Build the filter for the workspace at `root`
+    pub fn new(root: impl Into<PathBuf>, config: &MoonShineConfig) -> Result<Self> {
+        let root = root.into();
+        let linting = &config.linting;
+
+        let allowlist_patterns: Vec<String> = linting
+            .include_node_modules
+            .iter()
+            .map(|path| path.trim_end_matches('/'))
+            .filter(|path| !path.is_empty())
+            .flat_map(|path| [path.to_string(), format!("{}/**", path)])
+            .collect();
+
+        let mut gitignore = GitignoreBuilder::new(&root);
+        let gitignore_path = root.join(".gitignore");
+        if gitignore_path.is_file() {
+            if let Some(error) = gitignore.add(&gitignore_path) {
+                moon_warn!("Ignoring unreadable {}: {}", gitignore_path.display(), error);
+            }
+        }
+        let gitignore = gitignore.build().map_err(|e| Error::config(format!("invalid .gitignore: {}", e)))?;
+
+        Ok(Self {
+            include: build_glob_set("linting.include_patterns", &linting.include_patterns)?,
+            exclude: build_glob_set("linting.exclude_patterns", &linting.exclude_patterns)?,
+            allowlist: build_glob_set("linting.include_node_modules", &allowlist_patterns)?,
+            gitignore,
+            root,
+        })
+    }
+
+    /// Whether `path` is a source file this run should analyze
+    pub fn is_included(&self, path: impl AsRef<Path>) -> bool {
+        let relative = self.relative(path.as_ref());
+        self.include.is_match(relative) && !self.is_excluded_relative(relative)
+    }
+
+    /// Whether `path` is excluded by the exclude patterns or `.gitignore` and not allowlisted
+    pub fn is_excluded(&self, path: impl AsRef<Path>) -> bool {
+        self.is_excluded_relative(self.relative(path.as_ref()))
+    }
+
+    fn is_excluded_relative(&self, relative: &Path) -> bool {
+        if self.allowlist.is_match(relative) {
+            return false;
+        }
+
+        self.exclude.is_match(relative) || self.gitignore.matched_path_or_any_parents(relative, false).is_ignore()
+    }
+
+    fn relative<'p>(&self, path: &'p Path) -> &'p Path {
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        path.strip_prefix("./").unwrap_or(path)
+    }
+}
+
+/// Files a run processes under its file cap, and the ones it leaves out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileSelection {
+    pub selected: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Keep the first `max_files` of `files` in sorted path order; `None` keeps them all
+pub fn select_files(mut files: Vec<String>, max_files: Option<u32>) -> FileSelection {
+    files.sort();
+    files.dedup();
+    let limit = max_files.map_or(files.len(), |max| files.len().min(max as usize));
+    let skipped = files.split_off(limit);
+    FileSelection { selected: files, skipped }
+}
+
+/// Files matching `ai.ai_exclude_patterns`, which run static rules only
+#[derive(Debug, Clone)]
+pub struct AiExcludes {
+    patterns: GlobSet,
+}
+
+impl Default for AiExcludes {
+    fn default() -> Self {
+        Self { patterns: GlobSet::empty() }
+    }
+}
+
+impl AiExcludes {
+    /// Invalid globs are skipped with a warning so one typo does not fail the run
+    pub fn from_config(config: &MoonShineConfig) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &config.ai.ai_exclude_patterns {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => moon_warn!("Ignoring invalid ai.ai_exclude_patterns entry '{}': {}", pattern, e),
+            }
+        }
+
+        Self {
+            patterns: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    /// Whether AI passes skip `file_path`
+    pub fn is_excluded(&self, file_path: &str) -> bool {
+        let path = Path::new(file_path);
+        self.patterns.is_match(path.strip_prefix("./").unwrap_or(path))
+    }
+}
+
+fn build_glob_set(field: &str, patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| Error::config_field(format!("invalid glob: {}", e), field, Some(pattern.as_str())))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| Error::config_field(e.to_string(), field, None::<String>))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn filter(root: &Path, include_node_modules: &[&str]) -> FileFilter {
+        let mut config = MoonShineConfig::default();
+        config.linting.include_node_modules = include_node_modules.iter().map(|path| path.to_string()).collect();
+        FileFilter::new(root, &config).unwrap()
+    }
+
+    #[test]
+    fn test_node_modules_excluded_by_default() {
+        let dir = tempdir().unwrap();
+        let filter = filter(dir.path(), &[]);
+
+        assert!(filter.is_included("src/index.ts"));
+        assert!(!filter.is_included("node_modules/pkg/index.js"));
+        assert!(!filter.is_included("dist/bundle.js"));
+    }
+
+    #[test]
+    fn test_allowlisted_package_is_analyzed_and_others_stay_excluded() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "node_modules/\ncoverage/\n").unwrap();
+        let filter = filter(dir.path(), &["node_modules/pkg"]);
+
+        assert!(filter.is_included("node_modules/pkg/index.js"));
+        assert!(filter.is_included(dir.path().join("node_modules/pkg/lib/util.ts")));
+        assert!(!filter.is_included("node_modules/other/index.js"));
+        assert!(!filter.is_included("node_modules/pkg-extra/index.js"));
+        assert!(!filter.is_included("coverage/report.js"), ".gitignore still applies outside the allowlist");
+    }
+
+    #[test]
+    fn test_max_files_keeps_first_paths_in_sorted_order() {
+        let files = ["src/c.ts", "src/a.ts", "lib/z.ts", "src/b.ts", "src/a.ts"].map(String::from).to_vec();
+
+        let selection = select_files(files.clone(), Some(2));
+        assert_eq!(selection.selected, vec!["lib/z.ts", "src/a.ts"]);
+        assert_eq!(selection.skipped, vec!["src/b.ts", "src/c.ts"]);
+
+        let uncapped = select_files(files, None);
+        assert_eq!(uncapped.selected.len(), 4);
+        assert!(uncapped.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_allowlist_glob_is_a_config_error() {
+        let mut config = MoonShineConfig::default();
+        config.linting.include_node_modules = vec!["node_modules/[pkg".to_string()];
+
+        assert!(FileFilter::new(".", &config).is_err());
+    }
+}
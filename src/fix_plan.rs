@@ -0,0 +1,171 @@
+//! # Fix Plans
+//!
+//! `--fix-plan` runs the full analysis and fix computation but stops before anything is
+//! applied, and prints what would change as JSON: per file, each fix's rule, span, the text
+//! it replaces and the replacement, with the fix's safety level and, for AI fixes, the
+//! model's confidence. CI bots can post the plan and accept entries programmatically, which
+//! a unified diff does not allow.
+//!
+//! A plan lists the fixes the first fix pass would apply to the file as it is now; fixes
+//! that only become possible once those are applied show up in the next run's plan.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::buffer_lint::{lint_buffer, BufferLintResult};
+use crate::config::MoonShineConfig;
+use crate::error::Result;
+use crate::rule_types::FixSafety;
+use crate::rulebase::static_rules::line_column_to_offset;
+use serde::{Deserialize, Serialize};
+
+/// Source range a planned fix replaces, 1-based as in diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixSpan {
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+/// One change the run would make
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedFix {
+    pub rule: String,
+    pub span: FixSpan,
+    pub before: String,
+    pub after: String,
+    pub safety: FixSafety,
+    pub confidence: Option<f32>, // AI confidence (0.0-1.0); `None` for deterministic fixes
+}
+
+/// Planned fixes of one file, in file order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilePlan {
+    pub file_path: String,
+    pub fixes: Vec<PlannedFix>,
+}
+
+/// Planned fixes of a run, one entry per file with at least one fix
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FixPlan {
+    pub files: Vec<FilePlan>,
+}
+
+impl FixPlan {
+    /// Add `plan` unless it has no fixes
+    pub fn push(&mut self, plan: FilePlan) {
+        if !plan.fixes.is_empty() {
+            self.files.push(plan);
+        }
+    }
+
+    /// Fixes planned across all files
+    pub fn fix_count(&self) -> usize {
+        self.files.iter().map(|file| file.fixes.len()).sum()
+    }
+}
+
+/// Plan the fixes for `content`, labelled `file_path`, without applying them
+pub fn plan_buffer(content: &str, file_path: &str, config: &MoonShineConfig) -> Result<FilePlan> {
+    let result = lint_buffer(content, file_path, config)?;
+    Ok(file_plan(content, &result))
+}
+
+/// The fixes of `result`, linted from `source`, that its first fix pass applied.
+///
+/// Fixes rejected by the safety gate, verification or a span conflict are left out, as are
+/// all fixes when the fixed code as a whole was rejected.
+pub fn file_plan(source: &str, result: &BufferLintResult) -> FilePlan {
+    let mut plan = FilePlan {
+        file_path: result.virtual_path.clone(),
+        fixes: Vec::new(),
+    };
+    if result.fixed_code.is_none() {
+        return plan;
+    }
+
+    let mut applied: Vec<_> = result.applied_fixes.iter().collect();
+    for diagnostic in &result.diagnostics {
+        let Some(after) = diagnostic.suggested_fix.as_deref().filter(|_| diagnostic.fix_available) else {
+            continue;
+        };
+        let start = line_column_to_offset(source, diagnostic.line, diagnostic.column);
+        let end = line_column_to_offset(source, diagnostic.end_line, diagnostic.end_column);
+        let Some(before) = start.zip(end).and_then(|(start, end)| source.get(start..end)) else {
+            continue;
+        };
+        // Each applied fix accounts for one diagnostic
+        let Some(index) = applied
+            .iter()
+            .position(|fix| fix.rule_id == diagnostic.rule_name && fix.original_text == before && fix.fixed_text == after)
+        else {
+            continue;
+        };
+        let applied_fix = applied.swap_remove(index);
+
+        plan.fixes.push(PlannedFix {
+            rule: diagnostic.rule_name.clone(),
+            span: FixSpan {
+                line: diagnostic.line,
+                column: diagnostic.column,
+                end_line: diagnostic.end_line,
+                end_column: diagnostic.end_column,
+            },
+            before: before.to_string(),
+            after: after.to_string(),
+            safety: applied_fix.safety,
+            confidence: diagnostic.ai_confidence.map(|confidence| confidence / 100.0),
+        });
+    }
+
+    plan.fixes.sort_by_key(|fix| (fix.span.line, fix.span.column));
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_lint::lint_buffer_with_rules;
+    use crate::moon_pdk_interface::mutation_count;
+    use crate::rulebase::testing::static_rule;
+
+    #[test]
+    fn test_plan_lists_both_fixes_and_modifies_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("totals.ts");
+        let source = "let total = 1;\nconsole.log(total);\nlet label = 'sum';\nconsole.log(label);\n";
+        std::fs::write(&path, source).unwrap();
+        let file_path = path.to_str().unwrap();
+        let mutations = mutation_count();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let result = lint_buffer_with_rules(&content, file_path, &MoonShineConfig::default(), &[static_rule("prefer-const")]).unwrap();
+        let plan = file_plan(&content, &result);
+
+        let span = |line| FixSpan {
+            line,
+            column: 1,
+            end_line: line,
+            end_column: 4,
+        };
+        let planned: Vec<(&str, FixSpan, &str, &str)> = plan
+            .fixes
+            .iter()
+            .map(|fix| (fix.rule.as_str(), fix.span, fix.before.as_str(), fix.after.as_str()))
+            .collect();
+        assert_eq!(planned, vec![("prefer-const", span(1), "let", "const"), ("prefer-const", span(3), "let", "const")]);
+        assert!(plan.fixes.iter().all(|fix| fix.safety == FixSafety::Safe && fix.confidence.is_none()));
+        assert_eq!(plan.file_path, file_path);
+
+        let json = serde_json::to_value(&plan).unwrap();
+        assert_eq!(json["fixes"][1]["span"]["line"], 3);
+        assert_eq!(json["fixes"][0]["safety"], "safe");
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), source);
+        assert_eq!(mutation_count(), mutations);
+    }
+}
@@ -0,0 +1,99 @@
+//! # Generated File Detection
+//!
+//! Generated code (protobuf stubs, GraphQL clients, bundler output) is rewritten by its
+//! generator, so AI fixes on it are wasted tokens and hand edits are lost on the next
+//! regeneration. A file is generated when an `@generated` or `Code generated` comment
+//! appears in its header, or when its path matches `linting.generated_file_patterns`.
+//! AI passes never run on such files; `linting.generated_files` decides whether static
+//! rules and their fixes still do.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::config::{GeneratedFilePolicy, LintingConfig};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// Leading lines searched for a generated-file marker
+const HEADER_LINES: usize = 10;
+
+/// Whether a comment in the first lines of `source` marks it as generated
+pub fn has_generated_header(source: &str) -> bool {
+    source
+        .lines()
+        .take(HEADER_LINES)
+        .map(str::trim_start)
+        .filter(|line| line.starts_with("//") || line.starts_with("/*") || line.starts_with('*'))
+        .any(|line| line.contains("@generated") || line.trim_start_matches(['/', '*', '!', ' ']).starts_with("Code generated"))
+}
+
+/// Generated-file globs and the policy that applies to matching files
+#[derive(Debug, Clone)]
+pub struct GeneratedFiles {
+    patterns: GlobSet,
+    policy: GeneratedFilePolicy,
+}
+
+impl Default for GeneratedFiles {
+    fn default() -> Self {
+        Self::from_config(&LintingConfig::default())
+    }
+}
+
+impl GeneratedFiles {
+    /// Detection from `linting.generated_file_patterns` and `linting.generated_files`.
+    ///
+    /// Invalid globs are skipped with a warning; header detection still applies.
+    pub fn from_config(linting: &LintingConfig) -> Self {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &linting.generated_file_patterns {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => moon_warn!("Ignoring invalid linting.generated_file_patterns entry '{}': {}", pattern, e),
+            }
+        }
+
+        Self {
+            patterns: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+            policy: linting.generated_files,
+        }
+    }
+
+    pub fn policy(&self) -> GeneratedFilePolicy {
+        self.policy
+    }
+
+    /// Whether `file_path` with content `source` is generated
+    pub fn is_generated(&self, file_path: &str, source: &str) -> bool {
+        let path = Path::new(file_path);
+        self.patterns.is_match(path.strip_prefix("./").unwrap_or(path)) || has_generated_header(source)
+    }
+
+    /// The policy for `file_path` when it is generated
+    pub fn policy_for(&self, file_path: &str, source: &str) -> Option<GeneratedFilePolicy> {
+        self.is_generated(file_path, source).then_some(self.policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_headers_and_patterns_are_detected() {
+        let generated = GeneratedFiles::default();
+
+        assert!(generated.is_generated("src/api.ts", "// @generated by protoc-gen-ts\nexport const a = 1;\n"));
+        assert!(generated.is_generated("src/api.ts", "/**\n * @generated\n */\nexport const a = 1;\n"));
+        assert!(generated.is_generated("src/api.ts", "// Code generated by gqlgen. DO NOT EDIT.\nexport const a = 1;\n"));
+        assert!(generated.is_generated("src/proto/user.pb.ts", "export const a = 1;\n"));
+
+        assert!(!generated.is_generated("src/api.ts", "export const a = 1;\n"));
+        assert!(!generated.is_generated("src/api.ts", "const label = \"@generated\";\n"), "only comments mark a file");
+    }
+}
@@ -0,0 +1,154 @@
+//! # Retrying Runs on Transient Host Failures
+//!
+//! Reads the Moon host mediates (extension config, files) can fail transiently in CI: a
+//! busy filesystem, an interrupted call, a host timeout. Rather than abort the run on one
+//! flaky read, `execute_extension_logic` re-runs the extension up to `--host-retries`
+//! times when the failure is classified as transient. Configuration and logic errors are
+//! never retried. Backoff sleeps through the host, since WASM guests cannot block.
+//!
+//! @category orchestration
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// Retries after the first attempt when `--host-retries` is not given
+pub const DEFAULT_HOST_RETRIES: u32 = 2;
+
+/// Backoff before the first retry; doubled for each further retry
+const BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Host error messages that indicate a condition worth retrying
+const TRANSIENT_MARKERS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "temporarily unavailable",
+    "resource busy",
+    "interrupted",
+    "connection reset",
+    "try again",
+];
+
+/// How often and how patiently to retry a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostRetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl Default for HostRetryPolicy {
+    fn default() -> Self {
+        Self::new(DEFAULT_HOST_RETRIES)
+    }
+}
+
+impl HostRetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            base_delay: BASE_DELAY,
+        }
+    }
+
+    /// Backoff before retry number `retry` (0-based)
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.base_delay.saturating_mul(2u32.saturating_pow(retry))
+    }
+}
+
+/// Whether an I/O failure of this kind may succeed when retried
+pub fn is_transient_io_kind(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::Interrupted | ErrorKind::TimedOut | ErrorKind::WouldBlock | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted
+    )
+}
+
+/// Whether a host error message describes a transient condition
+pub fn is_transient_message(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    TRANSIENT_MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// Run `operation`, retrying while it fails with an error `is_transient` accepts.
+///
+/// `backoff` is called with the delay before each retry; pass [`host_backoff`] outside tests.
+pub fn retry_transient<T, E>(
+    policy: &HostRetryPolicy,
+    mut backoff: impl FnMut(Duration),
+    is_transient: impl Fn(&E) -> bool,
+    mut operation: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut retries_done = 0;
+    loop {
+        match operation() {
+            Err(error) if retries_done < policy.max_retries && is_transient(&error) => {
+                let delay = policy.delay(retries_done);
+                moon_warn!(
+                    "Transient host failure - retrying run in {}ms ({}/{})",
+                    delay.as_millis(),
+                    retries_done + 1,
+                    policy.max_retries
+                );
+                backoff(delay);
+                retries_done += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Wait `delay` by asking the host to sleep; WASM guests have no blocking sleep of their own
+pub fn host_backoff(delay: Duration) {
+    #[cfg(feature = "wasm")]
+    {
+        use crate::moon_pdk_interface::{execute_command, ExecCommandInput};
+
+        let input = ExecCommandInput {
+            command: "sleep".to_string(),
+            args: vec![format!("{:.3}", delay.as_secs_f64())],
+            env: std::collections::HashMap::new(),
+            working_dir: None,
+        };
+        if let Err(e) = execute_command(input) {
+            moon_debug!("Host backoff unavailable, retrying immediately: {}", e);
+        }
+    }
+    #[cfg(not(feature = "wasm"))]
+    {
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_retries_stop_at_policy_limit() {
+        let mut attempts = 0;
+        let mut delays = Vec::new();
+
+        let result: Result<(), Error> = retry_transient(&HostRetryPolicy::new(2), |delay| delays.push(delay), Error::is_transient_host, || {
+            attempts += 1;
+            Err(Error::moon_pdk("host_read_file timed out"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 3);
+        assert_eq!(delays, vec![Duration::from_millis(250), Duration::from_millis(500)]);
+    }
+
+    #[test]
+    fn test_transient_markers_classify_host_messages() {
+        assert!(is_transient_message("Resource temporarily unavailable (os error 11)"));
+        assert!(is_transient_message("host call Timed Out after 30s"));
+        assert!(!is_transient_message("invalid type: string \"fast\", expected f32"));
+        assert!(is_transient_io_kind(ErrorKind::Interrupted));
+        assert!(!is_transient_io_kind(ErrorKind::NotFound));
+    }
+}
@@ -0,0 +1,150 @@
+//! # Limits on Delegated Host Tasks
+//!
+//! TypeScript checks, ESLint and the formatter run as native commands through the Moon
+//! host. A hung compiler or a run that prints megabytes of diagnostics must not stall or
+//! bloat the extension, so each task is bounded by `host_task_timeout_ms` and
+//! `host_task_max_output_bytes`. A breach surfaces as `Error::Timeout` or
+//! `Error::OutputLimit` rather than a generic command failure.
+//!
+//! Natively the task runs on a worker thread and is abandoned when the timeout passes; its
+//! late result is discarded. WASM guests cannot preempt a host call, so there an overrun is
+//! detected once the call returns.
+//!
+//! @category orchestration
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::config::MoonShineConfig;
+use crate::error::{Error, Result};
+use crate::moon_pdk_interface::{ensure_mutation_allowed, execute_command, ExecCommandInput, ExecCommandOutput};
+use std::time::Duration;
+
+/// Bounds applied to every delegated host task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostTaskLimits {
+    pub timeout: Duration,
+    pub max_output_bytes: u64,
+}
+
+impl Default for HostTaskLimits {
+    fn default() -> Self {
+        Self::from_config(&MoonShineConfig::default())
+    }
+}
+
+impl HostTaskLimits {
+    /// Limits from `host_task_timeout_ms` and `host_task_max_output_bytes`
+    pub fn from_config(config: &MoonShineConfig) -> Self {
+        Self {
+            timeout: Duration::from_millis(config.host_task_timeout_ms),
+            max_output_bytes: config.host_task_max_output_bytes,
+        }
+    }
+}
+
+/// Run `input` through the host as `task_name`, within `limits`
+pub fn execute_host_task(task_name: &str, input: ExecCommandInput, limits: &HostTaskLimits) -> Result<ExecCommandOutput> {
+    // Safe mode is per thread, so it is checked here rather than on the worker
+    ensure_mutation_allowed(format!("execute `{}`", input.command))?;
+    run_with_limits(task_name, limits, move || execute_command(input).map_err(|e| e.to_string()))
+}
+
+/// Run `task` as `task_name`, failing with `Error::Timeout` or `Error::OutputLimit` when it breaches `limits`
+pub fn run_with_limits<F>(task_name: &str, limits: &HostTaskLimits, task: F) -> Result<ExecCommandOutput>
+where
+    F: FnOnce() -> std::result::Result<ExecCommandOutput, String> + Send + 'static,
+{
+    let timeout_ms = u64::try_from(limits.timeout.as_millis()).unwrap_or(u64::MAX);
+
+    #[cfg(not(feature = "wasm"))]
+    let result = {
+        use std::sync::mpsc::{self, RecvTimeoutError};
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The receiver is gone once the task timed out; its late result is dropped
+            let _ = sender.send(task());
+        });
+        match receiver.recv_timeout(limits.timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                moon_warn!("Host task {} exceeded {}ms and was abandoned", task_name, timeout_ms);
+                return Err(Error::timeout(task_name, timeout_ms));
+            }
+            Err(RecvTimeoutError::Disconnected) => Err("host task panicked".to_string()),
+        }
+    };
+    #[cfg(feature = "wasm")]
+    let result = {
+        let started = std::time::Instant::now();
+        let result = task();
+        if started.elapsed() > limits.timeout {
+            moon_warn!("Host task {} exceeded {}ms", task_name, timeout_ms);
+            return Err(Error::timeout(task_name, timeout_ms));
+        }
+        result
+    };
+
+    let output = result.map_err(|message| Error::moon_pdk(format!("{}: {}", task_name, message)))?;
+    let output_bytes = (output.stdout.len() + output.stderr.len()) as u64;
+    if output_bytes > limits.max_output_bytes {
+        moon_warn!("Host task {} produced {} bytes, over the {}-byte limit", task_name, output_bytes, limits.max_output_bytes);
+        return Err(Error::output_limit(task_name, limits.max_output_bytes, output_bytes));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(stdout: String) -> ExecCommandOutput {
+        ExecCommandOutput {
+            command: "eslint".to_string(),
+            exit_code: 0,
+            stdout,
+            stderr: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_slow_host_task_times_out() {
+        let limits = HostTaskLimits {
+            timeout: Duration::from_millis(20),
+            ..HostTaskLimits::default()
+        };
+
+        let result = run_with_limits("TypeScript check", &limits, || {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(output(String::new()))
+        });
+
+        assert!(matches!(result, Err(Error::Timeout { ref task_name, duration_ms: 20 }) if task_name == "TypeScript check"));
+        assert_eq!(result.unwrap_err().category(), "timeout");
+    }
+
+    #[test]
+    fn test_oversized_host_task_output_is_rejected() {
+        let limits = HostTaskLimits {
+            max_output_bytes: 1024,
+            ..HostTaskLimits::default()
+        };
+
+        let result = run_with_limits("ESLint", &limits, || Ok(output("x".repeat(4096))));
+        assert!(matches!(
+            result,
+            Err(Error::OutputLimit {
+                limit_bytes: 1024,
+                actual_bytes: 4096,
+                ..
+            })
+        ));
+
+        let within = run_with_limits("ESLint", &limits, || Ok(output("x".repeat(1024)))).unwrap();
+        assert_eq!(within.stdout.len(), 1024);
+        let failed = run_with_limits("ESLint", &limits, || Err("eslint: command not found".to_string())).unwrap_err();
+        assert_eq!(failed.category(), "moon_pdk");
+    }
+}
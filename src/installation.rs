@@ -18,6 +18,7 @@
 use crate::config::MoonShineConfig;
 use crate::moon_pdk_interface::get_moon_config;
 use crate::prompts;
+use serde::{Deserialize, Serialize};
 use serde_json;
 
 /// Initializes the `moon-shine` directory structure and base prompt templates.
@@ -251,8 +252,11 @@ fn create_initial_training_file() -> anyhow::Result<serde_json::Value> {
 ///
 /// This function implements a priority-based loading mechanism:
 /// 1. Custom prompts from the `MoonShineConfig` (if provided).
-/// 2. Embedded default prompts.
-/// 3. Built-in fallback prompts.
+/// 2. Externally stored prompts (prompt files, prompts.json, Moon config).
+/// 3. Embedded default prompts.
+/// 4. Built-in fallback prompts.
+///
+/// Resolved templates are cached until `custom_prompts` changes.
 ///
 /// @param rule_type The type of rule for which to load the prompt (e.g., "no_unused_vars").
 /// @param config An optional reference to the `MoonShineConfig` for custom prompt overrides.
@@ -265,7 +269,7 @@ fn create_initial_training_file() -> anyhow::Result<serde_json::Value> {
 /// @since 1.0.0
 pub fn load_prompt_from_storage(rule_type: &str, config: Option<&MoonShineConfig>) -> String {
     let custom_prompts = config.and_then(|c| c.custom_prompts.as_ref());
-    prompts::get_prompt(rule_type, custom_prompts)
+    prompts::cached_prompt(rule_type, custom_prompts)
 }
 
 /// Logs an optimized prompt learned by DSPy COPRO to `prompts.json`.
@@ -376,6 +380,321 @@ pub fn log_optimized_prompt_update(rule_type: &str, optimized_data: &serde_json:
     }
 }
 
+/// Health of a single configured AI provider as seen by `--doctor`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderHealth {
+    pub name: String,
+    pub command: String,
+    pub credentials_ok: bool,
+    pub reachable: bool,
+    pub detail: String,
+}
+
+/// Structured self-check report produced by `--doctor`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub version: String,
+    pub installed: bool,
+    pub config_valid: bool,
+    pub config_error: Option<String>,
+    pub providers: Vec<ProviderHealth>,
+    pub rules_loaded: usize,
+    /// Versions of the external tools; mismatches are reported without failing the check
+    pub tools: ToolReport,
+    pub healthy: bool,
+}
+
+/// Runs the `--doctor` self-check against the live environment.
+///
+/// Combines the installation check, configuration validation, a connectivity ping
+/// for every configured AI provider, the number of loaded rules and the external tool
+/// versions from [`verify_tools`] into a single [`HealthReport`]. Providers are pinged
+/// with `<command> --version`, so no AI requests (and no tokens) are spent.
+///
+/// @param config The configuration to validate and to load rules from.
+/// @returns A `HealthReport` describing the extension's health.
+///
+/// @category diagnostics
+/// @safe team
+/// @mvp core
+/// @complexity medium
+/// @since 2.0.0
+pub fn run_doctor(config: &MoonShineConfig) -> HealthReport {
+    let router = crate::provider_router::get_ai_router();
+    build_health_report(
+        config,
+        check_moonshine_installed(),
+        router.providers(),
+        |key| std::env::var(key).ok(),
+        ping_provider_cli,
+        verify_tools(),
+    )
+}
+
+/// Builds a [`HealthReport`] with injected environment lookup and provider ping.
+///
+/// `env_lookup` resolves credential environment variables, `ping` checks that a
+/// provider CLI is reachable and `tools` is the tool version check, which keeps the
+/// report deterministic under test.
+///
+/// @category diagnostics
+/// @safe team
+/// @mvp core
+/// @complexity medium
+/// @since 2.0.0
+pub fn build_health_report<E, P>(
+    config: &MoonShineConfig,
+    installed: bool,
+    providers: &[crate::provider_router::AIProviderConfig],
+    env_lookup: E,
+    ping: P,
+    tools: ToolReport,
+) -> HealthReport
+where
+    E: Fn(&str) -> Option<String>,
+    P: Fn(&crate::provider_router::AIProviderConfig) -> Result<(), String>,
+{
+    let config_error = config.clone().validate().err().map(|e| e.to_string());
+
+    let providers: Vec<ProviderHealth> = providers
+        .iter()
+        .map(|provider| {
+            let missing_credential = if provider.requires_api_key {
+                match provider.api_key_env.as_deref() {
+                    Some(key) if !key.is_empty() => env_lookup(key).filter(|value| !value.is_empty()).is_none().then(|| key.to_string()),
+                    _ => Some("<unset api_key_env>".to_string()),
+                }
+            } else {
+                None
+            };
+
+            let ping_result = ping(provider);
+            let detail = match (&missing_credential, &ping_result) {
+                (Some(key), _) => format!("missing credential: {}", key),
+                (None, Err(e)) => format!("unreachable: {}", e),
+                (None, Ok(())) => "ok".to_string(),
+            };
+
+            ProviderHealth {
+                name: provider.name.clone(),
+                command: provider.command.clone(),
+                credentials_ok: missing_credential.is_none(),
+                reachable: ping_result.is_ok(),
+                detail,
+            }
+        })
+        .collect();
+
+    let rules_loaded = crate::extension::get_available_rules(Some(config)).len();
+    let config_valid = config_error.is_none();
+    let healthy = installed && config_valid && rules_loaded > 0 && providers.iter().any(|p| p.credentials_ok && p.reachable);
+
+    HealthReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        installed,
+        config_valid,
+        config_error,
+        providers,
+        rules_loaded,
+        tools,
+        healthy,
+    }
+}
+
+/// Pings a provider CLI with `--version` to confirm it is installed and runnable.
+fn ping_provider_cli(provider: &crate::provider_router::AIProviderConfig) -> Result<(), String> {
+    let input = crate::moon_pdk_interface::ExecCommandInput {
+        command: provider.command.clone(),
+        args: vec!["--version".to_string()],
+        env: std::collections::HashMap::new(),
+        working_dir: None,
+    };
+
+    match crate::moon_pdk_interface::execute_command(input) {
+        Ok(output) if output.exit_code == 0 => Ok(()),
+        Ok(output) => Err(format!("exit code {}: {}", output.exit_code, output.stderr.trim())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Version requirement for an external tool that moon-shine drives through Moon tasks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolRequirement {
+    pub name: String,
+    pub command: String,
+    pub version_args: Vec<String>,
+    pub minimum: (u64, u64, u64),
+    /// First major version known to be incompatible, if any
+    pub incompatible_from_major: Option<u64>,
+}
+
+/// Outcome of checking a single tool against its [`ToolRequirement`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToolStatus {
+    Compatible,
+    Missing,
+    BelowMinimum,
+    Incompatible,
+    UnknownVersion,
+}
+
+/// Version check result for a single tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCheck {
+    pub name: String,
+    pub required: String,
+    pub found: Option<String>,
+    pub status: ToolStatus,
+}
+
+/// Report produced by [`verify_tools`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolReport {
+    pub tools: Vec<ToolCheck>,
+}
+
+impl ToolReport {
+    /// Tools that are missing or outside their supported range.
+    pub fn mismatches(&self) -> Vec<&ToolCheck> {
+        self.tools.iter().filter(|tool| tool.status != ToolStatus::Compatible).collect()
+    }
+
+    /// Whether every required tool is present and compatible.
+    pub fn is_compatible(&self) -> bool {
+        self.mismatches().is_empty()
+    }
+}
+
+/// Returns the supported version ranges for the tools moon-shine depends on.
+///
+/// This is the single source of truth for tool compatibility; [`verify_tools`], and
+/// through it `--doctor`, checks the installed tools against these ranges.
+///
+/// @returns The list of `ToolRequirement`s.
+///
+/// @category installation
+/// @safe team
+/// @mvp core
+/// @complexity low
+/// @since 2.0.0
+pub fn required_versions() -> Vec<ToolRequirement> {
+    let requirement = |name: &str, command: &str, minimum: (u64, u64, u64), incompatible_from_major: Option<u64>| ToolRequirement {
+        name: name.to_string(),
+        command: command.to_string(),
+        version_args: vec!["--version".to_string()],
+        minimum,
+        incompatible_from_major,
+    };
+
+    vec![
+        requirement("node", "node", (18, 0, 0), None),
+        requirement("typescript", "tsc", (5, 0, 0), Some(6)),
+        requirement("eslint", "eslint", (8, 57, 0), Some(10)),
+        requirement("prettier", "prettier", (3, 0, 0), Some(4)),
+    ]
+}
+
+/// Verifies that the required tools are installed at compatible versions.
+///
+/// Each tool's version is queried through the Moon host task runner, compared
+/// against [`required_versions`], and reported so environment drift (for example
+/// an outdated ESLint or a TypeScript major we do not support) is caught early
+/// instead of producing silently wrong results.
+///
+/// @returns A `ToolReport` listing every tool and its status.
+///
+/// @category installation
+/// @safe team
+/// @mvp core
+/// @complexity medium
+/// @since 2.0.0
+pub fn verify_tools() -> ToolReport {
+    verify_tools_with(&required_versions(), query_tool_version)
+}
+
+/// Verifies `requirements` using `probe` to obtain each tool's `--version` output.
+///
+/// `probe` returns `None` when the tool cannot be executed.
+///
+/// @category installation
+/// @safe team
+/// @mvp core
+/// @complexity medium
+/// @since 2.0.0
+pub fn verify_tools_with<F>(requirements: &[ToolRequirement], probe: F) -> ToolReport
+where
+    F: Fn(&ToolRequirement) -> Option<String>,
+{
+    let tools = requirements
+        .iter()
+        .map(|requirement| {
+            let (minimum_major, minimum_minor, minimum_patch) = requirement.minimum;
+            let required = match requirement.incompatible_from_major {
+                Some(major) => format!(">={}.{}.{} <{}", minimum_major, minimum_minor, minimum_patch, major),
+                None => format!(">={}.{}.{}", minimum_major, minimum_minor, minimum_patch),
+            };
+
+            let output = probe(requirement);
+            let version = output.as_deref().and_then(parse_tool_version);
+            let status = match (&output, version) {
+                (None, _) => ToolStatus::Missing,
+                (Some(_), None) => ToolStatus::UnknownVersion,
+                (Some(_), Some(found)) if found < requirement.minimum => ToolStatus::BelowMinimum,
+                (Some(_), Some((major, _, _))) if requirement.incompatible_from_major.is_some_and(|limit| major >= limit) => ToolStatus::Incompatible,
+                (Some(_), Some(_)) => ToolStatus::Compatible,
+            };
+
+            if status != ToolStatus::Compatible {
+                moon_warn!("Tool {} does not satisfy {} ({:?})", requirement.name, required, status);
+            }
+
+            ToolCheck {
+                name: requirement.name.clone(),
+                required,
+                found: version.map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch)),
+                status,
+            }
+        })
+        .collect();
+
+    ToolReport { tools }
+}
+
+/// Extracts the first `major.minor[.patch]` version from tool output such as `v18.19.0` or `Version 5.4.2`.
+fn parse_tool_version(output: &str) -> Option<(u64, u64, u64)> {
+    output.split(|c: char| c.is_whitespace() || c == ',').find_map(|token| {
+        let token = token.trim_start_matches(|c: char| !c.is_ascii_digit());
+        let mut parts = token.split('.').map(|part| part.chars().take_while(char::is_ascii_digit).collect::<String>());
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        let patch = parts.next().and_then(|part| part.parse().ok()).unwrap_or(0);
+        Some((major, minor, patch))
+    })
+}
+
+/// Runs `<command> --version` through the Moon host and returns its output.
+fn query_tool_version(requirement: &ToolRequirement) -> Option<String> {
+    let input = crate::moon_pdk_interface::ExecCommandInput {
+        command: requirement.command.clone(),
+        args: requirement.version_args.clone(),
+        env: std::collections::HashMap::new(),
+        working_dir: None,
+    };
+
+    match crate::moon_pdk_interface::execute_command(input) {
+        Ok(output) if output.exit_code == 0 => Some(output.stdout),
+        Ok(output) => {
+            moon_debug!("{} --version exited with {}: {}", requirement.command, output.exit_code, output.stderr.trim());
+            None
+        }
+        Err(e) => {
+            moon_debug!("{} --version failed: {}", requirement.command, e);
+            None
+        }
+    }
+}
+
 #[cfg(all(test, not(feature = "wasm")))]
 mod tests {
     use super::*;
@@ -607,4 +926,118 @@ mod tests {
         let training_meta = training.get("meta").unwrap();
         assert!(training_meta.get("created_at").is_some());
     }
+
+    fn doctor_provider(name: &str, api_key_env: Option<&str>) -> crate::provider_router::AIProviderConfig {
+        crate::provider_router::AIProviderConfig {
+            name: name.to_string(),
+            command: name.to_string(),
+            model: "test-model".to_string(),
+            api_key_env: api_key_env.map(str::to_string),
+            requires_api_key: api_key_env.is_some(),
+            capabilities: crate::provider_router::ProviderCapabilities {
+                code_analysis: 0.9,
+                code_generation: 0.9,
+                complex_reasoning: 0.9,
+                speed: 0.9,
+                context_length: 100000,
+                supports_sessions: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_doctor_reports_missing_provider_credential() {
+        let config = MoonShineConfig::default();
+        let providers = vec![doctor_provider("claude", None), doctor_provider("openai", Some("OPENAI_API_KEY"))];
+
+        let report = build_health_report(&config, true, &providers, |_| None, |_| Ok(()), ToolReport { tools: Vec::new() });
+
+        let claude = &report.providers[0];
+        assert!(claude.credentials_ok && claude.reachable);
+        assert_eq!(claude.detail, "ok");
+
+        let openai = &report.providers[1];
+        assert!(!openai.credentials_ok);
+        assert_eq!(openai.detail, "missing credential: OPENAI_API_KEY");
+
+        // One usable provider is enough for the extension to be healthy
+        assert!(report.healthy);
+    }
+
+    #[test]
+    fn test_doctor_reports_rulebase_load_count() {
+        let config = MoonShineConfig::default();
+        let providers = vec![doctor_provider("gemini", None)];
+
+        let report = build_health_report(
+            &config,
+            false,
+            &providers,
+            |_| None,
+            |_| Err("command not found".to_string()),
+            ToolReport { tools: Vec::new() },
+        );
+
+        assert_eq!(report.rules_loaded, crate::extension::get_available_rules(Some(&config)).len());
+        assert!(report.rules_loaded > 0);
+        assert!(report.config_valid);
+        assert!(!report.providers[0].reachable);
+        assert_eq!(report.providers[0].detail, "unreachable: command not found");
+        assert!(!report.healthy);
+    }
+
+    #[test]
+    fn test_verify_tools_reports_version_below_minimum() {
+        let requirements = required_versions();
+
+        let report = verify_tools_with(&requirements, |requirement| match requirement.name.as_str() {
+            "eslint" => Some("v8.10.0\n".to_string()),
+            "typescript" => Some("Version 6.0.1".to_string()),
+            "prettier" => None,
+            _ => Some("v20.11.1".to_string()),
+        });
+
+        let status = |name: &str| report.tools.iter().find(|tool| tool.name == name).unwrap().status;
+        assert_eq!(status("eslint"), ToolStatus::BelowMinimum);
+        assert_eq!(status("typescript"), ToolStatus::Incompatible);
+        assert_eq!(status("prettier"), ToolStatus::Missing);
+        assert_eq!(status("node"), ToolStatus::Compatible);
+
+        let eslint = report.tools.iter().find(|tool| tool.name == "eslint").unwrap();
+        assert_eq!(eslint.found.as_deref(), Some("8.10.0"));
+        assert_eq!(eslint.required, ">=8.57.0 <10");
+        assert_eq!(report.mismatches().len(), 3);
+        assert!(!report.is_compatible());
+    }
+
+    #[test]
+    fn test_doctor_reports_tool_version_mismatches() {
+        let config = MoonShineConfig::default();
+        let providers = vec![doctor_provider("claude", None)];
+        let tools = verify_tools_with(&required_versions(), |requirement| match requirement.name.as_str() {
+            "eslint" => Some("v8.10.0".to_string()),
+            _ => None,
+        });
+
+        let report = build_health_report(&config, true, &providers, |_| None, |_| Ok(()), tools);
+
+        let eslint = report.tools.tools.iter().find(|tool| tool.name == "eslint").unwrap();
+        assert_eq!(eslint.status, ToolStatus::BelowMinimum);
+        assert_eq!(report.tools.mismatches().len(), required_versions().len());
+        assert!(report.healthy, "tool mismatches are reported, not fatal");
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["tools"]["tools"].as_array().map(Vec::len), Some(required_versions().len()));
+    }
+
+    #[test]
+    fn test_verify_tools_all_compatible() {
+        let report = verify_tools_with(&required_versions(), |requirement| match requirement.name.as_str() {
+            "typescript" => Some("Version 5.4.2".to_string()),
+            "eslint" => Some("v9.1.0".to_string()),
+            "prettier" => Some("3.2.5".to_string()),
+            _ => Some("v18.19.0".to_string()),
+        });
+
+        assert!(report.is_compatible());
+    }
 }
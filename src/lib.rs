@@ -57,28 +57,44 @@ pub mod adaptive_workflow_planner; // Adaptive workflow planning and AI strategy
 pub mod ai_assistance; // AI enhancement and suggestion system
 pub mod ai_code_fixer; // AI-powered code fixing
 pub mod analysis;
+pub mod analytics; // Rule fix effectiveness over recent runs
+pub mod buffer_lint; // Lint in-memory buffers against a virtual path
 pub mod config;
+pub mod cross_file_context; // Imported-file declarations as read-only AI fix context
 pub mod data; // Shared data handling components
+pub mod declaration_files; // Detect .d.ts and ambient-only files, which get type and documentation rules only
+pub mod diff_filter; // Report only diagnostics on lines changed by a diff
 pub mod dspy; // Embedded full DSPy framework <!-- TODO: Verify the completeness and fidelity of this DSPy implementation against the original Python framework. -->
 pub mod error;
 pub mod extension;
+pub mod file_filter; // Include/exclude, .gitignore and node_modules allowlist filtering
+pub mod fix_plan; // Machine-readable plans of the fixes a run would apply
+pub mod generated_files; // Detect generated files, which never get AI passes
+pub mod host_retry; // Re-run the extension on transient host failures
+pub mod host_task; // Timeout and output caps on delegated host tasks
 pub mod installation;
 pub mod oxc_adapter; // Modern OXC + AI behavioral linting system
                      // pub mod linter; // Disabled - replaced by Biome + AI analysis system
+pub mod markdown_fences; // Lint TypeScript/JavaScript code fences in markdown
 pub mod message_types; // Message and ConversationHistory structures
 pub mod moon_host; // Centralized re-export of required Extism symbols
 pub mod moon_pdk_interface; // Moon PDK communication interface
                             // pub mod pattern_config; // Legacy pattern config - replaced by Biome + AI system
 pub mod prompts; // Embedded prompt management
 pub mod provider_router; // AI provider routing and selection
+pub mod reporting; // Human-readable terminal report output
 pub mod rule_registry; // Rule registry and metadata management
 pub mod rule_types; // Modern rule types for Biome + AI system
 pub mod rulebase; // JSON-based rulebase and execution scaffolding
+pub mod session; // Per-run session directories and retention pruning
+pub mod single_rule; // Run one rule alone on a buffer
+pub mod source_encoding; // UTF-8 checks and latin-1 fallback when reading source files
 pub mod storage; // Hybrid assemblage_kv + file persistence
 pub mod telemetry; // Telemetry logging for workflow runs
 pub mod token_usage; // LM token usage tracking
 pub mod types; // Core data structures for moon-shine code analysis
 pub mod workflow; // Sequential workflow engine for orchestrated analysis pipelines
+pub mod write_transaction; // All-or-nothing multi-file writes for --atomic
 pub mod multi_language_analyzer; // Multi-language analysis system (TypeScript/JavaScript + Rust)
 pub mod javascript_typescript_linter; // JavaScript/TypeScript linting using OXC
 pub mod templates;
@@ -87,14 +103,16 @@ pub mod tsdoc; // Lightweight TSDoc analysis helpers // Rule generation template
 
 
 // Re-exports for convenience
-pub use analysis::MoonShineResponse;
-pub use config::{MoonShineArgs, MoonShineConfig};
+pub use analysis::{MoonShineResponse, RunMetrics};
+pub use buffer_lint::{lint_buffer, lint_buffer_in_mode, lint_range, BufferLintResult};
+pub use config::{MoonShineArgs, MoonShineConfig, OperationMode};
 pub use data::{Example, Prediction}; // Add data types re-export
 // Legacy exports removed - using modern Biome + AI system
 pub use extension::{ExecuteExtensionInput, ExtensionManifest};
 pub use oxc_adapter::{AiBehavioralAnalyzer, MultiEngineAnalyzer, MultiEngineConfig, OxcAdapter};
 pub use multi_language_analyzer::{LanguageConfig, MultiLanguageAnalyzer, SupportedLanguage};
-pub use rule_types::{FixStatus, RuleCategory, RuleMetadata, RuleRegistryStats, RuleSeverity};
+pub use rule_types::{FixSafety, FixStatus, RuleCategory, RuleMetadata, RuleRegistryStats, RuleSeverity};
+pub use single_rule::run_single_rule;
 // Legacy workflow exports removed
 
 /// Registers the `moon-shine` extension with the Moon task orchestration system.
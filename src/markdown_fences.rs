@@ -0,0 +1,183 @@
+//! # Linting Code Fences in Markdown
+//!
+//! Documentation snippets drift from the real API because nothing checks them. With
+//! `linting.markdown` (`--markdown`) every fenced TypeScript/JavaScript block in a `.md`
+//! file is linted as its own virtual file, and diagnostics are mapped back to the
+//! markdown lines the code sits on. Fixes rewrite only the content between the fence
+//! markers. Fences in other languages, or without a language, are ignored.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::buffer_lint::{configured_rules, lint_buffer_with_rules};
+use crate::config::MoonShineConfig;
+use crate::error::Result;
+use crate::rule_registry::RuleRegistry;
+use crate::rule_types::RuleMetadata;
+use crate::types::LintDiagnostic;
+use std::ops::Range;
+
+/// A fenced TypeScript/JavaScript block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeFence {
+    pub extension: &'static str, // Extension of the virtual file, from the info string
+    pub content: Range<usize>,   // Byte range of the code between the fence lines
+    pub first_line: u32,         // Markdown line of the first code line, 1-based
+}
+
+/// Diagnostics of all fences of a markdown file, and the file with fixes applied
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownLintResult {
+    pub fences: usize,
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub fixed_markdown: Option<String>, // Set only when a fix changed a fence
+}
+
+/// Whether `path` is a markdown file
+pub fn is_markdown_path(path: &str) -> bool {
+    let path = path.to_ascii_lowercase();
+    path.ends_with(".md") || path.ends_with(".markdown")
+}
+
+/// Virtual file extension for a fence info string such as `ts` or `javascript title="a.js"`
+fn fence_extension(info: &str) -> Option<&'static str> {
+    match info.split_whitespace().next()?.to_ascii_lowercase().as_str() {
+        "ts" | "typescript" | "mts" | "cts" => Some("ts"),
+        "tsx" => Some("tsx"),
+        "js" | "javascript" | "mjs" | "cjs" => Some("js"),
+        "jsx" => Some("jsx"),
+        _ => None,
+    }
+}
+
+/// Fenced TypeScript/JavaScript blocks of `markdown`, in document order
+pub fn code_fences(markdown: &str) -> Vec<CodeFence> {
+    let mut fences = Vec::new();
+    // Marker character and length of the open fence, its extension, and where its content started
+    let mut open: Option<(char, usize, Option<&'static str>, usize, u32)> = None;
+    let mut offset = 0;
+
+    for (index, line) in markdown.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let indent = trimmed.len() - trimmed.trim_start_matches(' ').len();
+        let marker_line = &trimmed[indent..];
+        let marker = marker_line.chars().next().filter(|c| indent <= 3 && (*c == '`' || *c == '~'));
+        let marker_len = marker.map(|c| marker_line.len() - marker_line.trim_start_matches(c).len()).unwrap_or(0);
+
+        match open {
+            None if marker_len >= 3 => {
+                let info = &marker_line[marker_len..];
+                // Backtick fences cannot have backticks in their info string
+                if marker == Some('`') && info.contains('`') {
+                    continue;
+                }
+                open = Some((marker.unwrap_or('`'), marker_len, fence_extension(info), offset, index as u32 + 2));
+            }
+            Some((open_marker, open_len, extension, content_start, first_line))
+                if marker == Some(open_marker) && marker_len >= open_len && marker_line[marker_len..].trim().is_empty() =>
+            {
+                if let Some(extension) = extension {
+                    fences.push(CodeFence {
+                        extension,
+                        content: content_start..line_start,
+                        first_line,
+                    });
+                }
+                open = None;
+            }
+            _ => {}
+        }
+    }
+
+    // An unclosed fence runs to the end of the document
+    if let Some((_, _, Some(extension), content_start, first_line)) = open {
+        fences.push(CodeFence {
+            extension,
+            content: content_start..markdown.len(),
+            first_line,
+        });
+    }
+
+    fences
+}
+
+/// Lint the code fences of `markdown` with every enabled rule
+pub fn lint_markdown(markdown: &str, path: &str, config: &MoonShineConfig) -> Result<MarkdownLintResult> {
+    let registry = RuleRegistry::new()?;
+    lint_markdown_with_rules(markdown, path, config, &configured_rules(&registry, config))
+}
+
+/// Lint the code fences of `markdown` with an explicit rule set; see [`lint_markdown`]
+pub fn lint_markdown_with_rules(markdown: &str, path: &str, config: &MoonShineConfig, rules: &[RuleMetadata]) -> Result<MarkdownLintResult> {
+    let fences = code_fences(markdown);
+    let mut result = MarkdownLintResult {
+        fences: fences.len(),
+        ..MarkdownLintResult::default()
+    };
+    let mut fixes = Vec::new();
+
+    for (index, fence) in fences.iter().enumerate() {
+        let virtual_path = format!("{}#fence-{}.{}", path, index + 1, fence.extension);
+        let linted = lint_buffer_with_rules(&markdown[fence.content.clone()], &virtual_path, config, rules)?;
+
+        result.diagnostics.extend(linted.diagnostics.into_iter().map(|mut diagnostic| {
+            diagnostic.file_path = path.to_string();
+            diagnostic.line += fence.first_line - 1;
+            diagnostic.end_line += fence.first_line - 1;
+            diagnostic
+        }));
+        if let Some(fixed_code) = linted.fixed_code {
+            fixes.push((fence.content.clone(), fixed_code));
+        }
+    }
+
+    if !fixes.is_empty() {
+        let mut fixed = markdown.to_string();
+        // Back to front so earlier ranges stay valid
+        for (range, code) in fixes.into_iter().rev() {
+            fixed.replace_range(range, &code);
+        }
+        result.fixed_markdown = Some(fixed);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rulebase::testing::static_rule;
+
+    const README: &str = "# Usage\n\n```sh\nlet count=1\n```\n\nDouble the total:\n\n```ts\nimport { total } from \"./api\";\nlet doubled = total * 2;\nconsole.log(doubled);\n```\n";
+
+    #[test]
+    fn test_only_ts_and_js_fences_are_extracted() {
+        let fences = code_fences(README);
+
+        assert_eq!(fences.len(), 1);
+        assert_eq!(fences[0].extension, "ts");
+        assert_eq!(fences[0].first_line, 10);
+        assert!(README[fences[0].content.clone()].starts_with("import { total }"));
+        assert!(README[fences[0].content.clone()].ends_with("console.log(doubled);\n"));
+    }
+
+    #[test]
+    fn test_diagnostic_maps_to_markdown_line_and_fix_rewrites_only_fence() {
+        let result = lint_markdown_with_rules(README, "docs/README.md", &MoonShineConfig::default(), &[static_rule("prefer-const")]).unwrap();
+
+        assert_eq!(result.fences, 1);
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!((diagnostic.file_path.as_str(), diagnostic.line), ("docs/README.md", 11));
+        assert_eq!(README.lines().nth(diagnostic.line as usize - 1), Some("let doubled = total * 2;"));
+
+        let fixed = result.fixed_markdown.unwrap();
+        assert_eq!(fixed, README.replace("let doubled", "const doubled"));
+        assert!(fixed.contains("let count=1"), "non-code fences are untouched");
+    }
+}
@@ -8,6 +8,7 @@
 use crate::analysis::MoonTaskRequest;
 use crate::config::MoonShineConfig;
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 
 // Use Moon PDK for WASM host communication
 use extism_pdk::host_fn;
@@ -43,8 +44,43 @@ pub struct ExecCommandOutput {
     pub stderr: String,
 }
 
+thread_local! {
+    // The extension runs single-threaded under WASM, so per-thread state is per-run state
+    static SAFE_MODE: Cell<bool> = const { Cell::new(false) };
+    #[cfg(test)]
+    static MUTATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Turn safe mode on or off; while on, every file write and command execution is refused
+pub fn set_safe_mode(enabled: bool) {
+    SAFE_MODE.with(|flag| flag.set(enabled));
+}
+
+/// Whether safe mode is on for this run
+pub fn safe_mode() -> bool {
+    SAFE_MODE.with(Cell::get)
+}
+
+/// Refuse `action` in safe mode; every path that writes files or runs commands calls this first
+pub fn ensure_mutation_allowed(action: impl std::fmt::Display) -> crate::error::Result<()> {
+    if safe_mode() {
+        moon_debug!("Safe mode - refused to {}", action);
+        return Err(crate::error::Error::safe_mode(action.to_string()));
+    }
+    #[cfg(test)]
+    MUTATIONS.with(|count| count.set(count.get() + 1));
+    Ok(())
+}
+
+/// Writes and command executions allowed through [`ensure_mutation_allowed`] on this thread
+#[cfg(test)]
+pub(crate) fn mutation_count() -> usize {
+    MUTATIONS.with(Cell::get)
+}
+
 /// Execute command via Moon host (wrapper for host function)
 pub fn execute_command(input: ExecCommandInput) -> Result<ExecCommandOutput, Box<dyn std::error::Error>> {
+    ensure_mutation_allowed(format!("execute `{}`", input.command))?;
     #[cfg(feature = "wasm")]
     {
         // Use Moon PDK to execute commands
@@ -115,6 +151,23 @@ pub fn read_file_content(path: &str) -> Result<String, Box<dyn std::error::Error
     }
 }
 
+/// Read raw file bytes via Moon PDK, for callers that validate the encoding themselves
+///
+/// Only the native path returns the bytes on disk. The Moon host hands file contents back as a
+/// string, so under `wasm` these are the bytes of already-decoded UTF-8 text: a file that is not
+/// UTF-8 fails the host call instead of reaching the caller's encoding checks.
+pub fn read_file_bytes(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    #[cfg(feature = "wasm")]
+    {
+        let result = unsafe { host_read_file(path.to_string())? };
+        Ok(result.into_bytes())
+    }
+    #[cfg(not(feature = "wasm"))]
+    {
+        Ok(std::fs::read(path)?)
+    }
+}
+
 /// Check if file exists via Moon PDK
 pub fn check_file_exists(path: &str) -> Result<bool, Box<dyn std::error::Error>> {
     #[cfg(feature = "wasm")]
@@ -510,16 +563,21 @@ pub fn write_file_atomic(path: &str, content: &str) -> Result<(), Box<dyn std::e
     }
     #[cfg(not(feature = "wasm"))]
     {
+        ensure_mutation_allowed(format!("write {}", path))?;
         if let Some(parent) = std::path::Path::new(path).parent() {
             std::fs::create_dir_all(parent)?;
         }
-        std::fs::write(path, content)?;
+        // Stage next to the target and rename over it, so readers never see a partial file
+        let staging = format!("{}.tmp", path);
+        std::fs::write(&staging, content)?;
+        std::fs::rename(&staging, path)?;
         Ok(())
     }
 }
 
 /// Helper to write content to a file via Moon PDK
 pub fn write_file_to_host(path: &str, content: &str) -> Result<(), Box<dyn std::error::Error>> {
+    ensure_mutation_allowed(format!("write {}", path))?;
     #[cfg(feature = "wasm")]
     {
         let result = unsafe { host_write_file(path.to_string(), content.to_string())? };
@@ -649,6 +649,9 @@ mod tests {
             file_path: "test.js".to_string(),
             fix_available: false,
             suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
         };
 
         let pattern_id = analyzer.generate_pattern_id(&diagnostic);
@@ -219,6 +219,9 @@ impl AiBehavioralAnalyzer {
                         end_column: (column + 10) as u32,
                         fix_available: result.suggestion.is_some(),
                         suggested_fix: result.suggestion,
+                        explanation: None,
+                        patch: None,
+                        ai_confidence: Some(result.confidence * 100.0),
                     });
                 }
             }
@@ -248,6 +251,9 @@ impl AiBehavioralAnalyzer {
                 end_column: 1,
                 fix_available: true,
                 suggested_fix: Some("Consider extracting complex logic into separate functions".to_string()),
+                explanation: None,
+                patch: None,
+                ai_confidence: None,
             });
         }
 
@@ -264,6 +270,9 @@ impl AiBehavioralAnalyzer {
                 end_column: 1,
                 fix_available: true,
                 suggested_fix: Some("Add corresponding removeEventListener call".to_string()),
+                explanation: None,
+                patch: None,
+                ai_confidence: None,
             });
         }
 
@@ -0,0 +1,218 @@
+//! # Comment Preservation for Codegen Output
+//!
+//! OXC codegen reprints the AST and can drop or move comments, which is unacceptable
+//! for documentation-heavy files. The comment trivia collected by the parser is used
+//! to reattach every comment to the code it belonged to: leading comments (JSDoc
+//! blocks, line comments above a statement) go back on the lines before that
+//! statement, trailing comments back at the end of its line. [`missing_comments`]
+//! then verifies that no input comment is absent from the output.
+
+use oxc_ast::ast::Program;
+
+/// Where a comment sat relative to the code it belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentPlacement {
+    Leading,  // On its own line(s) before the anchor code
+    Trailing, // After the anchor code on the same line
+}
+
+/// A comment from the input together with the code it is attached to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceComment {
+    pub text: String, // Including delimiters, e.g. `/** Adds. */` or `// note`
+    pub placement: CommentPlacement,
+    pub anchor: String, // Code the comment belongs to, comments removed; empty at end of file
+}
+
+/// Comments of a parsed `program`, in source order, with the code each belongs to
+pub fn collect_comments(program: &Program, source: &str) -> Vec<SourceComment> {
+    let spans: Vec<(usize, usize)> = program
+        .comments
+        .iter()
+        .map(|comment| full_comment_span(source, comment.span.start as usize, comment.span.end as usize))
+        .collect();
+
+    // Source with every comment blanked out, so anchors contain code only
+    let mut code_only = source.as_bytes().to_vec();
+    for &(start, end) in &spans {
+        for byte in &mut code_only[start..end] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    let code_only = String::from_utf8_lossy(&code_only).into_owned();
+
+    spans
+        .into_iter()
+        .map(|(start, end)| {
+            let line_start = code_only[..start].rfind('\n').map(|index| index + 1).unwrap_or(0);
+            let before = code_only[line_start..start].trim();
+
+            let (placement, anchor) = if before.is_empty() {
+                let after = code_only[end..].lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or("");
+                (CommentPlacement::Leading, after)
+            } else {
+                (CommentPlacement::Trailing, before)
+            };
+
+            SourceComment {
+                text: source[start..end].to_string(),
+                placement,
+                anchor: anchor.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Put `comments` back into codegen `output` next to their anchor code.
+///
+/// Anchors are matched in source order with whitespace and quote style ignored, so
+/// reformatting does not break the match. Comments whose anchor cannot be found are
+/// left out and reported by [`missing_comments`].
+pub fn reattach_comments(output: &str, comments: &[SourceComment]) -> String {
+    let mut lines: Vec<String> = output.lines().map(str::to_string).collect();
+    let mut cursor = 0;
+
+    for comment in comments {
+        if comment.placement == CommentPlacement::Leading && comment.anchor.is_empty() {
+            lines.extend(comment.text.lines().map(str::to_string));
+            cursor = lines.len();
+            continue;
+        }
+
+        let Some(index) = (cursor..lines.len()).find(|&index| anchor_matches(&lines[index], &comment.anchor)) else {
+            continue;
+        };
+
+        match comment.placement {
+            CommentPlacement::Leading => {
+                let indent: String = lines[index].chars().take_while(|ch| ch.is_whitespace()).collect();
+                let block: Vec<String> = comment
+                    .text
+                    .lines()
+                    .enumerate()
+                    .map(|(line_index, line)| {
+                        let line = line.trim_start();
+                        if line_index > 0 && line.starts_with('*') {
+                            format!("{} {}", indent, line)
+                        } else {
+                            format!("{}{}", indent, line)
+                        }
+                    })
+                    .collect();
+                let inserted = block.len();
+                lines.splice(index..index, block);
+                // The anchor line itself can still take trailing comments
+                cursor = index + inserted;
+            }
+            CommentPlacement::Trailing => {
+                lines[index].push(' ');
+                lines[index].push_str(&comment.text);
+                cursor = index + 1;
+            }
+        }
+    }
+
+    let mut reattached = lines.join("\n");
+    if output.ends_with('\n') {
+        reattached.push('\n');
+    }
+    reattached
+}
+
+/// Comments from the input that do not appear in `output`, as written in the input
+pub fn missing_comments(comments: &[SourceComment], output: &str) -> Vec<String> {
+    let output = without_whitespace(output);
+    comments
+        .iter()
+        .filter(|comment| !output.contains(&without_whitespace(&comment.text)))
+        .map(|comment| comment.text.clone())
+        .collect()
+}
+
+/// Widen a comment span to include its delimiters if the parser reported content only
+pub(crate) fn full_comment_span(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let rest = &source[start..];
+    let start = if !rest.starts_with("//") && !rest.starts_with("/*") && (source[..start].ends_with("//") || source[..start].ends_with("/*")) {
+        start - 2
+    } else {
+        start
+    };
+    let end = if source[start..].starts_with("/*") && !source[..end].ends_with("*/") && source[end..].starts_with("*/") {
+        end + 2
+    } else {
+        end
+    };
+    (start, end)
+}
+
+fn anchor_matches(line: &str, anchor: &str) -> bool {
+    let line = normalize_code(line);
+    let anchor = normalize_code(anchor);
+    !line.is_empty() && !anchor.is_empty() && (line.starts_with(&anchor) || anchor.starts_with(&line))
+}
+
+/// Code with whitespace removed and quotes unified, so formatting changes still match
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| if ch == '\'' { '"' } else { ch })
+        .collect()
+}
+
+fn without_whitespace(text: &str) -> String {
+    text.chars().filter(|ch| !ch.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    fn comments_of(source: &str) -> Vec<SourceComment> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::default()).parse();
+        collect_comments(&ret.program, source)
+    }
+
+    #[test]
+    fn test_collect_classifies_leading_and_trailing_comments() {
+        let comments = comments_of("/** Docs. */\nfunction f() {}\nconst a = 1; // one\n");
+
+        assert_eq!(
+            comments,
+            vec![
+                SourceComment {
+                    text: "/** Docs. */".to_string(),
+                    placement: CommentPlacement::Leading,
+                    anchor: "function f() {}".to_string(),
+                },
+                SourceComment {
+                    text: "// one".to_string(),
+                    placement: CommentPlacement::Trailing,
+                    anchor: "const a = 1;".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reattach_into_reformatted_output() {
+        let comments = comments_of("// first\nlet x='a';   // trailing\nlet y = 2;\n");
+
+        let output = reattach_comments("let x = \"a\";\nlet y = 2;\n", &comments);
+
+        assert_eq!(output, "// first\nlet x = \"a\"; // trailing\nlet y = 2;\n");
+        assert!(missing_comments(&comments, &output).is_empty());
+    }
+
+    #[test]
+    fn test_missing_comments_reports_dropped_comments() {
+        let comments = comments_of("let x = 1; // kept\nlet y = 2; // dropped\n");
+
+        assert_eq!(missing_comments(&comments, "let x = 1; // kept\nlet y = 2;\n"), vec!["// dropped"]);
+    }
+}
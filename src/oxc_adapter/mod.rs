@@ -25,6 +25,7 @@
 
 pub mod adaptive_pattern_analyzer; // AI coder mistake pattern detection
 pub mod ai_behavioral;
+pub mod comment_preservation; // Reattach and verify comments around codegen output
 pub mod moon_integration; // Moon PDK integration approach
 pub mod multi_engine_analyzer;
 pub mod oxc_formatter; // OXC formatter integration (beta)
@@ -105,6 +106,9 @@ pub fn convert_diagnostics(oxc_diagnostics: &[OxcDiagnostic]) -> Vec<LintDiagnos
             },
             fix_available: false,
             suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
         })
         .collect()
 }
@@ -438,6 +442,7 @@ impl OxcAdapter {
                 category: oxc_rule.category.clone(),
                 severity: oxc_rule.severity.clone(),
                 fix_status: if oxc_rule.has_fix { FixStatus::Autofix } else { FixStatus::Manual },
+                fix_safety: crate::rulebase::static_rules::fix_safety(&oxc_rule.rule_name),
                 ai_enhanced: false,
                 cost: 1, // OXC rules are generally low cost
                 tags: vec!["oxc".to_string(), oxc_rule.group.clone()],
@@ -446,6 +451,7 @@ impl OxcAdapter {
                     rule_name: oxc_rule.rule_name.clone(),
                 },
                 config_schema: None,
+                applies_to: None,
             })
             .collect()
     }
@@ -11,10 +11,14 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+/// File in the cache directory holding modification times from previous runs
+const FILE_CACHE_NAME: &str = "incremental-timestamps.json";
+
 /// In-process analyzer that tracks file modification times and only re-lints changed files.
 pub struct OxcMoonAnalyzer {
     project_root: PathBuf,
     file_cache: HashMap<PathBuf, u64>,
+    cache_path: Option<PathBuf>, // Persisted `file_cache`, see `with_cache_directory`
     linter: WasmSafeLinter,
 }
 
@@ -34,10 +38,24 @@ impl OxcMoonAnalyzer {
         Self {
             project_root,
             file_cache: HashMap::new(),
+            cache_path: None,
             linter: WasmSafeLinter::new(),
         }
     }
 
+    /// Persist modification times in `cache_dir` (`cache_directory` config) across runs.
+    ///
+    /// A missing or unreadable cache file starts from an empty cache.
+    pub fn with_cache_directory(mut self, cache_dir: impl AsRef<Path>) -> Self {
+        let cache_path = cache_dir.as_ref().join(FILE_CACHE_NAME);
+        self.file_cache = fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        self.cache_path = Some(cache_path);
+        self
+    }
+
     /// Run analysis, only linting files that have changed since the last invocation.
     pub async fn analyze_incremental(&mut self) -> Result<MoonAnalysisResult, Box<dyn std::error::Error>> {
         let start_time = Instant::now();
@@ -115,6 +133,10 @@ impl OxcMoonAnalyzer {
                 }
             }
         }
+
+        if let Some(cache_path) = &self.cache_path {
+            crate::moon_pdk_interface::write_file_atomic(&cache_path.to_string_lossy(), &serde_json::to_string(&self.file_cache)?)?;
+        }
         Ok(())
     }
 }
@@ -7,6 +7,7 @@ use super::ai_behavioral::{AiBehavioralAnalyzer, AnalysisContext};
 use super::oxc_formatter::{OxcFormatter, OxcFormatterConfig};
 use super::oxc_linter::{OxcConfig, OxcLinter};
 use super::oxc_transformer::{OxcTransformer, OxcTransformerConfig};
+use crate::buffer_lint::parse_error_diagnostics;
 use crate::types::LintDiagnostic;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
@@ -164,8 +165,16 @@ impl MultiEngineAnalyzer {
         let source_type = self.detect_source_type(file_path);
         let ParserReturn { program, errors, .. } = Parser::new(&self.allocator, source_code, source_type).parse();
 
+        // Syntax errors are the result; no engine can run on a broken AST
         if !errors.is_empty() {
-            return Err(format!("Parse errors in {}: {} errors", file_path, errors.len()).into());
+            stats.total_duration_ms = start_time.elapsed().as_millis() as u64;
+            return Ok(MultiEngineAnalysisResult {
+                diagnostics: parse_error_diagnostics(&errors, source_code, file_path),
+                formatted_code: None,
+                transformed_code: None,
+                ast_program: None,
+                stats,
+            });
         }
 
         // Run OXC static analysis
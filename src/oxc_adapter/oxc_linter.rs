@@ -3,6 +3,7 @@
 //! High-performance JavaScript/TypeScript linting using OXC stack.
 //! 50-100x faster than ESLint with 570+ rules.
 
+use crate::buffer_lint::parse_error_diagnostics;
 use crate::types::{DiagnosticSeverity, LintDiagnostic};
 use oxc_allocator::Allocator;
 use oxc_ast::AstKind;
@@ -57,8 +58,14 @@ impl OxcLinter {
         // Parse the source code
         let ParserReturn { program, errors, .. } = Parser::new(&self.allocator, source_code, source_type).parse();
 
+        // Syntax errors are the result; no rule can run on a broken AST
         if !errors.is_empty() {
-            return Err(format!("Parse errors in {}: {} errors", file_path, errors.len()).into());
+            return Ok(OxcAnalysisResult {
+                diagnostics: parse_error_diagnostics(&errors, source_code, file_path),
+                analyzed_files: vec![PathBuf::from(file_path)],
+                analysis_time_ms: start_time.elapsed().as_millis() as u64,
+                rules_executed: 0,
+            });
         }
 
         // Semantic analysis
@@ -153,6 +160,9 @@ impl OxcLinter {
             severity,
             fix_available: false,
             suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
         })
     }
 
@@ -3,6 +3,7 @@
 //! High-performance JavaScript/TypeScript transformation using OXC.
 //! Includes minification, mangling, and code transformations.
 
+use super::comment_preservation::{collect_comments, missing_comments, reattach_comments};
 use crate::types::LintDiagnostic;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
@@ -85,6 +86,10 @@ impl OxcTransformer {
 
         let diagnostics = Vec::new();
 
+        // Minified output drops comments on purpose
+        let preserve_comments = self.config.preserve_comments && !self.config.minify;
+        let source_comments = if preserve_comments { collect_comments(&program, source_code) } else { Vec::new() };
+
         // Apply transformations
         if self.config.transform_jsx || self.config.transform_typescript {
             self.apply_transformations(&mut program, source_code)?;
@@ -100,13 +105,23 @@ impl OxcTransformer {
             self.apply_mangling(&mut program)?;
         }
 
-        // Generate the transformed code
+        // Generate the transformed code; comments are reattached from the parser trivia
+        // below rather than left where codegen would print them
         let codegen_options = CodegenOptions {
             minify: self.config.minify,
+            comments: false,
             ..CodegenOptions::default()
         };
 
-        let transformed_code = Codegen::new().with_options(codegen_options).build(&program).code;
+        let mut transformed_code = Codegen::new().with_options(codegen_options).build(&program).code;
+
+        if preserve_comments {
+            transformed_code = reattach_comments(&transformed_code, &source_comments);
+            let missing = missing_comments(&source_comments, &transformed_code);
+            if !missing.is_empty() {
+                return Err(format!("Transforming {} would drop {} comment(s): {}", file_path, missing.len(), missing.join(", ")).into());
+            }
+        }
 
         let transformed_size = transformed_code.len();
         let compression_ratio = if original_size > 0 {
@@ -277,6 +292,41 @@ mod tests {
         assert_eq!(transformation.original_size, source_code.len());
     }
 
+    #[test]
+    fn test_transform_preserves_jsdoc_and_inline_comments() {
+        let transformer = OxcTransformer::default();
+        let source_code = r#"/**
+ * Adds two numbers.
+ * @param a first operand
+ * @param b second operand
+ */
+function add(a, b) {
+  // sum them
+  return a + b; // inline result
+}
+
+/** The answer. */
+const answer = add(40, 2); /* block trailing */
+"#;
+
+        let output = transformer.transform_code(source_code, "math.js").unwrap().transformed_code;
+        let lines: Vec<&str> = output.lines().collect();
+        let line_of = |needle: &str| lines.iter().position(|line| line.contains(needle)).unwrap_or_else(|| panic!("{:?} missing from:\n{}", needle, output));
+
+        let function_line = line_of("function add");
+        assert_eq!(lines[function_line - 1].trim(), "*/");
+        assert_eq!(line_of("* Adds two numbers."), function_line - 4);
+        assert_eq!(line_of("/**"), function_line - 5);
+
+        let return_line = line_of("return a + b");
+        assert!(lines[return_line].ends_with("// inline result"));
+        assert_eq!(lines[return_line - 1].trim(), "// sum them");
+
+        let answer_line = line_of("const answer");
+        assert_eq!(lines[answer_line - 1].trim(), "/** The answer. */");
+        assert!(lines[answer_line].ends_with("/* block trailing */"));
+    }
+
     #[test]
     fn test_source_type_detection() {
         let transformer = OxcTransformer::default();
@@ -543,6 +543,9 @@ mod tests {
             file_path: "test.js".to_string(),
             fix_available: false,
             suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
         };
 
         assert!(detector.is_ai_mistake_pattern(&diagnostic));
@@ -1,9 +1,12 @@
 pub mod compiled; // Zero-runtime-cost compiled prompts
 
 use crate::error::{Error, Result};
-use crate::moon_pdk_interface::{get_moon_config, get_moon_config_safe, write_file_atomic};
+use crate::moon_pdk_interface::{get_moon_config, get_moon_config_safe, update_prompts_json, write_file_atomic};
+use crate::token_usage::tie_break;
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeSet, HashMap};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Mutex, OnceLock};
 
 // Re-exports
 pub use compiled::{available_template_names, get_compiled_prompt_template, get_template_metadata, has_template};
@@ -28,86 +31,130 @@ impl PromptTemplate {
     }
 
     pub fn render(&self, context: &std::collections::HashMap<String, String>) -> Result<String> {
-        let mut result = self.template.clone();
-
-        for var in &self.variables {
-            let placeholder = format!("{{{}}}", var);
-            if let Some(value) = context.get(var) {
-                result = result.replace(&placeholder, value);
-            } else {
-                return Err(Error::config(format!("Missing variable: {}", var)));
-            }
+        self.render_checked(context).map_err(|missing| {
+            let label = if missing.len() == 1 { "variable" } else { "variables" };
+            Error::config(format!("Missing {}: {}", label, missing.join(", ")))
+        })
+    }
+
+    /// Render the template, reporting every missing variable at once in first-seen order.
+    ///
+    /// `{{` and `}}` render as literal braces, and `{name?default}` renders `default` when
+    /// `name` is missing; only placeholders without a default can be missing.
+    pub fn render_checked(&self, context: &std::collections::HashMap<String, String>) -> std::result::Result<String, Vec<String>> {
+        let mut missing = Vec::new();
+        let result = self.fill(context, &mut missing);
+        if !missing.is_empty() {
+            return Err(missing);
         }
 
         Ok(result)
     }
 
-    /// Load prompt with priority: optimized > COPRO candidates > base template
-    pub fn load_with_priority(prompt_name: &str) -> Result<Self> {
-        // First, try to load optimized prompt from prompts.json
-        if let Some(optimized) = Self::load_optimized(prompt_name)? {
-            return Ok(optimized);
+    /// Fill in the variables present in `context`, for prompts whose context arrives in stages.
+    ///
+    /// Returns a template that a later [`render`](Self::render) completes: placeholders
+    /// without a value, including those with a default, stay as written and braces in the
+    /// substituted values are escaped. The names still missing that have no default come second.
+    pub fn render_partial(&self, context: &std::collections::HashMap<String, String>) -> (String, Vec<String>) {
+        let escape = |text: &str| text.replace('{', "{{").replace('}', "}}");
+        let mut result = String::with_capacity(self.template.len());
+        let mut missing: Vec<String> = Vec::new();
+        for segment in segments(&self.template) {
+            match segment {
+                Segment::Text(text) => result.push_str(&escape(text)),
+                Segment::Placeholder { name, default, raw } => match context.get(name) {
+                    Some(value) => result.push_str(&escape(value)),
+                    None => {
+                        if default.is_none() && !missing.iter().any(|var| var == name) {
+                            missing.push(name.to_string());
+                        }
+                        result.push_str(raw);
+                    }
+                },
+            }
         }
+        (result, missing)
+    }
 
-        // Second, try COPRO candidates
-        if let Some(copro_candidate) = Self::load_best_copro_candidate(prompt_name)? {
-            return Ok(copro_candidate);
+    /// Substitute `context` into the template, collecting names that have neither a value nor a default;
+    /// those stay as written
+    fn fill(&self, context: &std::collections::HashMap<String, String>, missing: &mut Vec<String>) -> String {
+        let mut result = String::with_capacity(self.template.len());
+        for segment in segments(&self.template) {
+            match segment {
+                Segment::Text(text) => result.push_str(text),
+                Segment::Placeholder { name, default, raw } => match context.get(name).map(String::as_str).or(default) {
+                    Some(value) => result.push_str(value),
+                    None => {
+                        if !missing.iter().any(|var| var == name) {
+                            missing.push(name.to_string());
+                        }
+                        result.push_str(raw);
+                    }
+                },
+            }
         }
+        result
+    }
 
-        // Fall back to base template
-        Self::load_base_template(prompt_name)
+    /// Load prompt with priority: optimized > COPRO candidates > base template
+    pub fn load_with_priority(prompt_name: &str) -> Result<Self> {
+        Self::load_with_priority_from(prompt_name, get_moon_config("moonshine_prompts").as_deref())
     }
 
-    /// Load optimized prompt from Moon storage (prompts.json)
-    fn load_optimized(prompt_name: &str) -> Result<Option<Self>> {
-        if let Some(prompts_json) = get_moon_config("moonshine_prompts") {
-            let prompts: serde_json::Value = serde_json::from_str(&prompts_json)?;
+    /// Priority resolution against a stored `moonshine_prompts` document
+    fn load_with_priority_from(prompt_name: &str, stored: Option<&str>) -> Result<Self> {
+        if let Some(prompts_json) = stored {
+            let prompts: serde_json::Value = serde_json::from_str(prompts_json)?;
 
-            if let Some(optimized_prompts) = prompts.get("optimized_prompts") {
-                if let Some(prompt_data) = optimized_prompts.get(prompt_name) {
-                    if let Some(template) = prompt_data.get("template").and_then(|v| v.as_str()) {
-                        return Ok(Some(Self::new(prompt_name, template)));
-                    }
-                }
+            // First, try the optimized prompt from prompts.json
+            if let Some(optimized) = Self::load_optimized(prompt_name, &prompts) {
+                return Ok(optimized);
+            }
+
+            // Second, try COPRO candidates
+            if let Some(copro_candidate) = Self::load_best_copro_candidate(prompt_name, &prompts) {
+                return Ok(copro_candidate);
             }
         }
-        Ok(None)
-    }
-
-    /// Load best COPRO candidate from Moon storage
-    fn load_best_copro_candidate(prompt_name: &str) -> Result<Option<Self>> {
-        if let Some(prompts_json) = get_moon_config("moonshine_prompts") {
-            let prompts: serde_json::Value = serde_json::from_str(&prompts_json)?;
-
-            if let Some(copro_candidates) = prompts.get("copro_candidates") {
-                if let Some(candidates) = copro_candidates.get("active").and_then(|v| v.as_array()) {
-                    // Find the highest scoring candidate for this prompt type
-                    let mut best_candidate = None;
-                    let mut best_score = 0.0;
-
-                    for candidate in candidates {
-                        if let (Some(template), Some(score)) = (
-                            candidate.get("template").and_then(|v| v.as_str()),
-                            candidate.get("score").and_then(|v| v.as_f64()),
-                        ) {
-                            if score > best_score {
-                                best_score = score;
-                                best_candidate = Some(template);
-                            }
-                        }
-                    }
 
-                    if let Some(template) = best_candidate {
-                        return Ok(Some(Self::new(format!("{}_copro", prompt_name), template)));
-                    }
+        // Fall back to base template
+        Ok(Self::load_base_template(prompt_name))
+    }
+
+    /// Optimized prompt from the stored prompts document
+    fn load_optimized(prompt_name: &str, prompts: &serde_json::Value) -> Option<Self> {
+        let template = prompts.get("optimized_prompts")?.get(prompt_name)?.get("template")?.as_str()?;
+        Some(Self::new(prompt_name, template))
+    }
+
+    /// Best COPRO candidate from the stored prompts document
+    fn load_best_copro_candidate(prompt_name: &str, prompts: &serde_json::Value) -> Option<Self> {
+        let candidates = prompts.get("copro_candidates")?.get("active")?.as_array()?;
+
+        // Find the highest scoring candidate for this prompt type; `tie_break` settles equal scores
+        let mut best_candidate: Option<(&str, f64)> = None;
+
+        for candidate in candidates {
+            if let (Some(template), Some(score)) = (
+                candidate.get("template").and_then(|v| v.as_str()),
+                candidate.get("score").and_then(|v| v.as_f64()),
+            ) {
+                let preferred = best_candidate.map_or(score > 0.0, |(best_template, best_score)| {
+                    score.total_cmp(&best_score).then_with(|| tie_break(template, best_template)) == Ordering::Greater
+                });
+                if preferred {
+                    best_candidate = Some((template, score));
                 }
             }
         }
-        Ok(None)
+
+        best_candidate.map(|(template, _)| Self::new(format!("{}_copro", prompt_name), template))
     }
 
     /// Load base template (fallback)
-    fn load_base_template(prompt_name: &str) -> Result<Self> {
+    fn load_base_template(prompt_name: &str) -> Self {
         // Default base templates for common prompt types
         let base_template = match prompt_name {
             "code_analysis" => "Analyze the following {language} code for potential issues:\n\n{code}\n\nProvide specific suggestions for improvement.",
@@ -116,33 +163,81 @@ impl PromptTemplate {
             _ => "Analyze the following {language} code:\n\n{code}\n\nProvide analysis and suggestions.",
         };
 
-        Ok(Self::new(prompt_name, base_template))
+        Self::new(prompt_name, base_template)
     }
 }
 
-fn extract_variables(template: &str) -> Vec<String> {
-    let mut variables = Vec::new();
-    let mut chars = template.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        if ch == '{' {
-            if let Some(&next_ch) = chars.peek() {
-                if next_ch != '{' {
-                    let mut var_name = String::new();
-                    for ch in chars.by_ref() {
-                        if ch == '}' {
-                            break;
-                        }
-                        var_name.push(ch);
-                    }
-                    if !var_name.is_empty() && !variables.contains(&var_name) {
-                        variables.push(var_name);
+/// Piece of a parsed template
+#[derive(Debug, PartialEq)]
+enum Segment<'t> {
+    Text(&'t str), // Literal text; an escaped brace is a one-brace text segment
+    Placeholder {
+        name: &'t str,
+        default: Option<&'t str>, // From `{name?default}`
+        raw: &'t str,             // The placeholder as written, braces included
+    },
+}
+
+/// Split `template` into text and placeholders.
+///
+/// `{{` and `}}` are literal braces, `{name}` is a placeholder and `{name?default}` one with
+/// a fallback value. An unterminated or empty `{` is text.
+fn segments(template: &str) -> Vec<Segment<'_>> {
+    let bytes = template.as_bytes();
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let brace = bytes[index];
+        if (brace == b'{' || brace == b'}') && bytes.get(index + 1) == Some(&brace) {
+            segments.push(Segment::Text(&template[text_start..=index]));
+            index += 2;
+            text_start = index;
+            continue;
+        }
+        if brace == b'{' {
+            if let Some(length) = template[index + 1..].find('}') {
+                let inner = &template[index + 1..index + 1 + length];
+                let (name, default) = match inner.split_once('?') {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (inner, None),
+                };
+                if !name.is_empty() {
+                    if text_start < index {
+                        segments.push(Segment::Text(&template[text_start..index]));
                     }
+                    let end = index + length + 2;
+                    segments.push(Segment::Placeholder {
+                        name,
+                        default,
+                        raw: &template[index..end],
+                    });
+                    index = end;
+                    text_start = index;
+                    continue;
                 }
             }
         }
+        index += 1;
+    }
+    if text_start < bytes.len() {
+        segments.push(Segment::Text(&template[text_start..]));
     }
 
+    segments
+}
+
+/// Placeholder names in first-seen order, without duplicates, whether or not they have a default
+fn extract_variables(template: &str) -> Vec<String> {
+    let mut variables: Vec<String> = Vec::new();
+    for segment in segments(template) {
+        if let Segment::Placeholder { name, .. } = segment {
+            if !variables.iter().any(|var| var == name) {
+                variables.push(name.to_string());
+            }
+        }
+    }
     variables
 }
 
@@ -150,38 +245,53 @@ fn extract_variables(template: &str) -> Vec<String> {
 /// Implements robust storage hierarchy: external JSON → Moon config → embedded defaults
 /// Features: retry logic, caching, multiple storage backends, graceful degradation
 pub fn get_default_templates() -> Vec<PromptTemplate> {
-    let mut templates = Vec::new();
+    get_templates(None)
+}
+
+/// Every prompt template, resolved per template name: `custom_prompts` > external storage > embedded defaults.
+///
+/// Sources only need the templates they change; the rest are inherited. Within external
+/// storage, prompt files win over the consolidated prompts.json, which wins over legacy
+/// Moon config keys.
+pub fn get_templates(custom_prompts: Option<&HashMap<String, String>>) -> Vec<PromptTemplate> {
+    let mut external = load_templates_from_moon_config();
+
+    if let Ok(json_templates) = load_prompts_from_json() {
+        external = override_templates(external, json_templates);
+    }
 
-    // First, try to load from external storage with robust error handling
     match load_prompts_from_external_storage() {
-        Ok(external_templates) if !external_templates.is_empty() => {
-            return external_templates;
-        }
-        Ok(_) => {
-            // External storage accessible but empty - continue to fallbacks
-        }
+        Ok(external_templates) => external = override_templates(external, external_templates),
         Err(e) => {
-            // Log error but continue gracefully to fallbacks
+            // Log error but continue gracefully with the other sources
             eprintln!("Warning: External prompt storage failed: {}", e);
         }
     }
 
-    // Second, try consolidated prompts.json from Moon storage
-    if let Ok(json_templates) = load_prompts_from_json() {
-        if !json_templates.is_empty() {
-            return json_templates;
-        }
-    }
+    resolve_templates(get_embedded_default_templates(), external, custom_prompts)
+}
 
-    // Third, fall back to Moon configuration keys
-    templates.extend(load_templates_from_moon_config());
+/// Layer `external` and then `custom_prompts` over `embedded`, one template name at a time
+pub fn resolve_templates(
+    embedded: Vec<PromptTemplate>,
+    external: Vec<PromptTemplate>,
+    custom_prompts: Option<&HashMap<String, String>>,
+) -> Vec<PromptTemplate> {
+    // Sorted so the result does not depend on map iteration order
+    let custom: BTreeMap<&String, &String> = custom_prompts.into_iter().flatten().collect();
+    let custom = custom.into_iter().map(|(name, template)| PromptTemplate::new(name.clone(), template.clone()));
+    override_templates(override_templates(embedded, external), custom)
+}
 
-    // Ultimate fallback to embedded defaults if all external sources fail
-    if templates.is_empty() {
-        templates.extend(get_embedded_default_templates());
+/// `base` with each template of `overrides` replacing the one of the same name, or appended when new
+fn override_templates(mut base: Vec<PromptTemplate>, overrides: impl IntoIterator<Item = PromptTemplate>) -> Vec<PromptTemplate> {
+    for template in overrides {
+        match base.iter_mut().find(|existing| existing.name == template.name) {
+            Some(existing) => *existing = template,
+            None => base.push(template),
+        }
     }
-
-    templates
+    base
 }
 
 /// Load prompt templates from external storage with production-grade robustness
@@ -384,19 +494,23 @@ fn load_prompts_from_json() -> Result<Vec<PromptTemplate>> {
     Ok(Vec::new())
 }
 
+/// Legacy per-prompt Moon config keys, as `(template name, config key)`
+pub const LEGACY_PROMPT_KEYS: &[(&str, &str)] = &[
+    ("code_analysis", "moonshine.prompts.code_analysis"),
+    ("code_fixing", "moonshine.prompts.code_fixing"),
+    ("optimization", "moonshine.prompts.optimization"),
+    ("typescript_strict", "moonshine.prompts.typescript_strict"),
+    ("security_analysis", "moonshine.prompts.security_analysis"),
+];
+
+/// Consolidated prompt storage written by `initialize_prompts_storage` and the `save_*` functions
+pub const PROMPTS_JSON_PATH: &str = ".moon/moonshine/prompts.json";
+
 /// Load templates from Moon configuration keys (fallback method)
 fn load_templates_from_moon_config() -> Vec<PromptTemplate> {
     let mut templates = Vec::new();
 
-    let config_keys = [
-        ("code_analysis", "moonshine.prompts.code_analysis"),
-        ("code_fixing", "moonshine.prompts.code_fixing"),
-        ("optimization", "moonshine.prompts.optimization"),
-        ("typescript_strict", "moonshine.prompts.typescript_strict"),
-        ("security_analysis", "moonshine.prompts.security_analysis"),
-    ];
-
-    for (name, config_key) in &config_keys {
+    for (name, config_key) in LEGACY_PROMPT_KEYS {
         if let Ok(Some(prompt_content)) = get_moon_config_safe(config_key) {
             templates.push(PromptTemplate::new(*name, prompt_content));
         }
@@ -553,14 +667,14 @@ fn get_embedded_default_templates() -> Vec<PromptTemplate> {
             "📊 **COMPLEXITY ANALYSIS + OPTIMIZATION**\n\n\
              Analyze and optimize complexity for the following {language} code:\n\n\
              ## Complexity Metrics\n\
-             - **Cyclomatic Complexity**: {cyclomatic_complexity} (threshold: 10)\n\
-             - **Halstead Difficulty**: {halstead_difficulty} (threshold: 20)\n\
-             - **Lines of Code**: {lines_of_code}\n\
-             - **Cognitive Complexity**: {cognitive_complexity}\n\n\
+             - **Cyclomatic Complexity**: {cyclomatic_complexity} (threshold: {max_cyclomatic_per_function} per function)\n\
+             - **Halstead Difficulty**: {halstead_difficulty} (threshold: {max_halstead_difficulty})\n\
+             - **Lines of Code**: {lines_of_code} (threshold: {max_file_loc} per file)\n\
+             - **Cognitive Complexity**: {cognitive_complexity} (threshold: {max_cognitive_per_function} per function)\n\n\
              File: {file_path}\n\
              Code:\n{code}\n\n\
              ## Optimization Goals\n\
-             1. **Reduce cyclomatic complexity** below 10 per function\n\
+             1. **Reduce cyclomatic complexity** below {max_cyclomatic_per_function} per function\n\
              2. **Simplify control flow** and eliminate nested conditions\n\
              3. **Extract helper functions** for complex logic\n\
              4. **Apply SOLID principles** for better maintainability\n\
@@ -886,47 +1000,148 @@ pub fn get_fallback_prompt(rule_type: &str) -> &'static str {
     }
 }
 
-/// Get prompt with fallback hierarchy: custom -> embedded -> built-in
+/// Get prompt with fallback hierarchy: custom -> external storage -> embedded -> built-in.
+///
+/// The first three are the per-name resolution of [`get_templates`]; rule types no template
+/// covers get their built-in fallback prompt.
 pub fn get_prompt(rule_type: &str, custom_prompts: Option<&HashMap<String, String>>) -> String {
-    if let Some(custom) = custom_prompts {
-        if let Some(prompt) = custom.get(rule_type) {
-            return prompt.clone();
+    get_templates(custom_prompts)
+        .into_iter()
+        .find(|template| template.name == rule_type)
+        .map(|template| template.template)
+        .unwrap_or_else(|| get_fallback_prompt(rule_type).to_string())
+}
+
+static PROMPT_CACHE: OnceLock<Mutex<PromptCache>> = OnceLock::new();
+
+/// Resolved prompts by rule type, valid for one prompt configuration.
+///
+/// The cache remembers a fingerprint of the prompt-relevant config (`custom_prompts`); a
+/// lookup under a different fingerprint drops every cached template first, so edited
+/// prompts take effect without clearing the cache by hand. Externally stored prompts are read
+/// when a template is first cached and are not part of the fingerprint.
+#[derive(Debug, Default)]
+pub struct PromptCache {
+    fingerprint: Option<String>,
+    templates: HashMap<String, String>,
+}
+
+impl PromptCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// [`get_prompt`], served from the cache while the prompt config is unchanged
+    pub fn get_prompt(&mut self, rule_type: &str, custom_prompts: Option<&HashMap<String, String>>) -> String {
+        let fingerprint = prompt_config_fingerprint(custom_prompts);
+        if self.fingerprint.as_deref() != Some(fingerprint.as_str()) {
+            if !self.templates.is_empty() {
+                moon_debug!("Prompt config changed - dropping {} cached template(s)", self.templates.len());
+            }
+            self.templates.clear();
+            self.fingerprint = Some(fingerprint);
         }
+
+        self.templates
+            .entry(rule_type.to_string())
+            .or_insert_with(|| get_prompt(rule_type, custom_prompts))
+            .clone()
     }
+}
+
+/// Hash of the config that decides which templates [`get_prompt`] returns
+pub fn prompt_config_fingerprint(custom_prompts: Option<&HashMap<String, String>>) -> String {
+    let mut hasher = blake3::Hasher::new();
+    // Sorted so the fingerprint does not depend on map iteration order
+    for (rule_type, template) in custom_prompts.into_iter().flatten().collect::<BTreeMap<_, _>>() {
+        hasher.update(&(rule_type.len() as u64).to_le_bytes());
+        hasher.update(rule_type.as_bytes());
+        hasher.update(&(template.len() as u64).to_le_bytes());
+        hasher.update(template.as_bytes());
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// [`get_prompt`] through the process-wide [`PromptCache`]
+pub fn cached_prompt(rule_type: &str, custom_prompts: Option<&HashMap<String, String>>) -> String {
+    let cache = PROMPT_CACHE.get_or_init(|| Mutex::new(PromptCache::new()));
+    match cache.lock() {
+        Ok(mut cache) => cache.get_prompt(rule_type, custom_prompts),
+        Err(_) => get_prompt(rule_type, custom_prompts),
+    }
+}
 
-    get_fallback_prompt(rule_type).to_string()
+/// The prompt `rule_type` would send, after optimized > COPRO > base resolution.
+///
+/// Variables present in `context` are filled in; the rest stay as `{name}` so the preview
+/// shows what the caller still has to supply. Unreadable stored prompts fall back to the base template.
+pub fn preview_prompt(rule_type: &str, context: &HashMap<String, String>) -> String {
+    preview_prompt_from(rule_type, context, get_moon_config("moonshine_prompts").as_deref())
+}
+
+fn preview_prompt_from(rule_type: &str, context: &HashMap<String, String>, stored: Option<&str>) -> String {
+    let template = PromptTemplate::load_with_priority_from(rule_type, stored).unwrap_or_else(|e| {
+        moon_warn!("Stored prompts unreadable, previewing base template for '{}': {}", rule_type, e);
+        PromptTemplate::load_base_template(rule_type)
+    });
+    template.fill(context, &mut Vec::new())
 }
 
 /// Save optimized prompt to JSON configuration
 pub fn save_optimized_prompt(prompt_name: &str, template: &str, score: f64) -> Result<()> {
-    let prompts_data = load_or_create_prompts_json()?;
-    let mut prompts_obj = prompts_data.as_object().cloned().unwrap_or_default();
+    apply_optimized_batch(vec![(prompt_name.to_string(), template.to_string(), score)])
+}
 
-    // Initialize optimized_prompts section if not exists
-    if !prompts_obj.contains_key("optimized_prompts") {
-        prompts_obj.insert("optimized_prompts".to_string(), serde_json::json!({}));
-    }
-
-    // Add the optimized prompt
-    if let Some(optimized_section) = prompts_obj.get_mut("optimized_prompts") {
-        if let Some(optimized_obj) = optimized_section.as_object_mut() {
-            optimized_obj.insert(
-                prompt_name.to_string(),
-                serde_json::json!({
-                    "template": template,
-                    "score": score,
-                    "optimized_at": chrono::Utc::now().to_rfc3339(),
-                    "version": "1.0.0"
-                }),
-            );
-        }
-    }
+/// Save several COPRO-optimized prompts as `(name, template, score)` in one update of prompts.json.
+///
+/// Every entry is validated first, then all of them go to the host in a single storage
+/// update, which the host applies as one atomic read-modify-write, so either all prompts
+/// are applied or none are.
+pub fn apply_optimized_batch(prompts: Vec<(String, String, f64)>) -> Result<()> {
+    apply_optimized_batch_with(prompts, update_prompts_json)
+}
 
-    // Save back to JSON
-    let json_content = serde_json::to_string_pretty(&prompts_obj).map_err(|e| Error::config(format!("Failed to serialize prompts JSON: {}", e)))?;
+/// [`apply_optimized_batch`] sending the prompts.json update with `update`
+pub fn apply_optimized_batch_with<U>(prompts: Vec<(String, String, f64)>, update: U) -> Result<()>
+where
+    U: FnOnce(&serde_json::Value) -> std::result::Result<(), Box<dyn std::error::Error>>,
+{
+    if prompts.is_empty() {
+        return Ok(());
+    }
+    if let Some((name, _, score)) = prompts.iter().find(|(name, template, score)| name.trim().is_empty() || template.trim().is_empty() || !score.is_finite()) {
+        return Err(Error::config(format!("Invalid optimized prompt '{}' (score {}); no prompts applied", name, score)));
+    }
 
-    write_file_atomic(".moon/moonshine/prompts.json", &json_content).map_err(|e| Error::config(format!("Failed to save prompts JSON: {}", e)))?;
+    let optimized_at = chrono::Utc::now().to_rfc3339();
+    let optimized: serde_json::Map<String, serde_json::Value> = prompts
+        .into_iter()
+        .map(|(name, template, score)| {
+            let entry = serde_json::json!({
+                "template": template,
+                "score": score,
+                "optimized_at": optimized_at,
+                "version": "1.0.0"
+            });
+            (name, entry)
+        })
+        .collect();
+    let count = optimized.len();
+    let updates = serde_json::json!({
+        "optimized_prompts": optimized,
+        "last_updated": optimized_at
+    });
+    update(&updates).map_err(|e| Error::config(format!("Failed to update prompts JSON, no optimized prompts applied: {}", e)))?;
 
+    moon_info!("Applied {} optimized prompts to {}", count, PROMPTS_JSON_PATH);
     Ok(())
 }
 
@@ -948,6 +1163,7 @@ pub fn save_copro_candidate(prompt_name: &str, template: &str, score: f64, gener
 
     // Add the candidate to active list
     if let Some(copro_section) = prompts_obj.get_mut("copro_candidates") {
+        let mut dropped = Vec::new();
         if let Some(active_array) = copro_section.get_mut("active").and_then(|v| v.as_array_mut()) {
             active_array.push(serde_json::json!({
                 "name": prompt_name,
@@ -964,7 +1180,14 @@ pub fn save_copro_candidate(prompt_name: &str, template: &str, score: f64, gener
                     let score_b = b.get("score").and_then(|v| v.as_f64()).unwrap_or(0.0);
                     score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal)
                 });
-                active_array.truncate(10);
+                dropped = active_array.split_off(10);
+            }
+        }
+
+        // Candidates pushed out of the active list stay in the score history
+        if let Some(copro_obj) = copro_section.as_object_mut() {
+            if let Some(archived_array) = copro_obj.entry("archived").or_insert_with(|| serde_json::json!([])).as_array_mut() {
+                archived_array.extend(dropped);
             }
         }
     }
@@ -977,9 +1200,57 @@ pub fn save_copro_candidate(prompt_name: &str, template: &str, score: f64, gener
     Ok(())
 }
 
+/// Score of one COPRO candidate, for charting optimization progress per generation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoproScorePoint {
+    pub generation: u32,
+    pub score: f64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Scores of `prompt_name`'s COPRO candidates, active and archived, in generation order
+pub fn copro_score_history(prompt_name: &str) -> Vec<CoproScorePoint> {
+    copro_score_history_in(PROMPTS_JSON_PATH, prompt_name)
+}
+
+/// [`copro_score_history`] read from the prompts.json at `path`; a missing or invalid file has no history
+pub fn copro_score_history_in(path: &str, prompt_name: &str) -> Vec<CoproScorePoint> {
+    let prompts = match std::fs::read_to_string(path).map(|content| serde_json::from_str::<serde_json::Value>(&content)) {
+        Ok(Ok(prompts)) => prompts,
+        Ok(Err(e)) => {
+            moon_warn!("Invalid prompts JSON in {}: {}", path, e);
+            return Vec::new();
+        }
+        Err(_) => return Vec::new(),
+    };
+
+    let candidates = &prompts["copro_candidates"];
+    let mut history: Vec<CoproScorePoint> = ["active", "archived"]
+        .iter()
+        .filter_map(|list| candidates[*list].as_array())
+        .flatten()
+        .filter(|candidate| candidate["name"].as_str() == Some(prompt_name))
+        .filter_map(|candidate| {
+            let Some(created_at) = candidate["created_at"].as_str().and_then(|timestamp| chrono::DateTime::parse_from_rfc3339(timestamp).ok()) else {
+                moon_debug!("Skipping COPRO candidate of {} with unparseable created_at", prompt_name);
+                return None;
+            };
+            Some(CoproScorePoint {
+                generation: u32::try_from(candidate["generation"].as_u64()?).ok()?,
+                score: candidate["score"].as_f64()?,
+                created_at: created_at.with_timezone(&chrono::Utc),
+            })
+        })
+        .collect();
+
+    history.sort_by(|a, b| a.generation.cmp(&b.generation).then(a.created_at.cmp(&b.created_at)));
+    history
+}
+
 /// Load existing prompts.json or create new structure
 fn load_or_create_prompts_json() -> Result<serde_json::Value> {
-    if let Ok(Some(json_content)) = get_moon_config_safe("moonshine_prompts_file") {
+    // Only missing prompts start from the default structure; a failed read is an error
+    if let Some(json_content) = get_moon_config_safe("moonshine_prompts_file")? {
         serde_json::from_str(&json_content).map_err(|e| Error::config(format!("Invalid existing prompts JSON: {}", e)))
     } else {
         // Create default structure
@@ -1002,7 +1273,16 @@ fn load_or_create_prompts_json() -> Result<serde_json::Value> {
 
 /// Initialize default prompts.json if it doesn't exist
 pub fn initialize_prompts_storage() -> Result<()> {
-    let default_prompts = serde_json::json!({
+    let json_content = serde_json::to_string_pretty(&default_prompts_json()).map_err(|e| Error::config(format!("Failed to serialize default prompts: {}", e)))?;
+
+    write_file_atomic(PROMPTS_JSON_PATH, &json_content).map_err(|e| Error::config(format!("Failed to initialize prompts storage: {}", e)))?;
+
+    Ok(())
+}
+
+/// Default prompts.json contents written by `initialize_prompts_storage`
+fn default_prompts_json() -> serde_json::Value {
+    serde_json::json!({
         "version": "1.0.0",
         "last_updated": chrono::Utc::now().to_rfc3339(),
         "base_prompts": {
@@ -1032,13 +1312,91 @@ pub fn initialize_prompts_storage() -> Result<()> {
             "prompt_count": 3,
             "initialized_at": chrono::Utc::now().to_rfc3339()
         }
-    });
+    })
+}
 
-    let json_content = serde_json::to_string_pretty(&default_prompts).map_err(|e| Error::config(format!("Failed to serialize default prompts: {}", e)))?;
+/// Outcome of `migrate_legacy_prompts`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptMigrationReport {
+    pub path: String,
+    pub migrated: Vec<String>, // Template names copied from legacy keys
+    pub replaced: Vec<String>, // Migrated names that overwrote a different base prompt
+    pub missing: Vec<String>,  // Legacy keys that were not set
+}
 
-    write_file_atomic(".moon/moonshine/prompts.json", &json_content).map_err(|e| Error::config(format!("Failed to initialize prompts storage: {}", e)))?;
+/// Consolidate the legacy `moonshine.prompts.*` Moon config keys into prompts.json (`--migrate-prompts`).
+///
+/// Each set key becomes a `base_prompts` entry under its template name. The legacy keys
+/// are only read, so rolling back means deleting the migrated entries.
+pub fn migrate_legacy_prompts() -> Result<PromptMigrationReport> {
+    migrate_legacy_prompts_with(|key| get_moon_config_safe(key).ok().flatten(), PROMPTS_JSON_PATH)
+}
 
-    Ok(())
+/// [`migrate_legacy_prompts`] with an injected config lookup and target path
+pub fn migrate_legacy_prompts_with<F>(lookup: F, path: &str) -> Result<PromptMigrationReport>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut prompts = match std::fs::read_to_string(path) {
+        Ok(content) if !content.trim().is_empty() => {
+            serde_json::from_str::<serde_json::Value>(&content).map_err(|e| Error::config(format!("Invalid existing prompts JSON in {}: {}", path, e)))?
+        }
+        _ => default_prompts_json(),
+    };
+    let prompts_obj = prompts.as_object_mut().ok_or_else(|| Error::config(format!("{} is not a JSON object", path)))?;
+
+    let mut report = PromptMigrationReport {
+        path: path.to_string(),
+        ..PromptMigrationReport::default()
+    };
+    let mut migrated = serde_json::Map::new();
+    for (name, config_key) in LEGACY_PROMPT_KEYS {
+        match lookup(config_key).filter(|template| !template.trim().is_empty()) {
+            Some(template) => {
+                migrated.insert(
+                    name.to_string(),
+                    serde_json::json!({
+                        "template": template,
+                        "variables": extract_variables(&template),
+                        "description": format!("Migrated from {}", config_key),
+                        "migrated_from": config_key,
+                    }),
+                );
+                report.migrated.push(name.to_string());
+            }
+            None => report.missing.push(config_key.to_string()),
+        }
+    }
+    if report.migrated.is_empty() {
+        return Ok(report);
+    }
+
+    let base_prompts = prompts_obj
+        .entry("base_prompts")
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| Error::config(format!("base_prompts in {} is not a JSON object", path)))?;
+    for (name, entry) in migrated {
+        let template = entry.get("template").cloned();
+        let previous = base_prompts.insert(name.clone(), entry);
+        if previous.is_some_and(|previous| previous.get("template") != template.as_ref()) {
+            report.replaced.push(name);
+        }
+    }
+    let prompt_count = base_prompts.len();
+
+    prompts_obj.insert("version".to_string(), serde_json::json!("1.0.0"));
+    prompts_obj.insert("last_updated".to_string(), serde_json::json!(chrono::Utc::now().to_rfc3339()));
+    let metadata = prompts_obj.entry("metadata").or_insert_with(|| serde_json::json!({}));
+    if let Some(metadata) = metadata.as_object_mut() {
+        metadata.insert("prompt_count".to_string(), serde_json::json!(prompt_count));
+        metadata.insert("migrated_at".to_string(), serde_json::json!(chrono::Utc::now().to_rfc3339()));
+    }
+
+    let json_content = serde_json::to_string_pretty(&prompts).map_err(|e| Error::config(format!("Failed to serialize prompts JSON: {}", e)))?;
+    write_file_atomic(path, &json_content).map_err(|e| Error::config(format!("Failed to save prompts JSON: {}", e)))?;
+
+    Ok(report)
 }
 
 #[cfg(test)]
@@ -1054,6 +1412,38 @@ mod tests {
         assert_eq!(template.variables, vec!["name"]);
     }
 
+    #[test]
+    fn test_prompt_cache_picks_up_custom_prompt_changes() {
+        let mut cache = PromptCache::new();
+        let mut custom_prompts = HashMap::from([("no_console".to_string(), "Remove console calls (v1)".to_string())]);
+
+        assert_eq!(cache.get_prompt("no_console", Some(&custom_prompts)), "Remove console calls (v1)");
+        assert_eq!(cache.get_prompt("no_console", Some(&custom_prompts)), "Remove console calls (v1)");
+        assert_eq!(cache.get_prompt("missing_types", Some(&custom_prompts)), get_fallback_prompt("missing_types"));
+        assert_eq!(cache.len(), 2);
+
+        custom_prompts.insert("no_console".to_string(), "Remove console calls (v2)".to_string());
+        assert_eq!(cache.get_prompt("no_console", Some(&custom_prompts)), "Remove console calls (v2)");
+        assert_eq!(cache.len(), 1, "the change dropped every template cached under the old config");
+
+        // Removing the override falls back to the built-in prompt
+        assert_eq!(cache.get_prompt("no_console", None), get_fallback_prompt("no_console"));
+    }
+
+    #[test]
+    fn test_prompt_config_fingerprint_ignores_map_order() {
+        let entries = [("a", "first"), ("b", "second"), ("c", "third")];
+        let forward: HashMap<String, String> = entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let reverse: HashMap<String, String> = entries.iter().rev().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+
+        assert_eq!(prompt_config_fingerprint(Some(&forward)), prompt_config_fingerprint(Some(&reverse)));
+        assert_eq!(prompt_config_fingerprint(None), prompt_config_fingerprint(Some(&HashMap::new())));
+        // Moving text between entries changes the fingerprint
+        let shifted = HashMap::from([("a".to_string(), "firsts".to_string()), ("b".to_string(), "econd".to_string())]);
+        let original = HashMap::from([("a".to_string(), "first".to_string()), ("b".to_string(), "second".to_string())]);
+        assert_ne!(prompt_config_fingerprint(Some(&shifted)), prompt_config_fingerprint(Some(&original)));
+    }
+
     #[test]
     fn test_prompt_rendering() {
         let template = PromptTemplate::new("greeting", "Hello {name}, you are {age} years old!");
@@ -1075,9 +1465,280 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Missing variable: name"));
     }
 
+    #[test]
+    fn test_preview_prompt_uses_optimized_template_over_base() {
+        let mut context = HashMap::new();
+        context.insert("code".to_string(), "let x = 1;".to_string());
+
+        let stored = r#"{
+            "optimized_prompts": { "code_analysis": { "template": "Optimized review of {language}:\n{code}", "score": 0.9 } },
+            "copro_candidates": { "active": [{ "template": "COPRO {code}", "score": 0.8 }] }
+        }"#;
+        assert_eq!(preview_prompt_from("code_analysis", &context, Some(stored)), "Optimized review of {language}:\nlet x = 1;");
+
+        let copro_only = r#"{ "copro_candidates": { "active": [{ "template": "COPRO {code}", "score": 0.8 }] } }"#;
+        assert_eq!(preview_prompt_from("code_analysis", &context, Some(copro_only)), "COPRO let x = 1;");
+
+        let base = preview_prompt_from("code_analysis", &context, None);
+        assert!(base.starts_with("Analyze the following {language} code for potential issues"), "{}", base);
+        assert!(base.contains("let x = 1;"));
+        assert_eq!(preview_prompt_from("code_analysis", &context, Some("not json")), base);
+    }
+
     #[test]
     fn test_variable_extraction() {
         let variables = extract_variables("Hello {name}, welcome to {place}!");
         assert_eq!(variables, vec!["name", "place"]);
     }
+
+    #[test]
+    fn test_render_checked_reports_all_missing_variables() {
+        let template = PromptTemplate::new("fix", "Fix {language} code in {file}:\n{code}\n({language})");
+        let mut context = HashMap::new();
+        context.insert("file".to_string(), "src/a.ts".to_string());
+
+        let missing = template.render_checked(&context).unwrap_err();
+        assert_eq!(missing, vec!["language", "code"]);
+        assert!(template.render(&context).unwrap_err().to_string().contains("Missing variables: language, code"));
+
+        context.insert("language".to_string(), "TypeScript".to_string());
+        context.insert("code".to_string(), "let a = 1;".to_string());
+        assert_eq!(
+            template.render_checked(&context).unwrap(),
+            "Fix TypeScript code in src/a.ts:\nlet a = 1;\n(TypeScript)"
+        );
+    }
+
+    #[test]
+    fn test_escaped_braces_render_as_literals() {
+        let template = PromptTemplate::new("json", "Reply with {{\"file\": \"{file_path}\"}} and keep {{literal}} as is");
+        assert_eq!(template.variables, vec!["file_path"]);
+
+        let context = HashMap::from([("file_path".to_string(), "src/a.ts".to_string())]);
+        assert_eq!(template.render(&context).unwrap(), "Reply with {\"file\": \"src/a.ts\"} and keep {literal} as is");
+    }
+
+    #[test]
+    fn test_placeholder_defaults_apply_only_when_missing() {
+        let template = PromptTemplate::new("fix", "Fix {issues_list} for {file_path} in a {tone?concise} style");
+        assert_eq!(template.variables, vec!["issues_list", "file_path", "tone"]);
+        let mut context = HashMap::from([
+            ("issues_list".to_string(), "no-var".to_string()),
+            ("file_path".to_string(), "src/a.ts".to_string()),
+            ("tone".to_string(), "detailed".to_string()),
+        ]);
+
+        assert_eq!(template.render(&context).unwrap(), "Fix no-var for src/a.ts in a detailed style");
+        context.remove("tone");
+        assert_eq!(template.render(&context).unwrap(), "Fix no-var for src/a.ts in a concise style");
+
+        // Without a default a missing variable is still an error
+        context.remove("file_path");
+        assert!(template.render(&context).unwrap_err().to_string().contains("Missing variable: file_path"));
+    }
+
+    #[test]
+    fn test_render_partial_fills_in_two_steps() {
+        let template = PromptTemplate::new("fix", "Fix {file_path} ({language?TypeScript}), keeping {{braces}}:\n{code}");
+        let first = HashMap::from([("file_path".to_string(), "src/{id}.ts".to_string())]);
+
+        let (partial, missing) = template.render_partial(&first);
+        assert_eq!(missing, vec!["code"]);
+        assert_eq!(partial, "Fix src/{{id}}.ts ({language?TypeScript}), keeping {{braces}}:\n{code}");
+
+        let second = HashMap::from([("code".to_string(), "var a = 1;".to_string())]);
+        let rendered = PromptTemplate::new("fix", partial).render(&second).unwrap();
+        assert_eq!(rendered, "Fix src/{id}.ts (TypeScript), keeping {braces}:\nvar a = 1;");
+
+        let mut full = first.clone();
+        full.extend(second);
+        assert_eq!(template.render_partial(&full).1, Vec::<String>::new());
+        assert_eq!(template.render(&full).unwrap(), rendered);
+    }
+
+    #[test]
+    fn test_single_embedded_template_overridden_by_name() {
+        let embedded = get_embedded_default_templates();
+        let external = vec![
+            PromptTemplate::new("google_style_modernizer", "Modernize {code} for {file_path}"),
+            PromptTemplate::new("typescript_compilation_fixer", "External fixer for {code}"),
+        ];
+        let custom = HashMap::from([("typescript_compilation_fixer".to_string(), "Team fixer for {code}".to_string())]);
+
+        let resolved = resolve_templates(embedded.clone(), external, Some(&custom));
+
+        let template = |name: &str| resolved.iter().find(|t| t.name == name).unwrap().template.as_str();
+        assert_eq!(template("google_style_modernizer"), "Modernize {code} for {file_path}");
+        assert_eq!(template("typescript_compilation_fixer"), "Team fixer for {code}", "custom wins over external");
+        assert_eq!(resolved.len(), embedded.len());
+        for default in embedded.iter().filter(|t| !["google_style_modernizer", "typescript_compilation_fixer"].contains(&t.name.as_str())) {
+            assert_eq!(template(&default.name), default.template, "{} keeps its embedded default", default.name);
+        }
+    }
+
+    #[test]
+    fn test_get_prompt_resolves_custom_then_embedded_then_fallback() {
+        let custom = HashMap::from([("google_style_modernizer".to_string(), "Team modernizer for {code}".to_string())]);
+        let embedded = |name: &str| get_embedded_default_templates().into_iter().find(|t| t.name == name).unwrap().template;
+
+        assert_eq!(get_prompt("google_style_modernizer", Some(&custom)), "Team modernizer for {code}");
+        assert_eq!(get_prompt("complexity_analysis", Some(&custom)), embedded("complexity_analysis"));
+        assert_ne!(embedded("complexity_analysis"), get_fallback_prompt("complexity_analysis"));
+        assert_eq!(get_prompt("no_console", None), get_fallback_prompt("no_console"));
+    }
+
+    #[test]
+    fn test_variable_order_is_first_seen_and_stable() {
+        let template = "{zeta} {alpha} {zeta} {mid} {alpha} {unterminated";
+        for _ in 0..10 {
+            assert_eq!(extract_variables(template), vec!["zeta", "alpha", "mid"]);
+        }
+    }
+
+    #[test]
+    fn test_complexity_prompt_thresholds_come_from_config() {
+        let template = get_embedded_default_templates().into_iter().find(|t| t.name == "complexity_analysis").unwrap();
+        let mut complexity = crate::config::ComplexityConfig::default();
+        complexity.max_cyclomatic_per_function = 7;
+
+        let mut context = complexity.prompt_variables();
+        for (name, value) in [
+            ("language", "TypeScript"),
+            ("cyclomatic_complexity", "9"),
+            ("halstead_difficulty", "12.5"),
+            ("lines_of_code", "40"),
+            ("cognitive_complexity", "11"),
+            ("file_path", "src/a.ts"),
+            ("code", "let a = 1;"),
+        ] {
+            context.insert(name.to_string(), value.to_string());
+        }
+
+        let rendered = template.render(&context).unwrap();
+        assert!(rendered.contains("**Cyclomatic Complexity**: 9 (threshold: 7 per function)"));
+        assert!(rendered.contains("below 7 per function"));
+    }
+
+    #[test]
+    fn test_migrate_legacy_prompts_consolidates_into_prompts_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".moon/moonshine/prompts.json");
+        let legacy = HashMap::from([
+            ("moonshine.prompts.code_fixing", "Fix {language} code in {file_path}:\n{code}"),
+            ("moonshine.prompts.security_analysis", "Audit {code}"),
+        ]);
+
+        let report = migrate_legacy_prompts_with(|key| legacy.get(key).map(|value| value.to_string()), path.to_str().unwrap()).unwrap();
+
+        assert_eq!(report.migrated, vec!["code_fixing", "security_analysis"]);
+        assert_eq!(report.missing.len(), LEGACY_PROMPT_KEYS.len() - 2);
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["version"], "1.0.0");
+        assert_eq!(written["base_prompts"]["code_fixing"]["template"], "Fix {language} code in {file_path}:\n{code}");
+        assert_eq!(written["base_prompts"]["code_fixing"]["variables"], serde_json::json!(["language", "file_path", "code"]));
+        assert_eq!(written["base_prompts"]["security_analysis"]["migrated_from"], "moonshine.prompts.security_analysis");
+        assert!(written["base_prompts"]["code_analysis"].is_object(), "default prompts are kept");
+
+        let templates = parse_external_prompts_json(&written).unwrap();
+        assert!(templates.iter().any(|template| template.name == "code_fixing" && template.template.starts_with("Fix {language}")));
+        assert!(templates.iter().any(|template| template.name == "security_analysis"));
+    }
+
+    #[test]
+    fn test_copro_score_history_in_generation_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompts.json");
+        let prompts = serde_json::json!({
+            "copro_candidates": {
+                "active": [
+                    {"name": "code_fixing", "template": "t3", "score": 0.91, "generation": 3, "created_at": "2026-03-03T10:00:00+00:00"},
+                    {"name": "security_analysis", "template": "s1", "score": 0.4, "generation": 1, "created_at": "2026-03-01T10:00:00Z"},
+                    {"name": "code_fixing", "template": "t2b", "score": 0.84, "generation": 2, "created_at": "2026-03-02T12:00:00Z"},
+                    {"name": "code_fixing", "template": "bad", "score": 0.99, "generation": 4, "created_at": "yesterday"}
+                ],
+                "archived": [
+                    {"name": "code_fixing", "template": "t2a", "score": 0.8, "generation": 2, "created_at": "2026-03-02T09:00:00+02:00"},
+                    {"name": "code_fixing", "template": "t1", "score": 0.62, "generation": 1, "created_at": "2026-03-01T10:00:00Z"}
+                ]
+            }
+        });
+        std::fs::write(&path, prompts.to_string()).unwrap();
+
+        let history = copro_score_history_in(path.to_str().unwrap(), "code_fixing");
+
+        let points: Vec<(u32, f64)> = history.iter().map(|point| (point.generation, point.score)).collect();
+        assert_eq!(points, vec![(1, 0.62), (2, 0.8), (2, 0.84), (3, 0.91)]);
+        assert_eq!(history[1].created_at.to_rfc3339(), "2026-03-02T07:00:00+00:00");
+        assert!(copro_score_history_in(dir.path().join("missing.json").to_str().unwrap(), "code_fixing").is_empty());
+    }
+
+    #[test]
+    fn test_migrate_legacy_prompts_reports_replaced_and_skips_when_nothing_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompts.json");
+        std::fs::write(&path, r#"{"version":"1.0.0","base_prompts":{"optimization":{"template":"old"}}}"#).unwrap();
+
+        let report = migrate_legacy_prompts_with(
+            |key| (key == "moonshine.prompts.optimization").then(|| "new {code}".to_string()),
+            path.to_str().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(report.replaced, vec!["optimization"]);
+
+        let untouched = dir.path().join("untouched.json");
+        let empty = migrate_legacy_prompts_with(|_| None, untouched.to_str().unwrap()).unwrap();
+        assert!(empty.migrated.is_empty());
+        assert!(!untouched.exists());
+    }
+
+    fn optimized_batch() -> Vec<(String, String, f64)> {
+        vec![
+            ("code_fixing".to_string(), "Fix {code}".to_string(), 0.91),
+            ("security_analysis".to_string(), "Audit {code}".to_string(), 0.84),
+            ("missing_jsdoc".to_string(), "Document {code}".to_string(), 0.77),
+        ]
+    }
+
+    #[test]
+    fn test_apply_optimized_batch_sends_all_prompts_in_one_update() {
+        let mut updates = Vec::new();
+
+        apply_optimized_batch_with(optimized_batch(), |update| {
+            updates.push(update.clone());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(updates.len(), 1);
+        let optimized = &updates[0]["optimized_prompts"];
+        assert_eq!(optimized.as_object().unwrap().len(), 3);
+        assert_eq!(optimized["code_fixing"]["template"], "Fix {code}");
+        assert_eq!(optimized["security_analysis"]["score"], 0.84);
+        assert_eq!(optimized["missing_jsdoc"]["template"], "Document {code}");
+        assert_eq!(updates[0]["last_updated"], optimized["code_fixing"]["optimized_at"]);
+    }
+
+    #[test]
+    fn test_apply_optimized_batch_failed_write_leaves_file_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompts.json");
+        let original = r#"{"version":"1.0.0","optimized_prompts":{}}"#;
+        std::fs::write(&path, original).unwrap();
+        let staging = dir.path().join("prompts.json.tmp");
+
+        // The update dies halfway through its staging file, before replacing prompts.json
+        let result = apply_optimized_batch_with(optimized_batch(), |update| {
+            let content = update.to_string();
+            std::fs::write(&staging, &content[..content.len() / 2])?;
+            Err("disk full".into())
+        });
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+
+        // An invalid entry rejects the whole batch before anything is sent
+        let mut invalid = optimized_batch();
+        invalid[1].2 = f64::NAN;
+        assert!(apply_optimized_batch_with(invalid, |_| panic!("nothing is written")).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    }
 }
@@ -0,0 +1,188 @@
+//! Behavioral pattern analysis through the provider router
+//!
+//! [`AIRouter`] implements [`AiAnalysisClient`], so the AI step of AI-enhanced rules runs on
+//! the routed providers with the router's retries and fallback. When every provider fails,
+//! the router's `Error::Multiple` is returned unchanged; the rule executor treats it like
+//! unavailable AI and keeps the static results.
+
+use super::{AIContext, AIRequest, AIRouter, AITaskKind, PostProcessorRegistry};
+use crate::oxc_adapter::ai_behavioral::{AiAnalysisClient, AiPatternResult, AnalysisContext, BehavioralPattern};
+use futures::executor::block_on;
+
+impl AiAnalysisClient for AIRouter {
+    fn analyze_patterns(
+        &self,
+        code: &str,
+        patterns: &[BehavioralPattern],
+        context: &AnalysisContext,
+    ) -> Result<Vec<AiPatternResult>, Box<dyn std::error::Error>> {
+        let language = if context.file_type.is_typescript() { "typescript" } else { "javascript" };
+        let request = AIRequest::new(
+            pattern_prompt(code, patterns),
+            AIContext::new(
+                String::new(),
+                AITaskKind::CodeAnalysis {
+                    language: language.to_string(),
+                    content: code.to_string(),
+                },
+            )
+            .with_file_path(context.file_path.clone()),
+        );
+
+        let response = block_on(self.execute(request))?;
+        Ok(parse_pattern_results(&response.content, patterns, code.len()))
+    }
+
+    fn suggest_fix(&self, code: &str, pattern: &BehavioralPattern, issue_span: (usize, usize)) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let snippet = code.get(issue_span.0..issue_span.1).unwrap_or(code);
+        let request = AIRequest::new(
+            format!(
+                "{}\n\nRewrite this code to resolve \"{}\" without changing its behavior. Return only the replacement code.\n\n{}",
+                pattern.ai_prompt, pattern.name, snippet
+            ),
+            AIContext::new(
+                String::new(),
+                AITaskKind::CodeFix {
+                    language: "typescript".to_string(),
+                    content: snippet.to_string(),
+                },
+            ),
+        );
+
+        let response = block_on(self.execute(request.clone()))?;
+        let fix = PostProcessorRegistry::with_defaults().process_for(&request.context, &response.content);
+        Ok(Some(fix).filter(|fix| !fix.trim().is_empty()))
+    }
+}
+
+/// The global router is shared, so rule executors hold it by reference
+impl AiAnalysisClient for &'static AIRouter {
+    fn analyze_patterns(
+        &self,
+        code: &str,
+        patterns: &[BehavioralPattern],
+        context: &AnalysisContext,
+    ) -> Result<Vec<AiPatternResult>, Box<dyn std::error::Error>> {
+        (**self).analyze_patterns(code, patterns, context)
+    }
+
+    fn suggest_fix(&self, code: &str, pattern: &BehavioralPattern, issue_span: (usize, usize)) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        (**self).suggest_fix(code, pattern, issue_span)
+    }
+}
+
+fn pattern_prompt(code: &str, patterns: &[BehavioralPattern]) -> String {
+    let checks: Vec<String> = patterns.iter().map(|pattern| format!("- {}: {}", pattern.id, pattern.ai_prompt)).collect();
+    format!(
+        "Check this code for the behavioral patterns below.\n\n{}\n\n\
+         Return a JSON array with one object per finding: 'pattern_id' (one of the IDs above), \
+         'confidence' (0.0-1.0), 'message', optional 'suggestion', and 'start_offset'/'end_offset' \
+         (byte offsets into the code). Return [] when nothing applies.\n\nCode:\n{}",
+        checks.join("\n"),
+        code
+    )
+}
+
+/// Findings in `response` for known patterns; offsets are clamped to the code
+fn parse_pattern_results(response: &str, patterns: &[BehavioralPattern], code_len: usize) -> Vec<AiPatternResult> {
+    let (Some(json_start), Some(json_end)) = (response.find('['), response.rfind(']')) else {
+        return Vec::new();
+    };
+    let Ok(findings) = serde_json::from_str::<Vec<serde_json::Value>>(response.get(json_start..=json_end).unwrap_or_default()) else {
+        return Vec::new();
+    };
+
+    findings
+        .iter()
+        .filter_map(|finding| {
+            let pattern_id = finding.get("pattern_id")?.as_str()?;
+            if !patterns.iter().any(|pattern| pattern.id == pattern_id) {
+                return None;
+            }
+            let offset = |key: &str| finding.get(key).and_then(serde_json::Value::as_u64).map_or(0, |offset| (offset as usize).min(code_len));
+            let start_offset = offset("start_offset");
+            Some(AiPatternResult {
+                pattern_id: pattern_id.to_string(),
+                confidence: finding.get("confidence")?.as_f64()? as f32,
+                message: finding.get("message")?.as_str()?.to_string(),
+                suggestion: finding.get("suggestion").and_then(serde_json::Value::as_str).map(str::to_string),
+                start_offset,
+                end_offset: offset("end_offset").max(start_offset),
+                related_patterns: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oxc_adapter::ai_behavioral::BehavioralPatternType;
+    use crate::provider_router::AIProviderConfig;
+    use crate::rule_types::RuleSeverity;
+    use crate::rulebase::execution_engine::{RuleExecutionContext, RuleExecutor};
+    use crate::rulebase::testing::static_rule;
+    use crate::rulebase::RuleImplementation;
+    use oxc_span::SourceType;
+
+    fn pattern(id: &str) -> BehavioralPattern {
+        BehavioralPattern {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            category: "architecture".to_string(),
+            severity: RuleSeverity::Warning,
+            pattern_type: BehavioralPatternType::ArchitecturalSmell,
+            ai_prompt: String::new(),
+            confidence_threshold: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_findings_are_parsed_for_known_patterns_only() {
+        let patterns = vec![pattern("god-object")];
+        let response = r#"Findings:
+[
+  {"pattern_id": "god-object", "confidence": 0.9, "message": "Too many responsibilities", "start_offset": 4, "end_offset": 400},
+  {"pattern_id": "invented", "confidence": 0.9, "message": "?"}
+]"#;
+
+        let results = parse_pattern_results(response, &patterns, 20);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].pattern_id, "god-object");
+        assert_eq!((results[0].start_offset, results[0].end_offset), (4, 20));
+        assert!(parse_pattern_results("no JSON here", &patterns, 20).is_empty());
+    }
+
+    #[test]
+    fn test_rules_fall_back_to_static_analysis_when_every_routed_provider_fails() {
+        // Neither CLI exists, so each provider fails and the router falls back until none is left
+        let missing_cli = |name: &str| AIProviderConfig {
+            name: name.to_string(),
+            command: format!("moonshine-test-missing-{}-cli", name),
+            api_key_env: None,
+            requires_api_key: false,
+            ..AIProviderConfig::claude()
+        };
+        let router = AIRouter::with_providers(vec![missing_cli("claude"), missing_cli("google")]);
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\nconsole.log(a);\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let mut ai_rule = static_rule("ai-complexity");
+        ai_rule.implementation = RuleImplementation::AiBehavioral {
+            pattern_type: "complexity".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), ai_rule];
+
+        let outcome = RuleExecutor::new().with_ai_availability(true).with_ai_client(Box::new(router)).evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.diagnostics.iter().filter(|d| d.rule_name == "prefer-const").count(), 1);
+        assert_eq!(outcome.executed_rules, 1);
+        assert_eq!(outcome.notices.len(), 1);
+        assert!(outcome.notices[0].contains("AI unavailable"));
+    }
+}
@@ -0,0 +1,341 @@
+//! Ensemble execution across several AI providers
+//!
+//! With `ai.ensemble_providers` above one, each AI fix request is sent to that many of
+//! the best-ranked providers, each response is scored with a [`MetricFn`], and the
+//! highest-scoring one wins. Every candidate (including failures) is kept in the
+//! [`EnsembleResult`] for debugging. Provider calls block, so native builds run them on
+//! scoped threads, at most `ai.max_concurrent_requests` at a time; under WASM the host
+//! runs commands one at a time and so do ensembles.
+
+use super::post_process::PostProcessorRegistry;
+use super::{AIProviderConfig, AIRequest, AIResponse, AITaskKind, OFFLINE_PROVIDER};
+use crate::buffer_lint::{lint_buffer, parse_guarded};
+use crate::config::MoonShineConfig;
+use crate::error::{Error, Result};
+use crate::token_usage::tie_break;
+use oxc_allocator::Allocator;
+use oxc_span::SourceType;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Scores a provider response for a request; higher is better
+pub type MetricFn = Box<dyn Fn(&AIRequest, &AIResponse) -> f64 + Send + Sync>;
+
+/// One provider's outcome in an ensemble run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleCandidate {
+    pub provider: String,
+    pub response: Option<AIResponse>,
+    pub score: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Best response of an ensemble run plus every candidate that was tried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleResult {
+    pub best: AIResponse,
+    pub best_score: f64,
+    pub candidates: Vec<EnsembleCandidate>,
+}
+
+/// Run `request` on every provider with at most `max_concurrent` in flight and keep the best.
+///
+/// Equal scores go to the response [`tie_break`] prefers (fewer tokens, then the smaller
+/// content hash), so the winner does not depend on provider order. Fails only when no
+/// provider produced a response.
+pub fn run_ensemble<F>(providers: &[&AIProviderConfig], request: &AIRequest, max_concurrent: usize, metric: &MetricFn, execute: F) -> Result<EnsembleResult>
+where
+    F: Fn(&AIProviderConfig) -> Result<AIResponse> + Sync,
+{
+    let Some(first_provider) = providers.first() else {
+        let dummy_config = AIProviderConfig::claude();
+        return Err(Error::ai_execution(&dummy_config, "No AI providers available for ensemble execution"));
+    };
+
+    let execute = &execute;
+    let mut outcomes: Vec<std::result::Result<AIResponse, String>> = Vec::with_capacity(providers.len());
+    for batch in providers.chunks(max_concurrent.max(1)) {
+        #[cfg(not(feature = "wasm"))]
+        std::thread::scope(|scope| {
+            let calls: Vec<_> = batch
+                .iter()
+                .map(|&provider| scope.spawn(move || execute(provider).map_err(|error| error.to_string())))
+                .collect();
+            outcomes.extend(calls.into_iter().map(|call| call.join().unwrap_or_else(|_| Err("provider call panicked".to_string()))));
+        });
+        #[cfg(feature = "wasm")]
+        outcomes.extend(batch.iter().map(|provider| execute(provider).map_err(|error| error.to_string())));
+    }
+
+    let candidates: Vec<EnsembleCandidate> = outcomes
+        .into_iter()
+        .enumerate()
+        .map(|(index, outcome)| match outcome {
+            Ok(response) => EnsembleCandidate {
+                provider: providers[index].name.clone(),
+                score: Some(metric(request, &response)),
+                response: Some(response),
+                error: None,
+            },
+            Err(error) => EnsembleCandidate {
+                provider: providers[index].name.clone(),
+                response: None,
+                score: None,
+                error: Some(error),
+            },
+        })
+        .collect();
+
+    let mut best: Option<(&AIResponse, f64)> = None;
+    for candidate in &candidates {
+        if let (Some(response), Some(score)) = (&candidate.response, candidate.score) {
+            let preferred = best.map_or(true, |(best_response, best_score)| {
+                score.total_cmp(&best_score).then_with(|| tie_break(&response.content, &best_response.content)) == Ordering::Greater
+            });
+            if preferred {
+                best = Some((response, score));
+            }
+        }
+    }
+
+    let Some((best, best_score)) = best.map(|(response, score)| (response.clone(), score)) else {
+        let failures: Vec<String> = candidates
+            .iter()
+            .map(|candidate| format!("{}: {}", candidate.provider, candidate.error.as_deref().unwrap_or("no response")))
+            .collect();
+        return Err(Error::ai_execution(
+            first_provider,
+            format!("All ensemble providers failed: {}", failures.join("; ")),
+        ));
+    };
+
+    moon_info!(
+        "AI ensemble selected {} (score {:.2}) from {} candidates",
+        best.provider_used,
+        best_score,
+        candidates.len()
+    );
+
+    Ok(EnsembleResult { best, best_score, candidates })
+}
+
+/// The code a response would apply: its content after the request's task post-processors
+pub fn applied_output(request: &AIRequest, response: &AIResponse) -> String {
+    PostProcessorRegistry::with_defaults().process_for(&request.context, &response.content)
+}
+
+/// 1.0 when the response's [`applied_output`] parses as the request's language, 0.0 otherwise
+pub fn compiles_ok() -> MetricFn {
+    Box::new(|request, response| {
+        let source_type = source_type_for(&request.context.task_kind);
+        let code = applied_output(request, response);
+        let allocator = Allocator::default();
+        if parse_guarded(&allocator, &code, source_type).is_ok_and(|parsed| parsed.errors.is_empty()) {
+            1.0
+        } else {
+            0.0
+        }
+    })
+}
+
+/// Fraction of the original diagnostics removed by the response, in `[-1.0, 1.0]`
+///
+/// `count_diagnostics` lints a piece of source code; the original is the `CodeFix`
+/// content of the request and the fix is the response's [`applied_output`]. Requests
+/// without code score 0.0.
+pub fn diagnostic_reduction<C>(count_diagnostics: C) -> MetricFn
+where
+    C: Fn(&str) -> usize + Send + Sync + 'static,
+{
+    Box::new(move |request, response| {
+        let AITaskKind::CodeFix { content, .. } = &request.context.task_kind else {
+            return 0.0;
+        };
+        let before = count_diagnostics(content) as f64;
+        let after = count_diagnostics(&applied_output(request, response)) as f64;
+        if before == 0.0 {
+            return if after == 0.0 { 0.0 } else { -1.0 };
+        }
+        ((before - after) / before).clamp(-1.0, 1.0)
+    })
+}
+
+/// Sum of several metrics, e.g. `compiles_ok` + `diagnostic_reduction`
+pub fn combined(metrics: Vec<MetricFn>) -> MetricFn {
+    Box::new(move |request, response| metrics.iter().map(|metric| metric(request, response)).sum())
+}
+
+/// Metric for fixes of `file_path`: `compiles_ok` plus the `diagnostic_reduction` of its
+/// static diagnostics under `config`; AI rules stay off so scoring never calls a provider
+pub fn fix_metric(file_path: &str, config: &MoonShineConfig) -> MetricFn {
+    let file_path = file_path.to_string();
+    let mut config = config.clone();
+    config.ai.providers = vec![OFFLINE_PROVIDER.to_string()];
+    combined(vec![
+        compiles_ok(),
+        diagnostic_reduction(move |code| lint_buffer(code, &file_path, &config).map_or(0, |result| result.diagnostics.len())),
+    ])
+}
+
+fn source_type_for(task_kind: &AITaskKind) -> SourceType {
+    let language = match task_kind {
+        AITaskKind::CodeFix { language, .. } | AITaskKind::CodeAnalysis { language, .. } | AITaskKind::CodeGeneration { language, .. } => language.as_str(),
+        _ => "typescript",
+    };
+    match language {
+        "javascript" | "js" => SourceType::mjs(),
+        "jsx" => SourceType::jsx(),
+        "tsx" => SourceType::tsx(),
+        _ => SourceType::ts(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider_router::AIContext;
+
+    fn mock_provider(name: &str) -> AIProviderConfig {
+        AIProviderConfig {
+            name: name.to_string(),
+            ..AIProviderConfig::claude()
+        }
+    }
+
+    fn fix_request(content: &str) -> AIRequest {
+        let context = AIContext::new(
+            "ensemble-test",
+            AITaskKind::CodeFix {
+                language: "typescript".to_string(),
+                content: content.to_string(),
+            },
+        )
+        .with_file_path("src/app.ts");
+        AIRequest::new("Fix the lint issues", context)
+    }
+
+    fn response(provider: &str, content: &str) -> AIResponse {
+        AIResponse {
+            provider_used: provider.to_string(),
+            content: content.to_string(),
+            session_id: "ensemble-test".to_string(),
+            success: true,
+            execution_time_ms: 1,
+            error_message: None,
+            routing_reason: "ensemble".to_string(),
+            finish_reason: None,
+        }
+    }
+
+    fn loose_equality_count(code: &str) -> usize {
+        code.matches(" == ").count()
+    }
+
+    #[test]
+    fn test_ensemble_selects_better_provider() {
+        let sloppy = mock_provider("sloppy");
+        let careful = mock_provider("careful");
+        let request = fix_request("if (a == b) {}\nif (c == d) {}");
+        let metric = combined(vec![compiles_ok(), diagnostic_reduction(loose_equality_count)]);
+
+        let result = run_ensemble(&[&sloppy, &careful], &request, 2, &metric, |provider| {
+            match provider.name.as_str() {
+                "sloppy" => Ok(response("sloppy", "if (a === b) {}\nif (c == d) {")),
+                _ => Ok(response("careful", "if (a === b) {}\nif (c === d) {}")),
+            }
+        })
+        .expect("ensemble should succeed");
+
+        assert_eq!(result.best.provider_used, "careful");
+        assert!((result.best_score - 2.0).abs() < f64::EPSILON);
+        assert_eq!(result.candidates.len(), 2);
+        assert_eq!(result.candidates[0].provider, "sloppy");
+        assert!((result.candidates[0].score.unwrap() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ensemble_tie_goes_to_shorter_response_in_any_order() {
+        let terse = mock_provider("terse");
+        let verbose = mock_provider("verbose");
+        let request = fix_request("if (a == b) {}");
+
+        for providers in [[&terse, &verbose], [&verbose, &terse]] {
+            let result = run_ensemble(&providers, &request, 2, &compiles_ok(), |provider| {
+                match provider.name.as_str() {
+                    "terse" => Ok(response("terse", "if (a === b) {}")),
+                    _ => Ok(response("verbose", "// Strict equality avoids coercion\nif (a === b) {}")),
+                }
+            })
+            .expect("ensemble should succeed");
+
+            assert_eq!(result.candidates[0].score, result.candidates[1].score, "both responses parse");
+            assert_eq!(result.best.provider_used, "terse");
+        }
+    }
+
+    #[test]
+    fn test_ensemble_records_failed_candidates() {
+        let broken = mock_provider("broken");
+        let working = mock_provider("working");
+        let request = fix_request("if (a == b) {}");
+
+        let result = run_ensemble(&[&broken, &working], &request, 1, &compiles_ok(), |provider| {
+            if provider.name == "broken" {
+                Err(Error::ai_execution(provider, "exit code 1"))
+            } else {
+                Ok(response("working", "if (a === b) {}"))
+            }
+        })
+        .expect("one provider succeeded");
+
+        assert_eq!(result.best.provider_used, "working");
+        assert!(result.candidates[0].error.as_deref().unwrap().contains("exit code 1"));
+        assert!(result.candidates[0].response.is_none());
+    }
+
+    #[test]
+    fn test_fenced_response_scored_on_the_code_it_applies() {
+        let request = fix_request("if (a == b) {}");
+        let fenced = response("fenced", "Here is the fix:\n```ts\nif (a === b) {}\n```\nIt uses strict equality.");
+
+        assert_eq!(applied_output(&request, &fenced).trim(), "if (a === b) {}");
+        assert!((compiles_ok()(&request, &fenced) - 1.0).abs() < f64::EPSILON);
+        assert!((diagnostic_reduction(loose_equality_count)(&request, &fenced) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    #[test]
+    fn test_providers_run_concurrently_up_to_the_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+        let providers: Vec<AIProviderConfig> = ["one", "two", "three", "four"].into_iter().map(mock_provider).collect();
+        let providers: Vec<&AIProviderConfig> = providers.iter().collect();
+        let request = fix_request("if (a == b) {}");
+
+        for max_concurrent in [1, 2, 4] {
+            let in_flight = AtomicUsize::new(0);
+            let peak = AtomicUsize::new(0);
+            run_ensemble(&providers, &request, max_concurrent, &compiles_ok(), |provider| {
+                peak.fetch_max(in_flight.fetch_add(1, AtomicOrdering::SeqCst) + 1, AtomicOrdering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                in_flight.fetch_sub(1, AtomicOrdering::SeqCst);
+                Ok(response(&provider.name, "if (a === b) {}"))
+            })
+            .expect("every provider answers");
+
+            assert_eq!(peak.load(AtomicOrdering::SeqCst), max_concurrent);
+        }
+    }
+
+    #[test]
+    fn test_ensemble_fails_when_every_provider_fails() {
+        let only = mock_provider("only");
+        let request = fix_request("if (a == b) {}");
+
+        let result = run_ensemble(&[&only], &request, 2, &compiles_ok(), |provider| {
+            Err(Error::ai_execution(provider, "timed out"))
+        });
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! JSON-mode output enforcement for structured AI tasks
+//!
+//! Free-form model text is brittle to parse, so for structured tasks the router asks
+//! JSON-capable models for a JSON payload and validates it against the signature's
+//! output fields. An invalid payload gets exactly one retry with a "return valid JSON"
+//! nudge before the request fails. Models without JSON mode keep using the
+//! `[[ ## field ## ]]` delimiter parser in the DSPy chat adapter.
+
+use super::AIProviderConfig;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Nudge appended to the prompt when the first JSON response fails validation.
+pub const JSON_RETRY_NUDGE: &str =
+    "Your previous response was not valid JSON. Return valid JSON only: a single object containing every requested output field, with no surrounding prose or code fences.";
+
+/// Model-level capabilities that affect how requests are built and parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    pub supports_json_mode: bool, // Whether the CLI can be asked for JSON-only output
+}
+
+impl ModelCapabilities {
+    /// Resolve model capabilities for a provider configuration
+    pub fn for_provider(provider: &AIProviderConfig) -> Self {
+        // Claude (`--output-format json`) and Codex (`--experimental-json`) expose JSON output;
+        // the Gemini CLI has no equivalent flag yet.
+        let supports_json_mode = matches!(provider.name.as_str(), "claude" | "openai");
+
+        Self { supports_json_mode }
+    }
+}
+
+impl AIProviderConfig {
+    /// Model capabilities for this provider
+    pub fn model_capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities::for_provider(self)
+    }
+}
+
+/// Validate a JSON-mode payload against a signature's output fields.
+///
+/// `output_fields` uses the `MetaSignature::output_fields()` shape: an object keyed by
+/// field name whose values carry a `type` (and optionally a `schema`). The payload must
+/// be a JSON object containing every output field; non-string field types must not be
+/// returned as bare strings. Surrounding ```json fences are tolerated.
+pub fn validate_json_payload(payload: &str, output_fields: &Value) -> Result<Value> {
+    let trimmed = strip_code_fence(payload);
+
+    let parsed: Value = serde_json::from_str(trimmed).map_err(|e| Error::Validation {
+        field: "json_payload".to_string(),
+        expected: "valid JSON object".to_string(),
+        actual: format!("unparseable response: {}", e),
+    })?;
+
+    let object = parsed.as_object().ok_or_else(|| Error::Validation {
+        field: "json_payload".to_string(),
+        expected: "JSON object".to_string(),
+        actual: json_kind(&parsed).to_string(),
+    })?;
+
+    if let Some(fields) = output_fields.as_object() {
+        for (field_name, field) in fields {
+            let value = object.get(field_name).ok_or_else(|| Error::Validation {
+                field: field_name.clone(),
+                expected: "field present in JSON payload".to_string(),
+                actual: "missing".to_string(),
+            })?;
+
+            let data_type = field["type"].as_str().unwrap_or("String");
+            if data_type != "String" && data_type != "string" && value.is_string() {
+                return Err(Error::Validation {
+                    field: field_name.clone(),
+                    expected: data_type.to_string(),
+                    actual: "string".to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// The model's own text inside a JSON-mode CLI's output; `None` when the output is not the expected wrapper.
+///
+/// Claude's `--output-format json` prints a result object whose `result` string is the model
+/// output; Codex's `--experimental-json` prints a JSONL event stream whose last agent message
+/// is. Other providers print the model output as is.
+pub fn model_output(provider: &AIProviderConfig, stdout: &str) -> Option<String> {
+    match provider.name.as_str() {
+        "claude" => {
+            let wrapper: Value = serde_json::from_str(stdout.trim()).ok()?;
+            if wrapper.get("is_error").and_then(Value::as_bool) == Some(true) {
+                return None;
+            }
+            wrapper.get("result")?.as_str().map(str::to_string)
+        }
+        "openai" => stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+            .filter_map(|event| agent_message(&event))
+            .last(),
+        _ => Some(stdout.to_string()),
+    }
+}
+
+/// Text of a Codex agent-message event, in the `msg` or the `item.completed` event shape
+fn agent_message(event: &Value) -> Option<String> {
+    if let Some(msg) = event.get("msg").filter(|msg| msg["type"] == "agent_message") {
+        return msg.get("message")?.as_str().map(str::to_string);
+    }
+    let item = event.get("item")?;
+    let kind = item.get("type").or_else(|| item.get("item_type"))?.as_str()?;
+    if kind != "agent_message" && kind != "assistant_message" {
+        return None;
+    }
+    item.get("text")?.as_str().map(str::to_string)
+}
+
+/// Run a JSON-mode request, retrying once with [`JSON_RETRY_NUDGE`] when validation fails.
+///
+/// `call` receives the prompt to send and returns the raw model output. The provider is
+/// only used for error attribution.
+pub fn execute_with_json_retry<F>(provider: &AIProviderConfig, prompt: &str, output_fields: &Value, mut call: F) -> Result<Value>
+where
+    F: FnMut(&str) -> Result<String>,
+{
+    let first = call(prompt)?;
+    let first_error = match validate_json_payload(&first, output_fields) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+
+    moon_warn!("AI Provider {} returned invalid JSON ({}) - retrying once", provider.name, first_error);
+
+    let retry_prompt = format!("{}\n\n{}", prompt, JSON_RETRY_NUDGE);
+    let second = call(&retry_prompt)?;
+
+    validate_json_payload(&second, output_fields).map_err(|error| Error::ai_execution(provider, format!("JSON mode response invalid after retry: {}", error)))
+}
+
+fn strip_code_fence(payload: &str) -> &str {
+    let trimmed = payload.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let rest = rest.strip_prefix("json").unwrap_or(rest);
+    rest.strip_suffix("```").unwrap_or(rest).trim()
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn json_capable_provider() -> AIProviderConfig {
+        AIProviderConfig {
+            name: "claude".to_string(),
+            command: "claude".to_string(),
+            model: "sonnet".to_string(),
+            api_key_env: None,
+            requires_api_key: false,
+            capabilities: super::super::ProviderCapabilities {
+                code_analysis: 0.9,
+                code_generation: 0.9,
+                complex_reasoning: 0.9,
+                speed: 0.8,
+                context_length: 200000,
+                supports_sessions: true,
+            },
+        }
+    }
+
+    fn output_fields() -> Value {
+        json!({
+            "fixed_code": { "type": "String", "desc": "Corrected code" },
+            "confidence": { "type": "f32", "desc": "Confidence score" }
+        })
+    }
+
+    #[test]
+    fn test_model_capabilities_json_mode() {
+        let mut provider = json_capable_provider();
+        assert!(provider.model_capabilities().supports_json_mode);
+
+        provider.name = "google".to_string();
+        assert!(!provider.model_capabilities().supports_json_mode);
+    }
+
+    #[test]
+    fn test_valid_json_accepted_without_retry() {
+        let provider = json_capable_provider();
+        let mut calls = Vec::new();
+
+        let value = execute_with_json_retry(&provider, "fix it", &output_fields(), |prompt| {
+            calls.push(prompt.to_string());
+            Ok(r#"{"fixed_code": "const a = 1;", "confidence": 0.9}"#.to_string())
+        })
+        .expect("valid JSON should be accepted");
+
+        assert_eq!(calls.len(), 1);
+        assert_eq!(value["fixed_code"], "const a = 1;");
+        assert_eq!(value["confidence"], 0.9);
+    }
+
+    #[test]
+    fn test_invalid_json_retries_once_then_errors() {
+        let provider = json_capable_provider();
+        let mut calls = Vec::new();
+
+        let result = execute_with_json_retry(&provider, "fix it", &output_fields(), |prompt| {
+            calls.push(prompt.to_string());
+            Ok("Sure! Here is the fixed code: const a = 1;".to_string())
+        });
+
+        assert!(matches!(result, Err(Error::AIExecution { .. })));
+        assert_eq!(calls.len(), 2);
+        assert!(!calls[0].contains(JSON_RETRY_NUDGE));
+        assert!(calls[1].ends_with(JSON_RETRY_NUDGE));
+    }
+
+    #[test]
+    fn test_retry_recovers_when_second_response_valid() {
+        let provider = json_capable_provider();
+        let mut attempts = 0;
+
+        let value = execute_with_json_retry(&provider, "fix it", &output_fields(), |_| {
+            attempts += 1;
+            if attempts == 1 {
+                Ok("not json".to_string())
+            } else {
+                Ok("```json\n{\"fixed_code\": \"x\", \"confidence\": 1.0}\n```".to_string())
+            }
+        })
+        .expect("second response should be accepted");
+
+        assert_eq!(attempts, 2);
+        assert_eq!(value["fixed_code"], "x");
+    }
+
+    #[test]
+    fn test_model_output_unwraps_cli_wrappers() {
+        let claude = json_capable_provider();
+        let wrapper = r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":2310,"num_turns":1,"result":"{\"fixed_code\": \"const a = 1;\", \"confidence\": 0.9}","session_id":"5c1e","total_cost_usd":0.0042}"#;
+        let payload = model_output(&claude, wrapper).expect("claude result wrapper");
+        assert_eq!(validate_json_payload(&payload, &output_fields()).unwrap()["fixed_code"], "const a = 1;");
+        assert_eq!(model_output(&claude, r#"{"type":"result","is_error":true,"result":"Credit balance too low"}"#), None);
+        assert_eq!(model_output(&claude, "plain text"), None);
+
+        let codex = AIProviderConfig {
+            name: "openai".to_string(),
+            ..json_capable_provider()
+        };
+        let stream = concat!(
+            r#"{"type":"thread.started","thread_id":"0199"}"#,
+            "\n",
+            r#"{"type":"item.completed","item":{"id":"item_0","type":"reasoning","text":"Fixing the declaration"}}"#,
+            "\n",
+            r#"{"type":"item.completed","item":{"id":"item_1","type":"agent_message","text":"{\"fixed_code\": \"x\", \"confidence\": 1.0}"}}"#,
+            "\n",
+            r#"{"type":"turn.completed","usage":{"input_tokens":812,"output_tokens":24}}"#,
+            "\n"
+        );
+        let payload = model_output(&codex, stream).expect("codex agent message");
+        assert_eq!(validate_json_payload(&payload, &output_fields()).unwrap()["fixed_code"], "x");
+        assert_eq!(model_output(&codex, r#"{"type":"turn.failed"}"#), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_missing_and_mistyped_fields() {
+        let missing = validate_json_payload(r#"{"fixed_code": "x"}"#, &output_fields());
+        assert!(matches!(missing, Err(Error::Validation { ref field, .. }) if field == "confidence"));
+
+        let mistyped = validate_json_payload(r#"{"fixed_code": "x", "confidence": "high"}"#, &output_fields());
+        assert!(matches!(mistyped, Err(Error::Validation { ref field, .. }) if field == "confidence"));
+
+        let not_object = validate_json_payload("[1, 2]", &output_fields());
+        assert!(matches!(not_object, Err(Error::Validation { ref actual, .. }) if actual == "array"));
+    }
+}
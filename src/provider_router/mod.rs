@@ -11,7 +11,14 @@
 //! - OpenAI Codex CLI (codex) - specialized for code generation and completion
 //! - Future providers (extensible architecture)
 
+pub mod behavioral_client; // Behavioral pattern analysis for AI-enhanced rules via the router
 pub mod compiled; // Zero-runtime-cost compiled provider capabilities
+pub mod ensemble; // Multi-provider execution with metric-based selection
+pub mod json_mode; // JSON-mode output enforcement for structured tasks
+pub mod post_process; // Per-task cleanup of raw AI responses
+pub mod provider_error; // Provider failure classification and retry policy
+pub mod system_prompt; // Project-wide system prompt prepended to every request
+pub mod warmup; // One-time provider connectivity checks reused across calls
 
 use crate::error::{Error, Result};
 use crate::moon_pdk_interface::AiLinterConfig;
@@ -22,9 +29,19 @@ use std::collections::HashMap;
 
 // Re-exports
 pub use compiled::{available_compiled_provider_names, get_compiled_provider_capabilities, has_compiled_provider};
+pub use json_mode::{validate_json_payload, ModelCapabilities};
+pub use post_process::{ExtractFirstCodeBlock, PostProcessorRegistry, RemoveLeadingProse, ResponsePostProcessor, StripMarkdownFences};
+pub use provider_error::{ProviderError, RetryDecision, RetryPolicy};
+pub use warmup::ProviderWarmup;
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
+/// `ai.providers` entry that disables AI entirely, for offline/static-only runs such as CI
+pub const OFFLINE_PROVIDER: &str = "none";
+
+/// Environment variable the Claude CLI reads its output token cap from
+const CLAUDE_MAX_OUTPUT_TOKENS_ENV: &str = "CLAUDE_CODE_MAX_OUTPUT_TOKENS";
+
 /// AI provider capabilities for intelligent routing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderCapabilities {
@@ -48,6 +65,18 @@ pub struct AIProviderConfig {
 }
 
 impl AIProviderConfig {
+    /// Whether the API key this provider requires (if any) is set in the environment
+    pub fn has_credentials(&self) -> bool {
+        if !self.requires_api_key {
+            return true;
+        }
+
+        match self.api_key_env.as_deref() {
+            Some(key) if !key.is_empty() => std::env::var(key).is_ok(),
+            _ => false,
+        }
+    }
+
     /// Create Claude provider configuration using model from config
     pub fn claude() -> Self {
         let config = crate::config::MoonShineConfig::from_moon_workspace().unwrap_or_default();
@@ -146,9 +175,9 @@ pub struct RequestRequirements {
     pub needs_sessions: bool,          // Requires session continuity
 }
 
-/// Execution context for AI requests (now focuses on task type)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum AIContext {
+/// Task an AI request performs; decides provider requirements and CLI flags
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub enum AITaskKind {
     /// Code fixing and optimization - needs strong code analysis
     CodeFix { language: String, content: String },
     /// DSPy optimization and training - needs complex reasoning and sessions
@@ -158,17 +187,85 @@ pub enum AIContext {
     /// Code analysis tasks - needs strong analysis capabilities
     CodeAnalysis { language: String, content: String },
     /// General prompt execution - balanced requirements
+    #[default]
     General,
 }
 
+/// Context every AI request carries down to the provider layer.
+///
+/// Routing reads `task_kind`, the budget check reads `remaining_budget`, and logging and
+/// provider working directories use `session_id` and `file_path`. Every field has a
+/// default, so partially built or deserialized contexts still route.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AIContext {
+    pub session_id: String, // Empty runs in the shared `default` session
+    pub file_path: Option<String>,
+    pub task_kind: AITaskKind,
+    pub remaining_budget: Option<u32>, // Prompt tokens the request may still spend; `None` is unbounded
+    pub system_prompt: Option<String>, // Replaces the router's `ai.system_prompt` for this request
+}
+
+impl AIContext {
+    pub fn new(session_id: impl Into<String>, task_kind: AITaskKind) -> Self {
+        Self {
+            session_id: session_id.into(),
+            task_kind,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_file_path(mut self, file_path: impl Into<String>) -> Self {
+        self.file_path = Some(file_path.into());
+        self
+    }
+
+    pub fn with_remaining_budget(mut self, tokens: u32) -> Self {
+        self.remaining_budget = Some(tokens);
+        self
+    }
+
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    /// Session the request runs in; never empty
+    pub fn session(&self) -> &str {
+        if self.session_id.is_empty() {
+            "default"
+        } else {
+            &self.session_id
+        }
+    }
+}
+
 /// AI execution request with intelligent routing requirements
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIRequest {
     pub prompt: String,
-    pub session_id: String,
-    pub file_path: Option<String>,
+    #[serde(default)]
     pub context: AIContext,
+    #[serde(default)]
     pub preferred_providers: Vec<String>,
+    #[serde(default)]
+    pub rule_id: Option<String>, // Rule the request serves; selects its `ai.rule_model_overrides` model
+}
+
+impl AIRequest {
+    pub fn new(prompt: impl Into<String>, context: AIContext) -> Self {
+        Self {
+            prompt: prompt.into(),
+            context,
+            preferred_providers: Vec::new(),
+            rule_id: None,
+        }
+    }
+
+    pub fn with_rule_id(mut self, rule_id: impl Into<String>) -> Self {
+        self.rule_id = Some(rule_id.into());
+        self
+    }
 }
 
 /// AI execution response with standardized format
@@ -181,12 +278,22 @@ pub struct AIResponse {
     pub execution_time_ms: u64,
     pub error_message: Option<String>,
     pub routing_reason: String, // Why this provider was selected
+    #[serde(default)]
+    pub finish_reason: Option<String>, // Provider stop reason when reported, e.g. "length" on output-limit truncation
 }
 
 /// Intelligent AI provider router
 #[derive(Debug)]
 pub struct AIRouter {
     providers: Vec<AIProviderConfig>,
+    offline: bool, // Configured with the `"none"` provider: every request short-circuits with `AiDisabled`
+    system_prompt: Option<String>, // `ai.system_prompt`, prepended to every request
+    rule_models: HashMap<String, String>, // `ai.rule_model_overrides`, by rule ID
+    max_output_tokens: Option<u32>,       // `ai.max_output_tokens`; `None` leaves the provider's own limit
+    task_output_tokens: HashMap<String, u32>, // `ai.max_output_tokens_by_task`, by task type
+    warmup: Option<ProviderWarmup>,           // `ai.provider_warmup`: validate each provider once and reuse the result
+    ensemble_providers: usize,                // `ai.ensemble_providers`: providers each code fix is sent to
+    max_concurrent: usize,                    // `ai.max_concurrent_requests`: ensemble calls in flight at once
 }
 
 impl Default for AIRouter {
@@ -196,15 +303,140 @@ impl Default for AIRouter {
 }
 
 impl AIRouter {
-    /// Create new AI router with available providers (Claude, Gemini, GPT5-Codex)
+    /// Create new AI router with available providers (Claude, Gemini, GPT5-Codex).
+    ///
+    /// Listing `"none"` in the workspace `ai.providers` yields an [`AIRouter::offline`] router.
     pub fn new() -> Self {
+        let config = crate::config::MoonShineConfig::from_moon_workspace().unwrap_or_default();
+        if config.ai_offline() {
+            return Self::offline();
+        }
+
+        Self::with_providers(vec![AIProviderConfig::claude(), AIProviderConfig::google(), AIProviderConfig::openai()])
+            .with_system_prompt(system_prompt::resolve_system_prompt(&config.ai))
+            .with_rule_model_overrides(config.ai.rule_model_overrides.clone())
+            .with_output_token_caps(Some(config.ai.max_output_tokens), config.ai.max_output_tokens_by_task.clone())
+            .with_warmup(config.ai.provider_warmup.then(ProviderWarmup::new))
+            .with_ensemble(config.ai.ensemble_providers as usize, config.ai.max_concurrent_requests as usize)
+    }
+
+    /// Create a router over an explicit provider list
+    pub fn with_providers(providers: Vec<AIProviderConfig>) -> Self {
+        Self {
+            providers,
+            offline: false,
+            system_prompt: None,
+            rule_models: HashMap::new(),
+            max_output_tokens: None,
+            task_output_tokens: HashMap::new(),
+            warmup: None,
+            ensemble_providers: 0,
+            max_concurrent: 1,
+        }
+    }
+
+    /// Validate provider connectivity through `warmup` before first use instead of on every call
+    pub fn with_warmup(mut self, warmup: Option<ProviderWarmup>) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Send code fixes to the `providers` best-ranked providers, `max_concurrent` at a time, and keep the best
+    pub fn with_ensemble(mut self, providers: usize, max_concurrent: usize) -> Self {
+        self.ensemble_providers = providers;
+        self.max_concurrent = max_concurrent.max(1);
+        self
+    }
+
+    /// Providers each code fix is sent to; more than one runs an ensemble
+    pub fn ensemble_providers(&self) -> usize {
+        self.ensemble_providers
+    }
+
+    /// Probe all available providers up front when warm-up is enabled; returns the names of those that are ready
+    pub fn warm_up(&self) -> Vec<String> {
+        let Some(warmup) = &self.warmup else {
+            return Vec::new();
+        };
+        let providers: Vec<AIProviderConfig> = self.available_providers().into_iter().cloned().collect();
+        warmup.warm_up(&providers)
+    }
+
+    /// Prepend `system_prompt` to every request, trimmed to each provider's context budget
+    pub fn with_system_prompt(mut self, system_prompt: Option<String>) -> Self {
+        self.system_prompt = system_prompt;
+        self
+    }
+
+    /// Run requests of the rules in `overrides` on the given model instead of the provider's
+    pub fn with_rule_model_overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        self.rule_models = overrides;
+        self
+    }
+
+    /// Cap provider output at `default` tokens, or at the `by_task` entry for the request's task type
+    pub fn with_output_token_caps(mut self, default: Option<u32>, by_task: HashMap<String, u32>) -> Self {
+        self.max_output_tokens = default;
+        self.task_output_tokens = by_task;
+        self
+    }
+
+    /// Output token cap sent with `request`: its task type's cap, else the default cap
+    pub fn max_output_tokens_for(&self, request: &AIRequest) -> Option<u32> {
+        self.task_output_tokens
+            .get(request.context.task_kind.task_type())
+            .copied()
+            .or(self.max_output_tokens)
+    }
+
+    /// Model `request` runs on with `provider`: its rule's override, else the provider's `ai.model`
+    pub fn model_for<'a>(&'a self, request: &AIRequest, provider: &'a AIProviderConfig) -> &'a str {
+        request
+            .rule_id
+            .as_ref()
+            .and_then(|rule_id| self.rule_models.get(rule_id))
+            .unwrap_or(&provider.model)
+    }
+
+    /// Router for the `"none"` provider: needs no credentials or network and answers every
+    /// request with [`Error::AiDisabled`]
+    pub fn offline() -> Self {
         Self {
-            providers: vec![AIProviderConfig::claude(), AIProviderConfig::google(), AIProviderConfig::openai()],
+            providers: Vec::new(),
+            offline: true,
+            system_prompt: None,
+            rule_models: HashMap::new(),
+            max_output_tokens: None,
+            task_output_tokens: HashMap::new(),
+            warmup: None,
+            ensemble_providers: 0,
+            max_concurrent: 1,
         }
     }
 
+    /// Whether AI was intentionally disabled with the `"none"` provider
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Configured providers in registration order
+    pub fn providers(&self) -> &[AIProviderConfig] {
+        &self.providers
+    }
+
+    /// Providers whose required credentials are present
+    pub fn available_providers(&self) -> Vec<&AIProviderConfig> {
+        self.providers.iter().filter(|provider| provider.has_credentials()).collect()
+    }
+
+    /// Whether at least one provider can serve requests
+    pub fn is_ai_available(&self) -> bool {
+        !self.available_providers().is_empty()
+    }
+
     /// Intelligently select the best provider for a request
     pub fn select_provider(&self, request: &AIRequest) -> Result<(&AIProviderConfig, String)> {
+        self.ensure_online()?;
         let mut ranked = self.rank_providers(request);
         if let Some((score, provider, reason)) = ranked.pop() {
             moon_info!("AI Provider Selected: {} (Score: {:.2}) - {}", provider.name, score, reason);
@@ -221,15 +453,8 @@ impl AIRouter {
             .providers
             .iter()
             .filter_map(|provider| {
-                if provider.requires_api_key {
-                    let env_key = match provider.api_key_env.as_ref() {
-                        Some(key) if !key.is_empty() => key,
-                        _ => return None,
-                    };
-
-                    if std::env::var(env_key).is_err() {
-                        return None;
-                    }
+                if !provider.has_credentials() {
+                    return None;
                 }
 
                 let (mut score, mut reason) = self.score_provider(provider, &requirements);
@@ -255,8 +480,8 @@ impl AIRouter {
 
     /// Infer request requirements from context
     fn infer_requirements(&self, request: &AIRequest) -> RequestRequirements {
-        match &request.context {
-            AIContext::CodeFix { content, .. } => RequestRequirements {
+        match &request.context.task_kind {
+            AITaskKind::CodeFix { content, .. } => RequestRequirements {
                 needs_code_analysis: true,
                 needs_code_generation: true,
                 needs_complex_reasoning: true,
@@ -264,7 +489,7 @@ impl AIRouter {
                 context_size: (content.len() / 4) as u32, // Rough token estimate
                 needs_sessions: false,                    // One-shot fixes
             },
-            AIContext::DSPyOptimization { .. } => RequestRequirements {
+            AITaskKind::DSPyOptimization { .. } => RequestRequirements {
                 needs_code_analysis: false,
                 needs_code_generation: false,
                 needs_complex_reasoning: true,
@@ -272,7 +497,7 @@ impl AIRouter {
                 context_size: 50000,  // DSPy typically has large context
                 needs_sessions: true, // Multi-turn optimization
             },
-            AIContext::CodeGeneration { specification, .. } => RequestRequirements {
+            AITaskKind::CodeGeneration { specification, .. } => RequestRequirements {
                 needs_code_analysis: false,
                 needs_code_generation: true,
                 needs_complex_reasoning: false,
@@ -280,7 +505,7 @@ impl AIRouter {
                 context_size: (specification.len() / 4) as u32,
                 needs_sessions: false,
             },
-            AIContext::CodeAnalysis { content, .. } => RequestRequirements {
+            AITaskKind::CodeAnalysis { content, .. } => RequestRequirements {
                 needs_code_analysis: true,
                 needs_code_generation: false,
                 needs_complex_reasoning: true,
@@ -288,7 +513,7 @@ impl AIRouter {
                 context_size: (content.len() / 4) as u32,
                 needs_sessions: false,
             },
-            AIContext::General => RequestRequirements {
+            AITaskKind::General => RequestRequirements {
                 needs_code_analysis: false,
                 needs_code_generation: false,
                 needs_complex_reasoning: false,
@@ -349,6 +574,9 @@ impl AIRouter {
 
     /// Execute AI request with intelligent provider selection and rate limiting
     pub async fn execute(&self, request: AIRequest) -> Result<AIResponse> {
+        self.ensure_online()?;
+        ensure_within_budget(&request)?;
+
         // Apply rate limiting before execution
         let default_config = AiLinterConfig::default();
         apply_rate_limiting(&default_config)?;
@@ -358,15 +586,34 @@ impl AIRouter {
             return Err(Error::ai_execution(&dummy_config, "No AI providers available - check API key configuration"));
         }
 
+        let policy = RetryPolicy::from_config(&default_config);
         let mut errors = Vec::new();
 
         for (score, provider, reason) in ranked.into_iter().rev() {
-            match self.execute_with_provider(provider, &request, score, &reason) {
-                Ok(response) => return Ok(response),
-                Err(error) => {
-                    moon_warn!("AI Provider {} failed: {} - attempting fallback", provider.name, error);
-                    errors.push(error);
-                    continue;
+            let mut retries_done = 0;
+            loop {
+                let error = match self.execute_with_provider(provider, &request, score, &reason) {
+                    Ok(response) => return Ok(response),
+                    Err(error) => error,
+                };
+
+                let kind = error.provider_error().cloned().unwrap_or(ProviderError::Unknown);
+                match policy.decide(&kind, retries_done) {
+                    RetryDecision::Retry { delay } => {
+                        moon_warn!("AI Provider {} failed ({}): retrying in {}ms", provider.name, kind, delay.as_millis());
+                        std::thread::sleep(delay);
+                        retries_done += 1;
+                    }
+                    RetryDecision::FallBack => {
+                        moon_warn!("AI Provider {} failed: {} - attempting fallback", provider.name, error);
+                        errors.push(error);
+                        break;
+                    }
+                    RetryDecision::Abort => {
+                        moon_warn!("AI Provider {} rejected the request ({}): not retrying", provider.name, kind);
+                        errors.push(error);
+                        return Err(Error::Multiple { errors, successful_count: 0 });
+                    }
                 }
             }
         }
@@ -374,12 +621,97 @@ impl AIRouter {
         Err(Error::Multiple { errors, successful_count: 0 })
     }
 
+    /// Execute a structured request, enforcing JSON output on JSON-capable models.
+    ///
+    /// Returns `Ok(None)` when the selected provider's model has no JSON mode so the caller
+    /// can fall back to delimiter parsing. Otherwise the payload is validated against
+    /// `output_fields` and retried once with a "return valid JSON" nudge before erroring.
+    pub async fn execute_json(&self, request: AIRequest, output_fields: &serde_json::Value) -> Result<Option<(AIResponse, serde_json::Value)>> {
+        self.ensure_online()?;
+        ensure_within_budget(&request)?;
+        let default_config = AiLinterConfig::default();
+        apply_rate_limiting(&default_config)?;
+
+        let Some((score, provider, reason)) = self.rank_providers(&request).pop() else {
+            let dummy_config = AIProviderConfig::claude();
+            return Err(Error::ai_execution(&dummy_config, "No AI providers available - check API key configuration"));
+        };
+
+        if !provider.model_capabilities().supports_json_mode {
+            moon_info!("AI Provider {} has no JSON mode - using delimiter parsing", provider.name);
+            return Ok(None);
+        }
+
+        let mut last_response = None;
+        let mut unwrapped = true;
+        let result = json_mode::execute_with_json_retry(provider, &request.prompt, output_fields, |prompt| {
+            let attempt = AIRequest {
+                prompt: prompt.to_string(),
+                ..request.clone()
+            };
+            let args = self.build_json_mode_args(&attempt, provider)?;
+            let mut response = self.execute_with_args(provider, &attempt, args, score, &reason)?;
+            // The CLI wraps the model's JSON in its own output format
+            let Some(content) = json_mode::model_output(provider, &response.content) else {
+                unwrapped = false;
+                return Err(Error::ai_execution(provider, "unrecognized JSON-mode CLI output"));
+            };
+            response.content = content.clone();
+            last_response = Some(response);
+            Ok(content)
+        });
+        if !unwrapped {
+            moon_info!("AI Provider {} JSON-mode output not recognized - using delimiter parsing", provider.name);
+            return Ok(None);
+        }
+
+        let payload = result?;
+        Ok(last_response.map(|response| (response, payload)))
+    }
+
+    /// Execute a request on the `provider_count` best-ranked providers and keep the response `metric` scores highest.
+    ///
+    /// Providers run on parallel threads, at most `ai.max_concurrent_requests` at a time (one
+    /// after another under WASM); every candidate is returned alongside the winner for debugging.
+    pub async fn execute_ensemble(&self, request: AIRequest, provider_count: usize, metric: &ensemble::MetricFn) -> Result<ensemble::EnsembleResult> {
+        self.ensure_online()?;
+        ensure_within_budget(&request)?;
+        let default_config = AiLinterConfig::default();
+        let ranked: Vec<(f32, &AIProviderConfig, String)> = self.rank_providers(&request).into_iter().rev().take(provider_count.max(1)).collect();
+        let providers: Vec<&AIProviderConfig> = ranked.iter().map(|(_, provider, _)| *provider).collect();
+
+        ensemble::run_ensemble(&providers, &request, self.max_concurrent, metric, |provider| {
+            let (score, reason) = ranked
+                .iter()
+                .find(|(_, candidate, _)| candidate.name == provider.name)
+                .map(|(score, _, reason)| (*score, reason.clone()))
+                .unwrap_or_default();
+            apply_rate_limiting(&default_config)?;
+            self.execute_with_provider(provider, &request, score, &reason)
+        })
+    }
+
+    /// Short-circuit with `AiDisabled` when the offline provider is configured; never warns
+    fn ensure_online(&self) -> Result<()> {
+        if self.offline {
+            return Err(Error::ai_disabled(format!("the \"{}\" provider is configured", OFFLINE_PROVIDER)));
+        }
+        Ok(())
+    }
+
     fn execute_with_provider(&self, provider: &AIProviderConfig, request: &AIRequest, score: f32, reason: &str) -> Result<AIResponse> {
+        let args = self.build_provider_args(request, provider)?;
+        self.execute_with_args(provider, request, args, score, reason)
+    }
+
+    fn execute_with_args(&self, provider: &AIProviderConfig, request: &AIRequest, args: Vec<String>, score: f32, reason: &str) -> Result<AIResponse> {
+        if let Some(warmup) = &self.warmup {
+            warmup.ensure_ready(provider)?;
+        }
         let start_time = std::time::Instant::now();
 
-        let args = self.build_provider_args(request, provider)?;
         let working_dir = self.get_working_directory(request);
-        let env = self.build_environment(provider)?;
+        let env = self.build_environment(provider, request)?;
 
         let command_input = ExecCommandInput {
             command: provider.command.clone(),
@@ -394,34 +726,40 @@ impl AIRouter {
 
         if output.exit_code != 0 {
             moon_info!(
-                "AI Provider {} failed with exit code {} in {}ms (score {:.2})",
+                "AI Provider {} failed with exit code {} in {}ms (score {:.2}, session {}, file {})",
                 provider.name,
                 output.exit_code,
                 execution_time,
-                score
+                score,
+                request.context.session(),
+                request.context.file_path.as_deref().unwrap_or("-")
             );
 
-            return Err(Error::ai_execution(
+            return Err(Error::ai_execution_with_source(
                 provider,
                 format!("AI CLI failed with exit code {}: {}", output.exit_code, output.stderr),
+                ProviderError::classify(output.exit_code, &output.stderr),
             ));
         }
 
         moon_info!(
-            "AI Provider {} executed successfully in {}ms (score {:.2})",
+            "AI Provider {} executed successfully in {}ms (score {:.2}, session {}, file {})",
             provider.name,
             execution_time,
-            score
+            score,
+            request.context.session(),
+            request.context.file_path.as_deref().unwrap_or("-")
         );
 
         Ok(AIResponse {
             provider_used: provider.name.clone(),
-            content: output.stdout,
-            session_id: request.session_id.clone(),
+            session_id: request.context.session_id.clone(),
             success: true,
             execution_time_ms: execution_time,
             error_message: None,
             routing_reason: format!("Score: {:.2} - {}", score, reason),
+            finish_reason: capped_finish_reason(&output.stdout, self.max_output_tokens_for(request)),
+            content: output.stdout,
         })
     }
 
@@ -429,24 +767,42 @@ impl AIRouter {
     fn build_provider_args(&self, request: &AIRequest, provider: &AIProviderConfig) -> Result<Vec<String>> {
         let mut args = Vec::new();
 
+        // Every path to a provider builds its arguments here, so the system prompt is added exactly once
+        let composed;
+        let request = match request.context.system_prompt.as_ref().or(self.system_prompt.as_ref()) {
+            Some(prompt) => {
+                let context_tokens = request
+                    .context
+                    .remaining_budget
+                    .map_or(provider.capabilities.context_length, |budget| budget.min(provider.capabilities.context_length));
+                composed = AIRequest {
+                    prompt: system_prompt::compose_prompt(prompt, &request.prompt, context_tokens),
+                    ..request.clone()
+                };
+                &composed
+            }
+            None => request,
+        };
+        let model = self.model_for(request, provider).to_string();
+
         match provider.name.as_str() {
             "claude" => {
                 // Claude CLI arguments
-                if !request.session_id.is_empty() {
-                    args.extend_from_slice(&["--session-id".to_string(), request.session_id.clone()]);
+                if !request.context.session_id.is_empty() {
+                    args.extend_from_slice(&["--session-id".to_string(), request.context.session_id.clone()]);
                 }
 
-                args.extend_from_slice(&["--model".to_string(), provider.model.clone(), "--no-stream".to_string()]);
+                args.extend_from_slice(&["--model".to_string(), model, "--no-stream".to_string()]);
 
                 // Add context-specific arguments
-                match &request.context {
-                    AIContext::CodeFix { .. } => {
+                match &request.context.task_kind {
+                    AITaskKind::CodeFix { .. } => {
                         args.push("code".to_string());
-                        if let Some(file_path) = &request.file_path {
+                        if let Some(file_path) = &request.context.file_path {
                             args.extend_from_slice(&["--file".to_string(), file_path.clone()]);
                         }
                     }
-                    AIContext::DSPyOptimization { .. } => {
+                    AITaskKind::DSPyOptimization { .. } => {
                         // DSPy-specific Claude arguments
                         args.extend_from_slice(&["--output-format".to_string(), "json".to_string()]);
                     }
@@ -464,23 +820,23 @@ impl AIRouter {
                 // Use short flags and positional prompts (modern Gemini CLI style)
 
                 // Add model specification
-                args.extend_from_slice(&["-m".to_string(), provider.model.clone()]);
+                args.extend_from_slice(&["-m".to_string(), model]);
 
                 // Add context-specific arguments
-                match &request.context {
-                    AIContext::CodeFix { .. } | AIContext::CodeAnalysis { .. } => {
-                        if let Some(file_path) = &request.file_path {
+                match &request.context.task_kind {
+                    AITaskKind::CodeFix { .. } | AITaskKind::CodeAnalysis { .. } => {
+                        if let Some(file_path) = &request.context.file_path {
                             // Use @file syntax for file context injection
                             let context_prompt = format!("@{} {}", file_path, request.prompt);
                             args.push(context_prompt);
                             return Ok(args); // Early return to avoid duplicate prompt
                         }
                     }
-                    AIContext::DSPyOptimization { .. } => {
+                    AITaskKind::DSPyOptimization { .. } => {
                         // Use sandbox mode for DSPy to ensure isolation
                         args.extend_from_slice(&["-s".to_string()]);
                     }
-                    AIContext::CodeGeneration { .. } => {
+                    AITaskKind::CodeGeneration { .. } => {
                         // Use non-interactive mode for code generation
                         args.extend_from_slice(&["-p".to_string(), request.prompt.clone()]);
                         return Ok(args); // Early return to avoid duplicate prompt
@@ -507,17 +863,20 @@ impl AIRouter {
                 args.extend_from_slice(&["--sandbox".to_string(), "read-only".to_string()]);
 
                 // Add model specification if not default
-                if provider.model != "gpt-5-codex" {
-                    args.extend_from_slice(&["--model".to_string(), provider.model.clone()]);
+                if model != "gpt-5-codex" {
+                    args.extend_from_slice(&["--model".to_string(), model]);
                 }
 
                 // Add reasoning effort from config
                 let config = crate::config::MoonShineConfig::from_moon_workspace().unwrap_or_default();
                 let reasoning_effort = "low".to_string();
                 args.extend_from_slice(&["--config".to_string(), format!("model_reasoning_effort=\"{}\"", reasoning_effort)]);
+                if let Some(cap) = self.max_output_tokens_for(request) {
+                    args.extend_from_slice(&["--config".to_string(), format!("model_max_output_tokens={}", cap)]);
+                }
 
                 // Set working directory if file path is provided
-                if let Some(file_path) = &request.file_path {
+                if let Some(file_path) = &request.context.file_path {
                     if let Some(parent_dir) = std::path::Path::new(file_path).parent() {
                         args.extend_from_slice(&["--cd".to_string(), parent_dir.to_string_lossy().to_string()]);
                     }
@@ -535,20 +894,34 @@ impl AIRouter {
         Ok(args)
     }
 
+    /// Build provider arguments with the JSON output flag enabled
+    fn build_json_mode_args(&self, request: &AIRequest, provider: &AIProviderConfig) -> Result<Vec<String>> {
+        let mut args = self.build_provider_args(request, provider)?;
+
+        // Codex always runs with --experimental-json; Claude needs it requested explicitly
+        if provider.name == "claude" && !args.iter().any(|arg| arg == "--output-format") {
+            let prompt_position = args.len().saturating_sub(1);
+            args.splice(prompt_position..prompt_position, ["--output-format".to_string(), "json".to_string()]);
+        }
+
+        Ok(args)
+    }
+
     /// Get standardized working directory for AI execution
     fn get_working_directory(&self, request: &AIRequest) -> Option<String> {
         // Standardized working directory pattern for all providers
-        match &request.context {
-            AIContext::DSPyOptimization { .. } => Some(format!("/tmp/moon-shine-dspy/{}", request.session_id)),
-            AIContext::CodeFix { .. } => Some(format!("/tmp/moon-shine-fixes/{}", request.session_id)),
-            AIContext::CodeGeneration { .. } => Some(format!("/tmp/moon-shine-generation/{}", request.session_id)),
-            AIContext::CodeAnalysis { .. } => Some(format!("/tmp/moon-shine-analysis/{}", request.session_id)),
-            AIContext::General => Some(format!("/tmp/moon-shine-general/{}", request.session_id)),
+        let session = request.context.session();
+        match &request.context.task_kind {
+            AITaskKind::DSPyOptimization { .. } => Some(format!("/tmp/moon-shine-dspy/{}", session)),
+            AITaskKind::CodeFix { .. } => Some(format!("/tmp/moon-shine-fixes/{}", session)),
+            AITaskKind::CodeGeneration { .. } => Some(format!("/tmp/moon-shine-generation/{}", session)),
+            AITaskKind::CodeAnalysis { .. } => Some(format!("/tmp/moon-shine-analysis/{}", session)),
+            AITaskKind::General => Some(format!("/tmp/moon-shine-general/{}", session)),
         }
     }
 
     /// Build environment variables for AI provider
-    fn build_environment(&self, provider: &AIProviderConfig) -> Result<HashMap<String, String>> {
+    fn build_environment(&self, provider: &AIProviderConfig, request: &AIRequest) -> Result<HashMap<String, String>> {
         let mut env = HashMap::new();
 
         // Add API key from environment
@@ -571,10 +944,13 @@ impl AIRouter {
         // Add provider-specific environment variables
         match provider.name.as_str() {
             "claude" => {
-                // Claude-specific environment setup
+                // The Claude CLI reads its output cap from the environment
+                if let Some(cap) = self.max_output_tokens_for(request) {
+                    env.insert(CLAUDE_MAX_OUTPUT_TOKENS_ENV.to_string(), cap.to_string());
+                }
             }
             "google" => {
-                // Google-specific environment setup
+                // Google-specific environment setup; the Gemini CLI has no output cap setting
                 if let Ok(creds) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
                     env.insert("GOOGLE_APPLICATION_CREDENTIALS".to_string(), creds);
                 }
@@ -597,6 +973,39 @@ impl AIRouter {
     }
 }
 
+/// `"length"` when output reached the cap it was generated under.
+///
+/// CLI providers do not report why they stopped, so output at or past the cap is treated
+/// as cut off; truncation detection then asks for a continuation instead of keeping it.
+fn capped_finish_reason(content: &str, max_output_tokens: Option<u32>) -> Option<String> {
+    let output_tokens = (content.len() / 4) as u32; // Rough token estimate
+    max_output_tokens.filter(|cap| output_tokens >= *cap).map(|_| "length".to_string())
+}
+
+/// Reject a request whose prompt alone exceeds its context's `remaining_budget`
+fn ensure_within_budget(request: &AIRequest) -> Result<()> {
+    let Some(budget) = request.context.remaining_budget else {
+        return Ok(());
+    };
+
+    let prompt_tokens = (request.prompt.len() / 4) as u32; // Rough token estimate
+    if prompt_tokens > budget {
+        moon_warn!(
+            "AI request for session {} ({}) needs ~{} tokens but only {} remain",
+            request.context.session(),
+            request.context.file_path.as_deref().unwrap_or("-"),
+            prompt_tokens,
+            budget
+        );
+        return Err(Error::validation(
+            "remaining_budget",
+            format!("at least {} tokens", prompt_tokens),
+            format!("{} tokens", budget),
+        ));
+    }
+    Ok(())
+}
+
 /// Rate limiting state for AI provider requests
 struct RateLimiterState {
     last_request_time: Option<Instant>,
@@ -668,18 +1077,36 @@ pub fn get_ai_router() -> &'static AIRouter {
     AI_ROUTER.get_or_init(AIRouter::new)
 }
 
+/// Whether any AI provider is usable; AI-enhanced rules fall back to static analysis when not
+pub fn ai_available() -> bool {
+    get_ai_router().is_ai_available()
+}
+
+/// Warm up the global router's providers when `ai.provider_warmup` is set
+pub fn warm_up_providers() -> Vec<String> {
+    get_ai_router().warm_up()
+}
+
+/// Whether AI was intentionally disabled with the `"none"` provider, as opposed to unavailable
+pub fn ai_disabled() -> bool {
+    get_ai_router().is_offline()
+}
+
 /// Convenience functions for common AI operations
 /// Execute code fixing with intelligent AI provider selection
 pub async fn fix_code_with_ai(session_id: String, file_path: String, content: String, language: String, prompt: String) -> Result<AIResponse> {
     let router = get_ai_router();
 
-    let request = AIRequest {
-        prompt,
-        session_id,
-        file_path: Some(file_path),
-        context: AIContext::CodeFix { language, content },
-        preferred_providers: Vec::new(),
-    };
+    let request = AIRequest::new(prompt, AIContext::new(session_id, AITaskKind::CodeFix { language, content }).with_file_path(file_path.clone()));
+
+    if router.ensemble_providers() > 1 {
+        let config = crate::config::MoonShineConfig::from_moon_workspace().unwrap_or_default();
+        let metric = ensemble::fix_metric(&file_path, &config);
+        return router
+            .execute_ensemble(request, router.ensemble_providers(), &metric)
+            .await
+            .map(|result| result.best);
+    }
 
     router.execute(request).await
 }
@@ -688,13 +1115,7 @@ pub async fn fix_code_with_ai(session_id: String, file_path: String, content: St
 pub async fn optimize_with_dspy(session_id: String, signature: String, messages: Vec<String>, prompt: String) -> Result<AIResponse> {
     let router = get_ai_router();
 
-    let request = AIRequest {
-        prompt,
-        session_id,
-        file_path: None,
-        context: AIContext::DSPyOptimization { signature, messages },
-        preferred_providers: Vec::new(),
-    };
+    let request = AIRequest::new(prompt, AIContext::new(session_id, AITaskKind::DSPyOptimization { signature, messages }));
 
     router.execute(request).await
 }
@@ -703,13 +1124,16 @@ pub async fn optimize_with_dspy(session_id: String, signature: String, messages:
 pub async fn execute_ai_prompt(session_id: String, prompt: String) -> Result<AIResponse> {
     let router = get_ai_router();
 
-    let request = AIRequest {
-        prompt,
-        session_id,
-        file_path: None,
-        context: AIContext::General,
-        preferred_providers: Vec::new(),
-    };
+    let request = AIRequest::new(prompt, AIContext::new(session_id, AITaskKind::General));
+
+    router.execute(request).await
+}
+
+/// Execute a prompt on behalf of `rule_id`, on the model `ai.rule_model_overrides` names for it
+pub async fn execute_rule_prompt(session_id: String, rule_id: &str, prompt: String) -> Result<AIResponse> {
+    let router = get_ai_router();
+
+    let request = AIRequest::new(prompt, AIContext::new(session_id, AITaskKind::General)).with_rule_id(rule_id);
 
     router.execute(request).await
 }
@@ -718,13 +1142,7 @@ pub async fn execute_ai_prompt(session_id: String, prompt: String) -> Result<AIR
 pub async fn analyze_code_with_ai(session_id: String, content: String, language: String, prompt: String) -> Result<AIResponse> {
     let router = get_ai_router();
 
-    let request = AIRequest {
-        prompt,
-        session_id,
-        file_path: None,
-        context: AIContext::CodeAnalysis { language, content },
-        preferred_providers: Vec::new(),
-    };
+    let request = AIRequest::new(prompt, AIContext::new(session_id, AITaskKind::CodeAnalysis { language, content }));
 
     router.execute(request).await
 }
@@ -733,13 +1151,7 @@ pub async fn analyze_code_with_ai(session_id: String, content: String, language:
 pub async fn generate_code_with_ai(session_id: String, specification: String, language: String, prompt: String) -> Result<AIResponse> {
     let router = get_ai_router();
 
-    let request = AIRequest {
-        prompt,
-        session_id,
-        file_path: None,
-        context: AIContext::CodeGeneration { language, specification },
-        preferred_providers: Vec::new(),
-    };
+    let request = AIRequest::new(prompt, AIContext::new(session_id, AITaskKind::CodeGeneration { language, specification }));
 
     router.execute(request).await
 }
@@ -774,6 +1186,34 @@ pub fn batch_process_files(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ai_unavailable_without_usable_providers() {
+        assert!(!AIRouter::with_providers(Vec::new()).is_ai_available());
+
+        let mut missing_key = AIProviderConfig::openai();
+        missing_key.requires_api_key = true;
+        missing_key.api_key_env = Some("MOONSHINE_TEST_UNSET_API_KEY".to_string());
+        let router = AIRouter::with_providers(vec![missing_key]);
+        assert!(router.available_providers().is_empty());
+        assert!(!router.is_ai_available());
+
+        assert!(AIRouter::with_providers(vec![AIProviderConfig::claude()]).is_ai_available());
+    }
+
+    #[tokio::test]
+    async fn test_offline_provider_short_circuits_with_ai_disabled() {
+        let router = AIRouter::offline();
+        assert!(router.is_offline());
+        assert!(!router.is_ai_available());
+
+        let request = AIRequest::new("Explain this code", AIContext::new("offline-test", AITaskKind::General));
+
+        let error = router.execute(request.clone()).await.unwrap_err();
+        assert!(error.is_ai_disabled());
+        assert!(!error.to_string().contains("API key"));
+        assert!(router.select_provider(&request).unwrap_err().is_ai_disabled());
+    }
+
     #[test]
     fn test_provider_capabilities_creation() {
         let capabilities = ProviderCapabilities {
@@ -848,79 +1288,81 @@ mod tests {
     }
 
     #[test]
-    fn test_ai_context_variants() {
-        let code_fix = AIContext::CodeFix {
+    fn test_ai_task_kind_variants() {
+        let code_fix = AITaskKind::CodeFix {
             language: "typescript".to_string(),
             content: "const x = 1;".to_string(),
         };
         match code_fix {
-            AIContext::CodeFix { language, content } => {
+            AITaskKind::CodeFix { language, content } => {
                 assert_eq!(language, "typescript");
                 assert_eq!(content, "const x = 1;");
             }
             _ => {
-                panic!("Expected CodeFix context but got different AIContext variant")
+                panic!("Expected CodeFix context but got different AITaskKind variant")
             }
         }
 
-        let dspy_optimization = AIContext::DSPyOptimization {
+        let dspy_optimization = AITaskKind::DSPyOptimization {
             signature: "input -> output".to_string(),
             messages: vec!["message1".to_string(), "message2".to_string()],
         };
         match dspy_optimization {
-            AIContext::DSPyOptimization { signature, messages } => {
+            AITaskKind::DSPyOptimization { signature, messages } => {
                 assert_eq!(signature, "input -> output");
                 assert_eq!(messages.len(), 2);
             }
-            _ => panic!("Expected DSPyOptimization context but got different AIContext variant"),
+            _ => panic!("Expected DSPyOptimization context but got different AITaskKind variant"),
         }
 
-        let code_analysis = AIContext::CodeAnalysis {
+        let code_analysis = AITaskKind::CodeAnalysis {
             language: "python".to_string(),
             content: "def test(): pass".to_string(),
         };
         match code_analysis {
-            AIContext::CodeAnalysis { language, content } => {
+            AITaskKind::CodeAnalysis { language, content } => {
                 assert_eq!(language, "python");
                 assert!(content.contains("def test"));
             }
-            _ => panic!("Expected CodeAnalysis context but got different AIContext variant"),
+            _ => panic!("Expected CodeAnalysis context but got different AITaskKind variant"),
         }
 
-        let code_generation = AIContext::CodeGeneration {
+        let code_generation = AITaskKind::CodeGeneration {
             language: "rust".to_string(),
             specification: "Create a function that sorts numbers".to_string(),
         };
         match code_generation {
-            AIContext::CodeGeneration { language, specification } => {
+            AITaskKind::CodeGeneration { language, specification } => {
                 assert_eq!(language, "rust");
                 assert!(specification.contains("sorts numbers"));
             }
-            _ => panic!("Expected CodeGeneration context but got different AIContext variant"),
+            _ => panic!("Expected CodeGeneration context but got different AITaskKind variant"),
         }
 
-        let general = AIContext::General;
-        assert!(matches!(general, AIContext::General));
+        let general = AITaskKind::General;
+        assert!(matches!(general, AITaskKind::General));
     }
 
     #[test]
     fn test_ai_request_creation() {
-        let request = AIRequest {
-            prompt: "Fix this TypeScript code".to_string(),
-            session_id: "session-123".to_string(),
-            file_path: Some("src/test.ts".to_string()),
-            context: AIContext::CodeFix {
+        let context = AIContext::new(
+            "session-123",
+            AITaskKind::CodeFix {
                 language: "typescript".to_string(),
                 content: "const x = 1;".to_string(),
             },
+        )
+        .with_file_path("src/test.ts");
+        let request = AIRequest {
             preferred_providers: vec!["claude".to_string(), "google".to_string()],
+            ..AIRequest::new("Fix this TypeScript code", context)
         };
 
         assert_eq!(request.prompt, "Fix this TypeScript code");
-        assert_eq!(request.session_id, "session-123");
-        assert_eq!(request.file_path, Some("src/test.ts".to_string()));
+        assert_eq!(request.context.session_id, "session-123");
+        assert_eq!(request.context.file_path, Some("src/test.ts".to_string()));
         assert_eq!(request.preferred_providers.len(), 2);
-        assert!(matches!(request.context, AIContext::CodeFix { .. }));
+        assert!(matches!(request.context.task_kind, AITaskKind::CodeFix { .. }));
     }
 
     #[test]
@@ -933,6 +1375,7 @@ mod tests {
             success: true,
             error_message: None,
             routing_reason: "Best for code analysis".to_string(),
+            finish_reason: None,
         };
 
         assert!(response.content.contains("Fixed code"));
@@ -958,31 +1401,31 @@ mod tests {
         let router = AIRouter::new();
 
         // Test code fix context (should prefer Claude for complex reasoning)
-        let code_fix_request = AIRequest {
-            prompt: "Fix this bug".to_string(),
-            session_id: "session-1".to_string(),
-            file_path: None,
-            context: AIContext::CodeFix {
-                language: "typescript".to_string(),
-                content: "const x = 1;".to_string(),
-            },
-            preferred_providers: vec![],
-        };
+        let code_fix_request = AIRequest::new(
+            "Fix this bug",
+            AIContext::new(
+                "session-1",
+                AITaskKind::CodeFix {
+                    language: "typescript".to_string(),
+                    content: "const x = 1;".to_string(),
+                },
+            ),
+        );
 
         let selected = router.select_provider(&code_fix_request);
         // Should select based on capabilities - Claude is good for code analysis
 
         // Test code generation context (should prefer OpenAI Codex)
-        let code_gen_request = AIRequest {
-            prompt: "Generate a function".to_string(),
-            session_id: "session-2".to_string(),
-            file_path: None,
-            context: AIContext::CodeGeneration {
-                language: "rust".to_string(),
-                specification: "Sort function".to_string(),
-            },
-            preferred_providers: vec![],
-        };
+        let code_gen_request = AIRequest::new(
+            "Generate a function",
+            AIContext::new(
+                "session-2",
+                AITaskKind::CodeGeneration {
+                    language: "rust".to_string(),
+                    specification: "Sort function".to_string(),
+                },
+            ),
+        );
 
         let selected_gen = router.select_provider(&code_gen_request);
         // Should work with provider selection logic
@@ -994,15 +1437,16 @@ mod tests {
     fn test_provider_selection_with_preferences() {
         let router = AIRouter::new();
 
-        let request_with_preference = AIRequest {
-            prompt: "Analyze this code".to_string(),
-            session_id: "session-pref".to_string(),
-            file_path: None,
-            context: AIContext::CodeAnalysis {
+        let context = AIContext::new(
+            "session-pref",
+            AITaskKind::CodeAnalysis {
                 language: "python".to_string(),
                 content: "def test(): pass".to_string(),
             },
+        );
+        let request_with_preference = AIRequest {
             preferred_providers: vec!["google".to_string()],
+            ..AIRequest::new("Analyze this code", context)
         };
 
         let selected = router.select_provider(&request_with_preference);
@@ -1010,6 +1454,111 @@ mod tests {
         // Should respect preferred provider if available
     }
 
+    #[test]
+    fn test_rule_model_override_selects_model() {
+        let claude = AIProviderConfig {
+            model: "sonnet".to_string(),
+            ..AIProviderConfig::claude()
+        };
+        let router = AIRouter::with_providers(vec![claude.clone()])
+            .with_rule_model_overrides(HashMap::from([("security-review".to_string(), "opus".to_string())]));
+        let rule_request = |rule_id: &str| AIRequest::new("Review this code", AIContext::default()).with_rule_id(rule_id);
+        let model_arg = |request: &AIRequest| {
+            let args = router.build_provider_args(request, &claude).unwrap();
+            let position = args.iter().position(|arg| arg == "--model").unwrap();
+            args[position + 1].clone()
+        };
+
+        assert_eq!(model_arg(&rule_request("security-review")), "opus");
+        assert_eq!(model_arg(&rule_request("prefer-const")), "sonnet");
+        assert_eq!(
+            model_arg(&AIRequest {
+                rule_id: None,
+                ..rule_request("security-review")
+            }),
+            "sonnet"
+        );
+    }
+
+    #[test]
+    fn test_ai_context_reaches_provider_args() {
+        let claude = AIProviderConfig::claude();
+        let router = AIRouter::with_providers(vec![claude.clone()]).with_system_prompt(Some("Prefer readonly types.".to_string()));
+        let context = AIContext::new(
+            "session-ctx",
+            AITaskKind::CodeFix {
+                language: "typescript".to_string(),
+                content: "let a = 1;".to_string(),
+            },
+        )
+        .with_file_path("src/app.ts")
+        .with_remaining_budget(10_000)
+        .with_system_prompt("Never use `any`.");
+        let request = AIRequest::new("Fix this file", context);
+
+        let args = router.build_provider_args(&request, &claude).unwrap();
+
+        let value_after = |flag: &str| args.iter().position(|arg| arg == flag).map(|position| args[position + 1].as_str());
+        assert_eq!(value_after("--session-id"), Some("session-ctx"));
+        assert_eq!(value_after("--file"), Some("src/app.ts"));
+        assert!(args.contains(&"code".to_string()), "the task kind selects the code subcommand");
+        assert_eq!(args.last().unwrap(), "System: Never use `any`.\n\nFix this file", "the request's system prompt wins");
+        assert_eq!(router.get_working_directory(&request).as_deref(), Some("/tmp/moon-shine-fixes/session-ctx"));
+        assert!(ensure_within_budget(&request).is_ok());
+    }
+
+    #[test]
+    fn test_output_token_cap_per_task_with_global_default() {
+        let openai = AIProviderConfig::openai();
+        let claude = AIProviderConfig::claude();
+        let router = AIRouter::with_providers(vec![claude.clone(), openai.clone()])
+            .with_output_token_caps(Some(8192), HashMap::from([("code_fix".to_string(), 1024)]));
+        let type_fix = AIRequest::new(
+            "Fix the types",
+            AIContext::new(
+                "session-cap",
+                AITaskKind::CodeFix {
+                    language: "typescript".to_string(),
+                    content: "let a: any = 1;".to_string(),
+                },
+            ),
+        );
+        let general = AIRequest::new("Summarize", AIContext::default());
+
+        let args = router.build_provider_args(&type_fix, &openai).unwrap();
+        assert!(args.contains(&"model_max_output_tokens=1024".to_string()), "the code_fix cap is sent: {:?}", args);
+        let env = router.build_environment(&claude, &general).unwrap();
+        assert_eq!(env.get(CLAUDE_MAX_OUTPUT_TOKENS_ENV).map(String::as_str), Some("8192"), "other tasks get the global cap");
+
+        let uncapped = AIRouter::with_providers(vec![openai.clone()]);
+        assert!(uncapped.max_output_tokens_for(&general).is_none());
+        assert!(!uncapped.build_provider_args(&general, &openai).unwrap().iter().any(|arg| arg.starts_with("model_max_output_tokens")));
+
+        // Output that fills the cap is reported as truncated so the fixer asks for a continuation
+        assert_eq!(capped_finish_reason(&"x".repeat(4096), Some(1024)).as_deref(), Some("length"));
+        assert!(capped_finish_reason(&"x".repeat(4000), Some(1024)).is_none());
+        assert!(capped_finish_reason(&"x".repeat(4096), None).is_none());
+    }
+
+    #[test]
+    fn test_missing_ai_context_fields_are_defaulted() {
+        let request: AIRequest = serde_json::from_str(r#"{"prompt": "Summarize", "context": {"file_path": "src/app.ts"}}"#).unwrap();
+
+        assert_eq!(request.context.session(), "default");
+        assert!(matches!(request.context.task_kind, AITaskKind::General));
+        assert!(request.context.remaining_budget.is_none() && request.context.system_prompt.is_none());
+        assert!(request.preferred_providers.is_empty());
+
+        let router = AIRouter::with_providers(vec![AIProviderConfig::claude()]);
+        let args = router.build_provider_args(&request, &AIProviderConfig::claude()).unwrap();
+        assert!(!args.contains(&"--session-id".to_string()));
+        assert_eq!(args.last().unwrap(), "Summarize");
+        assert_eq!(router.get_working_directory(&request).as_deref(), Some("/tmp/moon-shine-general/default"));
+
+        let over_budget = AIRequest::new("x".repeat(400), AIContext::default().with_remaining_budget(10));
+        assert!(ensure_within_budget(&over_budget).is_err());
+    }
+
     #[test]
     fn test_rate_limiter_state() {
         let state = RateLimiterState::new();
@@ -0,0 +1,234 @@
+//! Post-processing of raw AI responses before they are used
+//!
+//! Models wrap code in markdown fences, lead with "Here is the fixed code:" or follow it
+//! with explanations, and the cleanup each task needs differs. Processors are registered
+//! per task type (see [`AITaskKind::task_type`]) and applied in registration order to the
+//! raw response text.
+
+use super::{AIContext, AITaskKind};
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Task type for code-fix responses, cleaned before being applied as a fix
+pub const CODE_FIX_TASK: &str = "code_fix";
+
+/// A single cleanup step over raw AI output
+pub trait ResponsePostProcessor: Debug + Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Return the cleaned response
+    fn process(&self, response: &str) -> String;
+}
+
+/// Removes markdown fence lines and keeps everything between them
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripMarkdownFences;
+
+impl ResponsePostProcessor for StripMarkdownFences {
+    fn name(&self) -> &'static str {
+        "strip-markdown-fences"
+    }
+
+    fn process(&self, response: &str) -> String {
+        response
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("```"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
+    }
+}
+
+/// Keeps only the body of the first fenced code block; an unterminated fence (truncated
+/// output) keeps everything after the fence line. Unfenced responses are only trimmed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExtractFirstCodeBlock;
+
+impl ResponsePostProcessor for ExtractFirstCodeBlock {
+    fn name(&self) -> &'static str {
+        "extract-first-code-block"
+    }
+
+    fn process(&self, response: &str) -> String {
+        let Some(code_start) = response.find("```") else {
+            return response.trim().to_string();
+        };
+
+        let after_fence = &response[code_start + 3..];
+        if let Some(code_end) = after_fence.find("```") {
+            // Skip language identifier line if present
+            let code_block = &after_fence[..code_end];
+            return match code_block.find('\n') {
+                Some(newline) => code_block[newline + 1..].trim().to_string(),
+                None => code_block.trim().to_string(),
+            };
+        }
+
+        match after_fence.find('\n') {
+            Some(newline) => after_fence[newline + 1..].trim_start().to_string(),
+            None => response.trim().to_string(),
+        }
+    }
+}
+
+/// Drops explanatory lines before the first line that looks like code or a fence
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RemoveLeadingProse;
+
+impl ResponsePostProcessor for RemoveLeadingProse {
+    fn name(&self) -> &'static str {
+        "remove-leading-prose"
+    }
+
+    fn process(&self, response: &str) -> String {
+        let lines: Vec<&str> = response.lines().collect();
+        match lines.iter().position(|line| looks_like_code(line)) {
+            Some(first_code) => lines[first_code..].join("\n"),
+            None => response.to_string(),
+        }
+    }
+}
+
+const CODE_LINE_PREFIXES: &[&str] = &[
+    "```",
+    "import ",
+    "export ",
+    "const ",
+    "let ",
+    "var ",
+    "function ",
+    "async ",
+    "class ",
+    "interface ",
+    "type ",
+    "enum ",
+    "declare ",
+    "return ",
+    "if ",
+    "for ",
+    "while ",
+    "//",
+    "/*",
+    "*",
+    "@",
+    "#!",
+    "'use ",
+    "\"use ",
+    "{",
+    "}",
+    "(",
+    "[",
+    "<",
+];
+
+fn looks_like_code(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty()
+        && (CODE_LINE_PREFIXES.iter().any(|prefix| trimmed.starts_with(prefix)) || trimmed.ends_with(';') || trimmed.ends_with('{') || trimmed.ends_with('}'))
+}
+
+/// Ordered post-processors per task type
+#[derive(Debug, Default)]
+pub struct PostProcessorRegistry {
+    processors: HashMap<String, Vec<Box<dyn ResponsePostProcessor>>>,
+}
+
+impl PostProcessorRegistry {
+    /// Empty registry; responses pass through unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the built-in chains: code fixes keep the first code block
+    pub fn with_defaults() -> Self {
+        Self::new().register(CODE_FIX_TASK, ExtractFirstCodeBlock)
+    }
+
+    /// Append `processor` to the chain for `task_type`
+    pub fn register(mut self, task_type: impl Into<String>, processor: impl ResponsePostProcessor + 'static) -> Self {
+        self.processors.entry(task_type.into()).or_default().push(Box::new(processor));
+        self
+    }
+
+    /// Names of the processors applied for `task_type`, in order
+    pub fn processor_names(&self, task_type: &str) -> Vec<&'static str> {
+        self.processors
+            .get(task_type)
+            .map(|chain| chain.iter().map(|processor| processor.name()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Apply every processor registered for `task_type` to `response`, in order
+    pub fn process(&self, task_type: &str, response: &str) -> String {
+        let Some(chain) = self.processors.get(task_type) else {
+            return response.to_string();
+        };
+
+        chain.iter().fold(response.to_string(), |cleaned, processor| processor.process(&cleaned))
+    }
+
+    /// Apply the chain for the task type of `context`
+    pub fn process_for(&self, context: &AIContext, response: &str) -> String {
+        self.process(context.task_kind.task_type(), response)
+    }
+}
+
+impl AITaskKind {
+    /// Task type key used to select response post-processors
+    pub fn task_type(&self) -> &'static str {
+        match self {
+            AITaskKind::CodeFix { .. } => CODE_FIX_TASK,
+            AITaskKind::DSPyOptimization { .. } => "dspy_optimization",
+            AITaskKind::CodeGeneration { .. } => "code_generation",
+            AITaskKind::CodeAnalysis { .. } => "code_analysis",
+            AITaskKind::General => "general",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSY_RESPONSE: &str =
+        "Sure! I fixed the loose equality.\nHere is the updated code:\n\n```typescript\nconst total = 1;\nif (total === 2) {\n  report(total);\n}\n```";
+
+    #[test]
+    fn test_chained_processors_clean_messy_response() {
+        let registry = PostProcessorRegistry::new()
+            .register(CODE_FIX_TASK, RemoveLeadingProse)
+            .register(CODE_FIX_TASK, StripMarkdownFences);
+
+        assert_eq!(registry.processor_names(CODE_FIX_TASK), vec!["remove-leading-prose", "strip-markdown-fences"]);
+        assert_eq!(
+            registry.process(CODE_FIX_TASK, MESSY_RESPONSE),
+            "const total = 1;\nif (total === 2) {\n  report(total);\n}"
+        );
+    }
+
+    #[test]
+    fn test_first_code_block_drops_trailing_explanation() {
+        let response = format!("{}\n\nThis replaces `==` with `===`.\n\n```ts\nother();\n```", MESSY_RESPONSE);
+        let context = AIContext::new(
+            "post-process-test",
+            AITaskKind::CodeFix {
+                language: "typescript".to_string(),
+                content: String::new(),
+            },
+        );
+
+        assert_eq!(
+            PostProcessorRegistry::with_defaults().process_for(&context, &response),
+            "const total = 1;\nif (total === 2) {\n  report(total);\n}"
+        );
+        assert_eq!(ExtractFirstCodeBlock.process("```ts\nconst a = 1;\nconst b"), "const a = 1;\nconst b");
+    }
+
+    #[test]
+    fn test_unregistered_task_type_passes_through() {
+        let registry = PostProcessorRegistry::with_defaults();
+        assert_eq!(registry.process_for(&AIContext::default(), MESSY_RESPONSE), MESSY_RESPONSE);
+    }
+}
@@ -0,0 +1,218 @@
+//! Structured classification of AI provider failures
+//!
+//! Provider CLIs report failures as an exit code plus free-form stderr. The router
+//! classifies them into a [`ProviderError`] so retry and fallback can tell an expired
+//! key from a rate limit: authentication failures and bad requests are never retried,
+//! rate limits honor the provider's `retry-after`, and transient failures back off.
+
+use crate::moon_pdk_interface::AiLinterConfig;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Longest wait between retries, whatever the provider asks for
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Why an AI provider request failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+pub enum ProviderError {
+    #[error("authentication failed")]
+    Auth,
+    #[error("rate limited")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("request timed out")]
+    Timeout,
+    #[error("provider server error")]
+    Server,
+    #[error("bad request")]
+    BadRequest,
+    #[error("network error")]
+    Network,
+    #[error("unknown provider failure")]
+    Unknown,
+}
+
+impl ProviderError {
+    /// Classify a failed provider CLI run from its exit code and stderr
+    pub fn classify(exit_code: i32, stderr: &str) -> Self {
+        let text = stderr.to_ascii_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| text.contains(needle));
+
+        if has(&["429", "rate limit", "rate_limit", "too many requests", "quota exceeded"]) {
+            return Self::RateLimited {
+                retry_after: parse_retry_after(&text),
+            };
+        }
+        if has(&[
+            "401",
+            "403",
+            "unauthorized",
+            "forbidden",
+            "invalid api key",
+            "invalid x-api-key",
+            "authentication",
+            "not logged in",
+        ]) {
+            return Self::Auth;
+        }
+        if has(&["timed out", "timeout", "etimedout", "deadline exceeded"]) || exit_code == 124 {
+            return Self::Timeout;
+        }
+        if has(&[
+            "econnrefused",
+            "econnreset",
+            "enotfound",
+            "getaddrinfo",
+            "network error",
+            "connection reset",
+            "connection refused",
+        ]) {
+            return Self::Network;
+        }
+        if has(&[
+            "500",
+            "502",
+            "503",
+            "529",
+            "internal server error",
+            "bad gateway",
+            "service unavailable",
+            "overloaded",
+        ]) {
+            return Self::Server;
+        }
+        if has(&["400", "bad request", "invalid_request", "invalid request", "context length", "too long"]) {
+            return Self::BadRequest;
+        }
+        Self::Unknown
+    }
+}
+
+/// Seconds from `retry-after: 30`, `retry after 30s` or `try again in 30 seconds`
+fn parse_retry_after(text: &str) -> Option<Duration> {
+    ["retry-after:", "retry-after", "retry after", "try again in"].iter().find_map(|marker| {
+        let start = text.find(marker)? + marker.len();
+        let digits: String = text[start..]
+            .trim_start_matches([' ', ':', '='])
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits.parse().ok().map(Duration::from_secs)
+    })
+}
+
+/// What the router does after a provider failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    Retry { delay: Duration }, // Same provider again after `delay`
+    FallBack,                  // Next ranked provider
+    Abort,                     // Stop; no provider can serve this request
+}
+
+/// Retry policy for provider failures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32, // Retries per provider before falling back
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Policy from the AI request limits (`retry_attempts`, `retry_delay_ms`)
+    pub fn from_config(config: &AiLinterConfig) -> Self {
+        Self {
+            max_retries: config.retry_attempts,
+            base_delay: Duration::from_millis(config.retry_delay_ms as u64),
+        }
+    }
+
+    /// Decide how to continue after `error` on the provider's `retries_done`-th retry
+    pub fn decide(&self, error: &ProviderError, retries_done: u32) -> RetryDecision {
+        let retries_left = retries_done < self.max_retries;
+        match error {
+            // Another provider has its own credentials; retrying this one cannot help
+            ProviderError::Auth | ProviderError::Unknown => RetryDecision::FallBack,
+            // The same request is malformed for every provider
+            ProviderError::BadRequest => RetryDecision::Abort,
+            ProviderError::RateLimited { retry_after } if retries_left => RetryDecision::Retry {
+                delay: retry_after.unwrap_or_else(|| self.backoff(retries_done)).min(MAX_RETRY_DELAY),
+            },
+            ProviderError::Timeout | ProviderError::Server | ProviderError::Network if retries_left => RetryDecision::Retry {
+                delay: self.backoff(retries_done),
+            },
+            _ => RetryDecision::FallBack,
+        }
+    }
+
+    fn backoff(&self, retries_done: u32) -> Duration {
+        self.base_delay.saturating_mul(2u32.saturating_pow(retries_done)).min(MAX_RETRY_DELAY)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&AiLinterConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classifies_representative_provider_failures() {
+        let cases = [
+            ("Error: 401 Unauthorized - invalid x-api-key", ProviderError::Auth),
+            ("Invalid API key · Please run /login", ProviderError::Auth),
+            (
+                "API Error: 429 Too Many Requests (retry-after: 30)",
+                ProviderError::RateLimited {
+                    retry_after: Some(Duration::from_secs(30)),
+                },
+            ),
+            ("Quota exceeded for model", ProviderError::RateLimited { retry_after: None }),
+            ("Request timed out after 600000ms", ProviderError::Timeout),
+            ("API Error: 529 overloaded_error", ProviderError::Server),
+            ("fetch failed: getaddrinfo ENOTFOUND api.anthropic.com", ProviderError::Network),
+            ("400 invalid_request_error: prompt is too long", ProviderError::BadRequest),
+            ("segmentation fault", ProviderError::Unknown),
+        ];
+
+        for (stderr, expected) in cases {
+            assert_eq!(ProviderError::classify(1, stderr), expected, "{}", stderr);
+        }
+    }
+
+    #[test]
+    fn test_retry_policy_decisions() {
+        let policy = RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(100),
+        };
+
+        assert_eq!(policy.decide(&ProviderError::Auth, 0), RetryDecision::FallBack);
+        assert_eq!(policy.decide(&ProviderError::BadRequest, 0), RetryDecision::Abort);
+        assert_eq!(
+            policy.decide(
+                &ProviderError::RateLimited {
+                    retry_after: Some(Duration::from_secs(7))
+                },
+                0
+            ),
+            RetryDecision::Retry { delay: Duration::from_secs(7) }
+        );
+        assert_eq!(
+            policy.decide(&ProviderError::Server, 1),
+            RetryDecision::Retry {
+                delay: Duration::from_millis(200)
+            }
+        );
+        assert_eq!(policy.decide(&ProviderError::Timeout, 2), RetryDecision::FallBack);
+    }
+
+    #[test]
+    fn test_classification_carried_by_execution_error() {
+        let error = crate::error::Error::ai_execution_with_source(&super::super::AIProviderConfig::claude(), "AI CLI failed", ProviderError::Auth);
+        assert_eq!(error.provider_error(), Some(&ProviderError::Auth));
+        assert_eq!(crate::error::Error::config("unrelated").provider_error(), None);
+    }
+}
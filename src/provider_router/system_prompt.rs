@@ -0,0 +1,97 @@
+//! Project-wide system prompt prepended to every AI request
+//!
+//! Teams put shared context (coding standards, banned patterns) in `ai.system_prompt` or
+//! in a file named by `ai.system_prompt_file`. The router prepends it as a `System:`
+//! message, the same form [`DirectAILM`](crate::dspy::core::lm::DirectAILM) uses for
+//! conversation system messages, so DSPy and plain prompts carry it alike. It is capped to
+//! a share of the provider's context window so it never crowds out the request itself.
+
+use crate::config::AiConfig;
+use crate::moon_pdk_interface::read_file_content;
+
+/// Largest share of the context window the system prompt may take
+const MAX_CONTEXT_SHARE: u32 = 4;
+
+/// Rough characters per token, matching the token estimates used elsewhere
+const CHARS_PER_TOKEN: usize = 4;
+
+/// The configured system prompt; inline `system_prompt` wins over `system_prompt_file`
+pub fn resolve_system_prompt(ai: &AiConfig) -> Option<String> {
+    let prompt = match (&ai.system_prompt, &ai.system_prompt_file) {
+        (Some(prompt), _) => prompt.clone(),
+        (None, Some(path)) => match read_file_content(path) {
+            Ok(content) => content,
+            Err(error) => {
+                moon_warn!("Failed to read ai.system_prompt_file {}: {}", path, error);
+                return None;
+            }
+        },
+        (None, None) => return None,
+    };
+
+    let prompt = prompt.trim();
+    (!prompt.is_empty()).then(|| prompt.to_string())
+}
+
+/// `system_prompt` cut to at most `max_tokens`, at a whitespace boundary where possible
+pub fn trim_to_budget(system_prompt: &str, max_tokens: u32) -> &str {
+    let max_chars = max_tokens as usize * CHARS_PER_TOKEN;
+    if system_prompt.len() <= max_chars {
+        return system_prompt;
+    }
+
+    let mut end = max_chars;
+    while !system_prompt.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = &system_prompt[..end];
+    cut.rfind(char::is_whitespace).map(|boundary| &cut[..boundary]).unwrap_or(cut).trim_end()
+}
+
+/// `prompt` with the system prompt prepended, trimmed to fit a `context_tokens` window
+pub fn compose_prompt(system_prompt: &str, prompt: &str, context_tokens: u32) -> String {
+    let prompt_tokens = (prompt.len() / CHARS_PER_TOKEN) as u32;
+    let budget = (context_tokens / MAX_CONTEXT_SHARE).min(context_tokens.saturating_sub(prompt_tokens));
+    let system_prompt = trim_to_budget(system_prompt, budget);
+
+    if system_prompt.is_empty() {
+        return prompt.to_string();
+    }
+    if system_prompt.len() < prompt.len() {
+        moon_debug!("System prompt trimmed to {} of the {} token context", budget, context_tokens);
+    }
+    format!("System: {}\n\n{}", system_prompt, prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_prompt_included_in_composed_prompt() {
+        let mut ai = AiConfig::default();
+        ai.system_prompt = Some("  Never use `any` in TypeScript.\n".to_string());
+
+        let system_prompt = resolve_system_prompt(&ai).unwrap();
+        let composed = compose_prompt(&system_prompt, "User: Fix this file", 200_000);
+
+        assert_eq!(composed, "System: Never use `any` in TypeScript.\n\nUser: Fix this file");
+        assert_eq!(resolve_system_prompt(&AiConfig::default()), None);
+    }
+
+    #[test]
+    fn test_system_prompt_trimmed_when_over_budget() {
+        let standards = "Prefer const over let. ".repeat(100);
+
+        // A 400-token window leaves the system prompt at most 100 tokens (400 chars)
+        let composed = compose_prompt(&standards, "Fix this", 400);
+        let system_part = composed.strip_prefix("System: ").unwrap().split("\n\n").next().unwrap();
+        assert!(system_part.len() <= 400);
+        assert!(standards.starts_with(system_part) && standards[system_part.len()..].starts_with(' '), "cut at a word boundary");
+        assert!(composed.ends_with("\n\nFix this"));
+
+        // A prompt filling the whole window leaves no room for the system prompt
+        let long_prompt = "x".repeat(1_600);
+        assert_eq!(compose_prompt(&standards, &long_prompt, 400), long_prompt);
+    }
+}
@@ -0,0 +1,170 @@
+//! Provider warm-up and connectivity reuse
+//!
+//! With `ai.provider_warmup`, every configured provider is probed once at run start, in
+//! parallel where threads are available, and the outcome is kept for the rest of the run:
+//! later AI calls reuse it instead of each paying for its own cold start, and a provider
+//! that failed the probe is reported without launching a request. A provider first used
+//! after start-up is probed on that first call. CLI providers are spawned per request, so
+//! the reuse is of validated connectivity; under WASM the host owns the processes and any
+//! connections they keep alive.
+
+use super::AIProviderConfig;
+use crate::error::{Error, Result};
+use crate::moon_pdk_interface::{ensure_mutation_allowed, execute_command, ExecCommandInput};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Checks that a provider is reachable; the error describes why it is not
+pub type ProbeFn = dyn Fn(&AIProviderConfig) -> std::result::Result<(), String> + Send + Sync;
+
+/// Connectivity of each provider, validated once per run
+pub struct ProviderWarmup {
+    probe: Box<ProbeFn>,
+    validated: Mutex<HashMap<String, std::result::Result<(), String>>>, // Probe outcome by provider name
+}
+
+impl std::fmt::Debug for ProviderWarmup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderWarmup").field("validated", &self.validated).finish_non_exhaustive()
+    }
+}
+
+impl Default for ProviderWarmup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProviderWarmup {
+    /// Warm-up probing each provider's CLI with `--version`
+    pub fn new() -> Self {
+        Self::with_probe(version_probe)
+    }
+
+    /// Warm-up using `probe` to check providers
+    pub fn with_probe(probe: impl Fn(&AIProviderConfig) -> std::result::Result<(), String> + Send + Sync + 'static) -> Self {
+        Self {
+            probe: Box::new(probe),
+            validated: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Probe every provider in `providers` not validated yet; returns the names of those that are ready
+    pub fn warm_up(&self, providers: &[AIProviderConfig]) -> Vec<String> {
+        let pending: Vec<&AIProviderConfig> = {
+            let validated = self.validated.lock().unwrap_or_else(|e| e.into_inner());
+            providers.iter().filter(|provider| !validated.contains_key(&provider.name)).collect()
+        };
+        // Safe mode is per thread, so it is checked here rather than on the probe threads
+        if !pending.is_empty() && ensure_mutation_allowed("probe AI providers").is_err() {
+            return Vec::new();
+        }
+
+        #[cfg(not(feature = "wasm"))]
+        let outcomes: Vec<_> = std::thread::scope(|scope| {
+            let probes: Vec<_> = pending.iter().map(|provider| scope.spawn(move || (self.probe)(provider))).collect();
+            probes
+                .into_iter()
+                .map(|probe| probe.join().unwrap_or_else(|_| Err("provider probe panicked".to_string())))
+                .collect()
+        });
+        #[cfg(feature = "wasm")]
+        let outcomes: Vec<_> = pending.iter().map(|provider| (self.probe)(provider)).collect();
+
+        let mut validated = self.validated.lock().unwrap_or_else(|e| e.into_inner());
+        for (provider, outcome) in pending.into_iter().zip(outcomes) {
+            match &outcome {
+                Ok(()) => moon_debug!("AI provider {} ready", provider.name),
+                Err(reason) => moon_warn!("AI provider {} failed warm-up: {}", provider.name, reason),
+            }
+            validated.entry(provider.name.clone()).or_insert(outcome);
+        }
+        providers
+            .iter()
+            .filter(|provider| matches!(validated.get(&provider.name), Some(Ok(()))))
+            .map(|provider| provider.name.clone())
+            .collect()
+    }
+
+    /// Fail unless `provider` is reachable, probing it only if it has not been validated yet
+    pub fn ensure_ready(&self, provider: &AIProviderConfig) -> Result<()> {
+        let known = self.validated.lock().unwrap_or_else(|e| e.into_inner()).get(&provider.name).cloned();
+        let outcome = match known {
+            Some(outcome) => outcome,
+            None => {
+                self.warm_up(std::slice::from_ref(provider));
+                self.validated
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(&provider.name)
+                    .cloned()
+                    .unwrap_or(Ok(()))
+            }
+        };
+        outcome.map_err(|reason| Error::ai_execution(provider, format!("Provider unavailable at warm-up: {}", reason)))
+    }
+}
+
+/// Run the provider's CLI with `--version`
+fn version_probe(provider: &AIProviderConfig) -> std::result::Result<(), String> {
+    let output = execute_command(ExecCommandInput {
+        command: provider.command.clone(),
+        args: vec!["--version".to_string()],
+        env: HashMap::new(),
+        working_dir: None,
+    })
+    .map_err(|e| e.to_string())?;
+    if output.exit_code == 0 {
+        Ok(())
+    } else {
+        Err(format!("`{} --version` exited with {}: {}", provider.command, output.exit_code, output.stderr.trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn counting_warmup(failing: &'static str) -> (ProviderWarmup, Arc<AtomicUsize>) {
+        let probes = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&probes);
+        let warmup = ProviderWarmup::with_probe(move |provider| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            if provider.name == failing {
+                Err("command not found".to_string())
+            } else {
+                Ok(())
+            }
+        });
+        (warmup, probes)
+    }
+
+    #[test]
+    fn test_providers_validated_once_at_startup_and_reused() {
+        let providers = vec![AIProviderConfig::claude(), AIProviderConfig::google(), AIProviderConfig::openai()];
+        let (warmup, probes) = counting_warmup("google");
+
+        let ready = warmup.warm_up(&providers);
+        assert_eq!(ready, vec!["claude".to_string(), "openai".to_string()]);
+        assert_eq!(probes.load(Ordering::SeqCst), 3);
+
+        for _ in 0..5 {
+            warmup.ensure_ready(&providers[0]).unwrap();
+            assert!(warmup.ensure_ready(&providers[1]).is_err());
+        }
+        warmup.warm_up(&providers);
+        assert_eq!(probes.load(Ordering::SeqCst), 3, "later calls reuse the start-up validation");
+    }
+
+    #[test]
+    fn test_provider_first_used_after_startup_is_probed_once() {
+        let (warmup, probes) = counting_warmup("none");
+        let claude = AIProviderConfig::claude();
+
+        warmup.ensure_ready(&claude).unwrap();
+        warmup.ensure_ready(&claude).unwrap();
+        assert_eq!(probes.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,803 @@
+//! # Terminal Reporting
+//!
+//! Compact, human-readable rendering of lint diagnostics for local runs. Diagnostics are
+//! grouped per file as aligned `file:line:col  severity  rule  message` lines followed by a
+//! one-line summary. Color is used only when the host indicates a TTY (or forces color),
+//! and the output degrades to plain text otherwise. Collection paths cap AI suggestions per
+//! file at `linting.max_suggestions` with [`limit_ai_suggestions`]; static diagnostics are
+//! never dropped.
+//!
+//! [`render_report`] also produces JSON and SARIF for `--format`, and [`write_report`]
+//! archives the rendered report at the `--output` path (`-` meaning stdout).
+//!
+//! [`quality_score`] weighs findings by rule category (`linting.category_weights`), so a
+//! security error costs more than a style nit, and ranks categories by their share.
+//! [`diff_results`] compares two runs by diagnostic [`fingerprint`], which ignores
+//! positions so an issue that only moved is not reported as fixed and new.
+//!
+//! @category reporting
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::config::FailOnConfig;
+use crate::error::{Error, Result};
+use crate::moon_pdk_interface::write_file_atomic;
+use crate::types::{DiagnosticSeverity, LintDiagnostic};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+
+/// Rule-name prefix of diagnostics produced by AI behavioral analysis
+const AI_SUGGESTION_PREFIX: &str = "ai-behavioral:";
+
+/// Weight of a category missing from `linting.category_weights`
+const DEFAULT_CATEGORY_WEIGHT: f64 = 1.0;
+/// Category of diagnostics whose rule is not in the registry
+const UNCATEGORIZED: &str = "uncategorized";
+
+/// `--output` value that sends the report to stdout instead of a file
+pub const STDOUT_OUTPUT: &str = "-";
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Report formats accepted by `--format` and `output_format` in `moon.yml`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// Grouped, human-readable text
+    #[default]
+    Terminal,
+    /// Pretty-printed array of diagnostics
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools
+    Sarif,
+}
+
+impl ReportFormat {
+    /// All formats, in the order they are listed in error messages
+    pub const ALL: [ReportFormat; 3] = [ReportFormat::Terminal, ReportFormat::Json, ReportFormat::Sarif];
+
+    /// Canonical name used on the CLI and in config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportFormat::Terminal => "terminal",
+            ReportFormat::Json => "json",
+            ReportFormat::Sarif => "sarif",
+        }
+    }
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let normalized = value.trim().to_ascii_lowercase();
+        Self::ALL.iter().copied().find(|format| format.as_str() == normalized).ok_or_else(|| {
+            let valid: Vec<&str> = Self::ALL.iter().map(ReportFormat::as_str).collect();
+            Error::config_field(
+                format!("Unknown output format '{}'. Valid formats: {}", value, valid.join(", ")),
+                "output_format",
+                Some(value),
+            )
+        })
+    }
+}
+
+/// Options for [`to_terminal`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerminalReportOptions {
+    pub color: bool,
+}
+
+impl TerminalReportOptions {
+    /// Options for the current host: color when stdout is a TTY, honoring `NO_COLOR`/`FORCE_COLOR`
+    pub fn detect() -> Self {
+        Self::from_host(|key| std::env::var(key).ok(), std::io::stdout().is_terminal())
+    }
+
+    /// Resolve options from host environment lookups and TTY state
+    pub fn from_host<E>(env: E, is_tty: bool) -> Self
+    where
+        E: Fn(&str) -> Option<String>,
+    {
+        let set = |key: &str| env(key).is_some_and(|value| !value.is_empty() && value != "0");
+        let color = if set("NO_COLOR") { false } else { set("FORCE_COLOR") || is_tty };
+        Self { color }
+    }
+}
+
+/// Render diagnostics grouped by file with a trailing summary
+pub fn to_terminal(results: &[LintDiagnostic], options: TerminalReportOptions) -> String {
+    if results.is_empty() {
+        return "No problems found\n".to_string();
+    }
+
+    let mut by_file: BTreeMap<&str, Vec<&LintDiagnostic>> = BTreeMap::new();
+    for diagnostic in results {
+        by_file.entry(diagnostic.file_path.as_str()).or_default().push(diagnostic);
+    }
+
+    let mut output = String::new();
+    for (file_path, diagnostics) in &mut by_file {
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+
+        let rows: Vec<(String, &DiagnosticSeverity, &str, &str)> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    format!("{}:{}:{}", d.file_path, d.line, d.column),
+                    &d.severity,
+                    d.rule_name.as_str(),
+                    d.message.as_str(),
+                )
+            })
+            .collect();
+        let location_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+        let severity_width = rows.iter().map(|row| severity_label(row.1).len()).max().unwrap_or(0);
+        let rule_width = rows.iter().map(|row| row.2.len()).max().unwrap_or(0);
+
+        output.push_str(&paint(file_path, BOLD, options));
+        output.push('\n');
+        for (location, severity, rule, message) in rows {
+            let severity_cell = format!("{:<width$}", severity_label(severity), width = severity_width);
+            let line = format!(
+                "  {:<location_width$}  {}  {}  {}",
+                location,
+                paint(&severity_cell, severity_color(severity), options),
+                paint(&format!("{:<rule_width$}", rule), DIM, options),
+                message
+            );
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&summary(results, by_file.len(), options));
+    output.push('\n');
+    output
+}
+
+/// Render diagnostics in the requested format
+pub fn render_report(results: &[LintDiagnostic], format: ReportFormat, options: TerminalReportOptions) -> String {
+    match format {
+        ReportFormat::Terminal => to_terminal(results, options),
+        ReportFormat::Json => to_json(results),
+        ReportFormat::Sarif => to_sarif(results),
+    }
+}
+
+/// Diagnostics as a pretty-printed JSON array
+pub fn to_json(results: &[LintDiagnostic]) -> String {
+    serde_json::to_string_pretty(results).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Diagnostics as a single-run SARIF 2.1.0 log
+pub fn to_sarif(results: &[LintDiagnostic]) -> String {
+    let rule_ids: BTreeSet<&str> = results.iter().map(|d| d.rule_name.as_str()).collect();
+    let rules: Vec<serde_json::Value> = rule_ids.into_iter().map(|id| serde_json::json!({ "id": id })).collect();
+    let sarif_results: Vec<serde_json::Value> = results
+        .iter()
+        .map(|d| {
+            serde_json::json!({
+                "ruleId": d.rule_name,
+                "level": sarif_level(&d.severity),
+                "message": { "text": d.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": d.file_path },
+                        "region": {
+                            "startLine": d.line,
+                            "startColumn": d.column,
+                            "endLine": d.end_line,
+                            "endColumn": d.end_column
+                        }
+                    }
+                }]
+            })
+        })
+        .collect();
+
+    let log = serde_json::json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "moon-shine",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                }
+            },
+            "results": sarif_results
+        }]
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Write a rendered report to `path`, or to stdout when `path` is [`STDOUT_OUTPUT`].
+///
+/// Files go through `write_file_atomic`, which creates missing parent directories.
+pub fn write_report(path: &str, report: &str) -> Result<()> {
+    if path == STDOUT_OUTPUT {
+        print!("{}", report);
+        return Ok(());
+    }
+    write_file_atomic(path, report).map_err(|e| Error::config_field(format!("Failed to write report: {}", e), "output_file", Some(path)))
+}
+
+/// Overall outcome of a run under `linting.fail_on`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum RunStatus {
+    Passed,
+    Failed { reasons: Vec<String> }, // One entry per exceeded threshold
+}
+
+impl RunStatus {
+    /// Process exit code for the status
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RunStatus::Passed => 0,
+            RunStatus::Failed { .. } => 1,
+        }
+    }
+}
+
+/// Check the run's diagnostics against the `fail_on` thresholds
+pub fn evaluate_run_status(results: &[LintDiagnostic], fail_on: &FailOnConfig) -> RunStatus {
+    let errors = results.iter().filter(|d| d.severity == DiagnosticSeverity::Error).count();
+    let warnings = results.iter().filter(|d| d.severity == DiagnosticSeverity::Warning).count();
+    let fixable = results.iter().filter(|d| d.fix_available).count();
+
+    let mut reasons = Vec::new();
+    if let Some(max_errors) = fail_on.max_errors.filter(|max| errors > *max as usize) {
+        reasons.push(format!("{} error(s) exceed the limit of {}", errors, max_errors));
+    }
+    if let Some(max_warnings) = fail_on.max_warnings.filter(|max| warnings > *max as usize) {
+        reasons.push(format!("{} warning(s) exceed the limit of {}", warnings, max_warnings));
+    }
+    if fail_on.fail_on_fixable && fixable > 0 {
+        reasons.push(format!("{} diagnostic(s) have a fix available", fixable));
+    }
+
+    if reasons.is_empty() {
+        RunStatus::Passed
+    } else {
+        RunStatus::Failed { reasons }
+    }
+}
+
+/// AI suggestions kept after applying `max_suggestions`
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionLimit {
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub suppressed: usize,
+    pub notes: Vec<String>, // One "N more suppressed" note per truncated file
+}
+
+/// Keep at most `max_suggestions` AI suggestions per file.
+///
+/// Suggestions are ranked by severity, then by AI confidence; the rest are suppressed with
+/// a note per file. Static diagnostics always pass through, and order is preserved.
+pub fn limit_ai_suggestions(diagnostics: Vec<LintDiagnostic>, max_suggestions: u32) -> SuggestionLimit {
+    let max_suggestions = max_suggestions as usize;
+
+    let mut per_file: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, diagnostic) in diagnostics.iter().enumerate() {
+        if is_ai_suggestion(diagnostic) {
+            per_file.entry(diagnostic.file_path.as_str()).or_default().push(index);
+        }
+    }
+
+    let mut dropped = HashSet::new();
+    let mut notes = Vec::new();
+    let mut truncated_files: Vec<(&str, Vec<usize>)> = per_file.into_iter().filter(|(_, indices)| indices.len() > max_suggestions).collect();
+    truncated_files.sort_by_key(|(file_path, _)| *file_path);
+    for (file_path, mut indices) in truncated_files {
+        indices.sort_by(|&a, &b| {
+            let (a, b) = (&diagnostics[a], &diagnostics[b]);
+            severity_rank(&a.severity)
+                .cmp(&severity_rank(&b.severity))
+                .then_with(|| b.ai_confidence.unwrap_or(0.0).total_cmp(&a.ai_confidence.unwrap_or(0.0)))
+        });
+        let suppressed = indices.len() - max_suggestions;
+        dropped.extend(indices.into_iter().skip(max_suggestions));
+        notes.push(format!(
+            "{} more AI suggestion(s) suppressed for {} (linting.max_suggestions = {})",
+            suppressed, file_path, max_suggestions
+        ));
+    }
+
+    let suppressed = dropped.len();
+    let diagnostics = diagnostics
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !dropped.contains(index))
+        .map(|(_, diagnostic)| diagnostic)
+        .collect();
+
+    SuggestionLimit {
+        diagnostics,
+        suppressed,
+        notes,
+    }
+}
+
+/// Raw and weighted findings of one rule category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryScore {
+    pub category: String,
+    pub count: usize,
+    pub weight: f64,
+    /// Severity-scaled findings times `weight`; what the category costs the quality score
+    pub weighted: f64,
+}
+
+/// Category-weighted quality of a diagnostic set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityScore {
+    /// 100 with no findings; every 10 weighted findings halve what is left
+    pub score: f64,
+    /// Categories with findings, costliest first
+    pub categories: Vec<CategoryScore>,
+}
+
+/// Quality score of `results` with each rule's category from `categories` (rule ID to
+/// lowercase category) weighted by `weights`.
+///
+/// An error counts 1, a warning 0.5, info 0.1 and a hint nothing, times its category weight.
+pub fn quality_score(results: &[LintDiagnostic], categories: &HashMap<String, String>, weights: &HashMap<String, f64>) -> QualityScore {
+    let mut by_category: BTreeMap<&str, (usize, f64)> = BTreeMap::new();
+    for diagnostic in results {
+        let category = categories.get(&diagnostic.rule_name).map(String::as_str).unwrap_or(UNCATEGORIZED);
+        let entry = by_category.entry(category).or_default();
+        entry.0 += 1;
+        entry.1 += severity_points(&diagnostic.severity);
+    }
+
+    let mut categories: Vec<CategoryScore> = by_category
+        .into_iter()
+        .map(|(category, (count, points))| {
+            let weight = weights.get(category).copied().unwrap_or(DEFAULT_CATEGORY_WEIGHT);
+            CategoryScore {
+                category: category.to_string(),
+                count,
+                weight,
+                weighted: points * weight,
+            }
+        })
+        .collect();
+    categories.sort_by(|a, b| b.weighted.total_cmp(&a.weighted).then_with(|| a.category.cmp(&b.category)));
+
+    let penalty: f64 = categories.iter().map(|category| category.weighted).sum();
+    QualityScore {
+        score: 100.0 / (1.0 + penalty / 10.0),
+        categories,
+    }
+}
+
+/// One-line rendering of `quality`, e.g. `Quality score 62.5/100 (security 2 x4, style 3 x1)`
+pub fn quality_summary(quality: &QualityScore, options: TerminalReportOptions) -> String {
+    let breakdown: Vec<String> = quality
+        .categories
+        .iter()
+        .map(|category| format!("{} {} x{}", category.category, category.count, category.weight))
+        .collect();
+    let text = if breakdown.is_empty() {
+        format!("Quality score {:.1}/100", quality.score)
+    } else {
+        format!("Quality score {:.1}/100 ({})", quality.score, breakdown.join(", "))
+    };
+    paint(&text, BOLD, options)
+}
+
+/// Position-independent identity of a diagnostic: its file, rule and whitespace-normalized message
+pub fn fingerprint(diagnostic: &LintDiagnostic) -> String {
+    let message = diagnostic.message.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hasher = blake3::Hasher::new();
+    for part in [diagnostic.file_path.as_str(), diagnostic.rule_name.as_str(), message.as_str()] {
+        hasher.update(part.as_bytes());
+        hasher.update(&[0]);
+    }
+    hasher.finalize().to_hex()[..16].to_string()
+}
+
+/// How diagnostics changed between two runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResultDiff {
+    /// In `before` only
+    pub fixed: Vec<LintDiagnostic>,
+    /// In `after` only
+    pub new: Vec<LintDiagnostic>,
+    /// In both, as reported in `after`
+    pub unchanged: Vec<LintDiagnostic>,
+}
+
+impl ResultDiff {
+    /// Whether `after` resolved something without introducing anything
+    pub fn is_improvement(&self) -> bool {
+        self.new.is_empty() && !self.fixed.is_empty()
+    }
+}
+
+/// Classify diagnostics as fixed, new or unchanged by [`fingerprint`].
+///
+/// Diagnostics sharing a fingerprint are paired in line order, so one of three identical
+/// findings being fixed shows up as one fixed and two unchanged.
+pub fn diff_results(before: &[LintDiagnostic], after: &[LintDiagnostic]) -> ResultDiff {
+    let group = |results: &[LintDiagnostic]| {
+        let mut groups: BTreeMap<String, Vec<LintDiagnostic>> = BTreeMap::new();
+        for diagnostic in results {
+            groups.entry(fingerprint(diagnostic)).or_default().push(diagnostic.clone());
+        }
+        for diagnostics in groups.values_mut() {
+            diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+        }
+        groups
+    };
+    let mut before = group(before);
+    let after = group(after);
+
+    let mut diff = ResultDiff::default();
+    for (key, mut after) in after {
+        let mut before = before.remove(&key).unwrap_or_default();
+        let paired = before.len().min(after.len());
+        diff.fixed.extend(before.split_off(paired));
+        diff.new.extend(after.split_off(paired));
+        diff.unchanged.extend(after);
+    }
+    diff.fixed.extend(before.into_values().flatten());
+    diff
+}
+
+/// Whether the diagnostic comes from AI behavioral analysis
+pub fn is_ai_suggestion(diagnostic: &LintDiagnostic) -> bool {
+    diagnostic.rule_name.starts_with(AI_SUGGESTION_PREFIX)
+}
+
+fn severity_rank(severity: &DiagnosticSeverity) -> u8 {
+    match severity {
+        DiagnosticSeverity::Error => 0,
+        DiagnosticSeverity::Warning => 1,
+        DiagnosticSeverity::Info => 2,
+        DiagnosticSeverity::Hint => 3,
+    }
+}
+
+fn severity_points(severity: &DiagnosticSeverity) -> f64 {
+    match severity {
+        DiagnosticSeverity::Error => 1.0,
+        DiagnosticSeverity::Warning => 0.5,
+        DiagnosticSeverity::Info => 0.1,
+        DiagnosticSeverity::Hint => 0.0,
+    }
+}
+
+fn summary(results: &[LintDiagnostic], file_count: usize, options: TerminalReportOptions) -> String {
+    let count = |severity: DiagnosticSeverity| results.iter().filter(|d| d.severity == severity).count();
+    let breakdown: Vec<String> = [
+        (count(DiagnosticSeverity::Error), "error", "errors"),
+        (count(DiagnosticSeverity::Warning), "warning", "warnings"),
+        (count(DiagnosticSeverity::Info), "info", "info"),
+        (count(DiagnosticSeverity::Hint), "hint", "hints"),
+    ]
+    .into_iter()
+    .filter(|(n, _, _)| *n > 0)
+    .map(|(n, singular, plural)| format!("{} {}", n, if n == 1 { singular } else { plural }))
+    .collect();
+
+    let text = format!(
+        "{} {} ({}) in {} {}",
+        results.len(),
+        if results.len() == 1 { "problem" } else { "problems" },
+        breakdown.join(", "),
+        file_count,
+        if file_count == 1 { "file" } else { "files" }
+    );
+    paint(&text, BOLD, options)
+}
+
+fn severity_label(severity: &DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info => "info",
+        DiagnosticSeverity::Hint => "hint",
+    }
+}
+
+fn sarif_level(severity: &DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Info | DiagnosticSeverity::Hint => "note",
+    }
+}
+
+fn severity_color(severity: &DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => RED,
+        DiagnosticSeverity::Warning => YELLOW,
+        DiagnosticSeverity::Info => BLUE,
+        DiagnosticSeverity::Hint => DIM,
+    }
+}
+
+fn paint(text: &str, color: &str, options: TerminalReportOptions) -> String {
+    if options.color {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(file_path: &str, line: u32, column: u32, severity: DiagnosticSeverity, rule_name: &str, message: &str) -> LintDiagnostic {
+        LintDiagnostic {
+            rule_name: rule_name.to_string(),
+            message: message.to_string(),
+            file_path: file_path.to_string(),
+            line,
+            column,
+            end_line: line,
+            end_column: column,
+            severity,
+            fix_available: false,
+            suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
+        }
+    }
+
+    fn results() -> Vec<LintDiagnostic> {
+        vec![
+            diagnostic("src/b.ts", 3, 1, DiagnosticSeverity::Info, "no-console", "Unexpected console statement"),
+            diagnostic("src/a.ts", 12, 10, DiagnosticSeverity::Warning, "eqeqeq", "Expected '===' and instead saw '=='"),
+            diagnostic("src/a.ts", 1, 1, DiagnosticSeverity::Error, "prefer-const", "'total' is never reassigned"),
+        ]
+    }
+
+    #[test]
+    fn test_plain_text_layout_grouped_by_file() {
+        let report = to_terminal(&results(), TerminalReportOptions { color: false });
+
+        let expected = "\
+src/a.ts
+  src/a.ts:1:1    error    prefer-const  'total' is never reassigned
+  src/a.ts:12:10  warning  eqeqeq        Expected '===' and instead saw '=='
+
+src/b.ts
+  src/b.ts:3:1  info  no-console  Unexpected console statement
+
+3 problems (1 error, 1 warning, 1 info) in 2 files
+";
+        assert_eq!(report, expected);
+    }
+
+    #[test]
+    fn test_category_weights_change_quality_score_and_ranking() {
+        let results = vec![
+            diagnostic("src/a.ts", 1, 1, DiagnosticSeverity::Error, "no-eval", "eval can be harmful"),
+            diagnostic("src/a.ts", 2, 1, DiagnosticSeverity::Warning, "prefer-const", "'a' is never reassigned"),
+            diagnostic("src/a.ts", 3, 1, DiagnosticSeverity::Warning, "prefer-const", "'b' is never reassigned"),
+            diagnostic("src/a.ts", 4, 1, DiagnosticSeverity::Hint, "custom-rule", "unregistered rule"),
+        ];
+        let categories = HashMap::from([
+            ("no-eval".to_string(), "security".to_string()),
+            ("prefer-const".to_string(), "style".to_string()),
+        ]);
+
+        let security_first = quality_score(&results, &categories, &crate::config::defaults::lint_category_weights());
+        let style_first = quality_score(&results, &categories, &HashMap::from([("security".to_string(), 1.0), ("style".to_string(), 2.0)]));
+
+        // Weighted findings: 4 x 1 error + 1 x 2 warnings = 5 vs 1 x 1 error + 2 x 2 warnings = 3
+        assert!((security_first.score - 100.0 / 1.5).abs() < 1e-9);
+        assert!((style_first.score - 100.0 / 1.3).abs() < 1e-9);
+        assert!(style_first.score > security_first.score);
+
+        let ranking = |quality: &QualityScore| quality.categories.iter().map(|category| category.category.clone()).collect::<Vec<_>>();
+        assert_eq!(ranking(&security_first), vec!["security", "style", "uncategorized"]);
+        assert_eq!(ranking(&style_first), vec!["style", "security", "uncategorized"]);
+        assert_eq!(security_first.categories[1].count, 2);
+
+        assert_eq!(
+            quality_summary(&security_first, TerminalReportOptions::default()),
+            "Quality score 66.7/100 (security 1 x4, style 2 x1, uncategorized 1 x1)"
+        );
+    }
+
+    #[test]
+    fn test_diff_results_classifies_by_fingerprint() {
+        let before = vec![
+            diagnostic("src/a.ts", 1, 1, DiagnosticSeverity::Error, "prefer-const", "'total' is never reassigned"),
+            diagnostic("src/a.ts", 12, 10, DiagnosticSeverity::Warning, "eqeqeq", "Expected '===' and instead saw '=='"),
+            diagnostic("src/a.ts", 20, 10, DiagnosticSeverity::Warning, "eqeqeq", "Expected '===' and instead saw '=='"),
+            diagnostic("src/b.ts", 3, 1, DiagnosticSeverity::Info, "no-console", "Unexpected console statement"),
+        ];
+        let after = vec![
+            // Moved down five lines by an unrelated edit
+            diagnostic("src/a.ts", 6, 1, DiagnosticSeverity::Error, "prefer-const", "'total'  is never reassigned"),
+            diagnostic("src/a.ts", 17, 10, DiagnosticSeverity::Warning, "eqeqeq", "Expected '===' and instead saw '=='"),
+            diagnostic("src/c.ts", 2, 1, DiagnosticSeverity::Warning, "no-console", "Unexpected console statement"),
+        ];
+
+        let diff = diff_results(&before, &after);
+        let locations = |diagnostics: &[LintDiagnostic]| diagnostics.iter().map(|d| format!("{}:{}", d.file_path, d.line)).collect::<BTreeSet<_>>();
+
+        assert_eq!(locations(&diff.unchanged), BTreeSet::from(["src/a.ts:6".to_string(), "src/a.ts:17".to_string()]));
+        assert_eq!(locations(&diff.fixed), BTreeSet::from(["src/a.ts:20".to_string(), "src/b.ts:3".to_string()]));
+        assert_eq!(locations(&diff.new), BTreeSet::from(["src/c.ts:2".to_string()]));
+        assert!(!diff.is_improvement());
+
+        assert!(diff_results(&before, &after[..2]).is_improvement());
+        assert_eq!(diff_results(&before, &before).unchanged.len(), before.len());
+    }
+
+    #[test]
+    fn test_color_codes_only_when_enabled() {
+        let plain = to_terminal(&results(), TerminalReportOptions { color: false });
+        assert!(!plain.contains('\x1b'));
+
+        let colored = to_terminal(&results(), TerminalReportOptions { color: true });
+        assert!(colored.contains(RED) && colored.contains(RESET));
+    }
+
+    fn ai_suggestion(file_path: &str, line: u32, severity: DiagnosticSeverity, confidence: f32) -> LintDiagnostic {
+        LintDiagnostic {
+            ai_confidence: Some(confidence),
+            ..diagnostic(file_path, line, 1, severity, "ai-behavioral:complexity", &format!("Suggestion {}", line))
+        }
+    }
+
+    #[test]
+    fn test_ai_suggestions_truncated_to_cap_with_note() {
+        let mut diagnostics = vec![diagnostic("src/a.ts", 1, 1, DiagnosticSeverity::Hint, "prefer-const", "static")];
+        diagnostics.extend([
+            ai_suggestion("src/a.ts", 2, DiagnosticSeverity::Info, 99.0),
+            ai_suggestion("src/a.ts", 3, DiagnosticSeverity::Warning, 60.0),
+            ai_suggestion("src/a.ts", 4, DiagnosticSeverity::Warning, 95.0),
+            ai_suggestion("src/a.ts", 5, DiagnosticSeverity::Error, 50.0),
+            ai_suggestion("src/b.ts", 1, DiagnosticSeverity::Info, 10.0),
+        ]);
+
+        let limited = limit_ai_suggestions(diagnostics, 2);
+
+        let kept: Vec<(&str, u32)> = limited.diagnostics.iter().map(|d| (d.file_path.as_str(), d.line)).collect();
+        assert_eq!(kept, vec![("src/a.ts", 1), ("src/a.ts", 4), ("src/a.ts", 5), ("src/b.ts", 1)]);
+        assert_eq!(limited.suppressed, 2);
+        assert_eq!(
+            limited.notes,
+            vec!["2 more AI suggestion(s) suppressed for src/a.ts (linting.max_suggestions = 2)"]
+        );
+    }
+
+    #[test]
+    fn test_static_diagnostics_never_suppressed() {
+        let diagnostics: Vec<LintDiagnostic> = (1..=5)
+            .map(|line| diagnostic("src/a.ts", line, 1, DiagnosticSeverity::Warning, "eqeqeq", "static"))
+            .collect();
+
+        let limited = limit_ai_suggestions(diagnostics, 1);
+
+        assert_eq!(limited.diagnostics.len(), 5);
+        assert_eq!(limited.suppressed, 0);
+        assert!(limited.notes.is_empty());
+    }
+
+    #[test]
+    fn test_color_follows_host_tty_and_overrides() {
+        let env =
+            |vars: &'static [(&'static str, &'static str)]| move |key: &str| vars.iter().find(|(name, _)| *name == key).map(|(_, value)| value.to_string());
+
+        assert!(TerminalReportOptions::from_host(env(&[]), true).color);
+        assert!(!TerminalReportOptions::from_host(env(&[]), false).color);
+        assert!(!TerminalReportOptions::from_host(env(&[("NO_COLOR", "1")]), true).color);
+        assert!(TerminalReportOptions::from_host(env(&[("FORCE_COLOR", "1")]), false).color);
+    }
+
+    #[test]
+    fn test_report_format_parsing() {
+        assert_eq!("SARIF".parse::<ReportFormat>().unwrap(), ReportFormat::Sarif);
+        assert_eq!(" json ".parse::<ReportFormat>().unwrap(), ReportFormat::Json);
+        assert!(matches!("xml".parse::<ReportFormat>(), Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_json_report_written_to_output_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reports/nested/moonshine.json");
+        let report = render_report(&results(), ReportFormat::Json, TerminalReportOptions::default());
+
+        write_report(path.to_str().unwrap(), &report).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, report);
+        let parsed: Vec<LintDiagnostic> = serde_json::from_str(&written).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert_eq!(parsed[1].rule_name, "eqeqeq");
+    }
+
+    #[test]
+    fn test_sarif_report_written_to_output_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("moonshine.sarif");
+        let report = render_report(&results(), ReportFormat::Sarif, TerminalReportOptions::default());
+
+        write_report(path.to_str().unwrap(), &report).unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written, report);
+        let sarif: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        assert_eq!(run["results"][1]["ruleId"], "eqeqeq");
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(run["results"][0]["level"], "note");
+        assert_eq!(run["results"][1]["locations"][0]["physicalLocation"]["region"]["startLine"], 12);
+    }
+
+    #[test]
+    fn test_run_fails_when_errors_exceed_threshold() {
+        let diagnostics = vec![
+            diagnostic("src/a.ts", 1, 1, DiagnosticSeverity::Error, "eqeqeq", "loose"),
+            diagnostic("src/a.ts", 2, 1, DiagnosticSeverity::Warning, "prefer-const", "let"),
+        ];
+        let fail_on = FailOnConfig {
+            max_errors: Some(0),
+            max_warnings: Some(50),
+            fail_on_fixable: false,
+        };
+
+        let status = evaluate_run_status(&diagnostics, &fail_on);
+
+        assert_eq!(
+            status,
+            RunStatus::Failed {
+                reasons: vec!["1 error(s) exceed the limit of 0".to_string()]
+            }
+        );
+        assert_eq!(status.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_run_passes_within_limits() {
+        let diagnostics = vec![diagnostic("src/a.ts", 2, 1, DiagnosticSeverity::Warning, "prefer-const", "let")];
+        let fail_on = FailOnConfig {
+            max_errors: Some(0),
+            max_warnings: Some(1),
+            fail_on_fixable: false,
+        };
+
+        assert_eq!(evaluate_run_status(&diagnostics, &fail_on), RunStatus::Passed);
+        assert_eq!(evaluate_run_status(&diagnostics, &FailOnConfig::default()), RunStatus::Passed);
+
+        let mut fixable = diagnostics.clone();
+        fixable[0].fix_available = true;
+        let strict = FailOnConfig {
+            fail_on_fixable: true,
+            ..fail_on
+        };
+        assert_eq!(evaluate_run_status(&fixable, &strict).exit_code(), 1);
+    }
+}
@@ -7,8 +7,8 @@
 
 use crate::error::Result;
 // Legacy imports removed - using modern Biome + AI system
-use crate::rule_types::{RuleCategory, RuleMetadata, RuleSeverity};
-use crate::rulebase::RuleImplementation;
+use crate::rule_types::{RuleCategory, RuleMetadata};
+use crate::rulebase::{severities, RuleImplementation};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
@@ -226,29 +226,26 @@ impl RuleLoader {
                     name: rule_def.name.clone(),
                     description: rule_def.description.clone(),
                     category: RuleCategory::from(rule_def.category.as_str()),
-                    severity: match rule_def.severity.as_str() {
-                        "error" => RuleSeverity::Error,
-                        "warning" => RuleSeverity::Warning,
-                        "info" => RuleSeverity::Info,
-                        "hint" => RuleSeverity::Hint,
-                        _ => RuleSeverity::Warning,
-                    },
+                    severity: severities::default_severity(&rule_def.id),
                     fix_status: if rule_def.autofix {
                         crate::rule_types::FixStatus::Autofix
                     } else {
                         crate::rule_types::FixStatus::Manual
                     },
+                    fix_safety: crate::rulebase::static_rules::fix_safety(&rule_def.name),
                     ai_enhanced: rule_def.ai_enhanced,
                     cost: rule_def.cost,
                     tags: rule_def.tags.clone(),
                     dependencies: rule_def.dependencies.clone(),
                     implementation: RuleImplementation::from_rule_definition(rule_def),
                     config_schema: rule_def.config_schema.as_ref().map(|v| v.to_string()),
+                    applies_to: rule_def.applies_to.clone(),
                 };
 
                 rules.insert(rule_def.id.clone(), rule_metadata);
             }
 
+            debug_assert_all_severities(&rules);
             let rules_len = rules.len();
             Ok(Self { rules, total_rules: rules_len })
         }
@@ -265,8 +262,9 @@ impl RuleLoader {
                     name: "noUndeclaredVariables".to_string(),
                     description: "Disallow undeclared variables".to_string(),
                     category: RuleCategory::Correctness,
-                    severity: RuleSeverity::Error,
+                    severity: severities::default_severity("oxc:noUndeclaredVariables"),
                     fix_status: crate::rule_types::FixStatus::Autofix,
+                    fix_safety: crate::rule_types::FixSafety::Safe,
                     ai_enhanced: false,
                     cost: 1,
                     tags: vec!["oxc".to_string(), "correctness".to_string()],
@@ -275,9 +273,11 @@ impl RuleLoader {
                         rule_name: "noUndeclaredVariables".to_string(),
                     },
                     config_schema: None,
+                    applies_to: None,
                 },
             );
 
+            debug_assert_all_severities(&rules);
             Ok(Self { rules, total_rules: 1 })
         }
     }
@@ -298,6 +298,22 @@ impl RuleLoader {
     pub fn get_rule(&self, rule_id: &str) -> Option<&RuleMetadata> {
         self.rules.get(rule_id)
     }
+
+    /// Lowercase category of every rule by rule ID, as `linting.category_weights` keys them
+    pub fn rule_categories(&self) -> HashMap<String, String> {
+        self.rules
+            .iter()
+            .map(|(rule_id, rule)| (rule_id.clone(), rule.category.as_str().to_ascii_lowercase()))
+            .collect()
+    }
+}
+
+/// Every loaded rule must have a default severity in the central table
+fn debug_assert_all_severities(rules: &HashMap<String, RuleMetadata>) {
+    if cfg!(debug_assertions) {
+        let missing = severities::SeverityTable::global().missing(rules.keys().map(String::as_str));
+        debug_assert!(missing.is_empty(), "rules without a default severity: {:?}", missing);
+    }
 }
 
 /// Rulebase metadata structure
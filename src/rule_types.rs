@@ -1,6 +1,9 @@
 //! Rule-related types for the modern Biome + AI analysis system
 
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
 /// Rule severity levels
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -101,6 +104,27 @@ pub enum FixStatus {
     None,
 }
 
+/// How safe a rule's fix is to apply without review (mirrors ESLint fixes vs suggestions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixSafety {
+    /// Behavior-preserving; applied automatically in fix mode
+    #[default]
+    Safe,
+    /// May change behavior; only applied with `--apply-suggestions`
+    Suggestion,
+}
+
+impl FixSafety {
+    /// Whether a fix at this level is applied under the given opt-in
+    pub fn is_applied(self, apply_suggestions: bool) -> bool {
+        match self {
+            FixSafety::Safe => true,
+            FixSafety::Suggestion => apply_suggestions,
+        }
+    }
+}
+
 /// Modern rule metadata for Biome + AI system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleMetadata {
@@ -110,12 +134,74 @@ pub struct RuleMetadata {
     pub category: RuleCategory,
     pub severity: RuleSeverity,
     pub fix_status: FixStatus,
+    #[serde(default)]
+    pub fix_safety: FixSafety,
     pub ai_enhanced: bool,
     pub cost: u32,
     pub tags: Vec<String>,
     pub dependencies: Vec<String>,
     pub implementation: crate::rulebase::RuleImplementation,
     pub config_schema: Option<String>,
+    #[serde(default)]
+    pub applies_to: Option<Vec<String>>, // File globs this rule runs on; None runs on every file
+}
+
+impl RuleMetadata {
+    /// Whether this rule runs on `file_path`; invalid globs match nothing
+    ///
+    /// The globs are compiled into one [`GlobSet`] the first time the rule is checked and
+    /// reused for every later file, so invalid ones are reported once per rule.
+    pub fn applies_to_file(&self, file_path: &str) -> bool {
+        let Some(patterns) = &self.applies_to else {
+            return true;
+        };
+
+        let path = file_path.trim_start_matches("./");
+        let mut matchers = scope_matchers().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match matchers.get(&self.id) {
+            Some((compiled_patterns, globs)) if compiled_patterns == patterns => globs.is_match(path),
+            _ => {
+                let globs = self.compile_scope(patterns);
+                let applies = globs.is_match(path);
+                matchers.insert(self.id.clone(), (patterns.clone(), globs));
+                applies
+            }
+        }
+    }
+
+    fn compile_scope(&self, patterns: &[String]) -> GlobSet {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(error) => moon_warn!("Ignoring invalid applies_to glob '{}' for rule {}: {}", pattern, self.id, error),
+            }
+        }
+        builder.build().unwrap_or_else(|_| GlobSet::empty())
+    }
+}
+
+/// Compiled `applies_to` globs by rule ID, with the patterns they were built from
+static SCOPE_MATCHERS: OnceLock<Mutex<HashMap<String, (Vec<String>, GlobSet)>>> = OnceLock::new();
+
+fn scope_matchers() -> &'static Mutex<HashMap<String, (Vec<String>, GlobSet)>> {
+    SCOPE_MATCHERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Rules with `applies_to` replaced by the configured scope (`linting.rule_scopes`) where one is set
+pub fn with_rule_scopes(rules: &[RuleMetadata], scopes: &HashMap<String, Vec<String>>) -> Vec<RuleMetadata> {
+    rules
+        .iter()
+        .map(|rule| match scopes.get(&rule.id) {
+            Some(patterns) => RuleMetadata {
+                applies_to: Some(patterns.clone()),
+                ..rule.clone()
+            },
+            None => rule.clone(),
+        })
+        .collect()
 }
 
 /// Rule registry statistics
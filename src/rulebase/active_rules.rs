@@ -0,0 +1,368 @@
+//! Effective rule set after configuration
+//!
+//! Whether a rule runs, and at what severity, is decided in layers: the rule's own
+//! defaults, then `linting.preset`, `linting.disabled_categories`,
+//! `linting.disabled_tags` and finally per-rule `linting.rules` overrides. A file's
+//! inline `/* eslint rule: level */` settings can be layered on top with
+//! [`ActiveRuleSet::with_inline_levels`]. `--print-active-rules` prints the resolved set
+//! together with the layer that decided each rule, so configuration can be debugged.
+//!
+//! [`EnvLevels`] packages the common per-path case: `linting.env_globs` relaxes
+//! `no-console` and `no-debugger` in test and dev files while production code keeps
+//! the default severity.
+
+use crate::config::LintingConfig;
+use crate::rule_types::{RuleMetadata, RuleSeverity};
+use crate::rulebase::get_preset;
+use crate::rulebase::rule_aliases::RuleAliases;
+use crate::rulebase::severities::to_diagnostic_severity;
+use crate::types::LintDiagnostic;
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Configuration layer that last decided a rule's state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "layer", content = "name", rename_all = "lowercase")]
+pub enum RuleSource {
+    Default,
+    Preset(String),   // `linting.preset`
+    Category(String), // `linting.disabled_categories`
+    Tag(String),      // `linting.disabled_tags`
+    Override,         // `linting.rules`
+    Inline,           // File-level `/* eslint ... */` configuration
+}
+
+/// A level as written in config: off, or on at a severity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleLevel {
+    Off,
+    On(RuleSeverity),
+}
+
+/// Parse an ESLint-style level: `off`/`warn`/`error`, `0`/`1`/`2`, or `info`/`hint`
+pub fn parse_rule_level(value: &str) -> Option<RuleLevel> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "off" | "0" => Some(RuleLevel::Off),
+        "warn" | "warning" | "1" => Some(RuleLevel::On(RuleSeverity::Warning)),
+        "error" | "2" => Some(RuleLevel::On(RuleSeverity::Error)),
+        "info" => Some(RuleLevel::On(RuleSeverity::Info)),
+        "hint" => Some(RuleLevel::On(RuleSeverity::Hint)),
+        _ => None,
+    }
+}
+
+/// Resolved state of one rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EffectiveRule {
+    pub id: String,
+    pub enabled: bool,
+    pub severity: RuleSeverity,
+    pub source: RuleSource,
+}
+
+/// Every known rule with its resolved state, sorted by rule ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActiveRuleSet {
+    pub rules: Vec<EffectiveRule>,
+}
+
+impl ActiveRuleSet {
+    /// Resolve `rules` against the layered `linting` configuration
+    pub fn resolve(rules: &[RuleMetadata], linting: &LintingConfig) -> Self {
+        // Levels are keyed by canonical ID so setting one ID also sets its aliases
+        let aliases = RuleAliases::from_config(linting);
+        let preset_levels = canonical_levels(linting.preset.as_deref().map(preset_levels).unwrap_or_default(), &aliases);
+        let rule_levels = canonical_levels(configured_levels(linting), &aliases);
+
+        let mut resolved: Vec<EffectiveRule> = rules
+            .iter()
+            .map(|rule| {
+                let canonical_id = aliases.canonicalize(&rule.id);
+                let mut effective = EffectiveRule {
+                    id: rule.id.clone(),
+                    enabled: true,
+                    severity: rule.severity.clone(),
+                    source: RuleSource::Default,
+                };
+
+                if let (Some(preset), Some(level)) = (&linting.preset, preset_levels.get(&canonical_id)) {
+                    effective.set_level(level.clone(), RuleSource::Preset(preset.clone()));
+                }
+                if let Some(category) = linting.disabled_categories.iter().find(|category| rule.category.matches(category)) {
+                    effective.set_level(RuleLevel::Off, RuleSource::Category(category.clone()));
+                }
+                if let Some(tag) = linting.disabled_tags.iter().find(|tag| rule.tags.contains(tag)) {
+                    effective.set_level(RuleLevel::Off, RuleSource::Tag(tag.clone()));
+                }
+                if let Some(level) = rule_levels.get(&canonical_id) {
+                    effective.set_level(level.clone(), RuleSource::Override);
+                }
+
+                effective
+            })
+            .collect();
+
+        resolved.sort_by(|a, b| a.id.cmp(&b.id));
+        Self { rules: resolved }
+    }
+
+    /// Layer a file's inline rule levels over the resolved set
+    pub fn with_inline_levels(mut self, levels: &HashMap<String, RuleLevel>) -> Self {
+        for rule in &mut self.rules {
+            if let Some(level) = levels.get(&rule.id) {
+                rule.set_level(level.clone(), RuleSource::Inline);
+            }
+        }
+        self
+    }
+
+    /// Rules that will run
+    pub fn active(&self) -> impl Iterator<Item = &EffectiveRule> {
+        self.rules.iter().filter(|rule| rule.enabled)
+    }
+
+    /// The active subset of `rules`, each with its resolved severity
+    pub fn filter_rules(&self, rules: &[RuleMetadata]) -> Vec<RuleMetadata> {
+        let active: HashMap<&str, &EffectiveRule> = self.active().map(|rule| (rule.id.as_str(), rule)).collect();
+        rules
+            .iter()
+            .filter_map(|rule| {
+                let effective = active.get(rule.id.as_str())?;
+                let mut rule = rule.clone();
+                rule.severity = effective.severity.clone();
+                Some(rule)
+            })
+            .collect()
+    }
+}
+
+impl EffectiveRule {
+    fn set_level(&mut self, level: RuleLevel, source: RuleSource) {
+        match level {
+            RuleLevel::Off => self.enabled = false,
+            RuleLevel::On(severity) => {
+                self.enabled = true;
+                self.severity = severity;
+            }
+        }
+        self.source = source;
+    }
+}
+
+/// Severity overrides from the preset and `linting.rules`, for the executor's severity table
+pub fn severity_overrides(linting: &LintingConfig) -> Vec<(String, RuleSeverity)> {
+    let aliases = RuleAliases::from_config(linting);
+    let mut levels = canonical_levels(linting.preset.as_deref().map(preset_levels).unwrap_or_default(), &aliases);
+    levels.extend(canonical_levels(configured_levels(linting), &aliases));
+
+    let mut overrides: Vec<(String, RuleSeverity)> = levels
+        .into_iter()
+        .filter_map(|(rule_id, level)| match level {
+            RuleLevel::On(severity) => Some((rule_id, severity)),
+            RuleLevel::Off => None,
+        })
+        .collect();
+    overrides.sort_by(|a, b| a.0.cmp(&b.0));
+    overrides
+}
+
+/// Rules whose level `linting.env_globs` sets per file
+pub const ENV_RELAXED_RULES: &[&str] = &["no-console", "no-debugger"];
+
+/// Per-file levels for [`ENV_RELAXED_RULES`] from `linting.env_globs`
+#[derive(Debug, Clone, Default)]
+pub struct EnvLevels {
+    globs: Vec<(GlobMatcher, RuleLevel)>,
+}
+
+impl EnvLevels {
+    /// Invalid globs and levels are skipped with a warning
+    pub fn from_config(linting: &LintingConfig) -> Self {
+        let mut globs = Vec::new();
+        for (pattern, level) in &linting.env_globs {
+            let Some(level) = parse_rule_level(level) else {
+                moon_warn!("Ignoring linting.env_globs entry '{}': unknown level '{}'", pattern, level);
+                continue;
+            };
+            match Glob::new(pattern) {
+                Ok(glob) => globs.push((glob.compile_matcher(), level)),
+                Err(e) => moon_warn!("Ignoring invalid linting.env_globs entry '{}': {}", pattern, e),
+            }
+        }
+        Self { globs }
+    }
+
+    /// Level of the relaxed rules in `file_path`; the most relaxed matching glob wins
+    pub fn level_for(&self, file_path: &str) -> Option<&RuleLevel> {
+        let path = std::path::Path::new(file_path);
+        let path = path.strip_prefix("./").unwrap_or(path);
+        self.globs
+            .iter()
+            .filter(|(glob, _)| glob.is_match(path))
+            .map(|(_, level)| level)
+            .min_by_key(|level| level_rank(level))
+    }
+
+    /// Re-level the relaxed rules' diagnostics in `file_path`, dropping them where the level is `off`
+    pub fn apply(&self, file_path: &str, diagnostics: Vec<LintDiagnostic>) -> Vec<LintDiagnostic> {
+        let Some(level) = self.level_for(file_path) else {
+            return diagnostics;
+        };
+
+        diagnostics
+            .into_iter()
+            .filter_map(|mut diagnostic| {
+                if !ENV_RELAXED_RULES.contains(&diagnostic.rule_name.as_str()) {
+                    return Some(diagnostic);
+                }
+                match level {
+                    RuleLevel::Off => None,
+                    RuleLevel::On(severity) => {
+                        diagnostic.severity = to_diagnostic_severity(severity);
+                        Some(diagnostic)
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Strictness of a level, `off` lowest
+fn level_rank(level: &RuleLevel) -> u8 {
+    match level {
+        RuleLevel::Off => 0,
+        RuleLevel::On(RuleSeverity::Hint) => 1,
+        RuleLevel::On(RuleSeverity::Info) => 2,
+        RuleLevel::On(RuleSeverity::Warning) | RuleLevel::On(RuleSeverity::Custom(_)) => 3,
+        RuleLevel::On(RuleSeverity::Error) => 4,
+    }
+}
+
+/// Rule levels a named preset sets; unknown presets set none
+/// Valid `linting.rules` levels by the rule ID they were written under
+fn configured_levels(linting: &LintingConfig) -> HashMap<String, RuleLevel> {
+    linting.rules.iter().filter_map(|(rule_id, level)| Some((rule_id.clone(), parse_rule_level(level)?))).collect()
+}
+
+/// `levels` keyed by canonical rule ID; an entry written under the canonical ID wins over its aliases
+fn canonical_levels(levels: HashMap<String, RuleLevel>, aliases: &RuleAliases) -> HashMap<String, RuleLevel> {
+    let mut entries: Vec<(bool, String, String, RuleLevel)> = levels
+        .into_iter()
+        .map(|(rule_id, level)| {
+            let canonical = aliases.canonicalize(&rule_id);
+            (canonical == rule_id, rule_id, canonical, level)
+        })
+        .collect();
+    // Later entries win: aliases in ID order, then the canonical entry
+    entries.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    entries.into_iter().map(|(_, _, canonical, level)| (canonical, level)).collect()
+}
+
+fn preset_levels(name: &str) -> HashMap<String, RuleLevel> {
+    get_preset(name)
+        .and_then(|preset| preset.get("rules"))
+        .and_then(|rules| rules.as_object())
+        .map(|rules| {
+            rules
+                .iter()
+                .filter_map(|(rule_id, level)| {
+                    let level = match level {
+                        serde_json::Value::String(level) => parse_rule_level(level)?,
+                        serde_json::Value::Number(level) => parse_rule_level(&level.to_string())?,
+                        _ => return None,
+                    };
+                    Some((rule_id.clone(), level))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule_types::RuleCategory;
+    use crate::rulebase::testing::static_rule;
+
+    fn rule(id: &str, category: RuleCategory, tags: &[&str]) -> RuleMetadata {
+        RuleMetadata {
+            category,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..static_rule(id)
+        }
+    }
+
+    fn rules() -> Vec<RuleMetadata> {
+        vec![
+            rule("prefer-const", RuleCategory::Style, &[]),
+            rule("no-var", RuleCategory::Style, &["legacy"]),
+            rule("eqeqeq", RuleCategory::Correctness, &["legacy"]),
+        ]
+    }
+
+    fn active_ids(set: &ActiveRuleSet) -> Vec<&str> {
+        set.active().map(|rule| rule.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_disabled_category_removed_and_override_re_adds_rule() {
+        let mut linting = LintingConfig::default();
+        linting.disabled_categories = vec!["style".to_string()];
+
+        let set = ActiveRuleSet::resolve(&rules(), &linting);
+        assert_eq!(active_ids(&set), vec!["eqeqeq"]);
+        let no_var = set.rules.iter().find(|rule| rule.id == "no-var").unwrap();
+        assert_eq!(no_var.source, RuleSource::Category("style".to_string()));
+
+        linting.rules.insert("prefer-const".to_string(), "error".to_string());
+        let set = ActiveRuleSet::resolve(&rules(), &linting);
+        assert_eq!(active_ids(&set), vec!["eqeqeq", "prefer-const"]);
+        let prefer_const = set.active().find(|rule| rule.id == "prefer-const").unwrap();
+        assert_eq!((prefer_const.severity.clone(), prefer_const.source.clone()), (RuleSeverity::Error, RuleSource::Override));
+    }
+
+    #[test]
+    fn test_rule_levels_apply_to_every_alias_of_a_rule() {
+        let rules = vec![
+            rule("no-console", RuleCategory::Correctness, &[]),
+            rule("oxc:noConsole", RuleCategory::Correctness, &[]),
+            rule("eqeqeq", RuleCategory::Correctness, &[]),
+        ];
+        let mut linting = LintingConfig::default();
+        linting.rules.insert("eslint:no-console".to_string(), "off".to_string());
+        linting.rules.insert("oxc:eqeqeq".to_string(), "error".to_string());
+
+        let set = ActiveRuleSet::resolve(&rules, &linting);
+        assert_eq!(active_ids(&set), vec!["eqeqeq"]);
+        assert_eq!(severity_overrides(&linting), vec![("eqeqeq".to_string(), RuleSeverity::Error)]);
+
+        // An entry under the canonical ID wins over one under an alias
+        linting.rules.insert("no-console".to_string(), "warn".to_string());
+        linting.rules.insert("oxc:noConsole".to_string(), "off".to_string());
+        let set = ActiveRuleSet::resolve(&rules, &linting);
+        assert_eq!(active_ids(&set), vec!["eqeqeq", "no-console", "oxc:noConsole"]);
+    }
+
+    #[test]
+    fn test_tags_and_inline_levels_layer_over_config() {
+        let mut linting = LintingConfig::default();
+        linting.disabled_tags = vec!["legacy".to_string()];
+
+        let set = ActiveRuleSet::resolve(&rules(), &linting);
+        assert_eq!(active_ids(&set), vec!["prefer-const"]);
+
+        let inline = HashMap::from([("eqeqeq".to_string(), RuleLevel::On(RuleSeverity::Error)), ("prefer-const".to_string(), RuleLevel::Off)]);
+        let set = set.with_inline_levels(&inline);
+        assert_eq!(active_ids(&set), vec!["eqeqeq"]);
+        assert_eq!(set.filter_rules(&rules())[0].severity, RuleSeverity::Error);
+    }
+
+    #[test]
+    fn test_rule_levels_parse_eslint_forms() {
+        assert_eq!(parse_rule_level("off"), Some(RuleLevel::Off));
+        assert_eq!(parse_rule_level("2"), Some(RuleLevel::On(RuleSeverity::Error)));
+        assert_eq!(parse_rule_level("Warn"), Some(RuleLevel::On(RuleSeverity::Warning)));
+        assert_eq!(parse_rule_level("loud"), None);
+    }
+}
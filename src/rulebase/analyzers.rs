@@ -0,0 +1,121 @@
+//! Custom analyzers compiled into a build
+//!
+//! JSON rules can only select built-in OXC and AI behavioral checks. A fork that needs a
+//! check of its own implements [`Analyzer`] and calls [`register_analyzer`] at startup;
+//! every [`RuleExecutor`](super::RuleExecutor) created afterwards runs it on each parsed
+//! file after the configured rules, so its diagnostics go through suppression, severity
+//! and reporting like any other rule's.
+
+use super::RuleExecutionContext;
+use crate::types::LintDiagnostic;
+use oxc_ast::ast::Program;
+use oxc_semantic::Semantic;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A check over one parsed file
+pub trait Analyzer: Send + Sync {
+    /// Rule name the analyzer's diagnostics are reported under
+    fn id(&self) -> &str;
+
+    /// Diagnostics for the file `source` describes
+    fn analyze(&self, program: &Program<'_>, semantic: &Semantic<'_>, source: &RuleExecutionContext<'_>) -> Vec<LintDiagnostic>;
+}
+
+/// Analyzers run by a rule executor, in registration order
+#[derive(Clone, Default)]
+pub struct AnalyzerRegistry {
+    analyzers: Vec<Arc<dyn Analyzer>>,
+}
+
+impl fmt::Debug for AnalyzerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.ids()).finish()
+    }
+}
+
+/// Analyzers added with [`register_analyzer`]
+static REGISTERED: OnceLock<Mutex<AnalyzerRegistry>> = OnceLock::new();
+
+/// Add `analyzer` to every rule executor created from now on
+pub fn register_analyzer(analyzer: impl Analyzer + 'static) {
+    let registry = REGISTERED.get_or_init(Default::default);
+    registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).register(analyzer);
+}
+
+impl AnalyzerRegistry {
+    /// The analyzers added with [`register_analyzer`] so far
+    pub fn global() -> Self {
+        REGISTERED
+            .get()
+            .map(|registry| registry.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone())
+            .unwrap_or_default()
+    }
+
+    pub fn register(&mut self, analyzer: impl Analyzer + 'static) {
+        self.analyzers.push(Arc::new(analyzer));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.analyzers.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.analyzers.iter().map(|analyzer| analyzer.id())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Analyzer> {
+        self.analyzers.iter().map(|analyzer| analyzer.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_lint::lint_buffer_with_rules;
+    use crate::config::MoonShineConfig;
+    use crate::rulebase::static_rules::span_diagnostic;
+    use crate::types::DiagnosticSeverity;
+    use oxc_ast::AstKind;
+    use oxc_span::GetSpan;
+
+    /// Flags identifiers named `probeOnlyIdentifier`, so other tests never see it fire
+    struct ProbeAnalyzer;
+
+    impl Analyzer for ProbeAnalyzer {
+        fn id(&self) -> &str {
+            "custom-probe"
+        }
+
+        fn analyze(&self, _program: &Program<'_>, semantic: &Semantic<'_>, source: &RuleExecutionContext<'_>) -> Vec<LintDiagnostic> {
+            semantic
+                .nodes()
+                .iter()
+                .filter_map(|node| match node.kind() {
+                    AstKind::BindingIdentifier(identifier) if identifier.name.as_str() == "probeOnlyIdentifier" => Some(span_diagnostic(
+                        self.id(),
+                        "Probe identifier found",
+                        source.code,
+                        source.file_path,
+                        node.kind().span(),
+                        DiagnosticSeverity::Warning,
+                        None,
+                    )),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_registered_analyzer_diagnostics_appear_in_lint_output() {
+        register_analyzer(ProbeAnalyzer);
+        assert!(AnalyzerRegistry::global().ids().any(|id| id == "custom-probe"));
+
+        let content = "const probeOnlyIdentifier = 1;\nconst other = probeOnlyIdentifier;\n";
+        let result = lint_buffer_with_rules(content, "src/probe.ts", &MoonShineConfig::default(), &[]).unwrap();
+
+        let reported: Vec<(&str, u32, u32)> = result.diagnostics.iter().map(|d| (d.rule_name.as_str(), d.line, d.column)).collect();
+        assert_eq!(reported, vec![("custom-probe", 1, 7)]);
+    }
+}
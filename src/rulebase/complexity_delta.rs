@@ -0,0 +1,120 @@
+//! # Complexity Change of a Fix
+//!
+//! Measures file complexity on the original and the fixed source so a run can show
+//! whether its fixes made the code simpler. The measures come from
+//! [`function_complexities`], the same ones the `complexity` rule checks: cyclomatic and
+//! cognitive complexity summed over functions, the largest Halstead difficulty, and a
+//! maintainability index derived from them. With `complexity.max_fix_complexity_increase`
+//! set, fixes that raise cyclomatic complexity by more than that percentage are rejected.
+//!
+//! @category rulebase
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use super::static_rules::function_complexities;
+use crate::buffer_lint::parse_guarded;
+use crate::types::{AstAutoFixResult, ComplexityMetrics};
+use oxc_allocator::Allocator;
+use oxc_semantic::SemanticBuilder;
+use oxc_span::SourceType;
+use serde::{Deserialize, Serialize};
+
+/// How much a fix changed the complexity of a file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ComplexityDelta {
+    pub complexity_improvement: f64,      // Percentage drop in cyclomatic complexity; negative when it rose
+    pub maintainability_improvement: f64, // Maintainability index points gained
+}
+
+impl ComplexityDelta {
+    /// Whether the fix raised cyclomatic complexity by more than `max_increase` percent
+    pub fn exceeds(&self, max_increase: f64) -> bool {
+        -self.complexity_improvement > max_increase
+    }
+
+    /// Record the delta on a fix result
+    pub fn apply_to(&self, result: &mut AstAutoFixResult) {
+        result.complexity_improvement = self.complexity_improvement;
+        result.maintainability_improvement = self.maintainability_improvement;
+    }
+}
+
+/// File-level complexity of `source`, or `None` when it does not parse
+pub fn measure_complexity(source: &str, source_type: SourceType) -> Option<ComplexityMetrics> {
+    let allocator = Allocator::default();
+    let parsed = parse_guarded(&allocator, source, source_type).ok()?;
+    if !parsed.errors.is_empty() {
+        return None;
+    }
+    let semantic = SemanticBuilder::new().build(&parsed.program).semantic;
+    let functions = function_complexities(&semantic, source);
+
+    let cyclomatic_complexity = functions.iter().map(|function| function.cyclomatic).sum::<u32>().max(1);
+    let halstead_difficulty = functions.iter().map(|function| function.halstead_difficulty).fold(0.0, f64::max);
+    let lines_of_code = source.lines().count().max(1) as u32;
+
+    Some(ComplexityMetrics {
+        cyclomatic_complexity,
+        cognitive_complexity: functions.iter().map(|function| function.cognitive).sum(),
+        halstead_difficulty,
+        lines_of_code,
+        maintainability_index: maintainability_index(cyclomatic_complexity, halstead_difficulty, lines_of_code),
+        ..ComplexityMetrics::default()
+    })
+}
+
+/// Change from `before` to `after`
+pub fn complexity_delta(before: &ComplexityMetrics, after: &ComplexityMetrics) -> ComplexityDelta {
+    let before_cyclomatic = f64::from(before.cyclomatic_complexity.max(1));
+    ComplexityDelta {
+        complexity_improvement: (before_cyclomatic - f64::from(after.cyclomatic_complexity)) / before_cyclomatic * 100.0,
+        maintainability_improvement: after.maintainability_index - before.maintainability_index,
+    }
+}
+
+/// Complexity change from `original` to `fixed`, or `None` when either does not parse
+pub fn measure_fix(original: &str, fixed: &str, source_type: SourceType) -> Option<ComplexityDelta> {
+    Some(complexity_delta(&measure_complexity(original, source_type)?, &measure_complexity(fixed, source_type)?))
+}
+
+/// Maintainability index on the 0-100 scale.
+///
+/// The classic formula takes Halstead volume; difficulty × lines stands in for it, since
+/// volume is not measured per function.
+fn maintainability_index(cyclomatic: u32, halstead_difficulty: f64, lines_of_code: u32) -> f64 {
+    let lines = f64::from(lines_of_code);
+    let raw = 171.0 - 5.2 * (1.0 + halstead_difficulty * lines).ln() - 0.23 * f64::from(cyclomatic) - 16.2 * lines.ln();
+    (raw * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NESTED: &str = "function sign(n: number) {\n  if (n > 0) {\n    return 1;\n  } else {\n    if (n < 0) {\n      return -1;\n    }\n  }\n  return 0;\n}\n";
+
+    #[test]
+    fn test_fix_reducing_cyclomatic_complexity_is_an_improvement() {
+        let fixed = "function sign(n: number) {\n  return Math.sign(n);\n}\n";
+
+        let delta = measure_fix(NESTED, fixed, SourceType::ts()).unwrap();
+
+        // Cyclomatic complexity drops from 3 to 1
+        assert!((delta.complexity_improvement - 200.0 / 3.0).abs() < 1e-9);
+        assert!(delta.maintainability_improvement > 0.0);
+        assert!(!delta.exceeds(0.0));
+
+        let reverted = measure_fix(fixed, NESTED, SourceType::ts()).unwrap();
+        assert!(reverted.exceeds(100.0), "going from 1 to 3 raises complexity by 200%");
+    }
+
+    #[test]
+    fn test_no_op_fix_has_zero_delta() {
+        let delta = measure_fix(NESTED, NESTED, SourceType::ts()).unwrap();
+
+        assert_eq!(delta, ComplexityDelta::default());
+        assert_eq!(measure_fix(NESTED, "function (", SourceType::ts()), None);
+    }
+}
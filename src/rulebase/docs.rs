@@ -0,0 +1,55 @@
+//! Documented rationale and good/bad examples per rule
+//!
+//! Each [`RuleDoc`] explains why the rule exists and pairs code the rule must flag with
+//! code it must accept. The rationale backs `--explain` output; the examples double as
+//! user-facing documentation and as test fixtures:
+//! [`validate_rule_docs`](super::testing::validate_rule_docs) runs every rule against its
+//! own examples so the docs cannot drift from the implementation.
+
+/// Rationale and good/bad examples for one rule, keyed by canonical rule ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleDoc {
+    pub rule_id: &'static str,
+    /// Why the reported pattern is a problem
+    pub rationale: &'static str,
+    /// How to fix a reported problem
+    pub fix: &'static str,
+    /// Snippets the rule must not report
+    pub good: &'static [&'static str],
+    /// Snippets the rule must report at least once
+    pub bad: &'static [&'static str],
+}
+
+/// Documented examples of rules implemented in Rust
+pub const RULE_DOCS: &[RuleDoc] = &[
+    RuleDoc {
+        rule_id: "prefer-const",
+        rationale: "A `let` binding that is never reassigned hides the fact that the value is constant, so readers must scan the whole scope to rule out later writes.",
+        fix: "Declare the binding with `const`.",
+        good: &[
+            "const greeting = 'hi';\nconsole.log(greeting);\n",
+            "let count = 0;\ncount += 1;\n",
+            "for (let i = 0; i < 3; i++) {\n  console.log(i);\n}\n",
+        ],
+        bad: &["let greeting = 'hi';\nconsole.log(greeting);\n", "let { a, b } = options;\nconsole.log(a, b);\n"],
+    },
+    RuleDoc {
+        rule_id: "eqeqeq",
+        rationale: "`==` and `!=` coerce operands of different types before comparing, so `0 == ''` and `null == undefined` are both true and comparisons silently match unintended values.",
+        fix: "Use `===` or `!==`, converting the operands explicitly if a cross-type comparison is intended.",
+        good: &["if (a === b) {}\n", "if (value !== undefined) {}\n"],
+        bad: &["if (a == b) {}\n", "if (value != null) {}\n"],
+    },
+    RuleDoc {
+        rule_id: "eqeqeq:smart",
+        rationale: "Loose equality coerces operand types; the smart variant still allows the idioms where coercion is well understood, such as `== null` and `typeof` comparisons.",
+        fix: "Use `===` or `!==` outside the allowed `null` and `typeof` comparisons.",
+        good: &["if (value == null) {}\n", "if (typeof value == 'string') {}\n"],
+        bad: &["if (a == b) {}\n"],
+    },
+];
+
+/// Documentation of `rule_id`, if it has any
+pub fn rule_doc(rule_id: &str) -> Option<&'static RuleDoc> {
+    RULE_DOCS.iter().find(|doc| doc.rule_id == rule_id)
+}
@@ -1,14 +1,44 @@
-use crate::oxc_adapter::ai_behavioral::AnalysisContext;
+use crate::buffer_lint::{parse_error_diagnostics, parse_guarded, parser_failure_diagnostic};
+use crate::declaration_files::{applies_to_declarations, is_declaration_file};
+use crate::config::{AsyncErrorGranularity, ComplexityConfig, ImportOrderConfig, LineEndingPolicy, UnsafeAssertionsConfig};
+use crate::file_filter::AiExcludes;
+use crate::generated_files::GeneratedFiles;
+use crate::oxc_adapter::ai_behavioral::{AiAnalysisClient, AnalysisContext};
 use crate::oxc_adapter::{AiBehavioralAnalyzer, MultiEngineAnalyzer, MultiEngineConfig, OxcAdapter};
-use crate::rule_types::RuleMetadata;
-use crate::rulebase::RuleImplementation;
-use crate::types::{DiagnosticSeverity, LintDiagnostic};
+use crate::rule_types::{RuleMetadata, RuleSeverity};
+use crate::rulebase::active_rules::EnvLevels;
+use crate::rulebase::analyzers::AnalyzerRegistry;
+use crate::rulebase::profile::{RuleProfile, RuleProfiler};
+use crate::rulebase::sampling::UnitRng;
+use crate::rulebase::severities::SeverityTable;
+use crate::rulebase::{import_order, static_rules, RuleImplementation};
+use crate::types::LintDiagnostic;
 use futures::executor::block_on;
+use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
+use oxc_semantic::{Semantic, SemanticBuilder};
 use oxc_span::SourceType;
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, OnceCell};
 use std::time::{Duration, Instant};
 
+/// Rule names `RuleExecutor` implements natively; the `OxcStatic` names that produce diagnostics
+pub const STATIC_RULE_NAMES: &[&str] = &[
+    "no-unused-vars",
+    "no-console",
+    "no-debugger",
+    "prefer-const",
+    "no-var",
+    "eqeqeq",
+    "eqeqeq:smart",
+    "complexity",
+    static_rules::TSDOC_REQUIRED_TAGS_RULE,
+    static_rules::LINE_ENDINGS_RULE,
+    static_rules::ASYNC_ERROR_HANDLING_RULE,
+    static_rules::UNSAFE_ASSERTIONS_RULE,
+    import_order::IMPORT_ORDER_RULE,
+];
+
 /// Modern execution context for OXC + AI analysis
 #[derive(Clone)]
 pub struct RuleExecutionContext<'a> {
@@ -26,12 +56,33 @@ pub struct RuleExecutionOutcome {
     pub executed_rules: usize,
     pub skipped_rules: usize,
     pub elapsed: Duration,
+    pub profile: Option<RuleProfile>, // Per-rule timings when profiling is enabled
+    pub notices: Vec<String>,         // Informational notes, e.g. AI steps skipped because no provider is available
+    pub deferred_rules: Vec<String>,  // AI rules not run because they exceeded the cost budget
+    pub semantic_builds: u32,         // Semantic models built for the file; static rules share one
 }
 
 /// Modern rule executor using OXC + AI multi-engine analysis
 pub struct RuleExecutor {
     multi_engine_analyzer: MultiEngineAnalyzer,
     oxc_adapter: OxcAdapter,
+    profiling: bool,
+    ai_available: Option<bool>,        // Overrides `provider_router::ai_available()` when set
+    ai_disabled: bool,                 // AI intentionally off (`"none"` provider): skip AI steps without notices
+    cost_budget: Option<u32>,          // Max cumulative cost of AI rules per file
+    severities: Option<SeverityTable>, // Overrides the shared default severity table when set
+    complexity_limits: ComplexityConfig,
+    tsdoc_required_tags: Vec<String>,
+    line_endings: LineEndingPolicy,
+    async_error_handling: AsyncErrorGranularity,
+    import_order: ImportOrderConfig,
+    unsafe_assertions: UnsafeAssertionsConfig,
+    sampling_seed: u64, // Run-level seed that per-unit sampling RNGs derive from
+    analyzers: AnalyzerRegistry, // Custom analyzers run after the rules on parsed files
+    generated_files: GeneratedFiles, // Files whose AI steps are always skipped
+    ai_excludes: AiExcludes,         // Files limited to static rules by `ai.ai_exclude_patterns`
+    env_levels: EnvLevels,           // Per-path levels of `no-console`/`no-debugger`
+    ai_analyzer: AiBehavioralAnalyzer, // Runs the AI step of AI-enhanced rules
 }
 
 impl RuleExecutor {
@@ -40,9 +91,155 @@ impl RuleExecutor {
         Self {
             multi_engine_analyzer: MultiEngineAnalyzer::with_config(config),
             oxc_adapter: OxcAdapter::new(),
+            profiling: false,
+            ai_available: None,
+            ai_disabled: false,
+            cost_budget: None,
+            severities: None,
+            complexity_limits: ComplexityConfig::default(),
+            tsdoc_required_tags: crate::config::defaults::lint_tsdoc_required_tags(),
+            line_endings: LineEndingPolicy::default(),
+            async_error_handling: AsyncErrorGranularity::default(),
+            import_order: ImportOrderConfig::default(),
+            unsafe_assertions: UnsafeAssertionsConfig::default(),
+            sampling_seed: 0,
+            analyzers: AnalyzerRegistry::global(),
+            generated_files: GeneratedFiles::default(),
+            ai_excludes: AiExcludes::default(),
+            env_levels: EnvLevels::default(),
+            ai_analyzer: AiBehavioralAnalyzer::new(),
         }
     }
 
+    /// Record per-rule wall time in `RuleExecutionOutcome::profile` (`--profile`)
+    pub fn with_profiling(mut self, profiling: bool) -> Self {
+        self.profiling = profiling;
+        self
+    }
+
+    /// Override AI provider availability instead of querying the provider router
+    pub fn with_ai_availability(mut self, available: bool) -> Self {
+        self.ai_available = Some(available);
+        self
+    }
+
+    /// Treat AI as intentionally disabled (the offline `"none"` provider): AI steps are skipped
+    /// like when no provider is available, but no "AI unavailable" notice is reported
+    pub fn with_ai_disabled(mut self, disabled: bool) -> Self {
+        self.ai_disabled = disabled;
+        self
+    }
+
+    /// Bound the cumulative cost of AI-enhanced rules per file (`linting.max_total_rule_cost`)
+    pub fn with_cost_budget(mut self, budget: Option<u32>) -> Self {
+        self.cost_budget = budget;
+        self
+    }
+
+    /// Report static rule results with severities from `table` instead of the shared defaults
+    pub fn with_severity_table(mut self, table: SeverityTable) -> Self {
+        self.severities = Some(table);
+        self
+    }
+
+    /// Limits for the `complexity` static rule (`complexity` config section)
+    pub fn with_complexity_limits(mut self, limits: ComplexityConfig) -> Self {
+        self.complexity_limits = limits;
+        self
+    }
+
+    /// Tags the `tsdoc-required-tags` static rule requires (`linting.tsdoc_required_tags`)
+    pub fn with_tsdoc_required_tags(mut self, tags: Vec<String>) -> Self {
+        self.tsdoc_required_tags = tags;
+        self
+    }
+
+    /// Line endings the `line-endings` static rule normalizes to (`linting.line_endings`)
+    pub fn with_line_endings(mut self, policy: LineEndingPolicy) -> Self {
+        self.line_endings = policy;
+        self
+    }
+
+    /// Whether the `async-error-handling` static rule reports per function or per await (`linting.async_error_handling`)
+    pub fn with_async_error_handling(mut self, granularity: AsyncErrorGranularity) -> Self {
+        self.async_error_handling = granularity;
+        self
+    }
+
+    /// Group order and sorting of the `import-order` static rule (`linting.import_order`)
+    pub fn with_import_order(mut self, config: ImportOrderConfig) -> Self {
+        self.import_order = config;
+        self
+    }
+
+    /// Files the `unsafe-assertions` static rule skips (`linting.unsafe_assertions`)
+    pub fn with_unsafe_assertions(mut self, config: UnsafeAssertionsConfig) -> Self {
+        self.unsafe_assertions = config;
+        self
+    }
+
+    /// Run-level seed for sampling rules (`linting.sampling_seed`)
+    pub fn with_sampling_seed(mut self, seed: u64) -> Self {
+        self.sampling_seed = seed;
+        self
+    }
+
+    /// Run `analyzers` instead of the ones added with `register_analyzer`
+    pub fn with_analyzers(mut self, analyzers: AnalyzerRegistry) -> Self {
+        self.analyzers = analyzers;
+        self
+    }
+
+    /// Detect generated files with `generated_files`; AI steps never run on them
+    pub fn with_generated_files(mut self, generated_files: GeneratedFiles) -> Self {
+        self.generated_files = generated_files;
+        self
+    }
+
+    /// Run only static rules on files matching `ai_excludes` (`ai.ai_exclude_patterns`)
+    pub fn with_ai_excludes(mut self, ai_excludes: AiExcludes) -> Self {
+        self.ai_excludes = ai_excludes;
+        self
+    }
+
+    /// Re-level `no-console` and `no-debugger` per file path (`linting.env_globs`)
+    pub fn with_env_levels(mut self, env_levels: EnvLevels) -> Self {
+        self.env_levels = env_levels;
+        self
+    }
+
+    /// Send the AI step of AI-enhanced rules to `client`
+    pub fn with_ai_client(mut self, client: Box<dyn AiAnalysisClient>) -> Self {
+        self.ai_analyzer = AiBehavioralAnalyzer::with_ai_client(client);
+        self
+    }
+
+    /// RNG for a rule that samples within `file_path`; independent of execution order
+    pub fn unit_rng(&self, file_path: &str, rule_id: &str) -> UnitRng {
+        UnitRng::for_unit(self.sampling_seed, file_path, rule_id)
+    }
+
+    /// Set the default severity of `rule` on the diagnostics its static rule `rule_name` produced
+    fn apply_default_severity(&self, rule: &RuleMetadata, rule_name: &str, diagnostics: &mut [LintDiagnostic]) {
+        let table = self.severities.as_ref().unwrap_or_else(|| SeverityTable::global());
+        if table.severity_of(&rule.id).is_none() {
+            return;
+        }
+
+        let severity = table.diagnostic_severity(&rule.id);
+        for diagnostic in diagnostics.iter_mut().filter(|diagnostic| diagnostic.rule_name == rule_name) {
+            diagnostic.severity = severity.clone();
+        }
+    }
+
+    fn is_ai_disabled(&self) -> bool {
+        self.ai_disabled || crate::provider_router::ai_disabled()
+    }
+
+    fn is_ai_available(&self) -> bool {
+        !self.is_ai_disabled() && self.ai_available.unwrap_or_else(crate::provider_router::ai_available)
+    }
+
     /// Execute rules using modern OXC + AI analysis
     pub async fn evaluate_async<'a>(&mut self, _rules: &[RuleMetadata], ctx: &RuleExecutionContext<'a>) -> RuleExecutionOutcome {
         let start = Instant::now();
@@ -57,6 +254,10 @@ impl RuleExecutor {
                 executed_rules: result.stats.oxc_rules_executed + result.stats.ai_patterns_checked,
                 skipped_rules: 0,
                 elapsed: start.elapsed(),
+                profile: None,
+                notices: Vec::new(),
+                deferred_rules: Vec::new(),
+                semantic_builds: 0,
             },
             Err(e) => {
                 log::error!("Analysis failed for {}: {}", ctx.file_path, e);
@@ -66,6 +267,10 @@ impl RuleExecutor {
                     executed_rules: 0,
                     skipped_rules: 0,
                     elapsed: start.elapsed(),
+                    profile: None,
+                    notices: Vec::new(),
+                    deferred_rules: Vec::new(),
+                    semantic_builds: 0,
                 }
             }
         }
@@ -73,185 +278,282 @@ impl RuleExecutor {
 
     /// Synchronous fallback for compatibility
     pub fn evaluate<'a>(&self, rules: &[RuleMetadata], ctx: &RuleExecutionContext<'a>) -> RuleExecutionOutcome {
+        self.evaluate_with_semantic(rules, ctx, None)
+    }
+
+    /// [`Self::evaluate`] sharing `semantic`, the caller's model of `ctx.program`, instead of building one
+    pub fn evaluate_with_semantic<'a>(
+        &self,
+        rules: &[RuleMetadata],
+        ctx: &RuleExecutionContext<'a>,
+        semantic: Option<&Semantic<'a>>,
+    ) -> RuleExecutionOutcome {
         let start = Instant::now();
 
         // Run OXC static analysis
         let mut diagnostics = Vec::new();
         let mut executed_rules = 0;
+        let mut profiler = self.profiling.then(RuleProfiler::new);
+        let generated = self.generated_files.is_generated(ctx.file_path, ctx.code);
+        let ai_excluded = self.ai_excludes.is_excluded(ctx.file_path);
+        let ai_available = !generated && !ai_excluded && self.is_ai_available();
+        let mut ai_skipped = Vec::new();
+
+        // Parse once and build the semantic model at most once; every static rule and
+        // analyzer shares them, so adding rules never multiplies parse or semantic work
+        let allocator = Allocator::default();
+        let parsed;
+        let mut parse_errors = None;
+        let program = match ctx.program {
+            Some(program) => Some(program),
+            None => match parse_guarded(&allocator, ctx.code, ctx.source_type) {
+                Ok(ret) => {
+                    parsed = ret;
+                    if parsed.errors.is_empty() {
+                        Some(&parsed.program)
+                    } else {
+                        parse_errors = Some(parse_error_diagnostics(&parsed.errors, ctx.code, ctx.file_path));
+                        None
+                    }
+                }
+                Err(message) => {
+                    parse_errors = Some(vec![parser_failure_diagnostic(ctx.file_path, message)]);
+                    None
+                }
+            },
+        };
+        let semantic_builds = Cell::new(0);
+        let shared_semantic = semantic.filter(|_| ctx.program.is_some());
+        let semantic = OnceCell::new();
+        let parsed_file = || {
+            program.map(|program| {
+                let semantic = shared_semantic.unwrap_or_else(|| {
+                    semantic.get_or_init(|| {
+                        semantic_builds.set(semantic_builds.get() + 1);
+                        SemanticBuilder::new().build(program).semantic
+                    })
+                });
+                (program, semantic)
+            })
+        };
+
+        // Declaration files hold no runtime code; only type and documentation rules apply
+        let declaration_file = is_declaration_file(ctx.file_path, program);
+        let applicable: Vec<&RuleMetadata> = rules
+            .iter()
+            .filter(|rule| rule.applies_to_file(ctx.file_path) && (!declaration_file || applies_to_declarations(rule)))
+            .collect();
+        // Only AI steps that would actually run spend the budget
+        let deferred = if ai_available {
+            budget_deferred_rules(&applicable, self.cost_budget)
+        } else {
+            Vec::new()
+        };
 
-        for rule in rules {
-            match &rule.implementation {
-                RuleImplementation::OxcStatic { rule_name } => {
-                    // Execute OXC static rule
-                    if let Ok(rule_diagnostics) = self.execute_oxc_rule(rule_name, ctx.code, ctx.file_path) {
+        for rule in applicable {
+            if deferred.contains(&rule.id.as_str()) {
+                continue;
+            }
+
+            let rule_start = Instant::now();
+            let executed = match &rule.implementation {
+                RuleImplementation::OxcStatic { rule_name } => match parsed_file() {
+                    Some((program, semantic)) => {
+                        let mut rule_diagnostics = self.execute_oxc_rule(rule_name, program, semantic, ctx.code, ctx.file_path);
+                        self.apply_default_severity(rule, rule_name, &mut rule_diagnostics);
                         diagnostics.extend(rule_diagnostics);
-                        executed_rules += 1;
+                        true
+                    }
+                    None => {
+                        diagnostics.extend(parse_errors.take().unwrap_or_default());
+                        false
+                    }
+                },
+                RuleImplementation::AiBehavioral { .. } if !ai_available => {
+                    ai_skipped.push(rule.id.clone());
+                    false
+                }
+                RuleImplementation::Hybrid { oxc_rule, .. } if !ai_available => {
+                    // Static half only; the AI step needs a provider
+                    ai_skipped.push(rule.id.clone());
+                    match parsed_file() {
+                        Some((program, semantic)) => {
+                            let mut rule_diagnostics = self.execute_oxc_rule(oxc_rule, program, semantic, ctx.code, ctx.file_path);
+                            self.apply_default_severity(rule, oxc_rule, &mut rule_diagnostics);
+                            diagnostics.extend(rule_diagnostics);
+                            true
+                        }
+                        None => {
+                            diagnostics.extend(parse_errors.take().unwrap_or_default());
+                            false
+                        }
                     }
                 }
-                RuleImplementation::AiBehavioral { pattern_type } => {
-                    // Execute AI behavioral rule
-                    match self.execute_ai_rule(pattern_type, ctx.code, ctx.file_path, ctx.source_type) {
+                RuleImplementation::AiBehavioral { pattern_type } => match parsed_file() {
+                    // Execute AI behavioral rule on the shared program
+                    Some((program, _)) => match self.execute_ai_rule(program, ctx) {
                         Ok(ai_diagnostics) => {
                             diagnostics.extend(ai_diagnostics);
-                            executed_rules += 1;
+                            true
+                        }
+                        Err(error) if all_providers_failed(error.as_ref()) => {
+                            ai_skipped.push(rule.id.clone());
+                            false
                         }
                         Err(error) => {
                             log::warn!("AI behavioral rule '{}' failed for {}: {}", pattern_type, ctx.file_path, error);
+                            false
                         }
+                    },
+                    None => {
+                        diagnostics.extend(parse_errors.take().unwrap_or_default());
+                        false
                     }
-                }
-                RuleImplementation::Hybrid { oxc_rule, ai_pattern } => {
-                    // Execute hybrid rule
-                    match self.execute_hybrid_rule(oxc_rule, ai_pattern, ctx.code, ctx.file_path, ctx.source_type) {
-                        Ok(hybrid_diagnostics) => {
-                            diagnostics.extend(hybrid_diagnostics);
-                            executed_rules += 1;
-                        }
-                        Err(error) => {
-                            log::warn!("Hybrid rule '{}' failed for {}: {}", ai_pattern, ctx.file_path, error);
+                },
+                RuleImplementation::Hybrid { oxc_rule, ai_pattern } => match parsed_file() {
+                    // Execute hybrid rule: OXC analysis first, then AI analysis
+                    Some((program, semantic)) => {
+                        diagnostics.extend(self.execute_oxc_rule(oxc_rule, program, semantic, ctx.code, ctx.file_path));
+                        match self.execute_ai_rule(program, ctx) {
+                            Ok(ai_diagnostics) => diagnostics.extend(ai_diagnostics),
+                            // Static half only, as when no provider is available
+                            Err(error) if all_providers_failed(error.as_ref()) => ai_skipped.push(rule.id.clone()),
+                            Err(error) => log::warn!("Hybrid rule '{}' AI step failed for {}: {}", ai_pattern, ctx.file_path, error),
                         }
+                        true
                     }
-                }
+                    None => {
+                        diagnostics.extend(parse_errors.take().unwrap_or_default());
+                        false
+                    }
+                },
                 _ => {
                     // Skip unsupported rule types
                     continue;
                 }
+            };
+
+            if executed {
+                executed_rules += 1;
+                if let Some(profiler) = profiler.as_mut() {
+                    profiler.record(&rule.id, ctx.file_path, rule_start.elapsed());
+                }
             }
         }
 
+        if let Some((program, semantic)) = parsed_file().filter(|_| !self.analyzers.is_empty()) {
+            for analyzer in self.analyzers.iter() {
+                let analyzer_start = Instant::now();
+                diagnostics.extend(analyzer.analyze(program, semantic, ctx));
+                if let Some(profiler) = profiler.as_mut() {
+                    profiler.record(analyzer.id(), ctx.file_path, analyzer_start.elapsed());
+                }
+            }
+        }
+
+        let mut notices = Vec::new();
+        if !deferred.is_empty() {
+            let notice = format!(
+                "Rule cost budget of {} reached for {}: deferred {} AI rule(s): {}",
+                self.cost_budget.unwrap_or_default(),
+                ctx.file_path,
+                deferred.len(),
+                deferred.join(", ")
+            );
+            moon_info!("{}", notice);
+            notices.push(notice);
+        }
+        if !ai_skipped.is_empty() && generated {
+            moon_debug!("Generated file: ran static analysis only for {}: {}", ctx.file_path, ai_skipped.join(", "));
+        } else if !ai_skipped.is_empty() && ai_excluded {
+            moon_debug!("ai.ai_exclude_patterns: ran static analysis only for {}: {}", ctx.file_path, ai_skipped.join(", "));
+        } else if !ai_skipped.is_empty() && self.is_ai_disabled() {
+            moon_debug!("AI disabled: ran static analysis only for {}: {}", ctx.file_path, ai_skipped.join(", "));
+        } else if !ai_skipped.is_empty() {
+            let notice = format!(
+                "AI unavailable: ran static analysis only for {} ({} AI step(s) skipped)",
+                ctx.file_path,
+                ai_skipped.len()
+            );
+            moon_info!("{}: {}", notice, ai_skipped.join(", "));
+            notices.push(notice);
+        }
+
         RuleExecutionOutcome {
-            diagnostics,
+            diagnostics: self.env_levels.apply(ctx.file_path, diagnostics),
             evaluated_rules: rules.len(),
             executed_rules,
             skipped_rules: rules.len() - executed_rules,
             elapsed: start.elapsed(),
+            profile: profiler.map(|profiler| profiler.profile()),
+            notices,
+            deferred_rules: deferred.iter().map(|id| id.to_string()).collect(),
+            semantic_builds: semantic_builds.get(),
         }
     }
 
-    /// Execute a single OXC static rule
-    fn execute_oxc_rule(&self, rule_name: &str, code: &str, file_path: &str) -> Result<Vec<LintDiagnostic>, Box<dyn std::error::Error>> {
-        // Parse the code using OXC
-        let allocator = oxc_allocator::Allocator::default();
-        let source_type = SourceType::from_path(file_path)?;
-        let ret = oxc_parser::Parser::new(&allocator, code, source_type).parse();
-
-        if !ret.errors.is_empty() {
-            // Convert parse errors to diagnostics
-            return Ok(ret
-                .errors
-                .iter()
-                .map(|err| {
-                    let (line, column, end_line, end_column) = if let Some(labels) = &err.labels {
-                        if let Some(label) = labels.first() {
-                            // Extract line/column info from the span
-                            (1, 1, 1, 1) // Default values for now
-                        } else {
-                            (1, 1, 1, 1)
-                        }
-                    } else {
-                        (1, 1, 1, 1)
-                    };
-
-                    LintDiagnostic {
-                        rule_name: "parse-error".to_string(),
-                        message: err.message.to_string(),
-                        file_path: file_path.to_string(),
-                        line,
-                        column,
-                        end_line,
-                        end_column,
-                        severity: DiagnosticSeverity::Error,
-                        fix_available: false,
-                        suggested_fix: None,
-                    }
-                })
-                .collect());
-        }
-
-        // Apply specific OXC rule
+    /// Execute a single OXC static rule against the file's shared program and semantic model
+    fn execute_oxc_rule(&self, rule_name: &str, program: &Program, semantic: &Semantic, code: &str, file_path: &str) -> Vec<LintDiagnostic> {
         let mut diagnostics = Vec::new();
         match rule_name {
             "no-unused-vars" => {
-                diagnostics.extend(self.check_unused_variables(&ret.program));
+                diagnostics.extend(self.check_unused_variables(program));
             }
             "no-console" => {
-                diagnostics.extend(self.check_console_usage(&ret.program));
+                diagnostics.extend(static_rules::no_console(semantic, code, file_path));
+            }
+            "no-debugger" => {
+                diagnostics.extend(static_rules::no_debugger(semantic, code, file_path));
             }
             "prefer-const" => {
-                diagnostics.extend(self.check_prefer_const(&ret.program));
+                diagnostics.extend(static_rules::prefer_const(semantic, code, file_path));
+            }
+            "no-var" => {
+                diagnostics.extend(static_rules::no_var(semantic, code, file_path));
+            }
+            "eqeqeq" => {
+                diagnostics.extend(static_rules::eqeqeq(semantic, code, file_path, static_rules::EqeqeqMode::Always));
+            }
+            "eqeqeq:smart" => {
+                diagnostics.extend(static_rules::eqeqeq(semantic, code, file_path, static_rules::EqeqeqMode::Smart));
+            }
+            "complexity" => {
+                diagnostics.extend(static_rules::complexity(semantic, code, file_path, &self.complexity_limits));
+            }
+            static_rules::TSDOC_REQUIRED_TAGS_RULE => {
+                diagnostics.extend(static_rules::tsdoc_required_tags(program, code, file_path, &self.tsdoc_required_tags));
+            }
+            static_rules::LINE_ENDINGS_RULE => {
+                diagnostics.extend(static_rules::line_endings(code, file_path, self.line_endings));
+            }
+            static_rules::ASYNC_ERROR_HANDLING_RULE => {
+                diagnostics.extend(static_rules::async_error_handling(semantic, code, file_path, self.async_error_handling));
+            }
+            static_rules::UNSAFE_ASSERTIONS_RULE => {
+                diagnostics.extend(static_rules::unsafe_assertions(semantic, code, file_path, &self.unsafe_assertions));
+            }
+            import_order::IMPORT_ORDER_RULE => {
+                diagnostics.extend(import_order::import_order(program, code, file_path, &self.import_order));
             }
             _ => {
                 // Generic rule execution
-                diagnostics.extend(self.execute_generic_rule(rule_name, &ret.program));
+                diagnostics.extend(self.execute_generic_rule(rule_name, program));
             }
         }
 
-        Ok(diagnostics)
+        diagnostics
     }
 
-    /// Execute AI behavioral rule
-    fn execute_ai_rule(
-        &self,
-        pattern_type: &str,
-        code: &str,
-        file_path: &str,
-        source_type: SourceType,
-    ) -> Result<Vec<LintDiagnostic>, Box<dyn std::error::Error>> {
-        let allocator = oxc_allocator::Allocator::default();
-        let parse_result = oxc_parser::Parser::new(&allocator, code, source_type).parse();
-
-        if !parse_result.errors.is_empty() {
-            return Ok(parse_result
-                .errors
-                .iter()
-                .map(|err| LintDiagnostic {
-                    rule_name: format!("ai-behavioral-{}", pattern_type),
-                    message: err.message.to_string(),
-                    file_path: file_path.to_string(),
-                    line: 1,
-                    column: 1,
-                    end_line: 1,
-                    end_column: 1,
-                    severity: DiagnosticSeverity::Error,
-                    fix_available: false,
-                    suggested_fix: None,
-                })
-                .collect());
-        }
-
-        let analyzer = AiBehavioralAnalyzer::new();
+    /// Execute AI behavioral rule on the file's shared program; parse errors are reported by the caller
+    fn execute_ai_rule(&self, program: &Program, ctx: &RuleExecutionContext<'_>) -> Result<Vec<LintDiagnostic>, Box<dyn std::error::Error>> {
         let context = AnalysisContext {
-            file_path: file_path.to_string(),
-            file_type: source_type,
+            file_path: ctx.file_path.to_string(),
+            file_type: ctx.source_type,
             project_context: None,
             dependencies: Vec::new(),
         };
 
-        block_on(analyzer.analyze_behavioral_patterns(code, &parse_result.program, &context))
-    }
-
-    /// Execute hybrid rule (OXC + AI)
-    fn execute_hybrid_rule(
-        &self,
-        oxc_rule: &str,
-        ai_pattern: &str,
-        code: &str,
-        file_path: &str,
-        source_type: SourceType,
-    ) -> Result<Vec<LintDiagnostic>, Box<dyn std::error::Error>> {
-        let mut diagnostics = Vec::new();
-
-        // Run OXC analysis first
-        if let Ok(oxc_diagnostics) = self.execute_oxc_rule(oxc_rule, code, file_path) {
-            diagnostics.extend(oxc_diagnostics);
-        }
-
-        // Then run AI analysis
-        if let Ok(ai_diagnostics) = self.execute_ai_rule(ai_pattern, code, file_path, source_type) {
-            diagnostics.extend(ai_diagnostics);
-        }
-
-        Ok(diagnostics)
+        block_on(self.ai_analyzer.analyze_behavioral_patterns(ctx.code, program, &context))
     }
 
     /// Check for unused variables
@@ -262,22 +564,6 @@ impl RuleExecutor {
         diagnostics
     }
 
-    /// Check for console usage
-    fn check_console_usage(&self, program: &Program) -> Vec<LintDiagnostic> {
-        let diagnostics = Vec::new();
-        // Implementation would traverse AST and find console.* calls
-        // This is a simplified version
-        diagnostics
-    }
-
-    /// Check for prefer const
-    fn check_prefer_const(&self, _program: &Program) -> Vec<LintDiagnostic> {
-        let diagnostics = Vec::new();
-        // Implementation would traverse AST and find let declarations that could be const
-        // This is a simplified version
-        diagnostics
-    }
-
     /// Execute generic rule
     fn execute_generic_rule(&self, _rule_name: &str, _program: &Program) -> Vec<LintDiagnostic> {
         let diagnostics = Vec::new();
@@ -291,6 +577,56 @@ impl RuleExecutor {
     }
 }
 
+/// AI-enhanced rules to defer so their cumulative cost stays within `budget`.
+///
+/// Rules are admitted by priority - higher severity first, then cheaper first - and
+/// deferred once the next one would exceed the budget. Static rules are never deferred.
+fn budget_deferred_rules<'a>(rules: &[&'a RuleMetadata], budget: Option<u32>) -> Vec<&'a str> {
+    let Some(budget) = budget else {
+        return Vec::new();
+    };
+
+    let mut ai_rules: Vec<&RuleMetadata> = rules.iter().copied().filter(|rule| is_ai_rule(rule)).collect();
+    ai_rules.sort_by(|a, b| {
+        severity_rank(&a.severity)
+            .cmp(&severity_rank(&b.severity))
+            .then(a.cost.cmp(&b.cost))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let mut spent = 0u32;
+    ai_rules
+        .into_iter()
+        .filter(|rule| match spent.checked_add(rule.cost) {
+            Some(total) if total <= budget => {
+                spent = total;
+                false
+            }
+            _ => true,
+        })
+        .map(|rule| rule.id.as_str())
+        .collect()
+}
+
+fn is_ai_rule(rule: &RuleMetadata) -> bool {
+    matches!(rule.implementation, RuleImplementation::AiBehavioral { .. } | RuleImplementation::Hybrid { .. })
+}
+
+/// Whether an AI step failed because every provider did, which counts as AI being unavailable
+fn all_providers_failed(error: &(dyn std::error::Error + 'static)) -> bool {
+    error.downcast_ref::<crate::error::Error>().is_some_and(crate::error::Error::is_all_providers_failed)
+}
+
+fn severity_rank(severity: &RuleSeverity) -> u8 {
+    match severity {
+        RuleSeverity::Error => 0,
+        RuleSeverity::Warning => 1,
+        RuleSeverity::Info => 2,
+        RuleSeverity::Hint => 3,
+        RuleSeverity::Custom(_) => 4,
+    }
+}
+
 /// Lightweight execution plan used by higher-level schedulers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionPlan {
@@ -310,3 +646,445 @@ impl ExecutionPlan {
 // Note: Legacy OXC visitor-based rule implementations removed
 // Modern rule execution is handled by BiomeAdapter and UnifiedAnalyzer
 // which provide both static analysis (via Biome) and AI behavioral patterns
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oxc_adapter::ai_behavioral::AiPatternResult;
+    use crate::oxc_adapter::BehavioralPattern;
+    use crate::provider_router::AIProviderConfig;
+    use crate::rulebase::testing::static_rule;
+    use oxc_parser::Parser;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_profile_includes_executed_rules() {
+        let code = "let a = 1;\nif (a == 2) {}\n";
+        let ctx = RuleExecutionContext {
+            code,
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let rules = vec![static_rule("prefer-const"), static_rule("eqeqeq")];
+
+        let outcome = RuleExecutor::new().with_profiling(true).evaluate(&rules, &ctx);
+        let profile = outcome.profile.expect("profiling enabled");
+
+        assert_eq!(profile.entries.len(), 2);
+        for rule in ["prefer-const", "eqeqeq"] {
+            let entry = profile.entry(rule).expect("executed rule is profiled");
+            assert_eq!((entry.calls, entry.files), (1, 1));
+        }
+
+        let unprofiled = RuleExecutor::new().evaluate(&rules, &ctx);
+        assert!(unprofiled.profile.is_none());
+    }
+
+    #[test]
+    fn test_declaration_file_runs_documentation_rules_only() {
+        let code = "export interface Options {\n  debug: boolean;\n}\nlet fallback = 1;\nexport { fallback };\n";
+        let rules = vec![static_rule("prefer-const"), static_rule(static_rules::TSDOC_REQUIRED_TAGS_RULE)];
+        let fired = |file_path: &str| {
+            let ctx = RuleExecutionContext {
+                code,
+                file_path,
+                source_type: SourceType::ts(),
+                program: None,
+            };
+            let mut fired: Vec<String> = RuleExecutor::new().evaluate(&rules, &ctx).diagnostics.into_iter().map(|d| d.rule_name).collect();
+            fired.dedup();
+            fired
+        };
+
+        assert_eq!(fired("src/options.ts"), vec!["prefer-const", static_rules::TSDOC_REQUIRED_TAGS_RULE]);
+        assert_eq!(fired("types/options.d.ts"), vec![static_rules::TSDOC_REQUIRED_TAGS_RULE]);
+    }
+
+    #[test]
+    fn test_multi_rule_run_builds_semantic_once() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\nif (a == 2) {\n  console.log(a);\n}\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let mut hybrid_rule = static_rule("hybrid-prefer-const");
+        hybrid_rule.implementation = RuleImplementation::Hybrid {
+            oxc_rule: "prefer-const".to_string(),
+            ai_pattern: "const-intent".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), static_rule("eqeqeq"), static_rule("complexity"), hybrid_rule];
+
+        let outcome = RuleExecutor::new().with_ai_availability(false).evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.executed_rules, 4);
+        assert_eq!(outcome.semantic_builds, 1);
+        assert!(outcome.diagnostics.iter().any(|d| d.rule_name == "eqeqeq"));
+        assert_eq!(outcome.diagnostics.iter().filter(|d| d.rule_name == "prefer-const").count(), 2);
+    }
+
+    #[test]
+    fn test_caller_semantic_is_shared_instead_of_rebuilt() {
+        let code = "let a = 1;\nif (a == 2) {\n  console.log(a);\n}\n";
+        let allocator = Allocator::default();
+        let parsed = Parser::new(&allocator, code, SourceType::ts()).parse();
+        let semantic = SemanticBuilder::new().build(&parsed.program).semantic;
+        let ctx = RuleExecutionContext {
+            code,
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: Some(&parsed.program),
+        };
+        let rules = vec![static_rule("prefer-const"), static_rule("eqeqeq")];
+        let executor = RuleExecutor::new().with_ai_availability(false);
+
+        let shared = executor.evaluate_with_semantic(&rules, &ctx, Some(&semantic));
+        let built = executor.evaluate(&rules, &ctx);
+
+        assert_eq!(shared.semantic_builds, 0);
+        assert_eq!(built.semantic_builds, 1);
+        let rule_names = |outcome: &RuleExecutionOutcome| outcome.diagnostics.iter().map(|d| d.rule_name.clone()).collect::<Vec<_>>();
+        assert_eq!(rule_names(&shared), rule_names(&built));
+    }
+
+    #[test]
+    fn test_ai_steps_skipped_on_generated_files_only() {
+        let mut ai_rule = static_rule("ai-complexity");
+        ai_rule.implementation = RuleImplementation::AiBehavioral {
+            pattern_type: "complexity".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), ai_rule];
+        let run = |code: &str| {
+            let ctx = RuleExecutionContext {
+                code,
+                file_path: "src/api.ts",
+                source_type: SourceType::ts(),
+                program: None,
+            };
+            RuleExecutor::new().with_ai_availability(true).evaluate(&rules, &ctx)
+        };
+
+        let normal = run("let a = 1;\nconsole.log(a);\n");
+        assert_eq!(normal.executed_rules, 2, "the AI rule runs on a normal file");
+
+        let generated = run("// @generated by protoc-gen-ts\nlet a = 1;\nconsole.log(a);\n");
+        assert_eq!(generated.executed_rules, 1, "only the static rule runs");
+        assert!(generated.diagnostics.iter().any(|d| d.rule_name == "prefer-const"));
+        assert!(generated.notices.is_empty());
+    }
+
+    #[test]
+    fn test_ai_exclude_patterns_keep_static_rules_only() {
+        let mut ai_rule = static_rule("ai-complexity");
+        ai_rule.implementation = RuleImplementation::AiBehavioral {
+            pattern_type: "complexity".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), ai_rule];
+        let mut config = crate::config::MoonShineConfig::default();
+        config.ai.ai_exclude_patterns = vec!["fixtures/**".to_string()];
+        let run = |file_path: &str| {
+            let ctx = RuleExecutionContext {
+                code: "let a = 1;\nconsole.log(a);\n",
+                file_path,
+                source_type: SourceType::ts(),
+                program: None,
+            };
+            RuleExecutor::new()
+                .with_ai_availability(true)
+                .with_ai_excludes(AiExcludes::from_config(&config))
+                .evaluate(&rules, &ctx)
+        };
+
+        let source = run("src/api.ts");
+        assert_eq!(source.executed_rules, 2, "static and AI rules run on src/**");
+
+        let fixture = run("./fixtures/api.ts");
+        assert_eq!(fixture.executed_rules, 1, "only the static rule runs on fixtures/**");
+        assert!(fixture.diagnostics.iter().any(|d| d.rule_name == "prefer-const"));
+        assert!(!fixture.diagnostics.iter().any(crate::reporting::is_ai_suggestion));
+        assert!(fixture.notices.is_empty());
+    }
+
+    #[test]
+    fn test_env_globs_relax_console_and_debugger_outside_production() {
+        let code = "function load() {\n  console.log('loading');\n  debugger;\n}\n";
+        let rules = vec![static_rule("no-console"), static_rule("no-debugger")];
+        let mut linting = crate::config::LintingConfig::default();
+        linting.env_globs = HashMap::from([("**/*.test.ts".to_string(), "off".to_string())]);
+        let run = |linting: &crate::config::LintingConfig, file_path: &str| {
+            let ctx = RuleExecutionContext {
+                code,
+                file_path,
+                source_type: SourceType::ts(),
+                program: None,
+            };
+            RuleExecutor::new()
+                .with_ai_availability(false)
+                .with_env_levels(EnvLevels::from_config(linting))
+                .evaluate(&rules, &ctx)
+        };
+
+        let production = run(&linting, "src/load.ts");
+        let console = production.diagnostics.iter().find(|d| d.rule_name == "no-console").expect("console.log is reported in src");
+        assert_eq!((console.line, console.severity.clone()), (2, crate::types::DiagnosticSeverity::Error));
+        assert!(production.diagnostics.iter().any(|d| d.rule_name == "no-debugger" && d.line == 3));
+
+        let test = run(&linting, "src/load.test.ts");
+        assert!(test.diagnostics.is_empty(), "both rules are off in *.test.ts: {:?}", test.diagnostics);
+
+        linting.env_globs = crate::config::defaults::lint_env_globs();
+        let relaxed = run(&linting, "./src/load.spec.ts");
+        assert!(relaxed.diagnostics.iter().all(|d| d.severity == crate::types::DiagnosticSeverity::Warning));
+        assert_eq!(relaxed.diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn test_syntax_error_reported_once_and_rules_skipped() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\nif (a == 2 {\n}\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let mut ai_rule = static_rule("ai-complexity");
+        ai_rule.implementation = RuleImplementation::AiBehavioral {
+            pattern_type: "complexity".to_string(),
+        };
+        let mut hybrid_rule = static_rule("hybrid-prefer-const");
+        hybrid_rule.implementation = RuleImplementation::Hybrid {
+            oxc_rule: "prefer-const".to_string(),
+            ai_pattern: "const-intent".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), ai_rule, static_rule("eqeqeq"), hybrid_rule];
+        let allocator = Allocator::default();
+        let parse_errors = Parser::new(&allocator, ctx.code, ctx.source_type).parse().errors;
+
+        let outcome = RuleExecutor::new().with_ai_availability(true).evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.executed_rules, 0);
+        assert_eq!(outcome.semantic_builds, 0);
+        assert_eq!(outcome.diagnostics.len(), parse_error_diagnostics(&parse_errors, ctx.code, ctx.file_path).len());
+        assert!(outcome.diagnostics.iter().all(|d| d.rule_name == crate::buffer_lint::PARSE_ERROR_RULE && d.line == 2));
+    }
+
+    #[test]
+    fn test_run_without_ai_returns_static_diagnostics_with_notice() {
+        let code = "let a = 1;\nconsole.log(a);\n";
+        let ctx = RuleExecutionContext {
+            code,
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let mut ai_rule = static_rule("ai-complexity");
+        ai_rule.implementation = RuleImplementation::AiBehavioral {
+            pattern_type: "complexity".to_string(),
+        };
+        let mut hybrid_rule = static_rule("hybrid-prefer-const");
+        hybrid_rule.implementation = RuleImplementation::Hybrid {
+            oxc_rule: "prefer-const".to_string(),
+            ai_pattern: "const-intent".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), ai_rule, hybrid_rule];
+
+        let outcome = RuleExecutor::new().with_ai_availability(false).evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.diagnostics.iter().filter(|d| d.rule_name == "prefer-const").count(), 2);
+        assert!(outcome.diagnostics.iter().all(|d| !d.rule_name.starts_with("ai-behavioral")));
+        assert_eq!(outcome.executed_rules, 2);
+        assert_eq!(outcome.notices.len(), 1);
+        assert!(outcome.notices[0].contains("AI unavailable"));
+        assert!(outcome.notices[0].contains("2 AI step(s) skipped"));
+    }
+
+    /// Client whose every provider fails, as the router reports once fallback is exhausted
+    struct FailingProviders;
+
+    impl AiAnalysisClient for FailingProviders {
+        fn analyze_patterns(
+            &self,
+            _code: &str,
+            _patterns: &[BehavioralPattern],
+            _context: &AnalysisContext,
+        ) -> Result<Vec<AiPatternResult>, Box<dyn std::error::Error>> {
+            let failures = [AIProviderConfig::claude(), AIProviderConfig::google()]
+                .iter()
+                .map(|provider| crate::error::Error::ai_execution(provider, "AI CLI failed with exit code 1: service unavailable"))
+                .collect();
+            Err(Box::new(crate::error::Error::multiple(failures, 0)))
+        }
+
+        fn suggest_fix(&self, _code: &str, _pattern: &BehavioralPattern, _issue_span: (usize, usize)) -> Result<Option<String>, Box<dyn std::error::Error>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_all_providers_failing_falls_back_like_unavailable_ai() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\nconsole.log(a);\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let mut ai_rule = static_rule("ai-complexity");
+        ai_rule.implementation = RuleImplementation::AiBehavioral {
+            pattern_type: "complexity".to_string(),
+        };
+        let mut hybrid_rule = static_rule("hybrid-prefer-const");
+        hybrid_rule.implementation = RuleImplementation::Hybrid {
+            oxc_rule: "prefer-const".to_string(),
+            ai_pattern: "const-intent".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), ai_rule, hybrid_rule];
+
+        let outcome = RuleExecutor::new()
+            .with_ai_availability(true)
+            .with_ai_client(Box::new(FailingProviders))
+            .evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.diagnostics.iter().filter(|d| d.rule_name == "prefer-const").count(), 2);
+        assert_eq!(outcome.executed_rules, 2);
+        assert_eq!(outcome.notices.len(), 1);
+        assert!(outcome.notices[0].contains("AI unavailable"));
+        assert!(outcome.notices[0].contains("2 AI step(s) skipped"));
+    }
+
+    #[test]
+    fn test_disabled_ai_returns_static_diagnostics_without_notice() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\nconsole.log(a);\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let mut hybrid_rule = static_rule("hybrid-prefer-const");
+        hybrid_rule.implementation = RuleImplementation::Hybrid {
+            oxc_rule: "prefer-const".to_string(),
+            ai_pattern: "const-intent".to_string(),
+        };
+        let rules = vec![static_rule("prefer-const"), hybrid_rule];
+
+        let outcome = RuleExecutor::new().with_ai_availability(true).with_ai_disabled(true).evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.diagnostics.iter().filter(|d| d.rule_name == "prefer-const").count(), 2);
+        assert_eq!(outcome.executed_rules, 2);
+        assert!(outcome.notices.is_empty());
+    }
+
+    #[test]
+    fn test_cost_budget_defers_lowest_priority_ai_rule() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let ai_rule = |id: &str, severity: RuleSeverity, cost: u32| {
+            let mut rule = static_rule(id);
+            rule.severity = severity;
+            rule.cost = cost;
+            rule.ai_enhanced = true;
+            rule.implementation = RuleImplementation::AiBehavioral { pattern_type: id.to_string() };
+            rule
+        };
+        let rules = vec![
+            ai_rule("ai-style", RuleSeverity::Warning, 4),
+            static_rule("prefer-const"),
+            ai_rule("ai-security", RuleSeverity::Error, 5),
+            ai_rule("ai-naming", RuleSeverity::Warning, 3),
+        ];
+
+        let executor = || RuleExecutor::new().with_ai_availability(true).with_ai_client(Box::new(FailingProviders));
+        let outcome = executor().with_cost_budget(Some(8)).evaluate(&rules, &ctx);
+
+        assert_eq!(outcome.deferred_rules, vec!["ai-style"]);
+        assert!(outcome.diagnostics.iter().any(|d| d.rule_name == "prefer-const"), "static rules always run");
+        assert!(outcome.notices.iter().any(|notice| notice.contains("deferred 1 AI rule(s): ai-style")));
+
+        let unbounded = executor().evaluate(&rules, &ctx);
+        assert!(unbounded.deferred_rules.is_empty());
+    }
+
+    #[test]
+    fn test_cost_budget_counts_only_ai_rules_that_would_run() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\n",
+            file_path: "src/app.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+        let ai_rule = |id: &str, cost: u32, applies_to: Option<&str>| {
+            let mut rule = static_rule(id);
+            rule.cost = cost;
+            rule.ai_enhanced = true;
+            rule.implementation = RuleImplementation::AiBehavioral { pattern_type: id.to_string() };
+            rule.applies_to = applies_to.map(|pattern| vec![pattern.to_string()]);
+            rule
+        };
+        // The highest-priority rule is scoped to scripts, so it must not push the others over the budget
+        let mut rules = vec![ai_rule("ai-scripts", 5, Some("scripts/**")), ai_rule("ai-style", 4, None), ai_rule("ai-naming", 3, None)];
+        rules[0].severity = RuleSeverity::Error;
+
+        let scoped = RuleExecutor::new()
+            .with_ai_availability(true)
+            .with_ai_client(Box::new(FailingProviders))
+            .with_cost_budget(Some(8))
+            .evaluate(&rules, &ctx);
+        assert!(scoped.deferred_rules.is_empty());
+
+        let without_ai = RuleExecutor::new().with_ai_availability(false).with_cost_budget(Some(1)).evaluate(&rules, &ctx);
+        assert!(without_ai.deferred_rules.is_empty(), "skipped AI steps are not deferred");
+        assert!(without_ai.notices.iter().all(|notice| !notice.contains("cost budget")));
+    }
+
+    #[test]
+    fn test_no_notice_when_only_static_rules_run() {
+        let ctx = RuleExecutionContext {
+            code: "let a = 1;\n",
+            file_path: "test.ts",
+            source_type: SourceType::ts(),
+            program: None,
+        };
+
+        let outcome = RuleExecutor::new().with_ai_availability(false).evaluate(&[static_rule("prefer-const")], &ctx);
+
+        assert_eq!(outcome.diagnostics.len(), 1);
+        assert!(outcome.notices.is_empty());
+    }
+
+    #[test]
+    fn test_scoped_rule_runs_only_on_matching_files() {
+        let mut test_only = static_rule("prefer-const");
+        test_only.applies_to = Some(vec!["**/*.test.ts".to_string()]);
+        let rules = vec![test_only];
+        let run = |file_path: &str| {
+            let ctx = RuleExecutionContext {
+                code: "let a = 1;\n",
+                file_path,
+                source_type: SourceType::ts(),
+                program: None,
+            };
+            RuleExecutor::new().evaluate(&rules, &ctx)
+        };
+
+        let non_test = run("src/math.ts");
+        assert!(non_test.diagnostics.is_empty());
+        assert_eq!((non_test.executed_rules, non_test.skipped_rules), (0, 1));
+
+        let test_file = run("src/math.test.ts");
+        assert_eq!(test_file.diagnostics.len(), 1);
+        assert_eq!(test_file.diagnostics[0].rule_name, "prefer-const");
+    }
+
+    #[test]
+    fn test_configured_scope_replaces_rule_applies_to() {
+        let scopes = HashMap::from([("prefer-const".to_string(), vec!["scripts/**".to_string()])]);
+        let rules = crate::rule_types::with_rule_scopes(&[static_rule("prefer-const"), static_rule("eqeqeq")], &scopes);
+
+        assert!(rules[0].applies_to_file("scripts/build.ts"));
+        assert!(!rules[0].applies_to_file("src/app.ts"));
+        assert!(rules[1].applies_to_file("src/app.ts"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Rationale attached to diagnostics in `--explain` mode
+//!
+//! Diagnostic messages are terse. With `linting.explain` (`--explain`) each diagnostic
+//! gets an `explanation`: the [`RuleDoc`](super::docs::RuleDoc) rationale and recommended
+//! fix for static rules and, when AI is enabled, an AI-written explanation of the specific
+//! finding for AI-enhanced rules. Explain mode is off by default because the AI part costs
+//! tokens per diagnostic.
+
+use super::docs::rule_doc;
+use super::RuleImplementation;
+use crate::rule_types::RuleMetadata;
+use crate::types::LintDiagnostic;
+
+/// Prefix of rule names reported by AI behavioral analysis
+const AI_BEHAVIORAL_PREFIX: &str = "ai-behavioral";
+
+/// Documented rationale and recommended fix for `rule_id`
+pub fn static_explanation(rule_id: &str) -> Option<String> {
+    rule_doc(rule_id).map(|doc| format!("{} Recommended fix: {}", doc.rationale, doc.fix))
+}
+
+/// Whether `diagnostic` was produced by an AI-enhanced rule in `rules`
+pub fn is_ai_enhanced(diagnostic: &LintDiagnostic, rules: &[RuleMetadata]) -> bool {
+    diagnostic.rule_name.starts_with(AI_BEHAVIORAL_PREFIX)
+        || rules.iter().any(|rule| {
+            let reported_as = match &rule.implementation {
+                RuleImplementation::OxcStatic { rule_name } => rule_name.as_str(),
+                RuleImplementation::Hybrid { oxc_rule, .. } => oxc_rule.as_str(),
+                _ => rule.id.as_str(),
+            };
+            rule.ai_enhanced && (rule.id == diagnostic.rule_name || reported_as == diagnostic.rule_name)
+        })
+}
+
+/// Attach an explanation to every diagnostic that has one.
+///
+/// `ai_explain` receives the diagnostic and its static explanation, if any, and is only
+/// called for AI-enhanced rules when `ai_enabled`; its answer is appended to the static
+/// explanation.
+pub fn explain_diagnostics<F>(diagnostics: &mut [LintDiagnostic], rules: &[RuleMetadata], ai_enabled: bool, mut ai_explain: F)
+where
+    F: FnMut(&LintDiagnostic, Option<&str>) -> Option<String>,
+{
+    for diagnostic in diagnostics.iter_mut() {
+        let documented = static_explanation(&diagnostic.rule_name);
+        let ai = (ai_enabled && is_ai_enhanced(diagnostic, rules))
+            .then(|| ai_explain(diagnostic, documented.as_deref()))
+            .flatten();
+
+        diagnostic.explanation = match (documented, ai) {
+            (Some(documented), Some(ai)) => Some(format!("{}\n\n{}", documented, ai)),
+            (documented, ai) => documented.or(ai),
+        };
+    }
+}
+
+/// Ask the configured AI provider to explain `diagnostic` in `code`
+pub fn ai_explanation(diagnostic: &LintDiagnostic, documented: Option<&str>, code: &str) -> Option<String> {
+    let line = code.lines().nth(diagnostic.line.saturating_sub(1) as usize).unwrap_or_default();
+    let prompt = format!(
+        "Explain to a developer new to this codebase why the `{}` rule reported this problem and how to fix it. \
+         Answer in at most three sentences.\n\nFile: {}:{}\nMessage: {}\nCode: {}\n{}",
+        diagnostic.rule_name,
+        diagnostic.file_path,
+        diagnostic.line,
+        diagnostic.message,
+        line.trim(),
+        documented.map(|documented| format!("Rule rationale: {}\n", documented)).unwrap_or_default()
+    );
+
+    match futures::executor::block_on(crate::provider_router::execute_rule_prompt(
+        format!("explain-{}", diagnostic.rule_name),
+        &diagnostic.rule_name,
+        prompt,
+    )) {
+        Ok(response) if response.success && !response.content.trim().is_empty() => Some(response.content.trim().to_string()),
+        Ok(response) => {
+            moon_debug!("No AI explanation for {}: {}", diagnostic.rule_name, response.error_message.unwrap_or_default());
+            None
+        }
+        Err(error) => {
+            moon_warn!("AI explanation failed for {}: {}", diagnostic.rule_name, error);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule_types::{FixSafety, FixStatus, RuleCategory, RuleSeverity};
+    use crate::types::DiagnosticSeverity;
+
+    fn diagnostic(rule_name: &str) -> LintDiagnostic {
+        LintDiagnostic {
+            rule_name: rule_name.to_string(),
+            message: "problem".to_string(),
+            file_path: "src/a.ts".to_string(),
+            line: 1,
+            column: 1,
+            end_line: 1,
+            end_column: 2,
+            severity: DiagnosticSeverity::Warning,
+            fix_available: false,
+            suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
+        }
+    }
+
+    fn ai_rule(pattern_type: &str) -> RuleMetadata {
+        RuleMetadata {
+            id: pattern_type.to_string(),
+            name: pattern_type.to_string(),
+            description: String::new(),
+            category: RuleCategory::Complexity,
+            severity: RuleSeverity::Warning,
+            fix_status: FixStatus::Manual,
+            fix_safety: FixSafety::Suggestion,
+            ai_enhanced: true,
+            cost: 5,
+            tags: vec![],
+            dependencies: vec![],
+            implementation: RuleImplementation::AiBehavioral {
+                pattern_type: pattern_type.to_string(),
+            },
+            config_schema: None,
+            applies_to: None,
+        }
+    }
+
+    #[test]
+    fn test_static_rules_explained_from_rule_doc() {
+        let mut diagnostics = vec![diagnostic("eqeqeq"), diagnostic("undocumented-rule")];
+
+        explain_diagnostics(&mut diagnostics, &[], true, |_, _| panic!("static rules never ask the AI"));
+
+        let explanation = diagnostics[0].explanation.as_deref().unwrap();
+        assert!(explanation.contains("coerce operands"));
+        assert!(explanation.contains("Recommended fix: Use `===`"));
+        assert_eq!(diagnostics[1].explanation, None);
+    }
+
+    #[test]
+    fn test_ai_explanations_requested_only_when_ai_enabled() {
+        let rules = vec![ai_rule("callback-hell")];
+        let mut requested = Vec::new();
+
+        let mut offline = vec![diagnostic("callback-hell"), diagnostic("eqeqeq")];
+        explain_diagnostics(&mut offline, &rules, false, |diagnostic, _| {
+            requested.push(diagnostic.rule_name.clone());
+            None
+        });
+        assert!(requested.is_empty());
+        assert_eq!(offline[0].explanation, None);
+
+        let mut online = vec![diagnostic("callback-hell"), diagnostic("eqeqeq"), diagnostic("ai-behavioral-complexity")];
+        explain_diagnostics(&mut online, &rules, true, |diagnostic, _| {
+            requested.push(diagnostic.rule_name.clone());
+            Some(format!("AI: {}", diagnostic.rule_name))
+        });
+        assert_eq!(requested, vec!["callback-hell", "ai-behavioral-complexity"]);
+        assert_eq!(online[0].explanation.as_deref(), Some("AI: callback-hell"));
+        assert!(!online[1].explanation.as_deref().unwrap().contains("AI:"));
+    }
+}
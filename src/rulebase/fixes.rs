@@ -0,0 +1,625 @@
+//! # Span Fix Application
+//!
+//! Applies the span replacements carried by `LintDiagnostic::suggested_fix`, gated by
+//! each rule's [`FixSafety`]. Safe fixes apply in normal fix mode; suggestion-level
+//! fixes are skipped unless the run opted in with `--apply-suggestions`.
+//!
+//! Every attempted fix is tracked per rule in [`FixStats`]: applied, or rejected by
+//! verification (syntax, AI confidence, span conflict, deletion guard). The stats are
+//! returned with each run and can be persisted with the run's telemetry record.
+//!
+//! Fixing one problem can expose another, so [`fix_until_converged`] repeats lint-and-fix
+//! passes until a pass applies nothing (a fixpoint) or `linting.max_fix_iterations` is
+//! reached. The resulting [`FixConvergence`] makes oscillating fix loops visible in run metrics.
+//!
+//! Each applied change is kept as an [`AppliedFix`] naming the rule it came from and where
+//! its replacement sits in the fixed code, so a diff can be traced back to its rules.
+//!
+//! With `ai.suggest_only`, [`propose_ai_fixes`] turns AI fixes into unified-diff patches on
+//! their diagnostics before fixing, so static fixes still apply while AI stays advisory.
+//!
+//! @category rulebase
+//! @safe team
+//! @mvp core
+//! @complexity medium
+//! @since 2.0.0
+
+use super::static_rules::{line_column_to_offset, offset_to_line_column};
+use crate::buffer_lint::parse_guarded;
+use crate::config::MoonShineConfig;
+use crate::reporting::is_ai_suggestion;
+use crate::rule_types::FixSafety;
+use crate::types::LintDiagnostic;
+use oxc_allocator::Allocator;
+use oxc_span::SourceType;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::collections::BTreeMap;
+
+/// Fixes removing more lines than this (net of the replacement) are rejected
+pub const MAX_FIX_DELETED_LINES: usize = 10;
+
+/// Options controlling which fixes are applied
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FixOptions {
+    pub apply_suggestions: bool,
+    pub verify_syntax: Option<SourceType>, // Reject fixes that break parsing of a file that parsed before
+    pub min_ai_confidence: Option<f32>,    // Reject AI fixes below this confidence (0.0-1.0)
+}
+
+impl FixOptions {
+    /// Fix options configured for this run
+    pub fn from_config(config: &MoonShineConfig) -> Self {
+        Self {
+            apply_suggestions: config.linting.apply_suggestions,
+            verify_syntax: None,
+            min_ai_confidence: config.confidence_threshold.map(|threshold| threshold as f32),
+        }
+    }
+
+    /// Also verify that each fix keeps `source_type` code parseable
+    pub fn with_syntax_check(mut self, source_type: SourceType) -> Self {
+        self.verify_syntax = Some(source_type);
+        self
+    }
+}
+
+/// Why a fix was attempted but not applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixRejectionReason {
+    Syntax,        // The fixed code no longer parses
+    Confidence,    // AI confidence below `min_ai_confidence`
+    Conflict,      // Overlaps a fix already applied
+    DeletionGuard, // Removes more than `MAX_FIX_DELETED_LINES` lines
+}
+
+/// Fix outcomes for a single rule
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleFixStats {
+    pub attempted: u32,
+    pub applied: u32,
+    pub rejected: BTreeMap<FixRejectionReason, u32>,
+    #[serde(default)]
+    pub confidence_samples: u32, // Attempted AI fixes that reported a confidence
+    #[serde(default)]
+    pub confidence_total: u32, // Sum of those confidences, in whole percent
+}
+
+impl RuleFixStats {
+    /// Total rejected fixes across all reasons
+    pub fn rejected_total(&self) -> u32 {
+        self.rejected.values().sum()
+    }
+
+    /// Mean reported AI confidence (0-100) of the attempted fixes, if any reported one
+    pub fn average_confidence(&self) -> Option<f64> {
+        (self.confidence_samples > 0).then(|| f64::from(self.confidence_total) / f64::from(self.confidence_samples))
+    }
+
+    /// Add another file's or run's outcomes for the same rule
+    pub fn add(&mut self, other: &RuleFixStats) {
+        self.attempted += other.attempted;
+        self.applied += other.applied;
+        for (reason, count) in &other.rejected {
+            *self.rejected.entry(*reason).or_default() += count;
+        }
+        self.confidence_samples += other.confidence_samples;
+        self.confidence_total += other.confidence_total;
+    }
+}
+
+/// Per-rule fix outcomes for a run, keyed by rule name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixStats {
+    pub rules: BTreeMap<String, RuleFixStats>,
+}
+
+impl FixStats {
+    pub fn record_attempt(&mut self, rule_name: &str) {
+        self.rules.entry(rule_name.to_string()).or_default().attempted += 1;
+    }
+
+    pub fn record_applied(&mut self, rule_name: &str) {
+        self.rules.entry(rule_name.to_string()).or_default().applied += 1;
+    }
+
+    /// Record the AI confidence (0-100) an attempted fix reported
+    pub fn record_confidence(&mut self, rule_name: &str, confidence: f32) {
+        let stats = self.rules.entry(rule_name.to_string()).or_default();
+        stats.confidence_samples += 1;
+        stats.confidence_total += confidence.clamp(0.0, 100.0).round() as u32;
+    }
+
+    pub fn record_rejected(&mut self, rule_name: &str, reason: FixRejectionReason) {
+        *self.rules.entry(rule_name.to_string()).or_default().rejected.entry(reason).or_default() += 1;
+    }
+
+    /// Stats for one rule, if it attempted any fix
+    pub fn rule(&self, rule_name: &str) -> Option<&RuleFixStats> {
+        self.rules.get(rule_name)
+    }
+
+    /// Add another file's stats into this run's stats
+    pub fn merge(&mut self, other: &FixStats) {
+        for (rule_name, stats) in &other.rules {
+            self.rules.entry(rule_name.clone()).or_default().add(stats);
+        }
+    }
+
+    /// Stats summed over all rules
+    pub fn totals(&self) -> RuleFixStats {
+        let mut totals = RuleFixStats::default();
+        for stats in self.rules.values() {
+            totals.add(stats);
+        }
+        totals
+    }
+}
+
+/// One applied change and the rule that produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedFix {
+    pub rule_id: String,
+    pub line: u32, // Where `fixed_text` starts in the fixed code
+    pub column: u32,
+    pub end_line: u32, // Last line of `fixed_text` in the fixed code
+    pub original_text: String,
+    pub fixed_text: String,
+    #[serde(default)]
+    pub safety: FixSafety, // Safety level the fix was applied at
+}
+
+impl AppliedFix {
+    /// Lines this fix added to the file, negative when it removed lines
+    fn added_lines(&self) -> i64 {
+        self.fixed_text.matches('\n').count() as i64 - self.original_text.matches('\n').count() as i64
+    }
+}
+
+/// Result of applying fixes to a source file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixApplication {
+    pub code: String,
+    pub applied: Vec<AppliedFix>,         // Applied fixes in file order
+    pub skipped_suggestions: Vec<String>, // Rule names of suggestion fixes left unapplied
+    pub stats: FixStats,
+}
+
+impl FixApplication {
+    /// Rule names of the applied fixes, in file order
+    pub fn applied_rules(&self) -> Vec<&str> {
+        self.applied.iter().map(|fix| fix.rule_id.as_str()).collect()
+    }
+}
+
+/// How a multi-pass fix run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixConvergence {
+    pub iterations: u32, // Fix passes run, including the final pass that applied nothing
+    pub converged: bool, // A pass applied no fix before the iteration cap
+}
+
+/// Run fix passes over `source` until one applies no fix or `max_iterations` passes have run.
+///
+/// `pass` lints the given code and applies its fixes. Applied fixes and stats are
+/// accumulated across passes; the returned code is the output of the last pass, and
+/// fixes from earlier passes are moved to their lines in it.
+pub fn fix_until_converged<P>(source: &str, max_iterations: u32, mut pass: P) -> (FixApplication, FixConvergence)
+where
+    P: FnMut(&str) -> FixApplication,
+{
+    let mut combined = FixApplication {
+        code: source.to_string(),
+        ..FixApplication::default()
+    };
+    let mut convergence = FixConvergence {
+        iterations: 0,
+        converged: false,
+    };
+
+    while convergence.iterations < max_iterations.max(1) {
+        let result = pass(&combined.code);
+        convergence.iterations += 1;
+        combined.stats.merge(&result.stats);
+        combined.skipped_suggestions = result.skipped_suggestions;
+
+        if result.applied.is_empty() || result.code == combined.code {
+            convergence.converged = true;
+            break;
+        }
+        shift_past(&mut combined.applied, &result.applied);
+        combined.applied.extend(result.applied);
+        combined.code = result.code;
+    }
+
+    if !convergence.converged {
+        moon_warn!("Fixes did not converge after {} iteration(s); leaving remaining fixes unapplied", convergence.iterations);
+    }
+
+    (combined, convergence)
+}
+
+/// Move `earlier` fixes to their lines after `later` fixes, applied on top of them, added or removed lines above
+fn shift_past(earlier: &mut [AppliedFix], later: &[AppliedFix]) {
+    for fix in earlier {
+        let mut added_above = 0;
+        let mut shift = 0;
+        for later_fix in later {
+            // `later_fix.line` is in the newer code; map its end back to the code `fix` refers to
+            let original_end_line = i64::from(later_fix.line) - added_above + later_fix.original_text.matches('\n').count() as i64;
+            if original_end_line < i64::from(fix.line) {
+                shift += later_fix.added_lines();
+            }
+            added_above += later_fix.added_lines();
+        }
+        fix.line = (i64::from(fix.line) + shift).max(1) as u32;
+        fix.end_line = (i64::from(fix.end_line) + shift).max(1) as u32;
+    }
+}
+
+/// `code` with a trailing `// fixed by <rule>` comment on the last line of each applied fix.
+///
+/// Used for dry runs, where the fixed code is shown but never written.
+pub fn annotate_provenance(code: &str, applied: &[AppliedFix]) -> String {
+    let mut rules_by_line: BTreeMap<u32, Vec<&str>> = BTreeMap::new();
+    for fix in applied {
+        let rules = rules_by_line.entry(fix.end_line).or_default();
+        if !rules.contains(&fix.rule_id.as_str()) {
+            rules.push(&fix.rule_id);
+        }
+    }
+
+    let mut annotated: Vec<String> = code.split('\n').map(str::to_string).collect();
+    for (line, rules) in rules_by_line {
+        if let Some(text) = annotated.get_mut(line as usize - 1) {
+            text.push_str(&format!(" // fixed by {}", rules.join(", ")));
+        }
+    }
+    annotated.join("\n")
+}
+
+/// Attach each AI fix in `diagnostics` as a patch instead of an applicable fix.
+///
+/// The fix becomes the unified diff of applying it to `source`, stored on `patch`, and the
+/// diagnostic is marked not fixable so [`apply_fixes`] skips it whatever its confidence.
+pub fn propose_ai_fixes(source: &str, diagnostics: &mut [LintDiagnostic]) {
+    for diagnostic in diagnostics.iter_mut().filter(|diagnostic| diagnostic.fix_available && is_ai_suggestion(diagnostic)) {
+        diagnostic.fix_available = false;
+        let Some(replacement) = diagnostic.suggested_fix.as_deref() else {
+            continue;
+        };
+
+        let start = line_column_to_offset(source, diagnostic.line, diagnostic.column);
+        let end = line_column_to_offset(source, diagnostic.end_line, diagnostic.end_column);
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => {
+                let mut fixed = source.to_string();
+                fixed.replace_range(start..end, replacement);
+                let patch = TextDiff::from_lines(source, &fixed)
+                    .unified_diff()
+                    .header(&format!("a/{}", diagnostic.file_path), &format!("b/{}", diagnostic.file_path))
+                    .to_string();
+                diagnostic.patch = Some(patch);
+            }
+            _ => moon_warn!("No patch for {} fix in {}: span is out of range", diagnostic.rule_name, diagnostic.file_path),
+        }
+    }
+}
+
+/// Apply the fixes carried by `diagnostics` to `source`.
+///
+/// `safety_of` maps a rule name to its fix safety. Fixes are applied back to front so
+/// earlier spans stay valid; a fix overlapping one already applied is dropped. Every fix
+/// that passes the safety gate counts as attempted in `FixApplication::stats`.
+pub fn apply_fixes<F>(source: &str, diagnostics: &[LintDiagnostic], options: FixOptions, safety_of: F) -> FixApplication
+where
+    F: Fn(&str) -> FixSafety,
+{
+    let mut edits: Vec<(usize, usize, &str, &LintDiagnostic, FixSafety)> = Vec::new();
+    let mut skipped_suggestions = Vec::new();
+    let mut stats = FixStats::default();
+
+    for diagnostic in diagnostics {
+        let Some(replacement) = diagnostic.suggested_fix.as_deref().filter(|_| diagnostic.fix_available) else {
+            continue;
+        };
+
+        let safety = safety_of(&diagnostic.rule_name);
+        if !safety.is_applied(options.apply_suggestions) {
+            skipped_suggestions.push(diagnostic.rule_name.clone());
+            continue;
+        }
+
+        stats.record_attempt(&diagnostic.rule_name);
+        let confidence = diagnostic.ai_confidence;
+        if let Some(confidence) = confidence {
+            stats.record_confidence(&diagnostic.rule_name, confidence);
+        }
+        let below_confidence = options.min_ai_confidence.zip(confidence).is_some_and(|(min, confidence)| confidence / 100.0 < min);
+        if below_confidence {
+            stats.record_rejected(&diagnostic.rule_name, FixRejectionReason::Confidence);
+            continue;
+        }
+
+        let start = line_column_to_offset(source, diagnostic.line, diagnostic.column);
+        let end = line_column_to_offset(source, diagnostic.end_line, diagnostic.end_column);
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => edits.push((start, end, replacement, diagnostic, safety)),
+            _ => moon_warn!("Skipping {} fix in {}: span is out of range", diagnostic.rule_name, diagnostic.file_path),
+        }
+    }
+
+    edits.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+
+    let verify_syntax = options.verify_syntax.filter(|source_type| parses(source, *source_type));
+    let mut code = source.to_string();
+    let mut applied_edits = Vec::new();
+    let mut applied_from = usize::MAX;
+    for (start, end, replacement, diagnostic, safety) in edits {
+        if end > applied_from {
+            moon_debug!("Skipping overlapping {} fix at {}:{}", diagnostic.rule_name, diagnostic.line, diagnostic.column);
+            stats.record_rejected(&diagnostic.rule_name, FixRejectionReason::Conflict);
+            continue;
+        }
+
+        let deleted_lines = code[start..end].lines().count().saturating_sub(replacement.lines().count());
+        if deleted_lines > MAX_FIX_DELETED_LINES {
+            moon_warn!(
+                "Rejecting {} fix in {}: it deletes {} lines",
+                diagnostic.rule_name,
+                diagnostic.file_path,
+                deleted_lines
+            );
+            stats.record_rejected(&diagnostic.rule_name, FixRejectionReason::DeletionGuard);
+            continue;
+        }
+
+        let mut candidate = code.clone();
+        candidate.replace_range(start..end, replacement);
+        if verify_syntax.is_some_and(|source_type| !parses(&candidate, source_type)) {
+            moon_warn!("Rejecting {} fix in {}: the result does not parse", diagnostic.rule_name, diagnostic.file_path);
+            stats.record_rejected(&diagnostic.rule_name, FixRejectionReason::Syntax);
+            continue;
+        }
+
+        applied_edits.push((start, end, replacement, diagnostic, safety, code[start..end].to_string()));
+        code = candidate;
+        stats.record_applied(&diagnostic.rule_name);
+        applied_from = start;
+    }
+    applied_edits.reverse();
+
+    // Edits are now in file order; each one moves the ones after it by its length change
+    let mut growth: isize = 0;
+    let mut applied = Vec::with_capacity(applied_edits.len());
+    for (start, end, replacement, diagnostic, safety, original_text) in applied_edits {
+        let fixed_start = start.saturating_add_signed(growth);
+        let (line, column) = offset_to_line_column(&code, fixed_start as u32);
+        let (end_line, _) = offset_to_line_column(&code, (fixed_start + replacement.trim_end_matches('\n').len()) as u32);
+        applied.push(AppliedFix {
+            rule_id: diagnostic.rule_name.clone(),
+            line,
+            column,
+            end_line,
+            original_text,
+            fixed_text: replacement.to_string(),
+            safety,
+        });
+        growth += replacement.len() as isize - (end - start) as isize;
+    }
+
+    FixApplication {
+        code,
+        applied,
+        skipped_suggestions,
+        stats,
+    }
+}
+
+fn parses(code: &str, source_type: SourceType) -> bool {
+    let allocator = Allocator::default();
+    parse_guarded(&allocator, code, source_type).is_ok_and(|parsed| parsed.errors.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rulebase::static_rules;
+    use crate::types::DiagnosticSeverity;
+
+    fn fix(rule_name: &str, line: u32, column: u32, end_column: u32, replacement: &str) -> LintDiagnostic {
+        LintDiagnostic {
+            rule_name: rule_name.to_string(),
+            message: String::new(),
+            file_path: "test.ts".to_string(),
+            line,
+            column,
+            end_line: line,
+            end_column,
+            severity: DiagnosticSeverity::Warning,
+            fix_available: true,
+            suggested_fix: Some(replacement.to_string()),
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
+        }
+    }
+
+    const SOURCE: &str = "let a = 1;\nif (a == b) {}\n";
+
+    fn diagnostics() -> Vec<LintDiagnostic> {
+        vec![fix("prefer-const", 1, 1, 4, "const"), fix("eqeqeq", 2, 7, 9, "===")]
+    }
+
+    #[test]
+    fn test_suggestion_fix_not_applied_in_default_fix_mode() {
+        let result = apply_fixes(SOURCE, &diagnostics(), FixOptions::default(), static_rules::fix_safety);
+
+        assert_eq!(result.code, "const a = 1;\nif (a == b) {}\n");
+        assert_eq!(result.applied_rules(), vec!["prefer-const"]);
+        assert_eq!(result.skipped_suggestions, vec!["eqeqeq"]);
+    }
+
+    #[test]
+    fn test_suggestion_fix_applied_with_opt_in() {
+        let options = FixOptions {
+            apply_suggestions: true,
+            ..FixOptions::default()
+        };
+        let result = apply_fixes(SOURCE, &diagnostics(), options, static_rules::fix_safety);
+
+        assert_eq!(result.code, "const a = 1;\nif (a === b) {}\n");
+        assert_eq!(result.applied_rules(), vec!["prefer-const", "eqeqeq"]);
+        assert!(result.skipped_suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_applied_fixes_record_their_rule_and_location() {
+        let options = FixOptions {
+            apply_suggestions: true,
+            ..FixOptions::default()
+        };
+        let result = apply_fixes(SOURCE, &diagnostics(), options, static_rules::fix_safety);
+
+        let provenance: Vec<_> = result
+            .applied
+            .iter()
+            .map(|fix| (fix.rule_id.as_str(), fix.line, fix.column, fix.original_text.as_str(), fix.fixed_text.as_str()))
+            .collect();
+        assert_eq!(provenance, vec![("prefer-const", 1, 1, "let", "const"), ("eqeqeq", 2, 7, "==", "===")]);
+        assert_eq!(
+            annotate_provenance(&result.code, &result.applied),
+            "const a = 1; // fixed by prefer-const\nif (a === b) {} // fixed by eqeqeq\n"
+        );
+    }
+
+    #[test]
+    fn test_suggest_only_attaches_ai_patches_and_applies_static_fixes() {
+        let mut ai_fix = fix("ai-behavioral:naming", 2, 5, 6, "count");
+        ai_fix.message = "Rename `a` (AI confidence: 99.0%)".to_string();
+        ai_fix.ai_confidence = Some(99.0);
+        let mut diagnostics = vec![fix("prefer-const", 1, 1, 4, "const"), ai_fix];
+
+        propose_ai_fixes(SOURCE, &mut diagnostics);
+        let result = apply_fixes(SOURCE, &diagnostics, FixOptions::default(), static_rules::fix_safety);
+
+        assert_eq!(result.code, "const a = 1;\nif (a == b) {}\n");
+        assert_eq!(result.applied_rules(), vec!["prefer-const"]);
+        assert!(diagnostics[0].patch.is_none());
+        let ai_fix = &diagnostics[1];
+        assert!(!ai_fix.fix_available);
+        assert_eq!(
+            ai_fix.patch.as_deref(),
+            Some("--- a/test.ts\n+++ b/test.ts\n@@ -1,2 +1,2 @@\n let a = 1;\n-if (a == b) {}\n+if (count == b) {}\n")
+        );
+    }
+
+    #[test]
+    fn test_safe_fix_always_applies() {
+        for apply_suggestions in [false, true] {
+            let options = FixOptions {
+                apply_suggestions,
+                ..FixOptions::default()
+            };
+            let result = apply_fixes(SOURCE, &diagnostics()[..1], options, static_rules::fix_safety);
+            assert!(result.code.starts_with("const a = 1;"));
+        }
+    }
+
+    #[test]
+    fn test_ai_fix_applies_only_with_suggestions() {
+        let diagnostics = vec![fix("prefer-const", 1, 1, 4, "const"), fix("ai-behavioral:naming", 2, 5, 6, "count")];
+
+        let default = apply_fixes(SOURCE, &diagnostics, FixOptions::default(), static_rules::fix_safety);
+        assert_eq!(default.code, "const a = 1;\nif (a == b) {}\n");
+        assert_eq!(default.skipped_suggestions, vec!["ai-behavioral:naming".to_string()]);
+
+        let options = FixOptions {
+            apply_suggestions: true,
+            ..FixOptions::default()
+        };
+        let opted_in = apply_fixes(SOURCE, &diagnostics, options, static_rules::fix_safety);
+        assert_eq!(opted_in.code, "const a = 1;\nif (count == b) {}\n");
+    }
+
+    #[test]
+    fn test_stats_record_syntax_rejection_and_applied_fix() {
+        let diagnostics = vec![fix("prefer-const", 1, 1, 4, "const"), fix("no-broken-fix", 2, 7, 9, "= =")];
+        let options = FixOptions {
+            apply_suggestions: true,
+            ..FixOptions::default()
+        }
+        .with_syntax_check(SourceType::ts());
+
+        let result = apply_fixes(SOURCE, &diagnostics, options, static_rules::fix_safety);
+
+        assert_eq!(result.code, "const a = 1;\nif (a == b) {}\n");
+        let applied = result.stats.rule("prefer-const").unwrap();
+        assert_eq!((applied.attempted, applied.applied, applied.rejected_total()), (1, 1, 0));
+        let rejected = result.stats.rule("no-broken-fix").unwrap();
+        assert_eq!((rejected.attempted, rejected.applied), (1, 0));
+        assert_eq!(rejected.rejected, BTreeMap::from([(FixRejectionReason::Syntax, 1)]));
+
+        let totals = result.stats.totals();
+        assert_eq!((totals.attempted, totals.applied, totals.rejected_total()), (2, 1, 1));
+    }
+
+    #[test]
+    fn test_fix_passes_converging_in_two_iterations_are_reported() {
+        let mut passes = 0;
+        let (result, convergence) = fix_until_converged(SOURCE, 5, |code| {
+            passes += 1;
+            let diagnostics = if code.starts_with("let") { diagnostics() } else { Vec::new() };
+            apply_fixes(code, &diagnostics, FixOptions::default(), static_rules::fix_safety)
+        });
+
+        assert_eq!(convergence, FixConvergence { iterations: 2, converged: true });
+        assert_eq!(passes, 2);
+        assert_eq!(result.code, "const a = 1;\nif (a == b) {}\n");
+        assert_eq!(result.applied_rules(), vec!["prefer-const"]);
+        assert_eq!(result.stats.rule("prefer-const").unwrap().applied, 1);
+    }
+
+    #[test]
+    fn test_oscillating_fixes_hit_the_iteration_cap() {
+        // Each pass flips `let` and `var`, so no pass is ever a fixpoint
+        let (result, convergence) = fix_until_converged(SOURCE, 3, |code| {
+            let replacement = if code.starts_with("let") { "var" } else { "let" };
+            apply_fixes(code, &[fix("prefer-const", 1, 1, 4, replacement)], FixOptions::default(), static_rules::fix_safety)
+        });
+
+        assert_eq!(convergence, FixConvergence { iterations: 3, converged: false });
+        assert_eq!(result.code, "var a = 1;\nif (a == b) {}\n");
+        assert_eq!(result.stats.rule("prefer-const").unwrap().applied, 3);
+    }
+
+    #[test]
+    fn test_low_confidence_and_large_deletion_fixes_rejected() {
+        let source = format!("let a = 1;\n{}", "call();\n".repeat(MAX_FIX_DELETED_LINES + 2));
+        let mut low_confidence = fix("ai-behavioral:rename", 1, 5, 6, "total");
+        low_confidence.message = "Rename variable (AI confidence: 40.0%)".to_string();
+        low_confidence.ai_confidence = Some(40.0);
+        let deletion = LintDiagnostic {
+            end_line: MAX_FIX_DELETED_LINES as u32 + 3,
+            ..fix("no-dead-code", 2, 1, 1, "")
+        };
+        let options = FixOptions {
+            min_ai_confidence: Some(0.8),
+            apply_suggestions: true,
+            ..FixOptions::default()
+        };
+
+        let result = apply_fixes(&source, &[low_confidence, deletion], options, static_rules::fix_safety);
+
+        assert_eq!(result.code, source);
+        assert_eq!(
+            result.stats.rule("ai-behavioral:rename").unwrap().rejected,
+            BTreeMap::from([(FixRejectionReason::Confidence, 1)])
+        );
+        assert_eq!(
+            result.stats.rule("no-dead-code").unwrap().rejected,
+            BTreeMap::from([(FixRejectionReason::DeletionGuard, 1)])
+        );
+    }
+}
@@ -28,6 +28,8 @@ pub struct RuleDefinition {
     pub dependencies: Vec<String>,
     #[serde(default)]
     pub config_schema: Option<serde_json::Value>,
+    #[serde(default)]
+    pub applies_to: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -0,0 +1,491 @@
+//! # Import Cycle Diagnostics
+//!
+//! Builds the project [`DependencyGraph`] from relative `import`/`export ... from`
+//! statements, records the shortest cycle through each file on one in
+//! `circular_dependencies`, and reports each import statement on those cycles as an
+//! `import-cycle` diagnostic. Enumerating every elementary cycle is exponential in dense
+//! graphs, so the search stops at one cycle per file. A simple feedback-edge heuristic
+//! picks the import that participates in the most recorded cycles as the suggested
+//! break point, so the diagnostics say where to cut, not just that a cycle exists.
+//!
+//! Runs see the rule through [`configured_import_cycles`]: it is configured like any other
+//! rule, and each file's diagnostics pass its suppressions and `linting.ignore_messages`.
+//!
+//! @category rulebase
+//! @safe team
+//! @mvp enhanced
+//! @complexity medium
+//! @since 2.0.0
+
+use super::active_rules::ActiveRuleSet;
+use super::rule_aliases::RuleAliases;
+use super::severities;
+use super::static_rules::{offset_to_line_column, span_diagnostic};
+use super::suppressions::{apply_suppressions, ignore_messages};
+use super::RuleImplementation;
+use crate::buffer_lint::{canonical_diagnostics, parse_guarded};
+use crate::config::MoonShineConfig;
+use crate::rule_types::{with_rule_scopes, FixSafety, FixStatus, RuleCategory, RuleMetadata};
+use crate::types::{DependencyGraph, LintDiagnostic};
+use oxc_allocator::Allocator;
+use oxc_ast::ast::Statement;
+use oxc_span::{SourceType, Span};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Component, Path, PathBuf};
+
+pub const IMPORT_CYCLE_RULE: &str = "import-cycle";
+
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// A single import statement that resolves to another project file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSite {
+    pub from: String,
+    pub to: String,
+    pub specifier: String,
+    pub line: u32,
+}
+
+/// The import whose removal breaks the most cycles
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuggestedBreak {
+    pub import: ImportSite,
+    pub cycles_broken: usize,
+    pub total_cycles: usize,
+}
+
+/// Dependency graph, cycle break suggestion and per-import diagnostics for a project
+#[derive(Debug, Clone, Default)]
+pub struct ImportCycleReport {
+    pub graph: DependencyGraph,
+    pub suggested_break: Option<SuggestedBreak>,
+    pub diagnostics: Vec<LintDiagnostic>,
+}
+
+struct ParsedImport {
+    specifier: String,
+    span: Span,
+}
+
+/// Metadata of the project-wide `import-cycle` rule, which runs after the per-file rules
+pub fn import_cycle_rule() -> RuleMetadata {
+    RuleMetadata {
+        id: IMPORT_CYCLE_RULE.to_string(),
+        name: IMPORT_CYCLE_RULE.to_string(),
+        description: "Import statements that form a cycle between project files".to_string(),
+        category: RuleCategory::Maintainability,
+        severity: severities::default_severity(IMPORT_CYCLE_RULE),
+        fix_status: FixStatus::None,
+        fix_safety: FixSafety::Suggestion,
+        ai_enhanced: false,
+        cost: 1,
+        tags: vec!["imports".to_string()],
+        dependencies: vec![],
+        implementation: RuleImplementation::OxcStatic {
+            rule_name: IMPORT_CYCLE_RULE.to_string(),
+        },
+        config_schema: None,
+        applies_to: None,
+    }
+}
+
+/// Import cycle diagnostics of `files` as `config` has the rule: none when it is turned off,
+/// at its configured severity, only in files its scope matches, and without those each
+/// file suppresses or `linting.ignore_messages` drops
+pub fn configured_import_cycles(files: &[(String, String)], config: &MoonShineConfig) -> Vec<LintDiagnostic> {
+    let linting = &config.linting;
+    let rules = ActiveRuleSet::resolve(&[import_cycle_rule()], linting).filter_rules(&[import_cycle_rule()]);
+    let Some(rule) = with_rule_scopes(&rules, &linting.rule_scopes).pop() else {
+        return Vec::new();
+    };
+
+    let mut by_file: BTreeMap<String, Vec<LintDiagnostic>> = BTreeMap::new();
+    for mut diagnostic in analyze_import_cycles(files).diagnostics {
+        diagnostic.severity = severities::to_diagnostic_severity(&rule.severity);
+        by_file.entry(diagnostic.file_path.clone()).or_default().push(diagnostic);
+    }
+
+    let aliases = RuleAliases::from_config(linting);
+    let mut diagnostics = Vec::new();
+    for (file_path, file_diagnostics) in by_file {
+        let Some((_, source)) = files.iter().find(|(path, _)| normalize(Path::new(path)) == file_path) else {
+            continue;
+        };
+        if !rule.applies_to_file(&file_path) {
+            continue;
+        }
+        let suppression = apply_suppressions(source, &file_path, canonical_diagnostics(file_diagnostics, &aliases), false, &aliases);
+        diagnostics.extend(ignore_messages(suppression.diagnostics, &linting.ignore_messages, &aliases));
+    }
+    diagnostics
+}
+
+/// Analyze `(path, source)` pairs for import cycles
+pub fn analyze_import_cycles(files: &[(String, String)]) -> ImportCycleReport {
+    let paths: Vec<String> = files.iter().map(|(path, _)| normalize(Path::new(path))).collect();
+    let sites = import_sites(files, &paths, false);
+
+    let mut graph = DependencyGraph {
+        nodes: paths.clone(),
+        edges: sites.keys().copied().collect(),
+        circular_dependencies: Vec::new(),
+    };
+
+    let cycles = find_cycles(paths.len(), &graph.edges);
+    graph.circular_dependencies = cycles.iter().map(|cycle| cycle.iter().map(|&node| paths[node].clone()).collect()).collect();
+    if cycles.is_empty() {
+        return ImportCycleReport {
+            graph,
+            ..ImportCycleReport::default()
+        };
+    }
+
+    // Feedback-edge heuristic: the edge shared by the most cycles
+    let mut edge_cycles: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for (cycle_index, cycle) in cycles.iter().enumerate() {
+        for edge in cycle_edges(cycle) {
+            edge_cycles.entry(edge).or_default().push(cycle_index);
+        }
+    }
+    let break_edge = edge_cycles
+        .iter()
+        .max_by(|a, b| a.1.len().cmp(&b.1.len()).then_with(|| b.0.cmp(a.0)))
+        .map(|(edge, _)| *edge)
+        .expect("cycles have edges");
+
+    let (break_import, break_line) = &sites[&break_edge][0];
+    let suggested_break = SuggestedBreak {
+        import: ImportSite {
+            from: paths[break_edge.0].clone(),
+            to: paths[break_edge.1].clone(),
+            specifier: break_import.specifier.clone(),
+            line: *break_line,
+        },
+        cycles_broken: edge_cycles[&break_edge].len(),
+        total_cycles: cycles.len(),
+    };
+
+    let mut diagnostics = Vec::new();
+    for (edge, cycle_indices) in &edge_cycles {
+        let (from, _) = *edge;
+        let source = &files[from].1;
+        let cycle = describe_cycle(&cycles[cycle_indices[0]], &paths);
+
+        for (import, _) in &sites[edge] {
+            let message = if *edge == break_edge {
+                format!(
+                    "Import of '{}' is in {} of {} import cycle(s) (e.g. {}); removing it is the suggested break point",
+                    import.specifier, suggested_break.cycles_broken, suggested_break.total_cycles, cycle
+                )
+            } else {
+                format!(
+                    "Import of '{}' is part of an import cycle ({}); suggested break point: '{}' in {}:{}",
+                    import.specifier, cycle, suggested_break.import.specifier, suggested_break.import.from, suggested_break.import.line
+                )
+            };
+            diagnostics.push(span_diagnostic(
+                IMPORT_CYCLE_RULE,
+                message,
+                source,
+                &paths[from],
+                import.span,
+                severities::default_diagnostic_severity(IMPORT_CYCLE_RULE),
+                None,
+            ));
+        }
+    }
+    diagnostics.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line)));
+
+    moon_info!(
+        "Found {} import cycle(s); suggested break: '{}' in {}",
+        cycles.len(),
+        suggested_break.import.specifier,
+        suggested_break.import.from
+    );
+
+    ImportCycleReport {
+        graph,
+        suggested_break: Some(suggested_break),
+        diagnostics,
+    }
+}
+
+/// Dependency graph of `(path, source)` pairs including type-only imports, without cycle detection
+pub fn import_graph(files: &[(String, String)]) -> DependencyGraph {
+    let paths: Vec<String> = files.iter().map(|(path, _)| normalize(Path::new(path))).collect();
+    let edges = import_sites(files, &paths, true).into_keys().collect();
+    DependencyGraph {
+        nodes: paths,
+        edges,
+        circular_dependencies: Vec::new(),
+    }
+}
+
+/// Import statements per (importer, imported) file pair
+fn import_sites(files: &[(String, String)], paths: &[String], include_type_imports: bool) -> BTreeMap<(usize, usize), Vec<(ParsedImport, u32)>> {
+    let index_of: HashMap<&str, usize> = paths.iter().enumerate().map(|(index, path)| (path.as_str(), index)).collect();
+
+    let mut sites: BTreeMap<(usize, usize), Vec<(ParsedImport, u32)>> = BTreeMap::new();
+    for (from, (_, source)) in files.iter().enumerate() {
+        for import in parse_imports(&paths[from], source, include_type_imports) {
+            let Some(to) = resolve(&paths[from], &import.specifier, &index_of) else {
+                continue;
+            };
+            let (line, _) = offset_to_line_column(source, import.span.start);
+            sites.entry((from, to)).or_default().push((import, line));
+        }
+    }
+    sites
+}
+
+fn parse_imports(file_path: &str, source: &str, include_type_imports: bool) -> Vec<ParsedImport> {
+    let source_type = SourceType::from_path(Path::new(file_path)).unwrap_or_default();
+    let allocator = Allocator::default();
+    let ret = match parse_guarded(&allocator, source, source_type) {
+        Ok(ret) if ret.errors.is_empty() => ret,
+        Ok(_) => {
+            moon_debug!("Skipping imports of {}: parse errors", file_path);
+            return Vec::new();
+        }
+        Err(message) => {
+            moon_debug!("Skipping imports of {}: {}", file_path, message);
+            return Vec::new();
+        }
+    };
+
+    ret.program
+        .body
+        .iter()
+        .filter_map(|statement| {
+            let (source, span) = match statement {
+                // Type-only imports are erased at compile time and cannot form runtime cycles
+                Statement::ImportDeclaration(declaration) if declaration.import_kind.is_type() && !include_type_imports => return None,
+                Statement::ImportDeclaration(declaration) => (&declaration.source, declaration.span),
+                Statement::ExportAllDeclaration(declaration) => (&declaration.source, declaration.span),
+                Statement::ExportNamedDeclaration(declaration) => (declaration.source.as_ref()?, declaration.span),
+                _ => return None,
+            };
+            Some(ParsedImport {
+                specifier: source.value.to_string(),
+                span,
+            })
+        })
+        .collect()
+}
+
+/// Resolve a relative specifier against the known project files
+fn resolve(from: &str, specifier: &str, index_of: &HashMap<&str, usize>) -> Option<usize> {
+    if !specifier.starts_with('.') {
+        return None;
+    }
+
+    let base = normalize(&Path::new(from).parent().unwrap_or(Path::new("")).join(specifier));
+    // TypeScript ESM imports name the emitted `.js` file
+    let stem = base.strip_suffix(".js").unwrap_or(&base);
+
+    std::iter::once(base.clone())
+        .chain(RESOLVE_EXTENSIONS.iter().map(|extension| format!("{}.{}", stem, extension)))
+        .chain(RESOLVE_EXTENSIONS.iter().map(|extension| format!("{}/index.{}", base, extension)))
+        .find_map(|candidate| index_of.get(candidate.as_str()).copied())
+}
+
+pub(crate) fn normalize(path: &Path) -> String {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized.to_string_lossy().replace('\\', "/")
+}
+
+/// The shortest cycle through each file on one, each starting at its lowest node index and without duplicates
+fn find_cycles(node_count: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut graph: DiGraph<usize, ()> = DiGraph::new();
+    let nodes: Vec<NodeIndex> = (0..node_count).map(|index| graph.add_node(index)).collect();
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for &(from, to) in edges {
+        graph.add_edge(nodes[from], nodes[to], ());
+        successors[from].push(to);
+    }
+
+    let mut cycles: Vec<Vec<usize>> = Vec::new();
+    for component in tarjan_scc(&graph) {
+        let mut members: Vec<usize> = component.iter().map(|node| graph[*node]).collect();
+        let self_loop = members.len() == 1 && edges.contains(&(members[0], members[0]));
+        if members.len() < 2 && !self_loop {
+            continue;
+        }
+        members.sort_unstable();
+
+        for &start in &members {
+            let Some(mut cycle) = shortest_cycle(start, &members, &successors) else {
+                continue;
+            };
+            let lowest = cycle.iter().enumerate().min_by_key(|(_, node)| **node).map_or(0, |(position, _)| position);
+            cycle.rotate_left(lowest);
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+        }
+    }
+    cycles
+}
+
+/// Breadth-first search for the shortest cycle through `start`, staying inside its component `members`
+fn shortest_cycle(start: usize, members: &[usize], successors: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let mut parent: HashMap<usize, usize> = HashMap::from([(start, start)]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+        for &to in &successors[current] {
+            if to == start {
+                let mut cycle = vec![current];
+                let mut node = current;
+                while node != start {
+                    node = parent[&node];
+                    cycle.push(node);
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if members.binary_search(&to).is_ok() && !parent.contains_key(&to) {
+                parent.insert(to, current);
+                queue.push_back(to);
+            }
+        }
+    }
+    None
+}
+
+fn cycle_edges(cycle: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    cycle
+        .iter()
+        .enumerate()
+        .map(move |(position, &node)| (node, cycle[(position + 1) % cycle.len()]))
+}
+
+fn describe_cycle(cycle: &[usize], paths: &[String]) -> String {
+    cycle
+        .iter()
+        .chain(cycle.first())
+        .map(|&node| paths[node].as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> Vec<(String, String)> {
+        [
+            ("src/a.ts", "import { b } from './b';\nexport const a = b + 1;\n"),
+            ("src/b.ts", "import { c } from './c';\nimport { a } from './a';\nexport const b = 1;\n"),
+            ("src/c.ts", "export { a as c } from './a.js';\n"),
+            ("src/d.ts", "import { a } from './a';\nimport fs from 'node:fs';\n"),
+        ]
+        .into_iter()
+        .map(|(path, source)| (path.to_string(), source.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_overlapping_cycles_suggest_shared_edge() {
+        let report = analyze_import_cycles(&project());
+
+        // a -> b -> a and a -> b -> c -> a share the a -> b edge
+        assert_eq!(report.graph.circular_dependencies.len(), 2);
+        let suggested = report.suggested_break.expect("cycles found");
+        assert_eq!(
+            suggested.import,
+            ImportSite {
+                from: "src/a.ts".to_string(),
+                to: "src/b.ts".to_string(),
+                specifier: "./b".to_string(),
+                line: 1,
+            }
+        );
+        assert_eq!((suggested.cycles_broken, suggested.total_cycles), (2, 2));
+    }
+
+    #[test]
+    fn test_diagnostics_point_at_cycle_import_lines() {
+        let files = project();
+        let report = analyze_import_cycles(&files);
+
+        let locations: Vec<(&str, u32)> = report.diagnostics.iter().map(|d| (d.file_path.as_str(), d.line)).collect();
+        assert_eq!(locations, vec![("src/a.ts", 1), ("src/b.ts", 1), ("src/b.ts", 2), ("src/c.ts", 1)]);
+
+        for diagnostic in &report.diagnostics {
+            let source = &files.iter().find(|(path, _)| *path == diagnostic.file_path).unwrap().1;
+            let line = source.lines().nth(diagnostic.line as usize - 1).unwrap();
+            assert!(line.contains("from './"), "diagnostic should sit on an import: {}", line);
+            assert_eq!(diagnostic.rule_name, IMPORT_CYCLE_RULE);
+        }
+        assert!(report.diagnostics[0].message.contains("suggested break point"));
+        assert!(report.diagnostics[3].message.contains("'./b' in src/a.ts:1"));
+    }
+
+    #[test]
+    fn test_dense_graph_records_one_cycle_per_file() {
+        // Every file imports every other: billions of elementary cycles, one shortest cycle per file
+        let files: Vec<(String, String)> = (0..16)
+            .map(|index| {
+                let imports: String = (0..16).filter(|&other| other != index).map(|other| format!("import './m{}';\n", other)).collect();
+                (format!("src/m{}.ts", index), imports)
+            })
+            .collect();
+        let report = analyze_import_cycles(&files);
+
+        assert!(report.graph.circular_dependencies.len() <= files.len());
+        assert!(report.graph.circular_dependencies.iter().all(|cycle| cycle.len() == 2));
+        assert!(report.suggested_break.is_some());
+    }
+
+    #[test]
+    fn test_configured_cycles_follow_rule_level_suppressions_ignores_and_scope() {
+        let mut files = project();
+        let mut config = MoonShineConfig::default();
+        let reported = |files: &[(String, String)], config: &MoonShineConfig| -> Vec<(String, u32)> {
+            configured_import_cycles(files, config).into_iter().map(|d| (d.file_path, d.line)).collect()
+        };
+        assert_eq!(reported(&files, &config).len(), 4);
+
+        config.linting.rules.insert(IMPORT_CYCLE_RULE.to_string(), "error".to_string());
+        assert!(configured_import_cycles(&files, &config).iter().all(|d| d.severity == crate::types::DiagnosticSeverity::Error));
+        config.linting.rules.insert(IMPORT_CYCLE_RULE.to_string(), "off".to_string());
+        assert!(reported(&files, &config).is_empty());
+        config.linting.rules.clear();
+
+        files[2].1 = format!("// eslint-disable-next-line import-cycle\n{}", files[2].1);
+        config.linting.ignore_messages = vec![crate::config::IgnoreMessage {
+            rule_id: IMPORT_CYCLE_RULE.to_string(),
+            message_regex: "Import of './c'".to_string(),
+        }];
+        assert_eq!(reported(&files, &config), vec![("src/a.ts".to_string(), 1), ("src/b.ts".to_string(), 2)]);
+
+        config.linting.rule_scopes.insert(IMPORT_CYCLE_RULE.to_string(), vec!["src/b.ts".to_string()]);
+        assert_eq!(reported(&files, &config), vec![("src/b.ts".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_acyclic_project_has_no_diagnostics() {
+        let files = vec![
+            ("a.ts".to_string(), "import { b } from './b';\n".to_string()),
+            ("b.ts".to_string(), "export const b = 1;\n".to_string()),
+        ];
+        let report = analyze_import_cycles(&files);
+
+        assert_eq!(report.graph.edges, vec![(0, 1)]);
+        assert!(report.graph.circular_dependencies.is_empty());
+        assert!(report.suggested_break.is_none());
+        assert!(report.diagnostics.is_empty());
+    }
+}
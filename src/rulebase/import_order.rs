@@ -0,0 +1,316 @@
+//! # Deterministic Import Ordering
+//!
+//! Groups the leading imports of a file into builtin, external, internal-alias and relative
+//! modules, in the order `linting.import_order.groups` gives, and sorts each group by
+//! specifier. An out-of-order block gets a single `import-order` diagnostic whose fix
+//! rewrites the whole block, with a blank line between groups.
+//!
+//! Side-effect imports (`import './polyfill'`) stay where they are: they split the block
+//! into runs that are sorted independently, so their order relative to every other import
+//! is kept. Comments on the lines directly above an import, and a comment after it on the
+//! same line, move with it. A block that shares lines with other code or holds a comment
+//! attached to no import is left unchecked rather than rewritten lossily.
+//!
+//! @category rulebase
+//! @safe team
+//! @mvp enhanced
+//! @complexity medium
+//! @since 2.0.0
+
+use super::severities;
+use super::static_rules::span_diagnostic;
+use crate::config::{ImportGroup, ImportOrderConfig};
+use crate::oxc_adapter::comment_preservation::full_comment_span;
+use crate::types::LintDiagnostic;
+use oxc_ast::ast::{ImportDeclaration, Program, Statement};
+use oxc_span::Span;
+
+pub const IMPORT_ORDER_RULE: &str = "import-order";
+
+/// Node.js built-in modules importable without the `node:` prefix
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "timers",
+    "tls",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "worker_threads",
+    "zlib",
+];
+
+/// An import statement with the comments attached to it
+#[derive(Debug)]
+struct ImportChunk<'s> {
+    start: usize,
+    end: usize, // End of the import's last line, excluding the line break
+    text: &'s str,
+    specifier: String,
+    group: Option<ImportGroup>, // `None` for side-effect imports, which never move
+}
+
+/// import-order: flag a leading import block that is not grouped and sorted per `config`.
+pub fn import_order(program: &Program, source: &str, file_path: &str, config: &ImportOrderConfig) -> Vec<LintDiagnostic> {
+    let imports: Vec<&ImportDeclaration> = program
+        .body
+        .iter()
+        .map_while(|statement| match statement {
+            Statement::ImportDeclaration(import) => Some(&**import),
+            _ => None,
+        })
+        .collect();
+    if imports.len() < 2 {
+        return Vec::new();
+    }
+
+    let Some(chunks) = import_chunks(program, source, &imports, config) else {
+        moon_debug!("{}: imports of {} share lines with code or hold detached comments; not checked", IMPORT_ORDER_RULE, file_path);
+        return Vec::new();
+    };
+    let order = sorted_order(&chunks, config);
+    if order.iter().enumerate().all(|(position, &index)| position == index) {
+        return Vec::new();
+    }
+
+    let newline = if source.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut fixed = String::new();
+    let mut previous: Option<&ImportChunk> = None;
+    for chunk in order.iter().map(|&index| &chunks[index]) {
+        if let Some(previous) = previous {
+            fixed.push_str(newline);
+            if previous.group.is_some() && chunk.group.is_some() && previous.group != chunk.group {
+                fixed.push_str(newline);
+            }
+        }
+        fixed.push_str(chunk.text);
+        previous = Some(chunk);
+    }
+
+    let group_order: Vec<&str> = config.groups.iter().map(|group| group.as_str()).collect();
+    vec![span_diagnostic(
+        IMPORT_ORDER_RULE,
+        format!("Imports are not grouped and sorted ({}).", group_order.join(", ")),
+        source,
+        file_path,
+        Span::new(chunks[0].start as u32, chunks[chunks.len() - 1].end as u32),
+        severities::default_diagnostic_severity(IMPORT_ORDER_RULE),
+        Some(fixed),
+    )]
+}
+
+/// Group of a non-side-effect import of `specifier`
+pub fn import_group(specifier: &str, config: &ImportOrderConfig) -> ImportGroup {
+    if specifier == "." || specifier == ".." || specifier.starts_with("./") || specifier.starts_with("../") {
+        ImportGroup::Relative
+    } else if config.internal_prefixes.iter().any(|prefix| specifier.starts_with(prefix.as_str())) {
+        ImportGroup::Internal
+    } else if specifier.starts_with("node:") || NODE_BUILTINS.contains(&specifier.split('/').next().unwrap_or(specifier)) {
+        ImportGroup::Builtin
+    } else {
+        ImportGroup::External
+    }
+}
+
+/// Each import with its attached comments; `None` when the block cannot be rewritten losslessly
+fn import_chunks<'s>(program: &Program, source: &'s str, imports: &[&ImportDeclaration], config: &ImportOrderConfig) -> Option<Vec<ImportChunk<'s>>> {
+    let comments: Vec<(usize, usize)> = program
+        .comments
+        .iter()
+        .map(|comment| full_comment_span(source, comment.span.start as usize, comment.span.end as usize))
+        .collect();
+    let mut chunks: Vec<ImportChunk> = Vec::with_capacity(imports.len());
+
+    for import in imports {
+        let (statement_start, statement_end) = (import.span.start as usize, import.span.end as usize);
+        let previous_end = chunks.last().map_or(0, |chunk| chunk.end);
+
+        let mut start = line_start(source, statement_start);
+        if !source[start..statement_start].trim().is_empty() {
+            return None;
+        }
+        // Comments on the lines directly above belong to the import
+        while let Some(&(comment_start, comment_end)) = comments.iter().rev().find(|&&(_, comment_end)| comment_end <= start) {
+            let comment_line = line_start(source, comment_start);
+            let gap = &source[comment_end..start];
+            let own_line = source[comment_line..comment_start].trim().is_empty();
+            if comment_line < previous_end || !own_line || !gap.trim().is_empty() || gap.matches('\n').count() > 1 {
+                break;
+            }
+            start = comment_line;
+        }
+        // Anything but blank lines between two imports would be lost when they move
+        if !chunks.is_empty() && !source[previous_end..start].trim().is_empty() {
+            return None;
+        }
+
+        let mut end = line_end(source, statement_end);
+        let rest = source[statement_end..end].trim();
+        if !(rest.is_empty() || rest.starts_with("//") || (rest.starts_with("/*") && rest.ends_with("*/"))) {
+            return None;
+        }
+        if source[..end].ends_with('\r') {
+            end -= 1;
+        }
+
+        let specifier = import.source.value.to_string();
+        chunks.push(ImportChunk {
+            start,
+            end,
+            text: &source[start..end],
+            group: import.specifiers.is_some().then(|| import_group(&specifier, config)),
+            specifier,
+        });
+    }
+
+    Some(chunks)
+}
+
+/// Indices of `chunks` in configured order; side-effect imports keep their positions
+fn sorted_order(chunks: &[ImportChunk], config: &ImportOrderConfig) -> Vec<usize> {
+    let sort = |run: &mut Vec<usize>| {
+        run.sort_by_cached_key(|&index| {
+            let chunk = &chunks[index];
+            let rank = chunk
+                .group
+                .and_then(|group| config.groups.iter().position(|configured| *configured == group))
+                .unwrap_or(config.groups.len());
+            let key = if config.case_sensitive { chunk.specifier.clone() } else { chunk.specifier.to_lowercase() };
+            (rank, key)
+        })
+    };
+
+    let mut order = Vec::with_capacity(chunks.len());
+    let mut run = Vec::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        if chunk.group.is_some() {
+            run.push(index);
+            continue;
+        }
+        sort(&mut run);
+        order.append(&mut run);
+        order.push(index);
+    }
+    sort(&mut run);
+    order.append(&mut run);
+    order
+}
+
+fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind('\n').map_or(0, |index| index + 1)
+}
+
+fn line_end(source: &str, offset: usize) -> usize {
+    source[offset..].find('\n').map_or(source.len(), |index| offset + index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rulebase::fixes::{apply_fixes, FixOptions};
+    use crate::rulebase::static_rules;
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    fn reorder(source: &str, config: &ImportOrderConfig) -> Option<String> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        assert!(ret.errors.is_empty(), "fixture should parse");
+        let diagnostics = import_order(&ret.program, source, "src/app.ts", config);
+        assert!(diagnostics.len() <= 1, "one diagnostic per block");
+        (!diagnostics.is_empty()).then(|| apply_fixes(source, &diagnostics, FixOptions::default(), static_rules::fix_safety).code)
+    }
+
+    #[test]
+    fn test_scrambled_imports_grouped_with_comments_preserved() {
+        let source = "\
+import { join } from './paths';
+import 'reflect-metadata';
+// HTTP client
+import axios from 'axios';
+import { readFile } from 'fs'; // promises API later
+import { helper } from '@/utils/helper';
+import 'zone.js';
+import Zod from 'zod';
+import { api } from '../api';
+import { z } from './z';
+import * as Path from 'node:path';
+
+export const x = 1;
+";
+
+        let fixed = reorder(source, &ImportOrderConfig::default()).expect("block is out of order");
+
+        assert_eq!(
+            fixed,
+            "\
+import { join } from './paths';
+import 'reflect-metadata';
+import { readFile } from 'fs'; // promises API later
+
+// HTTP client
+import axios from 'axios';
+
+import { helper } from '@/utils/helper';
+import 'zone.js';
+import * as Path from 'node:path';
+
+import Zod from 'zod';
+
+import { api } from '../api';
+import { z } from './z';
+
+export const x = 1;
+"
+        );
+        assert_eq!(reorder(&fixed, &ImportOrderConfig::default()), None, "the fixed block is in order");
+    }
+
+    #[test]
+    fn test_configured_group_order_and_case_sensitivity() {
+        let source = "import b from 'b';\nimport B from 'B';\nimport { a } from './a';\nimport fs from 'fs';\n";
+        let config = ImportOrderConfig {
+            groups: vec![ImportGroup::Relative, ImportGroup::External, ImportGroup::Builtin],
+            case_sensitive: true,
+            ..ImportOrderConfig::default()
+        };
+
+        assert_eq!(
+            reorder(source, &config).as_deref(),
+            Some("import { a } from './a';\n\nimport B from 'B';\nimport b from 'b';\n\nimport fs from 'fs';\n")
+        );
+        // Ignoring case, `b` and `B` tie and keep their original order
+        assert_eq!(
+            reorder(source, &ImportOrderConfig::default()).as_deref(),
+            Some("import fs from 'fs';\n\nimport b from 'b';\nimport B from 'B';\n\nimport { a } from './a';\n")
+        );
+    }
+}
@@ -10,10 +10,25 @@
 //! @since 4.0.0
 
 // pub mod biome_rules; // Removed - replaced with OXC integration
+pub mod active_rules; // Effective rule set after presets, categories, tags and overrides
+pub mod analyzers; // Custom analyzers registered alongside the built-in rules
+pub mod complexity_delta; // Complexity before and after fixing
+pub mod docs; // Documented good/bad examples per rule
 pub mod execution_engine;
+pub mod explain; // Rationale attached to diagnostics in --explain mode
+pub mod fixes; // Safety-gated application of span fixes
 pub mod generated; // Auto-generated rule definitions
+pub mod import_cycles; // Import cycle diagnostics with suggested break points
+pub mod import_order; // Deterministic grouping and sorting of leading imports
 pub mod presets;
+pub mod profile; // Per-rule timing for --profile runs
+pub mod sampling; // Per-unit seeded RNG for reproducible sampling in parallel runs
+pub mod rule_aliases; // Canonical IDs for rules known under several engine-specific IDs
 pub mod rule_interface;
+pub mod severities; // Default severity per canonical rule ID
+pub mod static_rules; // Deterministic semantic-model rules with span fixes
+pub mod suppressions; // eslint-disable directives and unused-directive reports
+pub mod testing; // Checks documented rule examples against the rules
 
 // Modern rule implementation types
 use serde::{Deserialize, Serialize};
@@ -61,6 +76,7 @@ impl RuleImplementation {
 }
 
 // Re-exports
+pub use analyzers::{register_analyzer, Analyzer, AnalyzerRegistry};
 pub use execution_engine::{ExecutionPlan, RuleExecutionContext, RuleExecutionOutcome, RuleExecutor};
 pub use presets::{available_presets, get_preset, has_preset};
 pub use rule_interface::{Rule, RuleCategory, RuleContext, RuleResult, RuleSeverity};
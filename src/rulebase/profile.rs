@@ -0,0 +1,151 @@
+//! # Rule Profiling
+//!
+//! Per-rule wall-time collection for `--profile` runs. The executor records one
+//! sample per rule per file; samples aggregate into a [`RuleProfile`] sorted by total
+//! time so the slowest rules surface first in the run metrics.
+//!
+//! @category rulebase
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Aggregated timing for a single rule
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleProfileEntry {
+    pub rule: String,
+    pub total: Duration,
+    pub max: Duration,
+    pub calls: usize,
+    pub files: usize,
+}
+
+/// Per-rule timing breakdown, slowest rule first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleProfile {
+    pub entries: Vec<RuleProfileEntry>,
+}
+
+impl RuleProfile {
+    /// Entry for `rule`, if it was executed
+    pub fn entry(&self, rule: &str) -> Option<&RuleProfileEntry> {
+        self.entries.iter().find(|entry| entry.rule == rule)
+    }
+
+    /// Combined wall time across all rules
+    pub fn total(&self) -> Duration {
+        self.entries.iter().map(|entry| entry.total).sum()
+    }
+
+    /// Fold another file's profile into this one, keeping slowest-first order
+    pub fn merge(&mut self, other: RuleProfile) {
+        for incoming in other.entries {
+            match self.entries.iter_mut().find(|entry| entry.rule == incoming.rule) {
+                Some(entry) => {
+                    entry.total += incoming.total;
+                    entry.max = entry.max.max(incoming.max);
+                    entry.calls += incoming.calls;
+                    entry.files += incoming.files;
+                }
+                None => self.entries.push(incoming),
+            }
+        }
+        self.entries.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.rule.cmp(&b.rule)));
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RuleSamples {
+    total: Duration,
+    max: Duration,
+    calls: usize,
+    files: HashSet<String>,
+}
+
+/// Collects rule timings for one or more files
+#[derive(Debug, Clone, Default)]
+pub struct RuleProfiler {
+    samples: HashMap<String, RuleSamples>,
+}
+
+impl RuleProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` and record its wall time against `rule` and `file_path`
+    pub fn time<T>(&mut self, rule: &str, file_path: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(rule, file_path, start.elapsed());
+        result
+    }
+
+    /// Record an externally measured sample
+    pub fn record(&mut self, rule: &str, file_path: &str, elapsed: Duration) {
+        let samples = self.samples.entry(rule.to_string()).or_default();
+        samples.total += elapsed;
+        samples.max = samples.max.max(elapsed);
+        samples.calls += 1;
+        samples.files.insert(file_path.to_string());
+    }
+
+    /// Aggregate the samples into a profile sorted by total time, slowest first
+    pub fn profile(&self) -> RuleProfile {
+        let mut entries: Vec<RuleProfileEntry> = self
+            .samples
+            .iter()
+            .map(|(rule, samples)| RuleProfileEntry {
+                rule: rule.clone(),
+                total: samples.total,
+                max: samples.max,
+                calls: samples.calls,
+                files: samples.files.len(),
+            })
+            .collect();
+        entries.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.rule.cmp(&b.rule)));
+        RuleProfile { entries }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slow_rule_ranks_first() {
+        let mut profiler = RuleProfiler::new();
+
+        for file in ["a.ts", "b.ts"] {
+            profiler.time("fast-rule", file, || ());
+            profiler.time("slow-rule", file, || std::thread::sleep(Duration::from_millis(15)));
+        }
+
+        let profile = profiler.profile();
+        assert_eq!(profile.entries[0].rule, "slow-rule");
+        assert_eq!(profile.entries[0].calls, 2);
+        assert_eq!(profile.entries[0].files, 2);
+        assert!(profile.entries[0].total >= Duration::from_millis(30));
+        assert!(profile.entry("fast-rule").is_some());
+    }
+
+    #[test]
+    fn test_merge_accumulates_per_file_profiles() {
+        let mut first = RuleProfiler::new();
+        first.record("rule-a", "a.ts", Duration::from_millis(5));
+        let mut second = RuleProfiler::new();
+        second.record("rule-a", "b.ts", Duration::from_millis(3));
+        second.record("rule-b", "b.ts", Duration::from_millis(20));
+
+        let mut profile = first.profile();
+        profile.merge(second.profile());
+
+        assert_eq!(profile.entries[0].rule, "rule-b");
+        let rule_a = profile.entry("rule-a").unwrap();
+        assert_eq!((rule_a.total, rule_a.calls, rule_a.files), (Duration::from_millis(8), 2, 2));
+    }
+}
@@ -0,0 +1,106 @@
+//! Canonical rule IDs across engines
+//!
+//! The same logical rule reaches users under several IDs: `no-console` from the built-in
+//! rules, `eslint:no-console` or `eslint/no-console` from ESLint-style sources and
+//! `oxc:noConsole` from OXC. Every such ID maps to one canonical ID, the plain ESLint name,
+//! and suppression matching, severity overrides, `linting.rules` and reported diagnostics
+//! all compare canonical IDs, so configuring or suppressing one ID covers its equivalents.
+//! `linting.rule_aliases` adds project-specific mappings on top of [`BUILTIN_RULE_ALIASES`].
+
+use crate::config::LintingConfig;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Source-specific rule IDs and the canonical ID each stands for
+pub const BUILTIN_RULE_ALIASES: &[(&str, &str)] = &[
+    ("oxc:noConsole", "no-console"),
+    ("oxc:no-console", "no-console"),
+    ("oxc:noDebugger", "no-debugger"),
+    ("oxc:no-debugger", "no-debugger"),
+    ("oxc:eqeqeq", "eqeqeq"),
+    ("oxc:preferConst", "prefer-const"),
+    ("oxc:prefer-const", "prefer-const"),
+    ("oxc:noVar", "no-var"),
+    ("oxc:no-var", "no-var"),
+];
+
+/// Prefixes of ESLint-style IDs whose remainder already is the canonical ID
+const ESLINT_PREFIXES: &[&str] = &["eslint:", "eslint/"];
+
+static BUILTIN_ALIASES: OnceLock<RuleAliases> = OnceLock::new();
+
+/// Alias table mapping rule IDs to canonical IDs
+#[derive(Debug, Clone, Default)]
+pub struct RuleAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl RuleAliases {
+    /// Shared table of the built-in aliases
+    pub fn builtin() -> &'static RuleAliases {
+        BUILTIN_ALIASES.get_or_init(|| Self::default().with_aliases(BUILTIN_RULE_ALIASES.iter().map(|(alias, canonical)| (*alias, *canonical))))
+    }
+
+    /// Built-in aliases plus `linting.rule_aliases`; configured entries win
+    pub fn from_config(linting: &LintingConfig) -> Self {
+        Self::builtin()
+            .clone()
+            .with_aliases(linting.rule_aliases.iter().map(|(alias, canonical)| (alias.as_str(), canonical.as_str())))
+    }
+
+    /// Add `(alias, canonical)` mappings, replacing existing ones for the same alias
+    pub fn with_aliases<'a>(mut self, aliases: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        for (alias, canonical) in aliases {
+            self.aliases.insert(alias.to_string(), canonical.to_string());
+        }
+        self
+    }
+
+    /// Canonical ID of `rule_id`; IDs without an alias are their own canonical ID
+    pub fn canonicalize(&self, rule_id: &str) -> String {
+        if let Some(canonical) = self.aliases.get(rule_id) {
+            return canonical.clone();
+        }
+        match ESLINT_PREFIXES.iter().find_map(|prefix| rule_id.strip_prefix(prefix)) {
+            Some(name) => self.aliases.get(name).cloned().unwrap_or_else(|| name.to_string()),
+            None => rule_id.to_string(),
+        }
+    }
+
+    /// Whether `a` and `b` name the same logical rule
+    pub fn same_rule(&self, a: &str, b: &str) -> bool {
+        a == b || self.canonicalize(a) == self.canonicalize(b)
+    }
+}
+
+/// Canonical ID of `rule_id` under the built-in aliases
+pub fn canonicalize_rule_id(rule_id: &str) -> String {
+    RuleAliases::builtin().canonicalize(rule_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_specific_ids_share_a_canonical_id() {
+        for rule_id in ["no-console", "eslint:no-console", "eslint/no-console", "oxc:noConsole", "oxc:no-console"] {
+            assert_eq!(canonicalize_rule_id(rule_id), "no-console", "{}", rule_id);
+        }
+        assert_eq!(canonicalize_rule_id("oxc:noUndeclaredVariables"), "oxc:noUndeclaredVariables");
+        assert_eq!(canonicalize_rule_id("eslint:custom-rule"), "custom-rule");
+    }
+
+    #[test]
+    fn test_configured_aliases_extend_and_override_builtins() {
+        let mut linting = LintingConfig::default();
+        linting.rule_aliases.insert("legacy:no-log".to_string(), "no-console".to_string());
+        linting.rule_aliases.insert("oxc:eqeqeq".to_string(), "strict-equality".to_string());
+        let aliases = RuleAliases::from_config(&linting);
+
+        assert!(aliases.same_rule("legacy:no-log", "eslint:no-console"));
+        assert_eq!(aliases.canonicalize("oxc:eqeqeq"), "strict-equality");
+        // The shared built-in table is unaffected
+        assert_eq!(canonicalize_rule_id("oxc:eqeqeq"), "eqeqeq");
+    }
+}
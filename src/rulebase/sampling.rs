@@ -0,0 +1,148 @@
+//! Seeded randomness for rules and optimizers that sample
+//!
+//! Sampling must not depend on which thread reaches a file first. Instead of one shared
+//! generator, every unit of work (a rule on a file, an optimizer run for a rule type)
+//! gets its own [`UnitRng`] derived from the run-level `linting.sampling_seed` plus the
+//! unit's identifiers, so parallel runs with the same seed draw the same samples.
+
+/// Deterministic splitmix64 generator for one unit of work
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitRng {
+    state: u64,
+}
+
+impl UnitRng {
+    /// Generator seeded directly with `seed`
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generator for `rule_id` on `file_path` within the run seeded with `run_seed`
+    pub fn for_unit(run_seed: u64, file_path: &str, rule_id: &str) -> Self {
+        Self::new(unit_seed(run_seed, file_path, rule_id))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`; `bound` must be non-zero
+    pub fn below(&mut self, bound: usize) -> usize {
+        // Multiply-shift keeps the bias negligible for the small bounds used here
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// `count` distinct indices out of `0..len`, in ascending order
+    pub fn sample_indices(&mut self, len: usize, count: usize) -> Vec<usize> {
+        let count = count.min(len);
+        let mut indices: Vec<usize> = (0..len).collect();
+        // Partial Fisher-Yates: the first `count` slots end up a uniform sample
+        for slot in 0..count {
+            let pick = slot + self.below(len - slot);
+            indices.swap(slot, pick);
+        }
+        indices.truncate(count);
+        indices.sort_unstable();
+        indices
+    }
+}
+
+/// Seed for one unit of work: the run seed mixed with a stable hash of its identifiers.
+///
+/// FNV-1a is used rather than `DefaultHasher`, whose output may change between Rust
+/// releases and would make recorded seeds irreproducible.
+pub fn unit_seed(run_seed: u64, file_path: &str, rule_id: &str) -> u64 {
+    const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    let mut hash = FNV_OFFSET;
+    for byte in file_path.bytes().chain([0]).chain(rule_id.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    UnitRng::new(run_seed ^ hash).next_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DiagnosticSeverity, LintDiagnostic};
+
+    /// Spot-check rule: flags a random sample of the `console.log` calls in a file
+    fn sampled_console_rule(run_seed: u64, file_path: &str, source: &str) -> Vec<LintDiagnostic> {
+        let candidates: Vec<usize> = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.contains("console.log"))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut rng = UnitRng::for_unit(run_seed, file_path, "sampled-console");
+        rng.sample_indices(candidates.len(), 3)
+            .into_iter()
+            .map(|pick| LintDiagnostic {
+                rule_name: "sampled-console".to_string(),
+                message: "Sampled console.log call".to_string(),
+                file_path: file_path.to_string(),
+                line: candidates[pick] as u32 + 1,
+                column: 1,
+                end_line: candidates[pick] as u32 + 1,
+                end_column: 1,
+                severity: DiagnosticSeverity::Info,
+                fix_available: false,
+                suggested_fix: None,
+                explanation: None,
+                patch: None,
+                ai_confidence: None,
+            })
+            .collect()
+    }
+
+    fn run_in_parallel(seed: u64) -> Vec<(String, Vec<u32>)> {
+        let source: String = (0..40).map(|index| format!("console.log({});\n", index)).collect();
+        let files: Vec<String> = (0..8).map(|index| format!("src/file{}.ts", index)).collect();
+
+        let mut results: Vec<(String, Vec<u32>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .iter()
+                .map(|file| {
+                    let source = &source;
+                    scope.spawn(move || {
+                        let lines = sampled_console_rule(seed, file, source).iter().map(|diagnostic| diagnostic.line).collect();
+                        (file.clone(), lines)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+        results.sort();
+        results
+    }
+
+    #[test]
+    fn test_parallel_sampling_is_reproducible_for_same_seed() {
+        let first = run_in_parallel(42);
+        let second = run_in_parallel(42);
+
+        assert_eq!(first, second);
+        assert!(first.iter().all(|(_, lines)| lines.len() == 3));
+        // Files draw independent samples and other seeds draw different ones
+        assert!(first.iter().any(|(_, lines)| *lines != first[0].1));
+        assert_ne!(first, run_in_parallel(7));
+    }
+
+    #[test]
+    fn test_sample_indices_are_distinct_and_bounded() {
+        let mut rng = UnitRng::for_unit(1, "a.ts", "rule");
+
+        let sample = rng.sample_indices(10, 4);
+        assert_eq!(sample.len(), 4);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(sample.iter().all(|&index| index < 10));
+        assert_eq!(rng.sample_indices(3, 10), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Single source of default rule severities
+//!
+//! Every rule's default severity is looked up here by canonical rule ID: the rule loader
+//! when it builds `RuleMetadata`, the built-in static rules when they emit diagnostics,
+//! and the rule executor when it reports static results. Rulebase rules take their
+//! severity from the embedded rulebase; rules implemented directly in Rust are listed in
+//! [`BUILTIN_SEVERITIES`]. Changing an entry changes the emitted severity everywhere.
+
+use crate::rule_types::RuleSeverity;
+use crate::rulebase::rule_aliases::canonicalize_rule_id;
+use crate::types::DiagnosticSeverity;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Default severities of rules implemented in Rust rather than the JSON rulebase
+pub const BUILTIN_SEVERITIES: &[(&str, RuleSeverity)] = &[
+    ("prefer-const", RuleSeverity::Warning),
+    ("no-var", RuleSeverity::Warning),
+    ("eqeqeq", RuleSeverity::Warning),
+    ("complexity", RuleSeverity::Warning),
+    ("tsdoc-required-tags", RuleSeverity::Warning),
+    ("line-endings", RuleSeverity::Warning),
+    ("no-console", RuleSeverity::Error),
+    ("no-debugger", RuleSeverity::Error),
+    ("async-error-handling", RuleSeverity::Warning),
+    ("unsafe-assertions", RuleSeverity::Warning),
+    ("import-cycle", RuleSeverity::Warning),
+    ("import-order", RuleSeverity::Warning),
+    ("parse-error", RuleSeverity::Error),
+    ("parser-failure", RuleSeverity::Error),
+    ("unused-disable-directive", RuleSeverity::Warning),
+    ("skipped-file", RuleSeverity::Info),
+    ("oxc:noUndeclaredVariables", RuleSeverity::Error),
+];
+
+static DEFAULT_SEVERITIES: OnceLock<SeverityTable> = OnceLock::new();
+
+/// Default severity per canonical rule ID
+#[derive(Debug, Clone, Default)]
+pub struct SeverityTable {
+    severities: HashMap<String, RuleSeverity>,
+}
+
+impl SeverityTable {
+    /// Shared table of built-in and rulebase defaults
+    pub fn global() -> &'static SeverityTable {
+        DEFAULT_SEVERITIES.get_or_init(SeverityTable::defaults)
+    }
+
+    /// Built-in severities plus every rule in the embedded rulebase
+    pub fn defaults() -> Self {
+        let mut table = Self::default();
+        for (rule_id, severity) in BUILTIN_SEVERITIES {
+            table.severities.insert(rule_id.to_string(), severity.clone());
+        }
+
+        #[cfg(feature = "embedded_rulebase")]
+        for rule_def in crate::rulebase::generated::all_rules() {
+            table.severities.insert(rule_def.id.clone(), parse_severity(&rule_def.severity));
+        }
+
+        table
+    }
+
+    /// Replace the default severity of `rule_id` and of every alias of it
+    pub fn with_override(mut self, rule_id: impl Into<String>, severity: RuleSeverity) -> Self {
+        let canonical = canonicalize_rule_id(&rule_id.into());
+        for (_, existing) in self.severities.iter_mut().filter(|(id, _)| canonicalize_rule_id(id) == canonical) {
+            *existing = severity.clone();
+        }
+        self.severities.insert(canonical, severity);
+        self
+    }
+
+    /// Default severity of `rule_id`, if the table has one for it or its canonical ID
+    pub fn severity_of(&self, rule_id: &str) -> Option<&RuleSeverity> {
+        self.severities.get(rule_id).or_else(|| self.severities.get(&canonicalize_rule_id(rule_id)))
+    }
+
+    /// Diagnostic severity for `rule_id`, `Warning` when the rule is unknown
+    pub fn diagnostic_severity(&self, rule_id: &str) -> DiagnosticSeverity {
+        self.severity_of(rule_id).map(to_diagnostic_severity).unwrap_or(DiagnosticSeverity::Warning)
+    }
+
+    /// Rule IDs with no entry in the table
+    pub fn missing<'a>(&self, rule_ids: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+        rule_ids.into_iter().filter(|rule_id| !self.severities.contains_key(*rule_id)).collect()
+    }
+}
+
+/// Default severity of `rule_id` from the shared table
+pub fn default_severity(rule_id: &str) -> RuleSeverity {
+    SeverityTable::global().severity_of(rule_id).cloned().unwrap_or(RuleSeverity::Warning)
+}
+
+/// Default diagnostic severity of `rule_id` from the shared table
+pub fn default_diagnostic_severity(rule_id: &str) -> DiagnosticSeverity {
+    SeverityTable::global().diagnostic_severity(rule_id)
+}
+
+/// Parse a rulebase severity name; matching is case-insensitive and unknown names are warnings
+pub fn parse_severity(value: &str) -> RuleSeverity {
+    match value.to_ascii_lowercase().as_str() {
+        "error" => RuleSeverity::Error,
+        "warning" => RuleSeverity::Warning,
+        "info" => RuleSeverity::Info,
+        "hint" => RuleSeverity::Hint,
+        _ => RuleSeverity::Warning,
+    }
+}
+
+pub(crate) fn to_diagnostic_severity(severity: &RuleSeverity) -> DiagnosticSeverity {
+    match severity {
+        RuleSeverity::Error => DiagnosticSeverity::Error,
+        RuleSeverity::Warning => DiagnosticSeverity::Warning,
+        RuleSeverity::Info => DiagnosticSeverity::Info,
+        RuleSeverity::Hint => DiagnosticSeverity::Hint,
+        RuleSeverity::Custom(_) => DiagnosticSeverity::Warning,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule_registry::RuleRegistry;
+    use crate::rulebase::testing::static_rule;
+    use crate::rulebase::{RuleExecutionContext, RuleExecutor};
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    #[test]
+    fn test_every_registered_rule_has_default_severity() {
+        let registry = RuleRegistry::new().expect("registry loads");
+        let rule_ids: Vec<String> = registry.get_all_rule_names();
+        let table = SeverityTable::global();
+
+        assert!(table.missing(rule_ids.iter().map(String::as_str)).is_empty());
+        assert!(table.missing(BUILTIN_SEVERITIES.iter().map(|(rule_id, _)| *rule_id)).is_empty());
+        for rule_id in &rule_ids {
+            let rule = registry.get_rule(rule_id).unwrap();
+            assert_eq!(Some(&rule.severity), table.severity_of(rule_id));
+        }
+    }
+
+    #[test]
+    fn test_override_changes_emitted_severity() {
+        let code = "let total = 1;\nconsole.log(total);\n";
+        let allocator = Allocator::default();
+        let source_type = SourceType::default();
+        let parsed = Parser::new(&allocator, code, source_type).parse();
+        let ctx = RuleExecutionContext {
+            code,
+            file_path: "src/a.js",
+            source_type,
+            program: Some(&parsed.program),
+        };
+        let rules = vec![static_rule("prefer-const")];
+
+        let default = RuleExecutor::new().evaluate(&rules, &ctx);
+        assert_eq!(default.diagnostics[0].severity, DiagnosticSeverity::Warning);
+
+        let table = SeverityTable::defaults().with_override("prefer-const", RuleSeverity::Error);
+        let overridden = RuleExecutor::new().with_severity_table(table).evaluate(&rules, &ctx);
+        assert_eq!(overridden.diagnostics.len(), 1);
+        assert_eq!(overridden.diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+
+    #[test]
+    fn test_rulebase_severity_names_are_case_insensitive() {
+        assert_eq!(parse_severity("Error"), RuleSeverity::Error);
+        assert_eq!(parse_severity("info"), RuleSeverity::Info);
+        assert_eq!(parse_severity("unknown"), RuleSeverity::Warning);
+    }
+}
@@ -0,0 +1,1335 @@
+//! # Deterministic OXC Static Rules
+//!
+//! Semantic-model backed implementations for the OXC static rules dispatched by
+//! [`RuleExecutor`](super::RuleExecutor). Each rule returns `LintDiagnostic`s whose
+//! `suggested_fix` is the replacement text for the diagnostic's span, so fixes can be
+//! applied without a round-trip through the AI providers.
+//!
+//! @category rulebase
+//! @safe team
+//! @mvp core
+//! @complexity medium
+//! @since 2.0.0
+
+use crate::config::{AsyncErrorGranularity, ComplexityConfig, LineEndingPolicy, UnsafeAssertionsConfig};
+use crate::rule_types::FixSafety;
+use crate::rulebase::import_order::IMPORT_ORDER_RULE;
+use crate::rulebase::severities;
+use crate::types::{DiagnosticSeverity, LintDiagnostic};
+use crate::oxc_adapter::comment_preservation::full_comment_span;
+use oxc_ast::ast::{
+    BinaryExpression, BinaryOperator, BindingIdentifier, BindingPattern, BindingPatternKind, Declaration, Expression, ForStatementInit, MemberExpression,
+    Program, Statement, TSType, UnaryOperator, VariableDeclaration, VariableDeclarationKind,
+};
+use oxc_ast::AstKind;
+use oxc_semantic::{AstNode, Semantic, SymbolId};
+use globset::Glob;
+use oxc_span::{GetSpan, Span};
+use std::collections::{HashMap, HashSet};
+
+/// Convert a byte offset into a 1-based `(line, column)` pair.
+pub fn offset_to_line_column(source: &str, offset: u32) -> (u32, u32) {
+    // An offset inside a multi-byte character counts as that character's start
+    let mut offset = (offset as usize).min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let prefix = &source[..offset];
+    let line = prefix.matches('\n').count() as u32 + 1;
+    let line_start = prefix.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let column = prefix[line_start..].chars().count() as u32 + 1;
+    (line, column)
+}
+
+/// Convert a 1-based `(line, column)` pair back into a byte offset.
+pub fn line_column_to_offset(source: &str, line: u32, column: u32) -> Option<usize> {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        source.match_indices('\n').nth(line as usize - 2).map(|(index, _)| index + 1)?
+    };
+    let line_text = source[line_start..].split('\n').next().unwrap_or("");
+    let column_offset = match line_text.char_indices().nth(column.saturating_sub(1) as usize) {
+        Some((offset, _)) => offset,
+        None if column.saturating_sub(1) as usize == line_text.chars().count() => line_text.len(),
+        None => return None,
+    };
+    Some(line_start + column_offset)
+}
+
+/// Fix safety recorded in the metadata of the built-in rule `rule_name`.
+///
+/// Fixing goes by `RuleMetadata::fix_safety`; this only seeds it. Fixes that keep behavior
+/// (`prefer-const`, `no-var`, line endings, import order) are safe; every other rule, AI
+/// rules included, only suggests. Deleting `console`/`debugger` statements or unused
+/// variables, wrapping awaits in error handling and restructuring complex functions all
+/// change what the code does, `==` to `===` changes results when operand types differ,
+/// and `foo?.bar` yields `undefined` where `foo!.bar` throws.
+pub fn fix_safety(rule_name: &str) -> FixSafety {
+    match rule_name {
+        "prefer-const" | "no-var" | LINE_ENDINGS_RULE | IMPORT_ORDER_RULE => FixSafety::Safe,
+        _ => FixSafety::Suggestion,
+    }
+}
+
+/// Build a diagnostic covering `span` with an optional replacement for that span.
+pub fn span_diagnostic(
+    rule_name: &str,
+    message: impl Into<String>,
+    source: &str,
+    file_path: &str,
+    span: Span,
+    severity: DiagnosticSeverity,
+    suggested_fix: Option<String>,
+) -> LintDiagnostic {
+    let (line, column) = offset_to_line_column(source, span.start);
+    let (end_line, end_column) = offset_to_line_column(source, span.end);
+
+    LintDiagnostic {
+        rule_name: rule_name.to_string(),
+        message: message.into(),
+        file_path: file_path.to_string(),
+        line,
+        column,
+        end_line,
+        end_column,
+        severity,
+        fix_available: suggested_fix.is_some(),
+        suggested_fix,
+        explanation: None,
+        patch: None,
+        ai_confidence: None,
+    }
+}
+
+/// prefer-const: flag `let` declarations whose bindings are never reassigned.
+///
+/// The fix rewrites the `let` keyword to `const`. A declaration is only reported when
+/// every binding it introduces (including every destructuring target) has an initializer
+/// and no write references, it is not a `for (let ...;;)` loop counter, and no binding is
+/// referenced before its initializer finishes (a TDZ-sensitive position such as a
+/// self-referencing initializer or a hoisted function reading the binding early).
+pub fn prefer_const(semantic: &Semantic, source: &str, file_path: &str) -> Vec<LintDiagnostic> {
+    let loop_initializers: HashSet<Span> = semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::ForStatement(for_statement) => match &for_statement.init {
+                Some(ForStatementInit::VariableDeclaration(declaration)) => Some(declaration.span),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::VariableDeclaration(declaration) => Some(declaration),
+            _ => None,
+        })
+        .filter(|declaration| declaration.kind == VariableDeclarationKind::Let && !declaration.declare)
+        .filter(|declaration| !loop_initializers.contains(&declaration.span))
+        .filter(|declaration| is_never_reassigned(semantic, declaration))
+        .map(|declaration| {
+            let keyword_span = Span::new(declaration.span.start, declaration.span.start + 3);
+            span_diagnostic(
+                "prefer-const",
+                "Variable is never reassigned. Use 'const' instead of 'let'.",
+                source,
+                file_path,
+                keyword_span,
+                severities::default_diagnostic_severity("prefer-const"),
+                Some("const".to_string()),
+            )
+        })
+        .collect()
+}
+
+fn is_never_reassigned(semantic: &Semantic, declaration: &VariableDeclaration) -> bool {
+    let symbols = semantic.symbols();
+
+    declaration.declarations.iter().all(|declarator| {
+        // `const` requires an initializer; `let x; x = 1;` is left alone.
+        if declarator.init.is_none() {
+            return false;
+        }
+
+        let mut identifiers = Vec::new();
+        collect_binding_identifiers(&declarator.id, &mut identifiers);
+
+        identifiers.iter().all(|identifier| {
+            let Some(symbol_id) = identifier.symbol_id.get() else {
+                return false;
+            };
+
+            symbols.get_resolved_references(symbol_id).all(|reference| {
+                let reference_span = semantic.nodes().get_node(reference.node_id()).kind().span();
+                !reference.is_write() && reference_span.start >= declarator.span.end
+            })
+        })
+    })
+}
+
+/// Collect every identifier bound by a (possibly destructuring) binding pattern.
+fn collect_binding_identifiers<'p, 'a>(pattern: &'p BindingPattern<'a>, identifiers: &mut Vec<&'p BindingIdentifier<'a>>) {
+    match &pattern.kind {
+        BindingPatternKind::BindingIdentifier(identifier) => identifiers.push(identifier),
+        BindingPatternKind::ObjectPattern(object) => {
+            for property in &object.properties {
+                collect_binding_identifiers(&property.value, identifiers);
+            }
+            if let Some(rest) = &object.rest {
+                collect_binding_identifiers(&rest.argument, identifiers);
+            }
+        }
+        BindingPatternKind::ArrayPattern(array) => {
+            for element in array.elements.iter().flatten() {
+                collect_binding_identifiers(element, identifiers);
+            }
+            if let Some(rest) = &array.rest {
+                collect_binding_identifiers(&rest.argument, identifiers);
+            }
+        }
+        BindingPatternKind::AssignmentPattern(assignment) => collect_binding_identifiers(&assignment.left, identifiers),
+    }
+}
+
+/// no-var: flag `var` declarations and rewrite them to `let` or `const`.
+///
+/// The fix is `const` when every binding has an initializer and is never reassigned, and
+/// `let` otherwise (always `let` in a `for` head). It is withheld, leaving a manual
+/// suggestion, when block scoping would change behavior: a binding is used before its
+/// declaration (hoisting), used outside the block holding the declaration, declared more
+/// than once, or captured by a closure inside a loop, where `let` would give each
+/// iteration its own binding.
+pub fn no_var(semantic: &Semantic, source: &str, file_path: &str) -> Vec<LintDiagnostic> {
+    let severity = severities::default_diagnostic_severity("no-var");
+    let nodes = semantic.nodes();
+    let var_declarations: Vec<(&AstNode, &VariableDeclaration)> = nodes
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::VariableDeclaration(declaration) if declaration.kind == VariableDeclarationKind::Var && !declaration.declare => Some((node, declaration)),
+            _ => None,
+        })
+        .collect();
+
+    // `let` cannot redeclare a binding, so count every `var` declaring each symbol
+    let mut declaration_counts: HashMap<_, usize> = HashMap::new();
+    for (_, declaration) in &var_declarations {
+        for declarator in &declaration.declarations {
+            let mut identifiers = Vec::new();
+            collect_binding_identifiers(&declarator.id, &mut identifiers);
+            for symbol_id in identifiers.iter().filter_map(|identifier| identifier.symbol_id.get()) {
+                *declaration_counts.entry(symbol_id).or_default() += 1;
+            }
+        }
+    }
+
+    var_declarations
+        .into_iter()
+        .map(|(node, declaration)| {
+            let keyword_span = Span::new(declaration.span.start, declaration.span.start + 3);
+            let in_for_head = nodes.parent_node(node.id()).is_some_and(|parent| matches!(parent.kind(), AstKind::ForStatement(_)));
+            let (message, fix) = match block_scoping_hazard(semantic, node, declaration, &declaration_counts) {
+                Some(hazard) => (format!("Unexpected var, use 'let' or 'const' instead. Not auto-fixed: {}.", hazard), None),
+                None if !in_for_head && is_never_reassigned(semantic, declaration) => ("Unexpected var, use 'const' instead.".to_string(), Some("const")),
+                None => ("Unexpected var, use 'let' instead.".to_string(), Some("let")),
+            };
+            span_diagnostic("no-var", message, source, file_path, keyword_span, severity.clone(), fix.map(str::to_string))
+        })
+        .collect()
+}
+
+/// Why scoping the bindings of a `var` declaration to its block would change behavior, if it would
+fn block_scoping_hazard(
+    semantic: &Semantic,
+    node: &AstNode,
+    declaration: &VariableDeclaration,
+    declaration_counts: &HashMap<SymbolId, usize>,
+) -> Option<&'static str> {
+    let nodes = semantic.nodes();
+    let symbols = semantic.symbols();
+
+    // The span `let` would scope the bindings to, and whether a loop re-runs the declaration
+    let mut block = None;
+    let mut in_loop = false;
+    let mut child = node;
+    while let Some(parent) = nodes.parent_node(child.id()) {
+        match parent.kind() {
+            AstKind::Function(_) | AstKind::ArrowFunctionExpression(_) | AstKind::Program(_) => {
+                block.get_or_insert(parent.kind().span());
+                break;
+            }
+            AstKind::BlockStatement(_) | AstKind::FunctionBody(_) | AstKind::StaticBlock(_) | AstKind::SwitchStatement(_) => {
+                block.get_or_insert(parent.kind().span());
+            }
+            AstKind::ForStatement(_) | AstKind::ForInStatement(_) | AstKind::ForOfStatement(_) | AstKind::WhileStatement(_) | AstKind::DoWhileStatement(_) => {
+                block.get_or_insert(parent.kind().span());
+                in_loop = true;
+            }
+            _ => {}
+        }
+        child = parent;
+    }
+    let block = block?;
+    let declaring_function = enclosing_function(semantic, node);
+
+    for declarator in &declaration.declarations {
+        let mut identifiers = Vec::new();
+        collect_binding_identifiers(&declarator.id, &mut identifiers);
+
+        for identifier in identifiers {
+            let Some(symbol_id) = identifier.symbol_id.get() else {
+                return Some("the variable could not be resolved");
+            };
+            if declaration_counts.get(&symbol_id).copied().unwrap_or(0) > 1 || symbols.get_span(symbol_id) != identifier.span {
+                return Some("the variable is declared more than once");
+            }
+
+            for reference in symbols.get_resolved_references(symbol_id) {
+                let reference_node = nodes.get_node(reference.node_id());
+                let reference_span = reference_node.kind().span();
+                if reference_span.start < declarator.span.end {
+                    return Some("the variable is used before its declaration");
+                }
+                if reference_span.start < block.start || reference_span.end > block.end {
+                    return Some("the variable is used outside the enclosing block");
+                }
+                if in_loop && enclosing_function(semantic, reference_node) != declaring_function {
+                    return Some("a closure inside the loop captures the variable");
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Span of the innermost function containing `node`
+fn enclosing_function(semantic: &Semantic, node: &AstNode) -> Option<Span> {
+    let nodes = semantic.nodes();
+    let mut child = node;
+    while let Some(parent) = nodes.parent_node(child.id()) {
+        if matches!(parent.kind(), AstKind::Function(_) | AstKind::ArrowFunctionExpression(_)) {
+            return Some(parent.kind().span());
+        }
+        child = parent;
+    }
+    None
+}
+
+/// Options for the `eqeqeq` rule, mirroring ESLint's `"always"` / `"smart"` settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EqeqeqMode {
+    /// Flag every `==` / `!=`.
+    #[default]
+    Always,
+    /// Allow `== null`, `typeof` comparisons and literal-to-literal comparisons.
+    Smart,
+}
+
+/// eqeqeq: flag loose equality operators and suggest their strict counterparts.
+///
+/// Diagnostics cover the operator itself. A `===` / `!==` fix is only suggested when
+/// neither operand is `null` or `undefined`, because strict equality against either
+/// stops matching the other nullish value and would change behavior.
+pub fn eqeqeq(semantic: &Semantic, source: &str, file_path: &str, mode: EqeqeqMode) -> Vec<LintDiagnostic> {
+    semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::BinaryExpression(expression) => Some(expression),
+            _ => None,
+        })
+        .filter_map(|expression| {
+            let (loose, strict) = match expression.operator {
+                BinaryOperator::Equality => ("==", "==="),
+                BinaryOperator::Inequality => ("!=", "!=="),
+                _ => return None,
+            };
+
+            if mode == EqeqeqMode::Smart && is_smart_exempt(expression) {
+                return None;
+            }
+
+            let operator_span = find_operator_span(source, expression, loose)?;
+            let changes_behavior = is_nullish(&expression.left) || is_nullish(&expression.right);
+            let message = if changes_behavior {
+                format!(
+                    "Expected '{}' and instead saw '{}'. Not auto-fixed: strict comparison against a nullish value changes behavior.",
+                    strict, loose
+                )
+            } else {
+                format!("Expected '{}' and instead saw '{}'.", strict, loose)
+            };
+
+            Some(span_diagnostic(
+                "eqeqeq",
+                message,
+                source,
+                file_path,
+                operator_span,
+                severities::default_diagnostic_severity("eqeqeq"),
+                (!changes_behavior).then(|| strict.to_string()),
+            ))
+        })
+        .collect()
+}
+
+fn is_smart_exempt(expression: &BinaryExpression) -> bool {
+    let is_typeof = |operand: &Expression| matches!(operand, Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::Typeof);
+
+    expression.left.is_null()
+        || expression.right.is_null()
+        || is_typeof(&expression.left)
+        || is_typeof(&expression.right)
+        || (expression.left.is_literal() && expression.right.is_literal())
+}
+
+fn is_nullish(operand: &Expression) -> bool {
+    operand.is_null() || operand.is_undefined()
+}
+
+fn find_operator_span(source: &str, expression: &BinaryExpression, operator: &str) -> Option<Span> {
+    let gap_start = expression.left.span().end;
+    let gap = source.get(gap_start as usize..expression.right.span().start as usize)?;
+    let offset = gap.find(operator)? as u32;
+    Some(Span::new(gap_start + offset, gap_start + offset + operator.len() as u32))
+}
+
+/// Complexity of one function, as measured by [`complexity`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionComplexity {
+    pub name: String,
+    pub span: Span,
+    pub cyclomatic: u32,
+    pub cognitive: u32,
+    pub halstead_difficulty: f64,
+}
+
+/// complexity: flag functions and files over the configured `complexity` limits.
+///
+/// Per function: cyclomatic complexity (1 + branches and short-circuit operators), an
+/// approximate cognitive complexity (each control structure costs 1 + its nesting depth),
+/// and Halstead difficulty. Per file: line count. Nested functions are measured on their own.
+pub fn complexity(semantic: &Semantic, source: &str, file_path: &str, limits: &ComplexityConfig) -> Vec<LintDiagnostic> {
+    let severity = severities::default_diagnostic_severity("complexity");
+    let mut diagnostics = Vec::new();
+
+    let lines = source.lines().count() as u32;
+    if lines > limits.max_file_loc {
+        diagnostics.push(span_diagnostic(
+            "complexity",
+            format!("File has {} lines (maximum allowed is {}).", lines, limits.max_file_loc),
+            source,
+            file_path,
+            Span::new(0, 0),
+            severity.clone(),
+            None,
+        ));
+    }
+
+    for function in function_complexities(semantic, source) {
+        let mut exceeded = Vec::new();
+        if function.cyclomatic > limits.max_cyclomatic_per_function {
+            exceeded.push(format!("cyclomatic complexity {} (maximum {})", function.cyclomatic, limits.max_cyclomatic_per_function));
+        }
+        if function.cognitive > limits.max_cognitive_per_function {
+            exceeded.push(format!("cognitive complexity {} (maximum {})", function.cognitive, limits.max_cognitive_per_function));
+        }
+        if function.halstead_difficulty > limits.max_halstead_difficulty {
+            exceeded.push(format!(
+                "Halstead difficulty {:.1} (maximum {})",
+                function.halstead_difficulty, limits.max_halstead_difficulty
+            ));
+        }
+
+        if !exceeded.is_empty() {
+            diagnostics.push(span_diagnostic(
+                "complexity",
+                format!("Function '{}' has {}.", function.name, exceeded.join(", ")),
+                source,
+                file_path,
+                function.span,
+                severity.clone(),
+                None,
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// Measure every function and arrow function in the program
+pub fn function_complexities(semantic: &Semantic, source: &str) -> Vec<FunctionComplexity> {
+    let functions: Vec<(String, Span)> = semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::Function(function) => Some((
+                function.id.as_ref().map_or_else(|| "<anonymous>".to_string(), |id| id.name.to_string()),
+                function.span,
+            )),
+            AstKind::ArrowFunctionExpression(arrow) => Some(("<arrow>".to_string(), arrow.span)),
+            _ => None,
+        })
+        .collect();
+
+    // Innermost function containing `span`, by index into `functions`
+    let owner = |span: Span| {
+        functions
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, function))| function.start < span.start && span.end <= function.end)
+            .min_by_key(|(_, (_, function))| function.end - function.start)
+            .map(|(index, _)| index)
+    };
+
+    let mut measured: Vec<FunctionComplexity> = functions
+        .iter()
+        .map(|(name, span)| FunctionComplexity {
+            name: name.clone(),
+            span: *span,
+            cyclomatic: 1,
+            cognitive: 0,
+            halstead_difficulty: 0.0,
+        })
+        .collect();
+    let nesting_spans: Vec<Span> = semantic.nodes().iter().filter(|node| is_nesting_structure(&node.kind())).map(|node| node.kind().span()).collect();
+    let mut operators: HashMap<usize, (HashSet<&str>, HashSet<&str>, u32)> = HashMap::new();
+
+    for node in semantic.nodes().iter() {
+        let kind = node.kind();
+        let span = kind.span();
+        let Some(index) = owner(span) else {
+            continue;
+        };
+        let function_span = functions[index].1;
+
+        if is_branch(&kind) {
+            measured[index].cyclomatic += 1;
+        }
+        if is_nesting_structure(&kind) {
+            let nesting = nesting_spans
+                .iter()
+                .filter(|outer| function_span.start <= outer.start && outer.end <= function_span.end)
+                .filter(|outer| outer.start <= span.start && span.end <= outer.end && **outer != span)
+                .count() as u32;
+            measured[index].cognitive += 1 + nesting;
+        } else if matches!(kind, AstKind::LogicalExpression(_)) {
+            measured[index].cognitive += 1;
+        }
+
+        let (distinct_operators, distinct_operands, total_operands) = operators.entry(index).or_default();
+        match kind {
+            AstKind::BinaryExpression(expression) => {
+                distinct_operators.insert(expression.operator.as_str());
+            }
+            AstKind::LogicalExpression(expression) => {
+                distinct_operators.insert(expression.operator.as_str());
+            }
+            AstKind::AssignmentExpression(expression) => {
+                distinct_operators.insert(expression.operator.as_str());
+            }
+            AstKind::UnaryExpression(expression) => {
+                distinct_operators.insert(expression.operator.as_str());
+            }
+            AstKind::UpdateExpression(expression) => {
+                distinct_operators.insert(expression.operator.as_str());
+            }
+            AstKind::IdentifierReference(_) | AstKind::NumericLiteral(_) | AstKind::StringLiteral(_) | AstKind::BooleanLiteral(_) | AstKind::NullLiteral(_) => {
+                if let Some(text) = source.get(span.start as usize..span.end as usize) {
+                    distinct_operands.insert(text);
+                    *total_operands += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (index, (distinct_operators, distinct_operands, total_operands)) in operators {
+        if !distinct_operands.is_empty() {
+            measured[index].halstead_difficulty = (distinct_operators.len() as f64 / 2.0) * (f64::from(total_operands) / distinct_operands.len() as f64);
+        }
+    }
+
+    measured
+}
+
+/// Nodes adding a path through the function for cyclomatic complexity
+fn is_branch(kind: &AstKind) -> bool {
+    match kind {
+        AstKind::SwitchCase(case) => case.test.is_some(),
+        AstKind::LogicalExpression(_) => true,
+        _ => is_nesting_structure(kind),
+    }
+}
+
+/// Control structures that cost 1 + nesting depth for cognitive complexity
+fn is_nesting_structure(kind: &AstKind) -> bool {
+    matches!(
+        kind,
+        AstKind::IfStatement(_)
+            | AstKind::ConditionalExpression(_)
+            | AstKind::ForStatement(_)
+            | AstKind::ForInStatement(_)
+            | AstKind::ForOfStatement(_)
+            | AstKind::WhileStatement(_)
+            | AstKind::DoWhileStatement(_)
+            | AstKind::CatchClause(_)
+    )
+}
+
+/// Rule name of the required TSDoc tags check
+pub const TSDOC_REQUIRED_TAGS_RULE: &str = "tsdoc-required-tags";
+
+/// tsdoc-required-tags: flag exported declarations whose TSDoc block lacks a required tag.
+///
+/// The doc block is the `/** */` comment directly preceding the export statement. The
+/// diagnostic lists the missing tags; its suggested fix is the block completed with
+/// `TODO` placeholders for them (a new block when the export has none), left for the
+/// author or the AI fixer to fill in.
+pub fn tsdoc_required_tags(program: &Program, source: &str, file_path: &str, required_tags: &[String]) -> Vec<LintDiagnostic> {
+    let required: Vec<String> = required_tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.trim_start_matches('@').is_empty())
+        .map(|tag| if tag.starts_with('@') { tag.to_string() } else { format!("@{}", tag) })
+        .collect();
+    if required.is_empty() {
+        return Vec::new();
+    }
+
+    let severity = severities::default_diagnostic_severity(TSDOC_REQUIRED_TAGS_RULE);
+    let mut diagnostics = Vec::new();
+
+    for statement in &program.body {
+        let (name, export_start) = match statement {
+            Statement::ExportNamedDeclaration(export) => match export.declaration.as_ref().and_then(declaration_name) {
+                Some(name) => (name, export.span.start as usize),
+                None => continue,
+            },
+            Statement::ExportDefaultDeclaration(export) => ("default".to_string(), export.span.start as usize),
+            _ => continue,
+        };
+
+        let doc_block = preceding_doc_block(program, source, export_start);
+        let present: HashSet<&str> = doc_block
+            .map(|(start, end)| {
+                source[start..end]
+                    .split(|ch: char| ch.is_whitespace() || ch == '{' || ch == '}')
+                    .filter(|word| word.len() > 1 && word.starts_with('@'))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let missing: Vec<&str> = required.iter().map(String::as_str).filter(|tag| !present.contains(tag)).collect();
+        if missing.is_empty() {
+            continue;
+        }
+
+        let indent = line_indent(source, export_start);
+        let (span, completion) = match doc_block {
+            Some((start, end)) => (Span::new(start as u32, end as u32), complete_doc_block(&source[start..end], &missing, &indent)),
+            None => {
+                let tag_lines: String = missing.iter().map(|tag| format!("{} * {} TODO\n", indent, tag)).collect();
+                let block = format!("/**\n{} {}*/\n{}", tag_lines, indent, indent);
+                (Span::new(export_start as u32, export_start as u32), block)
+            }
+        };
+
+        diagnostics.push(span_diagnostic(
+            TSDOC_REQUIRED_TAGS_RULE,
+            format!("Exported '{}' is missing required TSDoc tags: {}.", name, missing.join(", ")),
+            source,
+            file_path,
+            span,
+            severity.clone(),
+            Some(completion),
+        ));
+    }
+
+    diagnostics
+}
+
+fn declaration_name(declaration: &Declaration) -> Option<String> {
+    match declaration {
+        Declaration::FunctionDeclaration(function) => function.id.as_ref().map(|id| id.name.to_string()),
+        Declaration::ClassDeclaration(class) => class.id.as_ref().map(|id| id.name.to_string()),
+        Declaration::VariableDeclaration(variable) => variable
+            .declarations
+            .first()
+            .and_then(|declarator| declarator.id.get_binding_identifier())
+            .map(|id| id.name.to_string()),
+        Declaration::TSTypeAliasDeclaration(alias) => Some(alias.id.name.to_string()),
+        Declaration::TSInterfaceDeclaration(interface) => Some(interface.id.name.to_string()),
+        Declaration::TSEnumDeclaration(enumeration) => Some(enumeration.id.name.to_string()),
+        _ => None,
+    }
+}
+
+/// Byte range of the `/** */` comment separated from `offset` by whitespace only
+fn preceding_doc_block(program: &Program, source: &str, offset: usize) -> Option<(usize, usize)> {
+    program
+        .comments
+        .iter()
+        .map(|comment| full_comment_span(source, comment.span.start as usize, comment.span.end as usize))
+        .rev()
+        .find(|&(_, end)| end <= offset)
+        .filter(|&(start, end)| source[start..end].starts_with("/**") && source[end..offset].trim().is_empty())
+}
+
+/// `block` with a `@tag TODO` line for each missing tag before its closing `*/`
+fn complete_doc_block(block: &str, missing: &[&str], indent: &str) -> String {
+    let body = block.trim_end_matches("*/");
+    let closing_line_start = body.rfind('\n').map(|index| index + 1);
+
+    match closing_line_start {
+        // Multi-line block whose last line holds only the closing `*/`
+        Some(line_start) if body[line_start..].trim().is_empty() => {
+            let prefix = &body[line_start..];
+            let tag_lines: String = missing.iter().map(|tag| format!("{}* {} TODO\n", prefix, tag)).collect();
+            format!("{}{}{}*/", &body[..line_start], tag_lines, prefix)
+        }
+        _ => {
+            let mut lines: Vec<String> = body.trim_start_matches("/**").lines().map(|line| line.trim().trim_start_matches('*').trim().to_string()).collect();
+            lines.retain(|line| !line.is_empty());
+            lines.extend(missing.iter().map(|tag| format!("{} TODO", tag)));
+            let body_lines: String = lines.iter().map(|line| format!("{} * {}\n", indent, line)).collect();
+            format!("/**\n{}{} */", body_lines, indent)
+        }
+    }
+}
+
+fn line_indent(source: &str, offset: usize) -> String {
+    let line_start = source[..offset].rfind('\n').map(|index| index + 1).unwrap_or(0);
+    source[line_start..offset].chars().take_while(|ch| ch.is_whitespace()).collect()
+}
+
+/// Rule name of the mixed line endings check
+pub const LINE_ENDINGS_RULE: &str = "line-endings";
+
+/// line-endings: flag line endings that differ from the rest of a mixed CRLF/LF file.
+///
+/// Only runs when `policy` is not `Preserve`, so files keep their endings unless a
+/// normalization target is configured. The target is the configured ending, or for
+/// `Dominant` the one most lines already use (LF on a tie). Each offending ending gets
+/// its own diagnostic whose fix replaces just that ending, so fixes never overlap.
+pub fn line_endings(source: &str, file_path: &str, policy: LineEndingPolicy) -> Vec<LintDiagnostic> {
+    let crlf = source.matches("\r\n").count();
+    let lf = source.matches('\n').count() - crlf;
+    if policy == LineEndingPolicy::Preserve || crlf == 0 || lf == 0 {
+        return Vec::new();
+    }
+
+    let to_crlf = match policy {
+        LineEndingPolicy::Crlf => true,
+        LineEndingPolicy::Dominant => crlf > lf,
+        LineEndingPolicy::Lf | LineEndingPolicy::Preserve => false,
+    };
+    let (expected, found, replacement) = if to_crlf { ("CRLF", "LF", "\r\n") } else { ("LF", "CRLF", "\n") };
+    let severity = severities::default_diagnostic_severity(LINE_ENDINGS_RULE);
+
+    source
+        .match_indices('\n')
+        .filter_map(|(offset, _)| {
+            let is_crlf = offset > 0 && source.as_bytes()[offset - 1] == b'\r';
+            if is_crlf == to_crlf {
+                return None;
+            }
+            let start = if is_crlf { offset - 1 } else { offset };
+            Some(span_diagnostic(
+                LINE_ENDINGS_RULE,
+                format!("Line ends with {} in a file that uses {}; line endings are mixed.", found, expected),
+                source,
+                file_path,
+                Span::new(start as u32, offset as u32 + 1),
+                severity.clone(),
+                Some(replacement.to_string()),
+            ))
+        })
+        .collect()
+}
+
+/// no-console: flag calls to `console.*`.
+///
+/// There is no fix; whether a log call should go, or become structured logging, is the
+/// author's call. `linting.env_globs` relaxes the rule in test and dev files.
+pub fn no_console(semantic: &Semantic, source: &str, file_path: &str) -> Vec<LintDiagnostic> {
+    let severity = severities::default_diagnostic_severity("no-console");
+    semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::CallExpression(call) => Some(call),
+            _ => None,
+        })
+        .filter_map(|call| {
+            let member = call.callee.as_member_expression()?;
+            if !matches!(member.object(), Expression::Identifier(object) if object.name == "console") {
+                return None;
+            }
+            let method = member.static_property_name().unwrap_or("*");
+            Some(span_diagnostic(
+                "no-console",
+                format!("Unexpected console statement (console.{}).", method),
+                source,
+                file_path,
+                call.span,
+                severity.clone(),
+                None,
+            ))
+        })
+        .collect()
+}
+
+/// no-debugger: flag `debugger` statements, which pause execution wherever devtools are open.
+pub fn no_debugger(semantic: &Semantic, source: &str, file_path: &str) -> Vec<LintDiagnostic> {
+    let severity = severities::default_diagnostic_severity("no-debugger");
+    semantic
+        .nodes()
+        .iter()
+        .filter_map(|node| match node.kind() {
+            AstKind::DebuggerStatement(statement) => Some(span_diagnostic(
+                "no-debugger",
+                "Unexpected 'debugger' statement.",
+                source,
+                file_path,
+                statement.span,
+                severity.clone(),
+                None,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rule name of the unguarded await check
+pub const ASYNC_ERROR_HANDLING_RULE: &str = "async-error-handling";
+
+/// async-error-handling: flag `await`s whose rejection is neither caught nor propagated.
+///
+/// An `await` is handled when it sits in the `try` block of a try/catch inside its own
+/// function, when its promise ends in `.catch(...)`, or when its result is returned to the
+/// caller (`return await ...` or an expression-bodied arrow). Top-level awaits are left to
+/// the module loader. `granularity` reports each unguarded await, or each async function
+/// once with the number it contains. There is no fix.
+pub fn async_error_handling(semantic: &Semantic, source: &str, file_path: &str, granularity: AsyncErrorGranularity) -> Vec<LintDiagnostic> {
+    let severity = severities::default_diagnostic_severity(ASYNC_ERROR_HANDLING_RULE);
+    let mut diagnostics = Vec::new();
+    // Enclosing function span -> (name, unguarded awaits), in source order
+    let mut functions: Vec<(Span, String, usize)> = Vec::new();
+
+    for node in semantic.nodes().iter() {
+        let AstKind::AwaitExpression(await_expression) = node.kind() else {
+            continue;
+        };
+        if is_caught_promise(&await_expression.argument) {
+            continue;
+        }
+        let Some((function_span, name)) = unguarded_await_owner(semantic, node) else {
+            continue;
+        };
+
+        match granularity {
+            AsyncErrorGranularity::Call => diagnostics.push(span_diagnostic(
+                ASYNC_ERROR_HANDLING_RULE,
+                format!("Awaited call in '{}' is not wrapped in try/catch and its rejection is not returned to the caller.", name),
+                source,
+                file_path,
+                await_expression.span,
+                severity.clone(),
+                None,
+            )),
+            AsyncErrorGranularity::Function => match functions.iter_mut().find(|(span, _, _)| *span == function_span) {
+                Some((_, _, count)) => *count += 1,
+                None => functions.push((function_span, name, 1)),
+            },
+        }
+    }
+
+    diagnostics.extend(functions.into_iter().map(|(span, name, count)| {
+        span_diagnostic(
+            ASYNC_ERROR_HANDLING_RULE,
+            format!("Async function '{}' has {} awaited call(s) outside try/catch whose rejections are not returned to the caller.", name, count),
+            source,
+            file_path,
+            span,
+            severity.clone(),
+            None,
+        )
+    }));
+    diagnostics
+}
+
+/// Rule name of the non-null assertion and unsafe cast check
+pub const UNSAFE_ASSERTIONS_RULE: &str = "unsafe-assertions";
+
+/// unsafe-assertions: flag non-null assertions, casts to `any` and double casts.
+///
+/// `foo!`, `x as any` and `x as unknown as T` (or `as any as T`) all make the compiler take
+/// the code's word for a type it cannot check. A double cast is reported once, not again for
+/// its inner cast; `as const` and casts between related types are left alone. A non-null
+/// assertion before a property access gets an optional-chaining suggestion; the others need
+/// a type guard written by hand. Files matching `test_patterns` are skipped when
+/// `allow_in_tests` is set.
+pub fn unsafe_assertions(semantic: &Semantic, source: &str, file_path: &str, config: &UnsafeAssertionsConfig) -> Vec<LintDiagnostic> {
+    if config.allow_in_tests && is_test_file(file_path, &config.test_patterns) {
+        return Vec::new();
+    }
+
+    let severity = severities::default_diagnostic_severity(UNSAFE_ASSERTIONS_RULE);
+    let mut diagnostics = Vec::new();
+    // Non-null assertions directly followed by `.property`, which `?.` can replace
+    let mut chainable: HashSet<Span> = HashSet::new();
+    // Inner casts of double casts, already reported with their outer cast
+    let mut double_cast_inner: HashSet<Span> = HashSet::new();
+
+    // Nodes come in source order, so a member expression precedes its object and a cast its operand
+    for node in semantic.nodes().iter() {
+        match node.kind() {
+            AstKind::MemberExpression(MemberExpression::StaticMemberExpression(member)) if !member.optional => {
+                if let Expression::TSNonNullExpression(assertion) = &member.object {
+                    chainable.insert(assertion.span);
+                }
+            }
+            AstKind::TSNonNullExpression(assertion) => {
+                let fix = chainable
+                    .contains(&assertion.span)
+                    .then(|| format!("{}?", &source[assertion.expression.span().start as usize..assertion.expression.span().end as usize]));
+                let advice = if fix.is_some() { "use optional chaining or guard" } else { "guard" };
+                diagnostics.push(span_diagnostic(
+                    UNSAFE_ASSERTIONS_RULE,
+                    format!("Non-null assertion hides a possible null or undefined; {} it with an explicit null check.", advice),
+                    source,
+                    file_path,
+                    assertion.span,
+                    severity.clone(),
+                    fix,
+                ));
+            }
+            AstKind::TSAsExpression(cast) => {
+                if let Expression::TSAsExpression(inner) = cast.expression.without_parentheses() {
+                    if matches!(inner.type_annotation, TSType::TSUnknownKeyword(_) | TSType::TSAnyKeyword(_)) {
+                        double_cast_inner.insert(inner.span);
+                        let through = if matches!(inner.type_annotation, TSType::TSAnyKeyword(_)) { "any" } else { "unknown" };
+                        diagnostics.push(span_diagnostic(
+                            UNSAFE_ASSERTIONS_RULE,
+                            format!("Double cast through '{}' forces an unrelated type; validate the value with a type guard instead.", through),
+                            source,
+                            file_path,
+                            cast.span,
+                            severity.clone(),
+                            None,
+                        ));
+                        continue;
+                    }
+                }
+                if matches!(cast.type_annotation, TSType::TSAnyKeyword(_)) && !double_cast_inner.contains(&cast.span) {
+                    diagnostics.push(span_diagnostic(
+                        UNSAFE_ASSERTIONS_RULE,
+                        "Cast to 'any' turns off type checking; narrow the value with a type guard instead.",
+                        source,
+                        file_path,
+                        cast.span,
+                        severity.clone(),
+                        None,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// Whether `file_path` matches one of the test file globs `patterns`; invalid globs match nothing
+fn is_test_file(file_path: &str, patterns: &[String]) -> bool {
+    let path = std::path::Path::new(file_path);
+    let path = path.strip_prefix("./").unwrap_or(path);
+    patterns
+        .iter()
+        .filter_map(|pattern| Glob::new(pattern).ok())
+        .any(|glob| glob.compile_matcher().is_match(path))
+}
+
+/// Whether `expression` is a promise chain ending in `.catch(...)`
+fn is_caught_promise(expression: &Expression) -> bool {
+    match expression.without_parentheses() {
+        Expression::CallExpression(call) => call.callee.as_member_expression().and_then(|member| member.static_property_name()) == Some("catch"),
+        _ => false,
+    }
+}
+
+/// Span and name of the function owning an unguarded `await` node; `None` when it is handled
+fn unguarded_await_owner(semantic: &Semantic, await_node: &AstNode) -> Option<(Span, String)> {
+    let nodes = semantic.nodes();
+    let mut child = await_node;
+    // The await itself or the outermost parentheses around it
+    let mut awaited = await_node.id();
+    let mut returned = false;
+
+    while let Some(parent) = nodes.parent_node(child.id()) {
+        let direct = child.id() == awaited;
+        match parent.kind() {
+            AstKind::ParenthesizedExpression(_) if direct => awaited = parent.id(),
+            AstKind::ReturnStatement(_) if direct => return None,
+            AstKind::ExpressionStatement(_) if direct => returned = true,
+            AstKind::FunctionBody(_) => {}
+            AstKind::TryStatement(statement) if statement.handler.is_some() && child.kind().span() == statement.block.span => return None,
+            AstKind::Function(function) => {
+                return Some((function.span, function.id.as_ref().map_or_else(|| "<anonymous>".to_string(), |id| id.name.to_string())));
+            }
+            AstKind::ArrowFunctionExpression(arrow) => {
+                // `async () => await fetchUser()` returns the awaited value
+                return (!(returned && arrow.expression)).then(|| (arrow.span, "<arrow>".to_string()));
+            }
+            _ => returned = false,
+        }
+        child = parent;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_semantic::SemanticBuilder;
+    use oxc_span::SourceType;
+
+    fn run_rule(source: &str, rule: impl Fn(&Semantic, &str, &str) -> Vec<LintDiagnostic>) -> Vec<LintDiagnostic> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        assert!(ret.errors.is_empty(), "fixture should parse");
+        let semantic_ret = SemanticBuilder::new().build(&ret.program);
+        rule(&semantic_ret.semantic, source, "test.ts")
+    }
+
+    fn run_prefer_const(source: &str) -> Vec<LintDiagnostic> {
+        run_rule(source, prefer_const)
+    }
+
+    fn run_eqeqeq(source: &str, mode: EqeqeqMode) -> Vec<LintDiagnostic> {
+        run_rule(source, |semantic, source, file_path| eqeqeq(semantic, source, file_path, mode))
+    }
+
+    #[test]
+    fn test_mixed_line_endings_normalized_to_configured_style() {
+        use crate::rulebase::fixes::{apply_fixes, FixOptions};
+
+        let source = "const a = 1;\r\nconst b = 2;\nconst c = 3;\n";
+        let normalize = |policy| {
+            let diagnostics = line_endings(source, "test.ts", policy);
+            (diagnostics.len(), apply_fixes(source, &diagnostics, FixOptions::default(), fix_safety).code)
+        };
+
+        assert!(line_endings(source, "test.ts", LineEndingPolicy::Preserve).is_empty());
+        assert!(line_endings("const a = 1;\r\nconst b = 2;\r\n", "test.ts", LineEndingPolicy::Lf).is_empty());
+
+        let dominant = line_endings(source, "test.ts", LineEndingPolicy::Dominant);
+        assert_eq!(dominant.len(), 1);
+        assert_eq!((dominant[0].rule_name.as_str(), dominant[0].line, dominant[0].column), (LINE_ENDINGS_RULE, 1, 13));
+
+        assert_eq!(normalize(LineEndingPolicy::Dominant), (1, "const a = 1;\nconst b = 2;\nconst c = 3;\n".to_string()));
+        assert_eq!(normalize(LineEndingPolicy::Lf), normalize(LineEndingPolicy::Dominant));
+        assert_eq!(normalize(LineEndingPolicy::Crlf), (2, "const a = 1;\r\nconst b = 2;\r\nconst c = 3;\r\n".to_string()));
+    }
+
+    #[test]
+    fn test_span_utilities_never_split_a_codepoint() {
+        let source = "const s = \"é\";\nlet a = 1;\n";
+        let inside_e_acute = source.find('é').unwrap() as u32 + 1;
+
+        assert_eq!(offset_to_line_column(source, inside_e_acute), (1, 12));
+        assert_eq!(offset_to_line_column(source, u32::MAX), (3, 1));
+        assert_eq!(line_column_to_offset(source, 2, 1), Some(source.find("let").unwrap()));
+        assert_eq!(line_column_to_offset(source, 1, 40), None);
+    }
+
+    #[test]
+    fn test_reassigned_let_not_fixed() {
+        let diagnostics = run_prefer_const("let count = 0;\ncount = count + 1;\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_never_reassigned_let_fixed_to_const() {
+        let diagnostics = run_prefer_const("let greeting = 'hi';\nconsole.log(greeting);\n");
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.rule_name, "prefer-const");
+        assert_eq!(diagnostic.suggested_fix.as_deref(), Some("const"));
+        assert!(diagnostic.fix_available);
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 1));
+        assert_eq!((diagnostic.end_line, diagnostic.end_column), (1, 4));
+    }
+
+    #[test]
+    fn test_for_loop_counter_not_fixed() {
+        let diagnostics = run_prefer_const("for (let i = 0; i < 10; i++) {\n  console.log(i);\n}\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_destructuring_with_reassigned_target_not_fixed() {
+        let diagnostics = run_prefer_const("let { a, b } = obj;\nb = 2;\nconsole.log(a, b);\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_uninitialized_let_not_fixed() {
+        let diagnostics = run_prefer_const("let value;\nvalue = 1;\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_var_never_reassigned_fixed_to_const() {
+        let diagnostics = run_rule("var greeting = 'hi';\nconsole.log(greeting);\n", no_var);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.rule_name, "no-var");
+        assert_eq!(diagnostic.suggested_fix.as_deref(), Some("const"));
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 1));
+        assert_eq!((diagnostic.end_line, diagnostic.end_column), (1, 4));
+    }
+
+    #[test]
+    fn test_reassigned_var_fixed_to_let() {
+        let diagnostics = run_rule("var count = 0;\ncount = count + 1;\n", no_var);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].suggested_fix.as_deref(), Some("let"));
+    }
+
+    #[test]
+    fn test_var_captured_by_closure_in_loop_left_as_suggestion() {
+        let source = "const handlers = [];\nfor (var i = 0; i < 3; i++) {\n  handlers.push(() => i);\n}\n";
+        let diagnostics = run_rule(source, no_var);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.line, 2);
+        assert!(!diagnostic.fix_available);
+        assert!(diagnostic.message.contains("closure inside the loop"), "{}", diagnostic.message);
+
+        // Hoisted and function-scoped uses are left alone too
+        let hoisted = run_rule("console.log(total);\nvar total = 1;\n", no_var);
+        assert!(hoisted[0].suggested_fix.is_none());
+        let escaping = run_rule("if (ready) {\n  var status = 'ok';\n}\nconsole.log(status);\n", no_var);
+        assert!(escaping[0].suggested_fix.is_none());
+    }
+
+    #[test]
+    fn test_eqeqeq_loose_equality_flagged_and_fixed() {
+        let diagnostics = run_eqeqeq("if (x == y) {}\n", EqeqeqMode::Smart);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.rule_name, "eqeqeq");
+        assert_eq!(diagnostic.suggested_fix.as_deref(), Some("==="));
+        assert_eq!((diagnostic.line, diagnostic.column), (1, 7));
+        assert_eq!((diagnostic.end_line, diagnostic.end_column), (1, 9));
+    }
+
+    #[test]
+    fn test_eqeqeq_null_comparison_allowed_in_smart_mode() {
+        assert!(run_eqeqeq("if (x == null) {}\n", EqeqeqMode::Smart).is_empty());
+
+        let always = run_eqeqeq("if (x == null) {}\n", EqeqeqMode::Always);
+        assert_eq!(always.len(), 1);
+        assert!(always[0].suggested_fix.is_none(), "== null must not be rewritten to === null");
+    }
+
+    #[test]
+    fn test_eqeqeq_undefined_comparison_flagged_without_fix() {
+        let diagnostics = run_eqeqeq("if (x != undefined) {}\n", EqeqeqMode::Smart);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].fix_available);
+        assert!(diagnostics[0].suggested_fix.is_none());
+    }
+
+    #[test]
+    fn test_eqeqeq_smart_mode_allows_typeof_and_literals() {
+        assert!(run_eqeqeq("if (typeof x == 'string') {}\nif ('a' == 'b') {}\n", EqeqeqMode::Smart).is_empty());
+        assert_eq!(run_eqeqeq("if (typeof x == 'string') {}\n", EqeqeqMode::Always).len(), 1);
+    }
+
+    fn run_complexity(source: &str, limits: &ComplexityConfig) -> Vec<LintDiagnostic> {
+        run_rule(source, |semantic, source, file_path| complexity(semantic, source, file_path, limits))
+    }
+
+    const BRANCHY: &str = "function branchy(a, b) {\n  if (a) {}\n  if (b) {}\n  if (a && b) {}\n  return a ? 1 : 2;\n}\n";
+
+    #[test]
+    fn test_function_over_configured_cyclomatic_limit_flagged() {
+        // 1 + three ifs + && + ?: = 6
+        let limits = ComplexityConfig {
+            max_cyclomatic_per_function: 5,
+            ..ComplexityConfig::default()
+        };
+
+        let diagnostics = run_complexity(BRANCHY, &limits);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule_name, "complexity");
+        assert!(diagnostics[0].message.contains("cyclomatic complexity 6 (maximum 5)"));
+        assert_eq!(diagnostics[0].line, 1);
+    }
+
+    #[test]
+    fn test_function_under_configured_cyclomatic_limit_not_flagged() {
+        let limits = ComplexityConfig {
+            max_cyclomatic_per_function: 6,
+            ..ComplexityConfig::default()
+        };
+
+        assert!(run_complexity(BRANCHY, &limits).is_empty());
+    }
+
+    #[test]
+    fn test_nested_functions_measured_separately_and_file_loc_limit() {
+        let source = "function outer(a) {\n  if (a) {}\n  return () => (a ? 1 : 2);\n}\n";
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        let semantic_ret = SemanticBuilder::new().build(&ret.program);
+
+        let measured = function_complexities(&semantic_ret.semantic, source);
+        let cyclomatic: Vec<(&str, u32)> = measured.iter().map(|f| (f.name.as_str(), f.cyclomatic)).collect();
+        assert_eq!(cyclomatic, vec![("outer", 2), ("<arrow>", 2)]);
+
+        let limits = ComplexityConfig {
+            max_file_loc: 3,
+            ..ComplexityConfig::default()
+        };
+        let diagnostics = run_complexity(source, &limits);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("File has 4 lines"));
+    }
+
+    fn run_tsdoc_required_tags(source: &str, required: &[&str]) -> Vec<LintDiagnostic> {
+        let allocator = Allocator::default();
+        let ret = Parser::new(&allocator, source, SourceType::ts()).parse();
+        assert!(ret.errors.is_empty(), "fixture should parse");
+        let required: Vec<String> = required.iter().map(|tag| tag.to_string()).collect();
+        tsdoc_required_tags(&ret.program, source, "test.ts", &required)
+    }
+
+    const REQUIRED_TAGS: &[&str] = &["@category", "@safe", "@mvp", "@complexity", "@since"];
+
+    #[test]
+    fn test_export_missing_since_tag_flagged_with_completion() {
+        let source = "/**\n * Parse a config file.\n *\n * @category config\n * @safe team\n * @mvp core\n * @complexity low\n */\nexport function parseConfig(path: string) {}\n";
+
+        let diagnostics = run_tsdoc_required_tags(source, REQUIRED_TAGS);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.rule_name, TSDOC_REQUIRED_TAGS_RULE);
+        assert_eq!(diagnostic.message, "Exported 'parseConfig' is missing required TSDoc tags: @since.");
+        assert_eq!((diagnostic.line, diagnostic.end_line), (1, 8));
+        assert_eq!(
+            diagnostic.suggested_fix.as_deref(),
+            Some("/**\n * Parse a config file.\n *\n * @category config\n * @safe team\n * @mvp core\n * @complexity low\n * @since TODO\n */")
+        );
+        assert_eq!(fix_safety(TSDOC_REQUIRED_TAGS_RULE), FixSafety::Suggestion);
+    }
+
+    #[test]
+    fn test_export_with_all_required_tags_not_flagged() {
+        let source = "/**\n * Parse a config file.\n * @category config\n * @safe team\n * @mvp core\n * @complexity low\n * @since 2.0.0\n */\nexport const parseConfig = (path: string) => path;\n\nfunction internal() {}\n";
+
+        assert!(run_tsdoc_required_tags(source, REQUIRED_TAGS).is_empty());
+    }
+
+    #[test]
+    fn test_undocumented_export_gets_new_block() {
+        let diagnostics = run_tsdoc_required_tags("// not a doc block\nexport interface Options {}\n", &["since", "@category"]);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Exported 'Options' is missing required TSDoc tags: @since, @category.");
+        assert_eq!(diagnostics[0].suggested_fix.as_deref(), Some("/**\n * @since TODO\n * @category TODO\n */\n"));
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 1));
+    }
+
+    fn run_async_error_handling(source: &str, granularity: AsyncErrorGranularity) -> Vec<LintDiagnostic> {
+        run_rule(source, |semantic, source, file_path| async_error_handling(semantic, source, file_path, granularity))
+    }
+
+    #[test]
+    fn test_unguarded_await_flagged_unless_caught_or_returned() {
+        let unguarded = "async function load() {\n  const user = await fetchUser();\n  return user;\n}\n";
+        let diagnostics = run_async_error_handling(unguarded, AsyncErrorGranularity::Call);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].rule_name.as_str(), diagnostics[0].line, diagnostics[0].column), (ASYNC_ERROR_HANDLING_RULE, 2, 16));
+
+        let guarded = "async function load() {\n  try {\n    return await fetchUser();\n  } catch (e) {\n    return null;\n  }\n}\n";
+        let caught = "async function load() {\n  try {\n    const user = await fetchUser();\n    return user;\n  } catch (e) {\n    return null;\n  }\n}\n";
+        let returned = "async function load() {\n  return await fetchUser();\n}\nconst loadArrow = async () => await fetchUser();\n";
+        let chained = "async function load() {\n  await fetchUser().catch(() => null);\n}\n";
+        for source in [guarded, caught, returned, chained] {
+            assert!(run_async_error_handling(source, AsyncErrorGranularity::Call).is_empty(), "handled await flagged in:\n{}", source);
+        }
+
+        // try/catch outside a callback does not guard awaits inside it
+        let callback = "async function load() {\n  try {\n    items.forEach(async (item) => {\n      await save(item);\n    });\n  } catch (e) {}\n}\n";
+        assert_eq!(run_async_error_handling(callback, AsyncErrorGranularity::Call).len(), 1);
+    }
+
+    fn run_unsafe_assertions(source: &str, file_path: &str, config: &UnsafeAssertionsConfig) -> Vec<LintDiagnostic> {
+        run_rule(source, |semantic, source, _| unsafe_assertions(semantic, source, file_path, config))
+    }
+
+    #[test]
+    fn test_non_null_assertions_and_unsafe_casts_flagged() {
+        use crate::rulebase::fixes::{apply_fixes, FixOptions};
+
+        let source = "const name = foo!.bar;\nconst user = x as unknown as User;\nconst raw = data as any;\n\
+            const sizes = [1, 2] as const;\nconst id = (input as string).trim();\n";
+        let diagnostics = run_unsafe_assertions(source, "src/user.ts", &UnsafeAssertionsConfig::default());
+
+        let flagged: Vec<(u32, u32, Option<&str>)> = diagnostics.iter().map(|d| (d.line, d.column, d.suggested_fix.as_deref())).collect();
+        assert_eq!(flagged, vec![(1, 14, Some("foo?")), (2, 14, None), (3, 13, None)]);
+        assert!(diagnostics[1].message.contains("through 'unknown'"));
+        assert!(diagnostics[2].message.contains("'any'"));
+
+        let options = FixOptions {
+            apply_suggestions: true,
+            ..FixOptions::default()
+        };
+        assert!(apply_fixes(source, &diagnostics, options, fix_safety).code.starts_with("const name = foo?.bar;\n"));
+        assert_eq!(run_unsafe_assertions("const el = find(id)!;\n", "src/user.ts", &UnsafeAssertionsConfig::default())[0].suggested_fix, None);
+    }
+
+    #[test]
+    fn test_unsafe_assertions_allowed_in_test_files_when_configured() {
+        let source = "const name = foo!.bar;\n";
+        let allowed = UnsafeAssertionsConfig {
+            allow_in_tests: true,
+            ..UnsafeAssertionsConfig::default()
+        };
+
+        assert!(run_unsafe_assertions(source, "./src/user.test.ts", &allowed).is_empty());
+        assert_eq!(run_unsafe_assertions(source, "src/user.ts", &allowed).len(), 1);
+        assert_eq!(run_unsafe_assertions(source, "src/user.test.ts", &UnsafeAssertionsConfig::default()).len(), 1);
+    }
+
+    #[test]
+    fn test_async_error_granularity_reports_per_function_or_per_call() {
+        let source = "async function sync() {\n  await a();\n  await b();\n}\n";
+
+        let per_call = run_async_error_handling(source, AsyncErrorGranularity::Call);
+        assert_eq!(per_call.iter().map(|d| d.line).collect::<Vec<_>>(), vec![2, 3]);
+
+        let per_function = run_async_error_handling(source, AsyncErrorGranularity::Function);
+        assert_eq!(per_function.len(), 1);
+        assert_eq!(per_function[0].line, 1);
+        assert!(per_function[0].message.contains("'sync' has 2 awaited call(s)"));
+    }
+}
@@ -0,0 +1,530 @@
+//! ESLint-style suppression directives
+//!
+//! `eslint-disable-next-line`, `eslint-disable-line` and `eslint-disable` / `eslint-enable`
+//! comments drop matching diagnostics from a file's results. A directive without rule
+//! names covers every rule. With `linting.report_unused_disables` (`--report-unused-disables`)
+//! each directive that suppressed nothing is reported on its own line as
+//! [`UNUSED_DIRECTIVE_RULE`], so stale suppressions stop hiding future problems.
+//! `linting.ignore_messages` mutes single message variants of a rule project-wide; see
+//! [`ignore_messages`]. Rule IDs match by canonical ID, so a directive naming `no-console`
+//! also covers `eslint:no-console` and `oxc:noConsole` (see [`RuleAliases`]).
+//!
+//! Block directives nest as a stack, tracked per rule. `eslint-disable a, b` pushes one
+//! entry for each named rule, a bare `eslint-disable` pushes one entry covering every rule.
+//! `eslint-enable a` closes the innermost open entry covering `a`: an entry for `a` ends at
+//! that line, while a bare entry keeps covering every other rule and stops covering `a`.
+//! A bare `eslint-enable` closes the entries of the innermost open directive. So nested
+//! disables of the same rule need one enable each, and overlapping blocks for different
+//! rules end independently. An enable that closes nothing is ignored with a warning and
+//! listed in [`SuppressionOutcome::unmatched_enables`]; open entries run to the end of the file.
+
+use crate::buffer_lint::PARSE_ERROR_RULE;
+use crate::config::IgnoreMessage;
+use crate::rulebase::rule_aliases::RuleAliases;
+use crate::rulebase::severities;
+use crate::types::LintDiagnostic;
+
+/// Rule name of diagnostics reporting directives that suppressed nothing
+pub const UNUSED_DIRECTIVE_RULE: &str = "unused-disable-directive";
+
+/// What a directive covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectiveKind {
+    /// `eslint-disable-next-line`
+    NextLine,
+    /// `eslint-disable-line`
+    Line,
+    /// `eslint-disable` until a matching `eslint-enable` or the end of the file
+    Block,
+    /// `eslint-enable`
+    Enable,
+}
+
+/// A suppression comment found in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuppressionDirective {
+    pub kind: DirectiveKind,
+    pub rules: Vec<String>, // Empty covers every rule
+    pub line: u32,
+    pub column: u32,
+    pub end_column: u32,
+    pub text: String, // Comment text without delimiters, e.g. `eslint-disable-next-line eqeqeq`
+}
+
+/// Diagnostics left after suppression, plus the unused-directive reports when requested
+#[derive(Debug, Clone, Default)]
+pub struct SuppressionOutcome {
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub suppressed: usize,
+    pub unused: Vec<LintDiagnostic>,
+    pub unmatched_enables: Vec<u32>, // Lines of `eslint-enable` directives that closed nothing
+}
+
+/// Lines and rules one directive suppresses
+#[derive(Debug, Clone)]
+struct Coverage {
+    directive: usize,
+    rules: Vec<String>, // Empty covers every rule
+    first: u32,
+    last: u32,
+    reenabled: Vec<(String, u32)>, // Rules an `eslint-enable` took out of a bare block, after the given line
+}
+
+impl Coverage {
+    fn new(directive: usize, rules: Vec<String>, first: u32, last: u32) -> Self {
+        Self {
+            directive,
+            rules,
+            first,
+            last,
+            reenabled: Vec::new(),
+        }
+    }
+
+    /// Whether this still covers `rule_name` after its last `eslint-enable`
+    fn covers_rule(&self, rule_name: &str, aliases: &RuleAliases) -> bool {
+        if self.rules.is_empty() {
+            !self.reenabled.iter().any(|(rule, _)| aliases.same_rule(rule, rule_name))
+        } else {
+            self.rules.iter().any(|rule| aliases.same_rule(rule, rule_name))
+        }
+    }
+
+    fn covers(&self, diagnostic: &LintDiagnostic, aliases: &RuleAliases) -> bool {
+        let line = diagnostic.line;
+        (self.first..=self.last).contains(&line)
+            && (self.rules.is_empty() || self.rules.iter().any(|rule| aliases.same_rule(rule, &diagnostic.rule_name)))
+            && !self.reenabled.iter().any(|(rule, at)| line > *at && aliases.same_rule(rule, &diagnostic.rule_name))
+    }
+}
+
+/// Find every suppression directive in `source`, in source order
+pub fn parse_directives(source: &str) -> Vec<SuppressionDirective> {
+    let mut directives = Vec::new();
+
+    for (index, line_text) in source.lines().enumerate() {
+        let mut search_from = 0;
+        while let Some((start, body, end)) = next_comment(line_text, search_from) {
+            search_from = end;
+            let Some((kind, rest)) = directive_kind(body.trim_start()) else {
+                continue;
+            };
+
+            // `-- reason` trails the rule list
+            let rule_list = rest.split("--").next().unwrap_or("");
+            let rules = rule_list.split(',').map(str::trim).filter(|rule| !rule.is_empty()).map(str::to_string).collect();
+
+            directives.push(SuppressionDirective {
+                kind,
+                rules,
+                line: index as u32 + 1,
+                column: line_text[..start].chars().count() as u32 + 1,
+                end_column: line_text[..end].chars().count() as u32 + 1,
+                text: body.trim().to_string(),
+            });
+        }
+    }
+
+    directives
+}
+
+/// Drop diagnostics covered by a directive in `source`.
+///
+/// Parse errors are never suppressed. With `report_unused`, directives (other than
+/// `eslint-enable`) that covered no diagnostic are returned in `unused`.
+pub fn apply_suppressions(source: &str, file_path: &str, diagnostics: Vec<LintDiagnostic>, report_unused: bool, aliases: &RuleAliases) -> SuppressionOutcome {
+    let directives = parse_directives(source);
+    if directives.is_empty() {
+        return SuppressionOutcome {
+            diagnostics,
+            ..SuppressionOutcome::default()
+        };
+    }
+
+    let (coverages, unmatched_enables) = coverages(&directives, file_path, aliases);
+    let mut used = vec![false; directives.len()];
+    let mut kept = Vec::new();
+    let mut suppressed = 0;
+
+    for diagnostic in diagnostics {
+        let covering = (diagnostic.rule_name != PARSE_ERROR_RULE)
+            // The innermost covering directive is the one marked used
+            .then(|| coverages.iter().rev().find(|coverage| coverage.covers(&diagnostic, aliases)))
+            .flatten();
+
+        match covering {
+            Some(coverage) => {
+                used[coverage.directive] = true;
+                suppressed += 1;
+            }
+            None => kept.push(diagnostic),
+        }
+    }
+
+    let unused = if report_unused {
+        directives
+            .iter()
+            .zip(&used)
+            .filter(|(directive, used)| directive.kind != DirectiveKind::Enable && !**used)
+            .map(|(directive, _)| unused_directive_diagnostic(directive, file_path))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    SuppressionOutcome {
+        diagnostics: kept,
+        suppressed,
+        unused,
+        unmatched_enables,
+    }
+}
+
+/// What each directive suppresses under the stack semantics above, with the lines of unmatched enables
+fn coverages(directives: &[SuppressionDirective], file_path: &str, aliases: &RuleAliases) -> (Vec<Coverage>, Vec<u32>) {
+    let mut closed = Vec::new();
+    let mut open: Vec<Coverage> = Vec::new();
+    let mut unmatched = Vec::new();
+
+    for (index, directive) in directives.iter().enumerate() {
+        let line = directive.line;
+        match directive.kind {
+            DirectiveKind::NextLine => closed.push(Coverage::new(index, directive.rules.clone(), line + 1, line + 1)),
+            DirectiveKind::Line => closed.push(Coverage::new(index, directive.rules.clone(), line, line)),
+            DirectiveKind::Block if directive.rules.is_empty() => open.push(Coverage::new(index, Vec::new(), line, u32::MAX)),
+            DirectiveKind::Block => open.extend(directive.rules.iter().map(|rule| Coverage::new(index, vec![rule.clone()], line, u32::MAX))),
+            DirectiveKind::Enable if directive.rules.is_empty() => match open.last().map(|coverage| coverage.directive) {
+                Some(innermost) => {
+                    while open.last().is_some_and(|coverage| coverage.directive == innermost) {
+                        closed.extend(open.pop().map(|coverage| Coverage { last: line, ..coverage }));
+                    }
+                }
+                None => {
+                    moon_warn!("Ignoring eslint-enable on line {} of {}: no eslint-disable is open", line, file_path);
+                    unmatched.push(line);
+                }
+            },
+            DirectiveKind::Enable => {
+                let mut matched_all = true;
+                for rule in &directive.rules {
+                    match open.iter().rposition(|coverage| coverage.covers_rule(rule, aliases)) {
+                        Some(position) if open[position].rules.is_empty() => open[position].reenabled.push((rule.clone(), line)),
+                        Some(position) => {
+                            let coverage = open.remove(position);
+                            closed.push(Coverage { last: line, ..coverage });
+                        }
+                        None => {
+                            moon_warn!("Ignoring eslint-enable {} on line {} of {}: no open eslint-disable covers it", rule, line, file_path);
+                            matched_all = false;
+                        }
+                    }
+                }
+                if !matched_all {
+                    unmatched.push(line);
+                }
+            }
+        }
+    }
+
+    closed.extend(open);
+    closed.sort_by_key(|coverage| coverage.directive);
+    (closed, unmatched)
+}
+
+fn unused_directive_diagnostic(directive: &SuppressionDirective, file_path: &str) -> LintDiagnostic {
+    let message = if directive.rules.is_empty() {
+        "Unused eslint-disable directive (no problems were reported).".to_string()
+    } else {
+        format!("Unused eslint-disable directive (no problems were reported from {}).", directive.rules.join(", "))
+    };
+
+    LintDiagnostic {
+        rule_name: UNUSED_DIRECTIVE_RULE.to_string(),
+        message,
+        file_path: file_path.to_string(),
+        line: directive.line,
+        column: directive.column,
+        end_line: directive.line,
+        end_column: directive.end_column,
+        severity: severities::default_diagnostic_severity(UNUSED_DIRECTIVE_RULE),
+        fix_available: false,
+        suggested_fix: None,
+        explanation: None,
+        patch: None,
+        ai_confidence: None,
+    }
+}
+
+/// Next `//` or `/* */` comment at or after byte `from`: `(start, body, end)`
+fn next_comment(line: &str, from: usize) -> Option<(usize, &str, usize)> {
+    let rest = line.get(from..)?;
+    let line_comment = rest.find("//");
+    let block_comment = rest.find("/*");
+
+    match (line_comment, block_comment) {
+        (Some(line_start), block) if block.map_or(true, |block_start| line_start < block_start) => {
+            let start = from + line_start;
+            Some((start, &line[start + 2..], line.len()))
+        }
+        (_, Some(block_start)) => {
+            let start = from + block_start;
+            let body_start = start + 2;
+            let end = line[body_start..].find("*/").map(|offset| body_start + offset + 2).unwrap_or(line.len());
+            let body_end = if line[..end].ends_with("*/") { end - 2 } else { end };
+            Some((start, &line[body_start..body_end.max(body_start)], end))
+        }
+        _ => None,
+    }
+}
+
+fn directive_kind(body: &str) -> Option<(DirectiveKind, &str)> {
+    // Longest prefixes first: `eslint-disable` is a prefix of the line variants
+    [
+        ("eslint-disable-next-line", DirectiveKind::NextLine),
+        ("eslint-disable-line", DirectiveKind::Line),
+        ("eslint-disable", DirectiveKind::Block),
+        ("eslint-enable", DirectiveKind::Enable),
+    ]
+    .into_iter()
+    .find_map(|(prefix, kind)| {
+        let rest = body.strip_prefix(prefix)?;
+        (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some((kind, rest))
+    })
+}
+
+/// Drop diagnostics whose rule is an entry's `rule_id` and whose message matches its regex.
+///
+/// Parse errors are never dropped. Entries with invalid regexes are skipped; config
+/// validation rejects them before a run.
+pub fn ignore_messages(diagnostics: Vec<LintDiagnostic>, ignored: &[IgnoreMessage], aliases: &RuleAliases) -> Vec<LintDiagnostic> {
+    if ignored.is_empty() {
+        return diagnostics;
+    }
+
+    let patterns: Vec<(&str, regex::Regex)> = ignored.iter().filter_map(|ignore| Some((ignore.rule_id.as_str(), ignore.regex().ok()?))).collect();
+    diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            diagnostic.rule_name == PARSE_ERROR_RULE
+                || !patterns.iter().any(|(rule_id, regex)| aliases.same_rule(rule_id, &diagnostic.rule_name) && regex.is_match(&diagnostic.message))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DiagnosticSeverity;
+
+    fn diagnostic(rule_name: &str, line: u32) -> LintDiagnostic {
+        LintDiagnostic {
+            rule_name: rule_name.to_string(),
+            message: "problem".to_string(),
+            file_path: "src/a.ts".to_string(),
+            line,
+            column: 1,
+            end_line: line,
+            end_column: 2,
+            severity: DiagnosticSeverity::Warning,
+            fix_available: false,
+            suggested_fix: None,
+            explanation: None,
+            patch: None,
+            ai_confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_parses_directive_kinds_rules_and_reasons() {
+        let source = "// eslint-disable-next-line eqeqeq, prefer-const -- legacy\nfoo(); /* eslint-disable-line */\n/* eslint-disable */\n/* eslint-enable */\n// eslint-disabled-ish\n";
+
+        let directives = parse_directives(source);
+
+        let kinds: Vec<(DirectiveKind, u32)> = directives.iter().map(|d| (d.kind, d.line)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DirectiveKind::NextLine, 1),
+                (DirectiveKind::Line, 2),
+                (DirectiveKind::Block, 3),
+                (DirectiveKind::Enable, 4)
+            ]
+        );
+        assert_eq!(directives[0].rules, vec!["eqeqeq", "prefer-const"]);
+        assert!(directives[1].rules.is_empty());
+        assert_eq!(directives[1].column, 8);
+    }
+
+    #[test]
+    fn test_stale_next_line_directive_reported_as_unused() {
+        let source = "// eslint-disable-next-line eqeqeq\nif (a === b) {}\n";
+
+        let outcome = apply_suppressions(source, "src/a.ts", Vec::new(), true, RuleAliases::builtin());
+
+        assert_eq!(outcome.unused.len(), 1);
+        let unused = &outcome.unused[0];
+        assert_eq!(unused.rule_name, UNUSED_DIRECTIVE_RULE);
+        assert_eq!((unused.line, unused.column), (1, 1));
+        assert!(unused.message.contains("eqeqeq"));
+    }
+
+    #[test]
+    fn test_active_next_line_directive_suppresses_and_is_not_reported() {
+        let source = "// eslint-disable-next-line eqeqeq\nif (a == b) {}\nif (c == d) {}\n";
+        let diagnostics = vec![diagnostic("eqeqeq", 2), diagnostic("eqeqeq", 3)];
+
+        let outcome = apply_suppressions(source, "src/a.ts", diagnostics, true, RuleAliases::builtin());
+
+        assert_eq!(outcome.suppressed, 1);
+        assert_eq!(outcome.diagnostics.len(), 1);
+        assert_eq!(outcome.diagnostics[0].line, 3);
+        assert!(outcome.unused.is_empty());
+    }
+
+    #[test]
+    fn test_block_directive_covers_until_enable() {
+        let source = "/* eslint-disable prefer-const */\nlet a = 1;\n/* eslint-enable prefer-const */\nlet b = 2;\n";
+        let diagnostics = vec![diagnostic("prefer-const", 2), diagnostic("prefer-const", 4), diagnostic("eqeqeq", 2)];
+
+        let outcome = apply_suppressions(source, "src/a.ts", diagnostics, false, RuleAliases::builtin());
+
+        let remaining: Vec<(&str, u32)> = outcome.diagnostics.iter().map(|d| (d.rule_name.as_str(), d.line)).collect();
+        assert_eq!(remaining, vec![("prefer-const", 4), ("eqeqeq", 2)]);
+    }
+
+    fn remaining(outcome: &SuppressionOutcome) -> Vec<(&str, u32)> {
+        outcome.diagnostics.iter().map(|d| (d.rule_name.as_str(), d.line)).collect()
+    }
+
+    #[test]
+    fn test_nested_disables_for_different_rules_close_independently() {
+        let source = "\
+/* eslint-disable no-console */
+console.log(a == b);
+/* eslint-disable eqeqeq */
+console.log(a == b);
+/* eslint-enable no-console */
+console.log(a == b);
+/* eslint-enable eqeqeq */
+console.log(a == b);
+";
+        let diagnostics = [2, 4, 6, 8].into_iter().flat_map(|line| [diagnostic("no-console", line), diagnostic("eqeqeq", line)]).collect();
+
+        let outcome = apply_suppressions(source, "src/a.ts", diagnostics, true, RuleAliases::builtin());
+
+        assert_eq!(remaining(&outcome), vec![("eqeqeq", 2), ("no-console", 6), ("no-console", 8), ("eqeqeq", 8)]);
+        assert!(outcome.unused.is_empty());
+        assert!(outcome.unmatched_enables.is_empty());
+    }
+
+    #[test]
+    fn test_unmatched_enable_is_ignored() {
+        let source = "\
+/* eslint-enable no-console */
+console.log(a);
+/* eslint-disable no-console */
+console.log(b);
+/* eslint-enable eqeqeq */
+console.log(c);
+/* eslint-enable no-console */
+console.log(d);
+/* eslint-enable */
+";
+        let diagnostics = [2, 4, 6, 8].into_iter().map(|line| diagnostic("no-console", line)).collect();
+
+        let outcome = apply_suppressions(source, "src/a.ts", diagnostics, false, RuleAliases::builtin());
+
+        // The stray enables neither end the block early nor count as closing it
+        assert_eq!(remaining(&outcome), vec![("no-console", 2), ("no-console", 8)]);
+        assert_eq!(outcome.unmatched_enables, vec![1, 5, 9]);
+    }
+
+    #[test]
+    fn test_overlapping_blocks_follow_stack_order() {
+        let source = "\
+/* eslint-disable */
+debugger; console.log(a);
+/* eslint-disable no-console */
+debugger; console.log(b);
+/* eslint-enable no-console */
+debugger; console.log(c);
+/* eslint-enable no-console */
+debugger; console.log(d);
+/* eslint-enable */
+debugger; console.log(e);
+";
+        let diagnostics = [2, 4, 6, 8, 10]
+            .into_iter()
+            .flat_map(|line| [diagnostic("no-debugger", line), diagnostic("no-console", line)])
+            .collect();
+
+        let outcome = apply_suppressions(source, "src/a.ts", diagnostics, true, RuleAliases::builtin());
+
+        // The first enable closes the inner no-console block, the second takes no-console out
+        // of the bare block, and the bare enable closes what is left of it
+        assert_eq!(remaining(&outcome), vec![("no-console", 8), ("no-debugger", 10), ("no-console", 10)]);
+        assert!(outcome.unused.is_empty());
+        assert!(outcome.unmatched_enables.is_empty());
+    }
+
+    #[test]
+    fn test_canonical_id_suppresses_aliases_from_other_engines() {
+        let source = "// eslint-disable-next-line no-console\nconsole.log(a);\n/* eslint-disable-line oxc:noConsole */ console.log(b);\nconsole.log(c);\n";
+        let diagnostics = vec![
+            diagnostic("eslint:no-console", 2),
+            diagnostic("oxc:noConsole", 2),
+            diagnostic("no-console", 2),
+            diagnostic("eslint/no-console", 3),
+            diagnostic("oxc:noConsole", 4),
+            diagnostic("no-debugger", 2),
+        ];
+
+        let outcome = apply_suppressions(source, "src/a.ts", diagnostics, true, RuleAliases::builtin());
+
+        let remaining: Vec<(&str, u32)> = outcome.diagnostics.iter().map(|d| (d.rule_name.as_str(), d.line)).collect();
+        assert_eq!(remaining, vec![("oxc:noConsole", 4), ("no-debugger", 2)]);
+        assert_eq!(outcome.suppressed, 4);
+        assert!(outcome.unused.is_empty());
+
+        let ignored = vec![IgnoreMessage {
+            rule_id: "eslint:no-console".to_string(),
+            message_regex: "problem".to_string(),
+        }];
+        let kept = ignore_messages(outcome.diagnostics, &ignored, RuleAliases::builtin());
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].rule_name, "no-debugger");
+    }
+
+    #[test]
+    fn test_parse_errors_are_never_suppressed() {
+        let source = "// eslint-disable-next-line\nconst a = {;\n";
+
+        let outcome = apply_suppressions(source, "src/a.ts", vec![diagnostic(PARSE_ERROR_RULE, 2)], true, RuleAliases::builtin());
+
+        assert_eq!(outcome.diagnostics.len(), 1);
+        assert_eq!(outcome.unused.len(), 1);
+    }
+
+    #[test]
+    fn test_ignore_messages_mutes_one_variant_of_a_rule() {
+        let message = |rule_name: &str, line: u32, message: &str| LintDiagnostic {
+            message: message.to_string(),
+            ..diagnostic(rule_name, line)
+        };
+        let diagnostics = vec![
+            message("no-console", 1, "Unexpected console.debug statement"),
+            message("no-console", 2, "Unexpected console.log statement"),
+            message("no-debugger", 3, "Unexpected console.debug statement"),
+            message(PARSE_ERROR_RULE, 4, "Unexpected console.debug statement"),
+        ];
+        let ignored = vec![IgnoreMessage {
+            rule_id: "no-console".to_string(),
+            message_regex: r"console\.debug".to_string(),
+        }];
+
+        let kept = ignore_messages(diagnostics, &ignored, RuleAliases::builtin());
+
+        let lines: Vec<u32> = kept.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Test harness keeping rule documentation honest
+//!
+//! [`validate_rule_docs`] runs each documented rule through the [`RuleExecutor`] on its own
+//! examples and reports every bad example that is not flagged, every good example that is,
+//! and every example that fails to parse.
+
+use super::docs::{RuleDoc, RULE_DOCS};
+use super::{RuleExecutionContext, RuleExecutor, RuleImplementation};
+use crate::buffer_lint::PARSE_ERROR_RULE;
+use crate::rule_types::{FixStatus, RuleCategory, RuleMetadata};
+use crate::rulebase::{severities, static_rules};
+use oxc_span::SourceType;
+
+/// Virtual path examples are linted as
+const EXAMPLE_PATH: &str = "example.ts";
+
+/// A documented example that disagrees with its rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleDocFailure {
+    pub rule_id: String,
+    pub example: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for RuleDocFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} in example:\n{}", self.rule_id, self.reason, self.example)
+    }
+}
+
+/// Check every rule in [`RULE_DOCS`] against its own examples
+pub fn validate_rule_docs() -> Vec<RuleDocFailure> {
+    validate_docs(RULE_DOCS)
+}
+
+/// Check `docs` against their rules; an empty result means every example is accurate
+pub fn validate_docs(docs: &[RuleDoc]) -> Vec<RuleDocFailure> {
+    let executor = RuleExecutor::new().with_ai_availability(false);
+    let mut failures = Vec::new();
+
+    for doc in docs {
+        let rule = documented_rule(doc.rule_id);
+        let examples = doc.bad.iter().map(|example| (example, true)).chain(doc.good.iter().map(|example| (example, false)));
+        for (example, should_flag) in examples {
+            let ctx = RuleExecutionContext {
+                code: example,
+                file_path: EXAMPLE_PATH,
+                source_type: SourceType::ts(),
+                program: None,
+            };
+            let diagnostics = executor.evaluate(std::slice::from_ref(&rule), &ctx).diagnostics;
+
+            let reason = if diagnostics.iter().any(|diagnostic| diagnostic.rule_name == PARSE_ERROR_RULE) {
+                Some("example does not parse")
+            } else if should_flag && diagnostics.is_empty() {
+                Some("bad example is not flagged")
+            } else if !should_flag && !diagnostics.is_empty() {
+                Some("good example is flagged")
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                failures.push(RuleDocFailure {
+                    rule_id: doc.rule_id.to_string(),
+                    example: example.to_string(),
+                    reason: reason.to_string(),
+                });
+            }
+        }
+    }
+
+    failures
+}
+
+fn documented_rule(rule_id: &str) -> RuleMetadata {
+    RuleMetadata {
+        id: rule_id.to_string(),
+        name: rule_id.to_string(),
+        description: String::new(),
+        category: RuleCategory::Style,
+        severity: severities::default_severity(rule_id),
+        fix_status: FixStatus::Autofix,
+        fix_safety: static_rules::fix_safety(rule_id),
+        ai_enhanced: false,
+        cost: 1,
+        tags: vec![],
+        dependencies: vec![],
+        implementation: RuleImplementation::OxcStatic {
+            rule_name: rule_id.to_string(),
+        },
+        config_schema: None,
+        applies_to: None,
+    }
+}
+
+/// Metadata running the built-in `rule_id` as a warning with its built-in fix safety
+#[cfg(test)]
+pub(crate) fn static_rule(rule_id: &str) -> RuleMetadata {
+    RuleMetadata {
+        severity: crate::rule_types::RuleSeverity::Warning,
+        ..documented_rule(rule_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_documented_examples_match_their_rules() {
+        let failures = validate_rule_docs();
+        assert!(
+            failures.is_empty(),
+            "rule docs out of date:\n{}",
+            failures.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+        );
+    }
+
+    #[test]
+    fn test_inaccurate_examples_are_reported() {
+        let doc = RuleDoc {
+            rule_id: "eqeqeq",
+            rationale: "",
+            fix: "",
+            good: &["if (a == b) {}\n"],
+            bad: &["if (a === b) {}\n", "if (a == {\n"],
+        };
+
+        let reasons: Vec<String> = validate_docs(&[doc]).into_iter().map(|failure| failure.reason).collect();
+
+        assert_eq!(reasons, vec!["bad example is not flagged", "example does not parse", "good example is flagged"]);
+    }
+}
@@ -0,0 +1,469 @@
+//! # Session Directory Management
+//!
+//! Every moon-shine run gets its own session directory under
+//! `<moonshine_dir>/sessions/`, so concurrent Moon tasks never write into each
+//! other's prompts or debug artifacts. Session directory names encode the
+//! creation time, which lets retention pruning work without trusting filesystem
+//! timestamps, and an `.active` marker protects sessions that are still in use by
+//! another run. Caches that should survive across runs live in the separate
+//! `cache_directory` (see [`ensure_cache_directory`]), which pruning never touches.
+//!
+//! A run holds its session through a [`SessionGuard`]. If the run fails or bails out
+//! before calling [`SessionGuard::complete`], dropping the guard deletes the partial
+//! session directory instead of leaving it for pruning; with debug retention on, the
+//! directory is kept so the failure can be inspected. Release builds abort on panic, so
+//! a crashed run never drops its guard: its session keeps the `.active` marker until
+//! the marker goes stale, and later runs prune it like any other expired session.
+//!
+//! @category session
+//! @safe team
+//! @mvp core
+//! @complexity medium
+//! @since 2.0.0
+
+use crate::config::MoonShineConfig;
+use crate::error::{Error, Result};
+use crate::moon_pdk_interface::ensure_mutation_allowed;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix for every session directory name.
+const SESSION_PREFIX: &str = "session-";
+/// Timestamp format embedded in session directory names (UTC, millisecond precision).
+const SESSION_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+/// Marker file present while a run is using its session.
+const ACTIVE_MARKER: &str = ".active";
+/// Active markers older than this belong to crashed runs and stop protecting the session.
+const ACTIVE_MARKER_STALE_HOURS: i64 = 24;
+
+/// A single run's session directory with typed artifact paths.
+///
+/// The session stays marked active until it is released or dropped.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Session {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub dir: PathBuf,
+}
+
+impl Session {
+    /// Prompt snapshot used by this run
+    pub fn prompts_path(&self) -> PathBuf {
+        self.dir.join("prompts.json")
+    }
+
+    /// Debug artifacts (raw AI responses, intermediate diagnostics)
+    pub fn debug_dir(&self) -> PathBuf {
+        self.dir.join("debug")
+    }
+
+    /// Staged file contents awaiting an atomic commit
+    pub fn staging_dir(&self) -> PathBuf {
+        self.dir.join("staging")
+    }
+
+    /// Path for a named artifact at the session root
+    pub fn artifact_path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Release the session so future pruning may remove it once it expires
+    pub fn release(&self) -> Result<()> {
+        let marker = self.dir.join(ACTIVE_MARKER);
+        match fs::remove_file(&marker) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error(&marker, e)),
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            moon_warn!("Failed to release session {}: {}", self.id, e);
+        }
+    }
+}
+
+/// Deletes an unfinished run's session directory when dropped.
+///
+/// Dereferences to the guarded [`Session`].
+#[derive(Debug)]
+pub struct SessionGuard {
+    session: Option<Session>,
+    keep_unfinished: bool,
+}
+
+impl SessionGuard {
+    /// Guard `session`; `keep_unfinished` (debug retention) keeps it even if the run never completes
+    pub fn new(session: Session, keep_unfinished: bool) -> Self {
+        Self {
+            session: Some(session),
+            keep_unfinished,
+        }
+    }
+
+    /// Mark the run finished; the session is released and left to retention pruning
+    pub fn complete(mut self) -> Session {
+        self.session.take().expect("session is held until completed or dropped")
+    }
+}
+
+impl std::ops::Deref for SessionGuard {
+    type Target = Session;
+
+    fn deref(&self) -> &Session {
+        self.session.as_ref().expect("session is held until completed or dropped")
+    }
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        let Some(session) = self.session.take() else {
+            return;
+        };
+        if self.keep_unfinished {
+            moon_debug!("Keeping unfinished session {} for debugging", session.id);
+            return;
+        }
+
+        match fs::remove_dir_all(&session.dir) {
+            Ok(()) => moon_debug!("Removed unfinished session {}", session.id),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => moon_warn!("Failed to remove unfinished session {}: {}", session.id, e),
+        }
+    }
+}
+
+/// A session directory found on disk, possibly owned by another run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionInfo {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub dir: PathBuf,
+}
+
+impl SessionInfo {
+    /// Whether the session holds debug artifacts
+    pub fn is_debug(&self) -> bool {
+        self.dir.join("debug").is_dir()
+    }
+}
+
+/// Allocates per-run session directories and prunes expired ones.
+#[derive(Debug, Clone)]
+pub struct SessionManager {
+    sessions_root: PathBuf,
+}
+
+impl SessionManager {
+    /// Manage sessions under `<moonshine_dir>/sessions`
+    pub fn new(moonshine_dir: impl AsRef<Path>) -> Self {
+        Self {
+            sessions_root: moonshine_dir.as_ref().join("sessions"),
+        }
+    }
+
+    /// Directory containing every session
+    pub fn sessions_root(&self) -> &Path {
+        &self.sessions_root
+    }
+
+    /// Create a new, uniquely named session and mark it active.
+    ///
+    /// The run ID is a random UUID, so two runs starting in the same millisecond
+    /// still get distinct directories; `create_dir` (not `create_dir_all`) makes a
+    /// collision fail loudly instead of sharing a directory.
+    pub fn create_session(&self, now: DateTime<Utc>) -> Result<Session> {
+        ensure_mutation_allowed(format!("create a session in {}", self.sessions_root.display()))?;
+        fs::create_dir_all(&self.sessions_root).map_err(|e| io_error(&self.sessions_root, e))?;
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let dir = self
+            .sessions_root
+            .join(format!("{}{}-{}", SESSION_PREFIX, now.format(SESSION_TIMESTAMP_FORMAT), id));
+
+        fs::create_dir(&dir).map_err(|e| io_error(&dir, e))?;
+        let marker = dir.join(ACTIVE_MARKER);
+        fs::write(&marker, now.to_rfc3339()).map_err(|e| io_error(&marker, e))?;
+
+        moon_debug!("Allocated session {} at {}", id, dir.display());
+        Ok(Session { id, created_at: now, dir })
+    }
+
+    /// List sessions found on disk, ignoring entries that are not session directories.
+    pub fn list_sessions(&self) -> Result<Vec<SessionInfo>> {
+        let entries = match fs::read_dir(&self.sessions_root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(&self.sessions_root, e)),
+        };
+
+        let mut sessions: Vec<SessionInfo> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| parse_session_dir(&entry.path()))
+            .collect();
+        sessions.sort_by_key(|session| session.created_at);
+        Ok(sessions)
+    }
+
+    /// Remove sessions older than `max_age_hours`, returning the removed session IDs.
+    pub fn prune_older_than(&self, max_age_hours: u32, now: DateTime<Utc>) -> Result<Vec<String>> {
+        self.prune_with(now, |_| max_age_hours)
+    }
+
+    /// Remove sessions older than the retention `max_age_hours` returns for them.
+    ///
+    /// Sessions whose active marker is fresh belong to a concurrent run and are kept
+    /// regardless of age. Failures to remove one session are logged and do not stop
+    /// the sweep, since another run may be pruning the same directory.
+    pub fn prune_with<F>(&self, now: DateTime<Utc>, max_age_hours: F) -> Result<Vec<String>>
+    where
+        F: Fn(&SessionInfo) -> u32,
+    {
+        ensure_mutation_allowed(format!("prune sessions in {}", self.sessions_root.display()))?;
+        let mut removed = Vec::new();
+
+        for session in self.list_sessions()? {
+            let cutoff = now - Duration::hours(i64::from(max_age_hours(&session)));
+            if session.created_at >= cutoff || is_active(&session, now) {
+                continue;
+            }
+
+            match fs::remove_dir_all(&session.dir) {
+                Ok(()) => removed.push(session.id),
+                // Already removed by a concurrent prune
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => moon_warn!("Failed to prune session {}: {}", session.dir.display(), e),
+            }
+        }
+
+        if !removed.is_empty() {
+            moon_info!("Pruned {} expired session(s)", removed.len());
+        }
+        Ok(removed)
+    }
+}
+
+/// Remove expired sessions under `moonshine_dir` according to the retention config.
+///
+/// Regular sessions expire after `cleanup_sessions_older_than_hours`. When
+/// `keep_debug_sessions()` is enabled, sessions holding debug artifacts are kept for
+/// an additional `debug_session_retention_hours` window so they can still be
+/// inspected after ordinary sessions are gone. `now` is injected so callers and
+/// tests control the clock.
+pub fn cleanup_sessions(moonshine_dir: impl AsRef<Path>, config: &MoonShineConfig, now: DateTime<Utc>) -> Result<Vec<String>> {
+    let cleanup_hours = config.cleanup_sessions_older_than_hours();
+    let debug_hours = if config.keep_debug_sessions() {
+        cleanup_hours.saturating_add(config.debug_session_retention_hours())
+    } else {
+        cleanup_hours
+    };
+
+    SessionManager::new(moonshine_dir).prune_with(now, |session| if session.is_debug() { debug_hours } else { cleanup_hours })
+}
+
+/// Create the shared cache directory if needed and return its path.
+///
+/// This does not depend on any session existing; callers pass
+/// `MoonShineConfig::resolved_cache_directory()`.
+pub fn ensure_cache_directory(cache_dir: impl AsRef<Path>) -> Result<PathBuf> {
+    let cache_dir = cache_dir.as_ref();
+    ensure_mutation_allowed(format!("create {}", cache_dir.display()))?;
+    fs::create_dir_all(cache_dir).map_err(|e| io_error(cache_dir, e))?;
+    Ok(cache_dir.to_path_buf())
+}
+
+fn parse_session_dir(path: &Path) -> Option<SessionInfo> {
+    let name = path.file_name()?.to_str()?;
+    let rest = name.strip_prefix(SESSION_PREFIX)?;
+    let (timestamp, id) = rest.split_once('-')?;
+    let created_at = NaiveDateTime::parse_from_str(timestamp, SESSION_TIMESTAMP_FORMAT).ok()?.and_utc();
+
+    Some(SessionInfo {
+        id: id.to_string(),
+        created_at,
+        dir: path.to_path_buf(),
+    })
+}
+
+fn is_active(session: &SessionInfo, now: DateTime<Utc>) -> bool {
+    let Ok(contents) = fs::read_to_string(session.dir.join(ACTIVE_MARKER)) else {
+        return false;
+    };
+
+    DateTime::parse_from_rfc3339(contents.trim())
+        .map(|marked_at| now - marked_at.with_timezone(&Utc) < Duration::hours(ACTIVE_MARKER_STALE_HOURS))
+        .unwrap_or(false)
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> Error {
+    Error::Io {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sessions_are_unique_per_run() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let now = Utc::now();
+
+        let first = manager.create_session(now).unwrap();
+        let second = manager.create_session(now).unwrap();
+
+        assert_ne!(first.dir, second.dir);
+        assert!(first.dir.is_dir() && second.dir.is_dir());
+        assert_eq!(first.prompts_path(), first.dir.join("prompts.json"));
+        assert_eq!(manager.list_sessions().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_session_guard_removes_session_of_failed_run() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let run = |keep_unfinished: bool| -> Result<()> {
+            let session = SessionGuard::new(manager.create_session(Utc::now())?, keep_unfinished);
+            fs::create_dir_all(session.staging_dir()).unwrap();
+            fs::write(session.staging_dir().join("index.ts"), "let a = 1;\n").unwrap();
+            Err(Error::config("run failed mid-way"))
+        };
+
+        assert!(run(false).is_err());
+        assert!(manager.list_sessions().unwrap().is_empty(), "the partial session is cleaned up");
+
+        assert!(run(true).is_err());
+        let kept = manager.list_sessions().unwrap();
+        assert_eq!(kept.len(), 1, "debug retention keeps the unfinished session");
+        assert!(kept[0].dir.join("staging/index.ts").is_file());
+        assert!(!kept[0].dir.join(ACTIVE_MARKER).exists(), "the kept session is released");
+
+        let completed = SessionGuard::new(manager.create_session(Utc::now()).unwrap(), false).complete();
+        assert!(completed.dir.is_dir(), "completed sessions are left to retention pruning");
+    }
+
+    #[test]
+    fn test_prune_removes_old_sessions_and_keeps_active() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let now = Utc::now();
+
+        let expired = manager.create_session(now - Duration::hours(72)).unwrap();
+        expired.release().unwrap();
+        let recent = manager.create_session(now - Duration::hours(1)).unwrap();
+        recent.release().unwrap();
+        // A concurrent run started long ago but still holds its session
+        let concurrent = manager.create_session(now - Duration::hours(72)).unwrap();
+        fs::write(concurrent.dir.join(ACTIVE_MARKER), (now - Duration::minutes(5)).to_rfc3339()).unwrap();
+        let active = manager.create_session(now).unwrap();
+
+        let removed = manager.prune_older_than(48, now).unwrap();
+
+        assert_eq!(removed, vec![expired.id.clone()]);
+        assert!(!expired.dir.exists());
+        assert!(recent.dir.exists());
+        assert!(concurrent.dir.exists());
+        assert!(active.dir.exists());
+    }
+
+    #[test]
+    fn test_stale_active_marker_does_not_block_pruning() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let now = Utc::now();
+
+        // Crashed run: never released its session
+        let crashed = manager.create_session(now - Duration::hours(72)).unwrap();
+
+        assert_eq!(manager.prune_older_than(48, now).unwrap(), vec![crashed.id.clone()]);
+    }
+
+    #[test]
+    fn test_cleanup_sessions_honors_retention_and_keeps_debug_longer() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let now = Utc::now();
+        let mut config = MoonShineConfig::default();
+        config.debug.keep_sessions = Some(true);
+
+        let cleanup_hours = i64::from(config.cleanup_sessions_older_than_hours());
+        let debug_hours = cleanup_hours + i64::from(config.debug_session_retention_hours());
+
+        let fresh = manager.create_session(now - Duration::hours(cleanup_hours - 1)).unwrap();
+        let expired = manager.create_session(now - Duration::hours(cleanup_hours + 1)).unwrap();
+        let debug_in_window = manager.create_session(now - Duration::hours(cleanup_hours + 1)).unwrap();
+        fs::create_dir_all(debug_in_window.debug_dir()).unwrap();
+        let debug_expired = manager.create_session(now - Duration::hours(debug_hours + 1)).unwrap();
+        fs::create_dir_all(debug_expired.debug_dir()).unwrap();
+        for session in [&fresh, &expired, &debug_in_window, &debug_expired] {
+            session.release().unwrap();
+        }
+
+        let mut removed = cleanup_sessions(dir.path(), &config, now).unwrap();
+        removed.sort();
+        let mut expected = vec![expired.id.clone(), debug_expired.id.clone()];
+        expected.sort();
+
+        assert_eq!(removed, expected);
+        assert!(fresh.dir.exists());
+        assert!(debug_in_window.dir.exists());
+    }
+
+    #[test]
+    fn test_cleanup_sessions_treats_debug_like_regular_when_not_kept() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let now = Utc::now();
+        let config = MoonShineConfig::default();
+        assert!(!config.keep_debug_sessions());
+
+        let debug = manager
+            .create_session(now - Duration::hours(i64::from(config.cleanup_sessions_older_than_hours()) + 1))
+            .unwrap();
+        fs::create_dir_all(debug.debug_dir()).unwrap();
+        debug.release().unwrap();
+
+        assert_eq!(cleanup_sessions(dir.path(), &config, now).unwrap(), vec![debug.id.clone()]);
+    }
+
+    #[test]
+    fn test_cache_directory_created_without_sessions() {
+        let dir = tempdir().unwrap();
+        let mut config = MoonShineConfig::default();
+        config.cache_directory = Some(dir.path().join("cache").display().to_string());
+
+        let cache_dir = ensure_cache_directory(config.resolved_cache_directory()).unwrap();
+
+        assert!(cache_dir.is_dir());
+        assert!(!SessionManager::new(dir.path()).sessions_root().exists());
+    }
+
+    #[test]
+    fn test_caches_persist_across_session_cleanup() {
+        let dir = tempdir().unwrap();
+        let manager = SessionManager::new(dir.path());
+        let now = Utc::now();
+        let config = MoonShineConfig::default();
+
+        let cache_dir = ensure_cache_directory(dir.path().join("cache")).unwrap();
+        let cached_response = cache_dir.join("responses.json");
+        fs::write(&cached_response, "{}").unwrap();
+        let expired = manager
+            .create_session(now - Duration::hours(i64::from(config.cleanup_sessions_older_than_hours()) + 1))
+            .unwrap();
+        expired.release().unwrap();
+
+        assert_eq!(cleanup_sessions(dir.path(), &config, now).unwrap(), vec![expired.id.clone()]);
+        assert!(!expired.dir.exists());
+        assert!(cached_response.is_file());
+    }
+}
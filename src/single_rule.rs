@@ -0,0 +1,143 @@
+//! # Running One Rule in Isolation
+//!
+//! Rule authors and the editor's "run this rule" action need one rule's diagnostics without
+//! the rest of the pipeline: no other rules, no fix passes, no AI explanations. The file is
+//! parsed once and its semantic model built once, as in a full run, and only the requested
+//! rule executes. Inline suppressions still apply, so the result matches what the rule
+//! contributes to a full run.
+//!
+//! A rule ID resolves through the configured aliases to a built-in static rule first and to
+//! the rulebase otherwise. Rules defined outside the registry run through [`run_rule`].
+//!
+//! @category analysis
+//! @safe team
+//! @mvp enhanced
+//! @complexity low
+//! @since 2.0.0
+
+use crate::buffer_lint::{canonical_diagnostics, parse_error_diagnostics, rule_executor};
+use crate::config::MoonShineConfig;
+use crate::error::{Error, Result};
+use crate::rule_registry::RuleRegistry;
+use crate::rule_types::{FixStatus, RuleCategory, RuleMetadata};
+use crate::rulebase::execution_engine::STATIC_RULE_NAMES;
+use crate::rulebase::rule_aliases::RuleAliases;
+use crate::rulebase::suppressions::apply_suppressions;
+use crate::rulebase::{severities, static_rules, RuleExecutionContext, RuleImplementation};
+use crate::types::LintDiagnostic;
+use oxc_allocator::Allocator;
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use std::path::Path;
+
+/// Run the rule `rule_id` alone on `source`, labelled `file_path`.
+///
+/// @param rule_id A built-in or rulebase rule ID, or any alias of one.
+/// @param source File contents; never written anywhere.
+/// @param file_path Path used for source-type detection and diagnostics only.
+/// @param config Configuration; rule settings, aliases and severity overrides apply.
+/// @returns The rule's diagnostics, or parse errors when `source` does not parse.
+///
+/// @category analysis
+/// @safe team
+/// @mvp enhanced
+/// @complexity low
+/// @since 2.0.0
+pub fn run_single_rule(rule_id: &str, source: &str, file_path: &str, config: &MoonShineConfig) -> Result<Vec<LintDiagnostic>> {
+    let registry = RuleRegistry::new()?;
+    let rule = resolve_rule(&registry, rule_id, config)?;
+    run_rule(&rule, source, file_path, config)
+}
+
+/// Run `rule`, which need not be in the registry, alone on `source`; see [`run_single_rule`].
+pub fn run_rule(rule: &RuleMetadata, source: &str, file_path: &str, config: &MoonShineConfig) -> Result<Vec<LintDiagnostic>> {
+    let source_type = SourceType::from_path(Path::new(file_path))
+        .map_err(|_| Error::validation("file_path", "a TypeScript or JavaScript file path", file_path))?;
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, source_type).parse();
+    if !parsed.errors.is_empty() {
+        return Ok(parse_error_diagnostics(&parsed.errors, source, file_path));
+    }
+
+    let ctx = RuleExecutionContext {
+        code: source,
+        file_path,
+        source_type,
+        program: Some(&parsed.program),
+    };
+    let outcome = rule_executor(config).evaluate(std::slice::from_ref(rule), &ctx);
+    let aliases = RuleAliases::from_config(&config.linting);
+    let suppression = apply_suppressions(source, file_path, canonical_diagnostics(outcome.diagnostics, &aliases), false, &aliases);
+    Ok(suppression.diagnostics)
+}
+
+/// The rule `rule_id` names: a built-in static rule, else a rulebase rule
+pub fn resolve_rule(registry: &RuleRegistry, rule_id: &str, config: &MoonShineConfig) -> Result<RuleMetadata> {
+    let canonical = RuleAliases::from_config(&config.linting).canonicalize(rule_id);
+    if let Some(&rule_name) = STATIC_RULE_NAMES.iter().find(|&&name| name == canonical) {
+        return Ok(static_rule(rule_name, registry.get_rule(rule_name)));
+    }
+    registry.get_rule(rule_id).or_else(|| registry.get_rule(&canonical)).ok_or_else(|| {
+        Error::config_field(
+            format!("Unknown rule '{}': not a built-in rule or a rulebase rule", rule_id),
+            "rule_id",
+            Some(rule_id),
+        )
+    })
+}
+
+/// Metadata running the built-in `rule_name`, keeping the rulebase's description and category when it has the rule
+fn static_rule(rule_name: &str, registered: Option<RuleMetadata>) -> RuleMetadata {
+    let implementation = RuleImplementation::OxcStatic {
+        rule_name: rule_name.to_string(),
+    };
+    match registered {
+        Some(rule) => RuleMetadata { implementation, ..rule },
+        None => RuleMetadata {
+            id: rule_name.to_string(),
+            name: rule_name.to_string(),
+            description: String::new(),
+            category: RuleCategory::Correctness,
+            severity: severities::default_severity(rule_name),
+            fix_status: FixStatus::Autofix,
+            fix_safety: static_rules::fix_safety(rule_name),
+            ai_enhanced: false,
+            cost: 1,
+            tags: vec![],
+            dependencies: vec![],
+            implementation,
+            config_schema: None,
+            applies_to: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "let total = 1;\nconsole.log(total);\nif (total == 1) {\n  debugger;\n}\n";
+
+    #[test]
+    fn test_single_rule_reports_only_its_own_diagnostics() {
+        let config = MoonShineConfig::default();
+
+        let diagnostics = run_single_rule("oxc:noDebugger", SOURCE, "src/totals.ts", &config).unwrap();
+        let reported: Vec<(&str, u32)> = diagnostics.iter().map(|diagnostic| (diagnostic.rule_name.as_str(), diagnostic.line)).collect();
+        assert_eq!(reported, vec![("no-debugger", 4)]);
+
+        let suppressed = SOURCE.replace("  debugger;", "  // eslint-disable-next-line no-debugger\n  debugger;");
+        assert!(run_single_rule("no-debugger", &suppressed, "src/totals.ts", &config).unwrap().is_empty());
+
+        let eqeqeq = run_single_rule("eqeqeq", SOURCE, "src/totals.ts", &config).unwrap();
+        assert!(!eqeqeq.is_empty() && eqeqeq.iter().all(|diagnostic| diagnostic.rule_name == "eqeqeq"));
+    }
+
+    #[test]
+    fn test_unknown_rule_id_is_an_error() {
+        let error = run_single_rule("no-such-rule", SOURCE, "src/totals.ts", &MoonShineConfig::default()).unwrap_err();
+
+        assert!(matches!(error, Error::Config { ref field, .. } if field.as_deref() == Some("rule_id")));
+        assert!(error.to_string().contains("no-such-rule"));
+    }
+}
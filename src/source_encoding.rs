@@ -0,0 +1,140 @@
+//! # Source File Encoding
+//!
+//! The analyzers assume UTF-8 source text. Files are checked when they are read:
+//! binary files (containing NUL bytes) and invalid UTF-8 are skipped with an
+//! informational [`SKIPPED_FILE_RULE`] diagnostic instead of reaching the parser.
+//! With `linting.latin1_fallback`, non-UTF-8 text is decoded as latin-1 (every byte
+//! maps to the code point of the same value) and analyzed as usual.
+//!
+//! Invalid UTF-8 and the latin-1 fallback apply to native builds only: under `wasm` the
+//! Moon host returns file contents as a string, so a non-UTF-8 file fails to read (see
+//! [`read_file_bytes`](crate::moon_pdk_interface::read_file_bytes)). NUL bytes are
+//! detected on both.
+//!
+//! @category analysis
+//! @safe team
+//! @mvp core
+//! @complexity low
+//! @since 2.0.0
+
+use crate::error::{Error, Result};
+use crate::rulebase::severities;
+use crate::types::LintDiagnostic;
+
+/// Rule name of the informational diagnostic for files skipped because of their encoding or the file cap
+pub const SKIPPED_FILE_RULE: &str = "skipped-file";
+
+/// Why a file was not analyzed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    Binary,                             // Contains NUL bytes
+    InvalidUtf8 { valid_up_to: usize }, // First invalid byte offset
+    MaxFiles { limit: u32 },            // Beyond the run's `max_files` cap
+    Generated,                          // Generated file under `linting.generated_files = skip`
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Binary => write!(f, "binary file"),
+            SkipReason::InvalidUtf8 { valid_up_to } => write!(f, "invalid UTF-8 at byte {}", valid_up_to),
+            SkipReason::MaxFiles { limit } => write!(f, "beyond the max_files limit of {}", limit),
+            SkipReason::Generated => write!(f, "generated file"),
+        }
+    }
+}
+
+/// Source text ready for analysis, or why there is none
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedSource {
+    Utf8(String),
+    Latin1(String), // Best-effort decode of non-UTF-8 text
+    Skipped(SkipReason),
+}
+
+/// Decode raw file bytes, falling back to latin-1 for non-UTF-8 text when allowed
+pub fn decode_source(bytes: Vec<u8>, latin1_fallback: bool) -> DecodedSource {
+    if bytes.contains(&0) {
+        return DecodedSource::Skipped(SkipReason::Binary);
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(text) => DecodedSource::Utf8(text),
+        Err(error) if latin1_fallback => DecodedSource::Latin1(error.as_bytes().iter().map(|byte| char::from(*byte)).collect()),
+        Err(error) => DecodedSource::Skipped(SkipReason::InvalidUtf8 {
+            valid_up_to: error.utf8_error().valid_up_to(),
+        }),
+    }
+}
+
+/// Read and decode a source file; see [`decode_source`]
+pub fn read_source_file(path: &str, latin1_fallback: bool) -> Result<DecodedSource> {
+    let bytes = crate::moon_pdk_interface::read_file_bytes(path).map_err(|error| Error::Io {
+        path: path.to_string(),
+        source: std::io::Error::other(error.to_string()),
+    })?;
+    Ok(decode_source(bytes, latin1_fallback))
+}
+
+/// Informational diagnostic recording that `file_path` was not analyzed
+pub fn skipped_file_diagnostic(file_path: &str, reason: SkipReason) -> LintDiagnostic {
+    let message = match reason {
+        SkipReason::Binary => format!("File not analyzed: {}", reason),
+        SkipReason::InvalidUtf8 { .. } => format!("File not analyzed: {} (set linting.latin1_fallback to decode it as latin-1)", reason),
+        SkipReason::MaxFiles { .. } => format!("File not analyzed: {} (raise max_files or pass --max-files)", reason),
+        SkipReason::Generated => format!("File not analyzed: {} (set linting.generated_files to no-ai to lint it)", reason),
+    };
+
+    LintDiagnostic {
+        rule_name: SKIPPED_FILE_RULE.to_string(),
+        message,
+        file_path: file_path.to_string(),
+        line: 1,
+        column: 1,
+        end_line: 1,
+        end_column: 1,
+        severity: severities::default_diagnostic_severity(SKIPPED_FILE_RULE),
+        fix_available: false,
+        suggested_fix: None,
+        explanation: None,
+        patch: None,
+        ai_confidence: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DiagnosticSeverity;
+
+    const INVALID_UTF8: &[u8] = b"const name = \"caf\xe9\";\n";
+
+    #[test]
+    fn test_invalid_utf8_file_is_skipped_with_info_diagnostic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("latin1.ts");
+        std::fs::write(&path, INVALID_UTF8).unwrap();
+
+        let decoded = read_source_file(path.to_str().unwrap(), false).unwrap();
+
+        assert_eq!(decoded, DecodedSource::Skipped(SkipReason::InvalidUtf8 { valid_up_to: 17 }));
+        let diagnostic = skipped_file_diagnostic("latin1.ts", SkipReason::InvalidUtf8 { valid_up_to: 17 });
+        assert_eq!(diagnostic.rule_name, SKIPPED_FILE_RULE);
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Info);
+        assert!(diagnostic.message.contains("invalid UTF-8 at byte 17"));
+    }
+
+    #[test]
+    fn test_latin1_fallback_decodes_non_utf8_text() {
+        assert_eq!(
+            decode_source(INVALID_UTF8.to_vec(), true),
+            DecodedSource::Latin1("const name = \"café\";\n".to_string())
+        );
+        assert_eq!(decode_source(b"let a = 1;\n".to_vec(), false), DecodedSource::Utf8("let a = 1;\n".to_string()));
+    }
+
+    #[test]
+    fn test_binary_files_skipped_even_with_fallback() {
+        assert_eq!(decode_source(b"\x7fELF\x02\x01\x00\x00".to_vec(), true), DecodedSource::Skipped(SkipReason::Binary));
+    }
+}
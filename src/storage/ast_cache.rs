@@ -0,0 +1,291 @@
+//! Content-addressable cache of per-file analysis results
+//!
+//! Identical sources (vendored copies, generated files, an unchanged file on the next run)
+//! yield identical diagnostics, so each distinct source is parsed once. Entries are keyed by
+//! `blake3(source_type + path scope + source)`. OXC ASTs live in a per-parse arena and cannot
+//! be serialized, so the cache stores the derived [`AnalysisSummary`] rather than the AST.
+//! Results also depend on the configuration: [`AstCache::for_config`] keeps on-disk
+//! entries of different configurations apart, and [`PathScopes`] keeps apart copies whose
+//! paths match different path-dependent settings.
+
+use crate::config::MoonShineConfig;
+use crate::moon_pdk_interface::ensure_mutation_allowed;
+use crate::rule_types::{with_rule_scopes, RuleMetadata};
+use crate::types::{DiagnosticSeverity, LintDiagnostic};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use oxc_span::SourceType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Counts derived from one analysis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalysisMetrics {
+    pub lines_of_code: u32,
+    pub errors: u32,
+    pub warnings: u32,
+    pub fixable: u32,
+}
+
+/// What analyzing a source yields, independent of the file it was read from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalysisSummary {
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub metrics: AnalysisMetrics,
+}
+
+impl AnalysisSummary {
+    pub fn new(source: &str, diagnostics: Vec<LintDiagnostic>) -> Self {
+        let count = |keep: fn(&LintDiagnostic) -> bool| diagnostics.iter().filter(|d| keep(d)).count() as u32;
+        let metrics = AnalysisMetrics {
+            lines_of_code: source.lines().count() as u32,
+            errors: count(|d| d.severity == DiagnosticSeverity::Error),
+            warnings: count(|d| d.severity == DiagnosticSeverity::Warning),
+            fixable: count(|d| d.fix_available),
+        };
+        Self { diagnostics, metrics }
+    }
+}
+
+/// Globs of every setting that makes analysis depend on the file path: rule `applies_to`
+/// (after `linting.rule_scopes`), `linting.env_globs`, the `unsafe-assertions` test files,
+/// `ai.ai_exclude_patterns` and `linting.generated_file_patterns`
+#[derive(Debug, Clone)]
+pub struct PathScopes {
+    globs: GlobSet,
+}
+
+impl Default for PathScopes {
+    fn default() -> Self {
+        Self { globs: GlobSet::empty() }
+    }
+}
+
+impl PathScopes {
+    /// Path-dependent globs of `config` and the rules a run executes; invalid globs are skipped
+    pub fn from_config(config: &MoonShineConfig, rules: &[RuleMetadata]) -> Self {
+        let linting = &config.linting;
+        let applies_to = with_rule_scopes(rules, &linting.rule_scopes).into_iter().flat_map(|rule| rule.applies_to.unwrap_or_default());
+        let test_patterns = linting.unsafe_assertions.test_patterns.iter().filter(|_| linting.unsafe_assertions.allow_in_tests);
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in applies_to
+            .chain(linting.env_globs.keys().cloned())
+            .chain(test_patterns.cloned())
+            .chain(config.ai.ai_exclude_patterns.iter().cloned())
+            .chain(linting.generated_file_patterns.iter().cloned())
+        {
+            if let Ok(glob) = Glob::new(&pattern) {
+                builder.add(glob);
+            }
+        }
+        Self {
+            globs: builder.build().unwrap_or_else(|_| GlobSet::empty()),
+        }
+    }
+
+    /// The globs `file_path` matches; paths with equal scopes are analyzed alike
+    pub fn scope(&self, file_path: &str) -> String {
+        let path = Path::new(file_path);
+        let matched = self.globs.matches(path.strip_prefix("./").unwrap_or(path));
+        matched.iter().map(usize::to_string).collect::<Vec<_>>().join(",")
+    }
+}
+
+/// Analysis summaries by source content, in memory and optionally on disk
+#[derive(Debug, Default)]
+pub struct AstCache {
+    entries: HashMap<String, AnalysisSummary>,
+    dir: Option<PathBuf>,
+    scopes: PathScopes,
+}
+
+impl AstCache {
+    /// A cache that lives for one run
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// A cache that also keeps entries as JSON files in `dir`, for later runs
+    pub fn persistent(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            dir: Some(dir.into()),
+            scopes: PathScopes::default(),
+        }
+    }
+
+    /// Keep entries apart for files whose paths fall in different `scopes`
+    pub fn with_path_scopes(mut self, scopes: PathScopes) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// A persistent cache under `cache_dir` for results produced with `config`
+    pub fn for_config(cache_dir: impl AsRef<Path>, config: &MoonShineConfig) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(serde_json::to_string(config).unwrap_or_default().as_bytes());
+        let fingerprint = hasher.finalize().to_hex();
+        Self::persistent(cache_dir.as_ref().join("ast").join(&fingerprint[..16]))
+    }
+
+    /// Content address of `source` parsed as `source_type` in a file of path scope `scope`
+    pub fn key(source: &str, source_type: SourceType, scope: &str) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(format!("{:?}", source_type).as_bytes());
+        hasher.update(&[0]);
+        hasher.update(scope.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(source.as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The summary for `source`, running `analyze` only when no file with the same content
+    /// and path scope was analyzed before. Diagnostics are labelled with `file_path`.
+    pub fn get_or_analyze<E>(
+        &mut self,
+        file_path: &str,
+        source: &str,
+        source_type: SourceType,
+        analyze: impl FnOnce() -> Result<AnalysisSummary, E>,
+    ) -> Result<AnalysisSummary, E> {
+        let key = Self::key(source, source_type, &self.scopes.scope(file_path));
+        let cached = match self.entries.get(&key) {
+            Some(summary) => Some(summary.clone()),
+            None => self.read_entry(&key),
+        };
+
+        let mut summary = match cached {
+            Some(summary) => {
+                moon_debug!("AST cache hit for {}", file_path);
+                summary
+            }
+            None => {
+                let summary = analyze()?;
+                self.write_entry(&key, &summary);
+                summary
+            }
+        };
+        self.entries.entry(key).or_insert_with(|| summary.clone());
+
+        for diagnostic in &mut summary.diagnostics {
+            diagnostic.file_path = file_path.to_string();
+        }
+        Ok(summary)
+    }
+
+    fn entry_path(&self, key: &str) -> Option<PathBuf> {
+        self.dir.as_ref().map(|dir| dir.join(format!("{}.json", key)))
+    }
+
+    fn read_entry(&self, key: &str) -> Option<AnalysisSummary> {
+        let content = fs::read_to_string(self.entry_path(key)?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Best effort: a failed write only costs a re-parse on the next run
+    fn write_entry(&self, key: &str, summary: &AnalysisSummary) {
+        let Some(path) = self.entry_path(key) else {
+            return;
+        };
+        if ensure_mutation_allowed(format!("write {}", path.display())).is_err() {
+            return;
+        }
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, serde_json::to_string(summary).unwrap_or_default()));
+        if let Err(e) = written {
+            moon_debug!("AST cache entry not written to {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_lint::lint_buffer_with_rules;
+    use crate::error::Result;
+    use crate::rule_types::RuleMetadata;
+    use crate::rulebase::testing::static_rule;
+
+    const SOURCE: &str = "let total = 1;\nconsole.log(total);\n";
+
+    fn analyze(cache: &mut AstCache, file_path: &str, parses: &mut usize) -> AnalysisSummary {
+        analyze_with(cache, file_path, &MoonShineConfig::default(), &[static_rule("prefer-const")], parses)
+    }
+
+    fn analyze_with(cache: &mut AstCache, file_path: &str, config: &MoonShineConfig, rules: &[RuleMetadata], parses: &mut usize) -> AnalysisSummary {
+        let source_type = SourceType::from_path(file_path).unwrap();
+        cache
+            .get_or_analyze(file_path, SOURCE, source_type, || -> Result<AnalysisSummary> {
+                *parses += 1;
+                let result = lint_buffer_with_rules(SOURCE, file_path, config, rules)?;
+                Ok(AnalysisSummary::new(SOURCE, result.diagnostics))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_identical_content_is_parsed_once() {
+        let mut cache = AstCache::in_memory();
+        let mut parses = 0;
+
+        let first = analyze(&mut cache, "src/a.ts", &mut parses);
+        let second = analyze(&mut cache, "src/copy/a.ts", &mut parses);
+
+        assert_eq!(parses, 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(first.diagnostics.len(), 1);
+        assert_eq!(first.diagnostics[0].file_path, "src/a.ts");
+        assert_eq!(second.diagnostics[0].file_path, "src/copy/a.ts");
+        assert_eq!(first.metrics, second.metrics);
+        assert_eq!((second.metrics.lines_of_code, second.metrics.fixable), (2, 1));
+
+        // The same text parsed as JavaScript is a different entry
+        analyze(&mut cache, "src/a.js", &mut parses);
+        assert_eq!(parses, 2);
+    }
+
+    #[test]
+    fn test_persistent_entries_survive_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut parses = 0;
+
+        analyze(&mut AstCache::persistent(dir.path()), "src/a.ts", &mut parses);
+        let next_run = analyze(&mut AstCache::persistent(dir.path()), "src/b.ts", &mut parses);
+
+        assert_eq!(parses, 1);
+        assert_eq!(next_run.diagnostics[0].file_path, "src/b.ts");
+    }
+
+    #[test]
+    fn test_copies_in_differently_scoped_paths_are_analyzed_separately() {
+        let mut config = MoonShineConfig::default();
+        config.linting.env_globs = HashMap::from([("scripts/**".to_string(), "off".to_string())]);
+        let mut scoped = static_rule("prefer-const");
+        scoped.applies_to = Some(vec!["src/**".to_string()]);
+        let rules = [scoped];
+        let mut cache = AstCache::in_memory().with_path_scopes(PathScopes::from_config(&config, &rules));
+        let mut parses = 0;
+
+        let in_scope = analyze_with(&mut cache, "src/a.ts", &config, &rules, &mut parses);
+        let same_scope = analyze_with(&mut cache, "src/copy/a.ts", &config, &rules, &mut parses);
+        let out_of_scope = analyze_with(&mut cache, "lib/a.ts", &config, &rules, &mut parses);
+
+        assert_eq!(parses, 2);
+        assert_eq!((in_scope.diagnostics.len(), same_scope.diagnostics.len()), (1, 1));
+        assert!(out_of_scope.diagnostics.is_empty(), "the rule does not apply outside src/");
+        assert_ne!(cache.scopes.scope("scripts/a.ts"), cache.scopes.scope("lib/a.ts"));
+    }
+}
@@ -0,0 +1,125 @@
+//! Analysis results of open editor documents by version
+//!
+//! Editors send a document's URI with a version that increases on every change, so an
+//! unchanged buffer is recognized without hashing its content as [`AstCache`] does. Each URI
+//! keeps only the summary of its latest analyzed version; a request at any other version
+//! re-analyzes and replaces it. Entries live in memory until the document is closed.
+//!
+//! [`AstCache`]: super::AstCache
+
+use super::AnalysisSummary;
+use std::collections::HashMap;
+
+/// Version an entry was analyzed at, with its summary
+#[derive(Debug, Clone)]
+struct VersionedSummary {
+    version: i32,
+    summary: AnalysisSummary,
+}
+
+/// Analysis summaries of open documents, keyed by `(uri, version)`
+#[derive(Debug, Default)]
+pub struct DocumentCache {
+    entries: HashMap<String, VersionedSummary>,
+}
+
+impl DocumentCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Version of `uri` the cached summary belongs to
+    pub fn cached_version(&self, uri: &str) -> Option<i32> {
+        self.entries.get(uri).map(|entry| entry.version)
+    }
+
+    /// The summary for `uri` at `version`, running `analyze` only when that version has not
+    /// been analyzed yet. A failed analysis leaves the cached entry as it was.
+    pub fn get_or_analyze<E>(&mut self, uri: &str, version: i32, analyze: impl FnOnce() -> Result<AnalysisSummary, E>) -> Result<AnalysisSummary, E> {
+        if let Some(entry) = self.entries.get(uri).filter(|entry| entry.version == version) {
+            moon_debug!("Document cache hit for {} at version {}", uri, version);
+            return Ok(entry.summary.clone());
+        }
+
+        let summary = analyze()?;
+        self.entries.insert(
+            uri.to_string(),
+            VersionedSummary {
+                version,
+                summary: summary.clone(),
+            },
+        );
+        Ok(summary)
+    }
+
+    /// Drop the entry for `uri`, when the editor closes the document
+    pub fn invalidate(&mut self, uri: &str) -> bool {
+        self.entries.remove(uri).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_lint::lint_buffer_with_rules;
+    use crate::config::MoonShineConfig;
+    use crate::error::Result;
+    use crate::rulebase::testing::static_rule;
+
+    const URI: &str = "file:///workspace/src/index.ts";
+
+    fn analyze(cache: &mut DocumentCache, version: i32, source: &str, analyses: &mut usize) -> AnalysisSummary {
+        cache
+            .get_or_analyze(URI, version, || -> Result<AnalysisSummary> {
+                *analyses += 1;
+                let result = lint_buffer_with_rules(source, "src/index.ts", &MoonShineConfig::default(), &[static_rule("prefer-const")])?;
+                Ok(AnalysisSummary::new(source, result.diagnostics))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_same_version_skips_analysis() {
+        let mut cache = DocumentCache::new();
+        let mut analyses = 0;
+
+        let first = analyze(&mut cache, 1, "let total = 1;\nconsole.log(total);\n", &mut analyses);
+        // The version alone decides; content is not compared
+        let second = analyze(&mut cache, 1, "const total = 1;\nconsole.log(total);\n", &mut analyses);
+
+        assert_eq!(analyses, 1);
+        assert_eq!(first.diagnostics.len(), 1);
+        assert_eq!(second.diagnostics.len(), 1);
+        assert_eq!(cache.cached_version(URI), Some(1));
+    }
+
+    #[test]
+    fn test_version_bump_and_close_force_reanalysis() {
+        let mut cache = DocumentCache::new();
+        let mut analyses = 0;
+
+        analyze(&mut cache, 1, "let total = 1;\nconsole.log(total);\n", &mut analyses);
+        let fixed = analyze(&mut cache, 2, "const total = 1;\nconsole.log(total);\n", &mut analyses);
+        assert_eq!(analyses, 2);
+        assert!(fixed.diagnostics.is_empty());
+        assert_eq!((cache.len(), cache.cached_version(URI)), (1, Some(2)));
+
+        // A failed analysis keeps the last good entry
+        let failed: std::result::Result<_, &str> = cache.get_or_analyze(URI, 3, || Err("parser crashed"));
+        assert!(failed.is_err());
+        assert_eq!(cache.cached_version(URI), Some(2));
+
+        assert!(cache.invalidate(URI));
+        assert!(!cache.invalidate(URI));
+        analyze(&mut cache, 2, "const total = 1;\nconsole.log(total);\n", &mut analyses);
+        assert_eq!(analyses, 3);
+    }
+}
@@ -0,0 +1,99 @@
+//! Per-rule fix outcomes kept across runs
+//!
+//! Each run's [`FixStats`] is appended to a JSON history file so the value of a rule's fixes,
+//! AI-enhanced rules in particular, can be judged over time instead of from one run. Only the
+//! most recent `window` runs are kept; older runs are pruned when a new one is recorded.
+
+use crate::config::MoonShineConfig;
+use crate::moon_pdk_interface::ensure_mutation_allowed;
+use crate::rulebase::fixes::FixStats;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Runs kept in the history by default
+pub const DEFAULT_FIX_HISTORY_WINDOW: usize = 20;
+
+/// Fix outcomes of one run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixRun {
+    pub run_id: String,
+    pub recorded_at: DateTime<Utc>,
+    pub stats: FixStats,
+}
+
+/// The most recent runs' fix outcomes, in memory and optionally in a JSON file
+#[derive(Debug, Clone)]
+pub struct FixHistory {
+    runs: Vec<FixRun>,
+    window: usize,
+    path: Option<PathBuf>,
+}
+
+impl FixHistory {
+    /// A history that lives for one process
+    pub fn in_memory(window: usize) -> Self {
+        Self {
+            runs: Vec::new(),
+            window: window.max(1),
+            path: None,
+        }
+    }
+
+    /// A history loaded from and saved to `path`; a missing or unreadable file starts empty
+    pub fn persistent(path: impl Into<PathBuf>, window: usize) -> Self {
+        let path = path.into();
+        let mut runs: Vec<FixRun> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                moon_warn!("Ignoring unreadable fix history {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        let window = window.max(1);
+        runs.drain(..runs.len().saturating_sub(window));
+
+        Self {
+            runs,
+            window,
+            path: Some(path),
+        }
+    }
+
+    /// Where the project's fix history is kept
+    pub fn default_path() -> PathBuf {
+        Path::new(&MoonShineConfig::moonshine_directory()).join("fix-history.json")
+    }
+
+    /// Runs in the window, oldest first
+    pub fn runs(&self) -> &[FixRun] {
+        &self.runs
+    }
+
+    /// Append a run's outcomes, prune runs outside the window and save.
+    ///
+    /// Best effort: a failed write only loses this run from later analytics.
+    pub fn record_run(&mut self, run_id: impl Into<String>, recorded_at: DateTime<Utc>, stats: &FixStats) {
+        self.runs.push(FixRun {
+            run_id: run_id.into(),
+            recorded_at,
+            stats: stats.clone(),
+        });
+        self.runs.drain(..self.runs.len().saturating_sub(self.window));
+
+        let Some(path) = &self.path else {
+            return;
+        };
+        if ensure_mutation_allowed(format!("write {}", path.display())).is_err() {
+            return;
+        }
+        let written = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, serde_json::to_string_pretty(&self.runs).unwrap_or_default()));
+        if let Err(e) = written {
+            moon_warn!("Fix history not written to {}: {}", path.display(), e);
+        }
+    }
+}
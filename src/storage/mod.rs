@@ -2,6 +2,9 @@
 //!
 //! Self-documenting storage components.
 
+pub mod ast_cache;
+pub mod document_cache;
+pub mod fix_history;
 pub mod storage_core;
 
 // Include the full implementation for backward compatibility
@@ -9,4 +12,7 @@ pub mod storage_core;
 pub mod storage_full;
 
 // Re-export main functionality
+pub use ast_cache::{AnalysisMetrics, AnalysisSummary, AstCache, PathScopes};
+pub use document_cache::DocumentCache;
+pub use fix_history::{FixHistory, FixRun, DEFAULT_FIX_HISTORY_WINDOW};
 pub use storage_core::*;
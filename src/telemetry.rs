@@ -5,6 +5,7 @@
 //! filesystem access from WASM.
 
 use crate::moon_pdk_interface::{read_file_content, write_file_atomic};
+use crate::rulebase::fixes::{FixConvergence, FixStats};
 use serde::Serialize;
 
 /// Immutable snapshot describing a single workflow run.
@@ -17,6 +18,10 @@ pub struct TelemetryRecord {
     pub duration_ms: u128,
     pub issues_found: Option<u64>,
     pub ai_strategy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_stats: Option<FixStats>, // Per-rule fix outcomes for effectiveness trends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix_convergence: Option<FixConvergence>, // Fix passes run and whether they reached a fixpoint
 }
 
 const DEFAULT_MAX_RECORDS: usize = 5000;
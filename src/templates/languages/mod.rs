@@ -9,7 +9,7 @@ pub mod typescript;
 pub mod rust;
 
 use crate::dspy::{MetaSignature, Example};
-use crate::error::Result;
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -226,6 +226,9 @@ impl LanguageTemplateManager {
     }
 }
 
+/// Prefix of the `%%FIELD:{NAME}:START%%` / `%%FIELD:{NAME}:END%%` editable-field markers
+pub const FIELD_MARKER_PREFIX: &str = "%%FIELD:";
+
 /// Template protection wrapper for DSPy integration
 pub struct ProtectedTemplate {
     /// Template ID
@@ -292,6 +295,49 @@ impl ProtectedTemplate {
     pub fn get_base_content(&self) -> &str {
         &self.base_content
     }
+
+    /// Names of the editable `%%FIELD:{NAME}:START%%` ... `%%FIELD:{NAME}:END%%` fields in the
+    /// active content, in template order.
+    ///
+    /// Fails when a START has no matching END (or an END no START), since sed and DSPy field
+    /// edits would otherwise overwrite text outside the field.
+    pub fn list_fields(&self) -> Result<Vec<String>> {
+        let unbalanced = |name: &str, expected: &str, actual: &str| {
+            Error::validation(format!("{}: field {}", self.id, name), expected.to_string(), actual.to_string())
+        };
+
+        let mut fields = Vec::new();
+        let mut open: Option<&str> = None;
+        let mut rest = self.active_content.as_str();
+
+        while let Some(start) = rest.find(FIELD_MARKER_PREFIX) {
+            let marker_body = &rest[start + FIELD_MARKER_PREFIX.len()..];
+            let Some(marker_end) = marker_body.find("%%") else {
+                return Err(unbalanced(marker_body, "marker closed with %%", "unterminated marker"));
+            };
+            let marker = &marker_body[..marker_end];
+            rest = &marker_body[marker_end + 2..];
+
+            let (name, kind) = marker.rsplit_once(':').unwrap_or((marker, ""));
+            match (kind, open) {
+                ("START", None) => open = Some(name),
+                ("END", Some(current)) if current == name => {
+                    fields.push(name.to_string());
+                    open = None;
+                }
+                ("START", Some(current)) | ("END", Some(current)) => {
+                    return Err(unbalanced(current, &format!("%%FIELD:{}:END%%", current), &format!("%%FIELD:{}%%", marker)));
+                }
+                ("END", None) => return Err(unbalanced(name, &format!("%%FIELD:{}:START%% before END", name), "END without START")),
+                _ => return Err(unbalanced(name, "START or END marker", &format!("%%FIELD:{}%%", marker))),
+            }
+        }
+
+        match open {
+            Some(name) => Err(unbalanced(name, &format!("%%FIELD:{}:END%%", name), "end of template")),
+            None => Ok(fields),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -397,6 +443,37 @@ mod tests {
         assert_eq!(template.get_content(), "Original content");
     }
 
+    #[test]
+    fn test_list_fields_of_tsdoc_template() {
+        let template = ProtectedTemplate::new(
+            "tsdoc_instructions".to_string(),
+            typescript::TSDOC_INSTRUCTION_TEMPLATE.to_string(),
+            ProtectionLevel::FieldOnly,
+        );
+
+        assert_eq!(
+            template.list_fields().unwrap(),
+            vec!["TASK", "OUTPUT_REQUIREMENTS", "TAGS_SPECIFICATION", "DOCUMENTATION_PATTERNS", "EXECUTION_COMMAND"]
+        );
+    }
+
+    #[test]
+    fn test_list_fields_rejects_unbalanced_markers() {
+        let missing_end = ProtectedTemplate::new(
+            "broken".to_string(),
+            "%%FIELD:TASK:START%%Do it\n%%FIELD:OUTPUT:START%%Code%%FIELD:OUTPUT:END%%".to_string(),
+            ProtectionLevel::FieldOnly,
+        );
+        let error = missing_end.list_fields().unwrap_err();
+        assert!(matches!(error, Error::Validation { ref field, .. } if field.contains("TASK")));
+
+        let stray_end = ProtectedTemplate::new("broken".to_string(), "Do it%%FIELD:TASK:END%%".to_string(), ProtectionLevel::FieldOnly);
+        assert!(stray_end.list_fields().is_err());
+
+        let unterminated = ProtectedTemplate::new("broken".to_string(), "%%FIELD:TASK:START%%Do it".to_string(), ProtectionLevel::FieldOnly);
+        assert!(unterminated.list_fields().is_err());
+    }
+
     #[test]
     fn test_protection_levels() {
         let mut full_protected = ProtectedTemplate::new(
@@ -17,6 +17,8 @@
 //! @since 1.0.0
 
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::ops::Add;
 
 /// Language model usage statistics with comprehensive tracking
@@ -120,6 +122,108 @@ impl LanguageModelUsageMetrics {
     }
 }
 
+/// Provider key for usage that did not report which provider served it
+pub const UNKNOWN_PROVIDER: &str = "unknown";
+
+/// Token totals for a group of AI calls
+///
+/// Counts are `u64` so a long run cannot overflow the per-call `u32` fields.
+///
+/// @category ai-struct
+/// @safe team
+/// @mvp core
+/// @complexity low
+/// @since 2.0.0
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl UsageTotals {
+    fn add_usage(&mut self, usage: &LanguageModelUsageMetrics) {
+        self.calls += 1;
+        self.input_tokens += u64::from(usage.input_tokens);
+        self.output_tokens += u64::from(usage.output_tokens);
+        self.total_tokens += u64::from(usage.total_tokens);
+    }
+
+    fn add_totals(&mut self, other: &UsageTotals) {
+        self.calls += other.calls;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// Roll-up of every AI call made during one workflow run
+///
+/// Each recorded call is added to the run total, to its provider's totals and to
+/// the totals of the task kind that issued it (e.g. `fix`, `tsdoc`, `eslint`), so
+/// total spend can be reported and attributed after the run.
+///
+/// @category ai-struct
+/// @safe team
+/// @mvp core
+/// @complexity low
+/// @since 2.0.0
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageAccumulator {
+    pub total: UsageTotals,
+    pub by_provider: BTreeMap<String, UsageTotals>,
+    pub by_task_kind: BTreeMap<String, UsageTotals>,
+}
+
+impl UsageAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one call's usage, attributed to `task_kind` and the provider it reports
+    pub fn record(&mut self, task_kind: &str, usage: &LanguageModelUsageMetrics) {
+        let provider = usage.provider_used.as_deref().unwrap_or(UNKNOWN_PROVIDER);
+        self.total.add_usage(usage);
+        self.by_provider.entry(provider.to_string()).or_default().add_usage(usage);
+        self.by_task_kind.entry(task_kind.to_string()).or_default().add_usage(usage);
+    }
+
+    /// Fold another accumulator (e.g. from a sub-run) into this one
+    pub fn merge(&mut self, other: &UsageAccumulator) {
+        self.total.add_totals(&other.total);
+        for (provider, totals) in &other.by_provider {
+            self.by_provider.entry(provider.clone()).or_default().add_totals(totals);
+        }
+        for (task_kind, totals) in &other.by_task_kind {
+            self.by_task_kind.entry(task_kind.clone()).or_default().add_totals(totals);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total.calls == 0
+    }
+}
+
+/// Rough characters per token for text that has not been through a tokenizer
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Approximate token count of `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Order two equally scored candidates by their text; `Greater` when `a` is preferred.
+///
+/// The candidate with fewer estimated tokens wins, then the one whose blake3 hash is
+/// lexicographically smaller. Chain it after the score comparison so selecting the best of
+/// several candidates never depends on iteration or completion order.
+pub fn tie_break(a: &str, b: &str) -> Ordering {
+    estimate_tokens(b)
+        .cmp(&estimate_tokens(a))
+        .then_with(|| blake3::hash(b.as_bytes()).as_bytes().cmp(blake3::hash(a.as_bytes()).as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +374,53 @@ mod tests {
         assert_eq!(combined.input_tokens, 450);
         assert_eq!(combined.output_tokens, 225);
     }
+
+    #[test]
+    fn test_usage_accumulator_totals_match_individual_calls() {
+        let calls = vec![
+            ("fix", LanguageModelUsageMetrics::with_all_fields(120, 40, None, Some("claude".to_string()), None)),
+            ("fix", LanguageModelUsageMetrics::with_all_fields(300, 90, Some(30), Some("codex".to_string()), None)),
+            ("tsdoc", LanguageModelUsageMetrics::with_all_fields(80, 25, None, Some("claude".to_string()), None)),
+            ("tsdoc", LanguageModelUsageMetrics::new(10, 5)),
+        ];
+
+        let mut accumulator = UsageAccumulator::new();
+        for (task_kind, usage) in &calls {
+            accumulator.record(task_kind, usage);
+        }
+
+        let summed = calls.iter().map(|(_, usage)| usage.clone()).fold(LanguageModelUsageMetrics::default(), |acc, usage| acc + usage);
+        assert_eq!(accumulator.total.calls, 4);
+        assert_eq!(accumulator.total.input_tokens, u64::from(summed.input_tokens));
+        assert_eq!(accumulator.total.output_tokens, u64::from(summed.output_tokens));
+        assert_eq!(accumulator.total.total_tokens, u64::from(summed.total_tokens));
+
+        assert_eq!(
+            accumulator.by_provider["claude"],
+            UsageTotals {
+                calls: 2,
+                input_tokens: 200,
+                output_tokens: 65,
+                total_tokens: 265
+            }
+        );
+        assert_eq!(accumulator.by_provider["codex"].total_tokens, 420);
+        assert_eq!(accumulator.by_provider[UNKNOWN_PROVIDER].calls, 1);
+        assert_eq!(accumulator.by_task_kind["fix"].input_tokens, 420);
+        assert_eq!(accumulator.by_task_kind["tsdoc"].calls, 2);
+    }
+
+    #[test]
+    fn test_usage_accumulator_merge() {
+        let mut first = UsageAccumulator::new();
+        first.record("fix", &LanguageModelUsageMetrics::with_all_fields(100, 50, None, Some("claude".to_string()), None));
+        let mut second = UsageAccumulator::new();
+        second.record("fix", &LanguageModelUsageMetrics::with_all_fields(10, 5, None, Some("claude".to_string()), None));
+
+        first.merge(&second);
+
+        assert_eq!(first.total.total_tokens, 165);
+        assert_eq!(first.by_provider["claude"].calls, 2);
+        assert_eq!(first.by_task_kind["fix"].output_tokens, 55);
+    }
 }
@@ -113,6 +113,8 @@ pub struct AstFix {
     pub fixed_text: String,
     pub confidence: f32,
     pub impact_score: u8,
+    #[serde(default)]
+    pub rule_id: Option<String>, // Rule whose diagnostic produced this fix
 }
 
 /// Types of AST-based fixes available
@@ -322,6 +324,15 @@ pub struct LintDiagnostic {
     pub severity: DiagnosticSeverity,
     pub fix_available: bool,
     pub suggested_fix: Option<String>,
+    /// Why the rule fired and how to fix it; attached in `--explain` mode only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// Unified diff of a proposed fix that is never applied; set for AI fixes in `ai.suggest_only` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<String>,
+    /// Confidence (0-100) the AI provider reported; `None` for static rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_confidence: Option<f32>,
 }
 
 /// Structured description of an available autofix for a lint diagnostic.
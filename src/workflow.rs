@@ -1,5 +1,6 @@
-use crate::config::MoonShineConfig;
+use crate::config::{MoonShineConfig, OperationMode};
 use crate::error::{Error, Result};
+use crate::host_task::{execute_host_task, HostTaskLimits};
 use crate::moon_pdk_interface::{execute_command, ExecCommandInput};
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
@@ -40,22 +41,34 @@ impl WorkflowDefinition {
         }
     }
 
-    /// Create a workflow based on a user-provided mode string.
-    pub fn from_mode(mode: &str) -> Self {
+    /// Create the workflow for a validated operation mode.
+    pub fn for_operation_mode(mode: OperationMode) -> Self {
         match mode {
-            "static-analysis" | "lint-only" | "reporting-only" => Self::lint_only(),
-            "typescript-only" => WorkflowDefinition {
-                steps: vec![WorkflowStep::typescript_check()],
-            },
-            "eslint-only" => WorkflowDefinition {
-                steps: vec![WorkflowStep::eslint_lint()],
-            },
-            "prettier-only" => Self::formatter_only(),
-            "agent-based" | "tsdoc-only" => Self::ai_only(),
-            _ => Self::standard(),
+            OperationMode::LintOnly | OperationMode::ReportingOnly | OperationMode::ParallelLint => Self::lint_only(),
+            OperationMode::Fix | OperationMode::Comprehensive | OperationMode::DryRun => Self::standard(),
         }
     }
 
+    /// The same workflow without AI steps, e.g. for generated files.
+    pub fn without_ai(mut self) -> Self {
+        let ai_steps: Vec<String> = self
+            .steps
+            .iter()
+            .filter(|step| matches!(step.action, WorkflowAction::AiEnhancement))
+            .map(|step| step.id.clone())
+            .collect();
+        self.steps.retain(|step| !ai_steps.contains(&step.id));
+        for step in &mut self.steps {
+            step.depends_on.retain(|id| !ai_steps.contains(id));
+        }
+        self
+    }
+
+    /// Whether the workflow has an AI step
+    pub fn has_ai(&self) -> bool {
+        self.steps.iter().any(|step| matches!(step.action, WorkflowAction::AiEnhancement))
+    }
+
     fn chain(mut steps: Vec<WorkflowStep>) -> Self {
         for i in 1..steps.len() {
             let prev_id = steps[i - 1].id.clone();
@@ -245,13 +258,14 @@ fn run_typescript_check(config: &MoonShineConfig, file_path: &str) -> Result<Opt
         env: HashMap::new(),
         working_dir: config.typescript_cwd().map(|c| c.to_string()),
     };
-    match execute_command(input) {
+    match execute_host_task("TypeScript check", input, &HostTaskLimits::from_config(config)) {
         Ok(output) if output.exit_code == 0 => Ok(Some("TypeScript check succeeded".into())),
         Ok(output) => Err(Error::Analysis {
             operation: "TypeScript check".into(),
             file_path: Some(file_path.into()),
             source: Some(command_failure(format!("Command failed: {}", output.stderr))),
         }),
+        Err(err @ (Error::Timeout { .. } | Error::OutputLimit { .. })) => Err(err),
         Err(err) => Err(Error::Analysis {
             operation: "TypeScript check".into(),
             file_path: Some(file_path.into()),
@@ -278,13 +292,14 @@ fn run_eslint(config: &MoonShineConfig, file_path: &str) -> Result<Option<String
         env: HashMap::new(),
         working_dir: config.eslint_cwd().map(|c| c.to_string()),
     };
-    match execute_command(input) {
+    match execute_host_task("ESLint", input, &HostTaskLimits::from_config(config)) {
         Ok(output) if output.exit_code == 0 => Ok(Some("ESLint completed".into())),
         Ok(output) => Err(Error::Analysis {
             operation: "ESLint".into(),
             file_path: Some(file_path.into()),
             source: Some(command_failure(format!("Command failed: {}", output.stderr))),
         }),
+        Err(err @ (Error::Timeout { .. } | Error::OutputLimit { .. })) => Err(err),
         Err(err) => Err(Error::Analysis {
             operation: "ESLint".into(),
             file_path: Some(file_path.into()),
@@ -307,13 +322,14 @@ fn run_formatter(config: &MoonShineConfig, file_path: &str) -> Result<Option<Str
         env: HashMap::new(),
         working_dir: config.format_cwd().map(|c| c.to_string()),
     };
-    match execute_command(input) {
+    match execute_host_task("Formatter", input, &HostTaskLimits::from_config(config)) {
         Ok(output) if output.exit_code == 0 => Ok(Some("Formatter completed".into())),
         Ok(output) => Err(Error::Analysis {
             operation: "Formatter".into(),
             file_path: Some(file_path.into()),
             source: Some(command_failure(format!("Command failed: {}", output.stderr))),
         }),
+        Err(err @ (Error::Timeout { .. } | Error::OutputLimit { .. })) => Err(err),
         Err(err) => Err(Error::Analysis {
             operation: "Formatter".into(),
             file_path: Some(file_path.into()),
@@ -322,7 +338,15 @@ fn run_formatter(config: &MoonShineConfig, file_path: &str) -> Result<Option<Str
     }
 }
 
-fn run_ai_feedback(_config: &MoonShineConfig, file_path: &str, _file_content: &str) -> Result<Option<String>> {
+fn run_ai_feedback(config: &MoonShineConfig, file_path: &str, _file_content: &str) -> Result<Option<String>> {
+    if config.ai_offline() || crate::provider_router::ai_disabled() {
+        return Ok(None);
+    }
+    if !crate::provider_router::ai_available() {
+        info!("No AI provider available - skipping AI feedback for {}", file_path);
+        return Ok(Some("AI unavailable: static analysis results only".into()));
+    }
+
     info!("Collecting AI feedback for {}", file_path);
     Ok(Some("AI feedback collection deferred to provider router".into()))
 }
@@ -0,0 +1,275 @@
+//! # Transactional Multi-File Writes
+//!
+//! With `--atomic`, fixed file contents are staged in the session directory instead of
+//! being written as each file finishes. On commit every staged JavaScript or TypeScript
+//! file must re-parse; only then are the staged files written to their real paths via
+//! `write_file_atomic`. A verification failure commits nothing, and a write failure
+//! partway through restores the files that were already written, so a run never leaves
+//! the repository half-modified.
+//!
+//! @category persistence
+//! @safe team
+//! @mvp core
+//! @complexity medium
+//! @since 2.0.0
+
+use crate::buffer_lint::parse_guarded;
+use crate::error::{Error, Result};
+use crate::moon_pdk_interface::ensure_mutation_allowed;
+use oxc_allocator::Allocator;
+use oxc_span::SourceType;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A fixed file waiting in the staging directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedWrite {
+    pub target: String,
+    pub staged_path: PathBuf,
+}
+
+/// Stages file contents and commits them all-or-nothing
+#[derive(Debug)]
+pub struct WriteTransaction {
+    staging_dir: PathBuf,
+    staged: Vec<StagedWrite>,
+}
+
+impl WriteTransaction {
+    /// Stage writes under `staging_dir` (normally `Session::staging_dir()`)
+    pub fn new(staging_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            staging_dir: staging_dir.into(),
+            staged: Vec::new(),
+        }
+    }
+
+    /// Files staged so far
+    pub fn staged(&self) -> &[StagedWrite] {
+        &self.staged
+    }
+
+    /// Stage `content` to be written to `target` on commit.
+    ///
+    /// A staging failure abandons the transaction: every file staged so far is discarded.
+    pub fn stage(&mut self, target: &str, content: &str) -> Result<()> {
+        ensure_mutation_allowed(format!("stage {}", target))?;
+        let staged_path = self.staging_dir.join(format!("{:04}.staged", self.staged.len()));
+        let written = fs::create_dir_all(&self.staging_dir)
+            .map_err(|source| Error::Io {
+                path: self.staging_dir.display().to_string(),
+                source,
+            })
+            .and_then(|()| {
+                fs::write(&staged_path, content).map_err(|source| Error::Io {
+                    path: staged_path.display().to_string(),
+                    source,
+                })
+            });
+        if let Err(e) = written {
+            let _ = fs::remove_file(&staged_path);
+            self.discard();
+            self.staged.clear();
+            return Err(e);
+        }
+
+        self.staged.push(StagedWrite {
+            target: target.to_string(),
+            staged_path,
+        });
+        Ok(())
+    }
+
+    /// Verify every staged file and write them to their targets with `write_file_atomic`
+    pub fn commit(self) -> Result<Vec<String>> {
+        self.commit_with(|path, content| crate::moon_pdk_interface::write_file_atomic(path, content).map_err(|e| e.to_string()))
+    }
+
+    /// Verify every staged file, then write them with `write`.
+    ///
+    /// Returns the committed target paths. If any staged file fails to re-parse, nothing
+    /// is written and every verification failure is reported. If a write fails, targets
+    /// already written are restored to their original content.
+    pub fn commit_with<W>(self, mut write: W) -> Result<Vec<String>>
+    where
+        W: FnMut(&str, &str) -> std::result::Result<(), String>,
+    {
+        let mut contents = Vec::with_capacity(self.staged.len());
+        let mut failures = Vec::new();
+
+        for staged in &self.staged {
+            let content = fs::read_to_string(&staged.staged_path).map_err(|source| Error::Io {
+                path: staged.staged_path.display().to_string(),
+                source,
+            })?;
+
+            if let Err(reason) = verify_parses(&staged.target, &content) {
+                failures.push(Error::analysis_file(format!("staged fix does not re-parse: {}", reason), staged.target.clone()));
+            }
+            contents.push(content);
+        }
+
+        if !failures.is_empty() {
+            moon_error!(
+                "Atomic write aborted: {} of {} staged file(s) failed verification",
+                failures.len(),
+                self.staged.len()
+            );
+            self.discard();
+            return Err(Error::multiple(failures, 0));
+        }
+
+        let mut written: Vec<(&str, Option<String>)> = Vec::new();
+        for (staged, content) in self.staged.iter().zip(&contents) {
+            let original = fs::read_to_string(&staged.target).ok();
+
+            if let Err(reason) = write(&staged.target, content) {
+                moon_error!("Atomic write failed on {}: {} - rolling back {} file(s)", staged.target, reason, written.len());
+                for (target, original) in written.iter().rev() {
+                    let restored = match original {
+                        Some(original) => write(target, original),
+                        None => fs::remove_file(target).map_err(|e| e.to_string()),
+                    };
+                    if let Err(e) = restored {
+                        moon_error!("Rollback failed for {}: {}", target, e);
+                    }
+                }
+                let successful_count = written.len();
+                self.discard();
+                return Err(Error::multiple(
+                    vec![Error::data_access(format!("failed to write {}: {}", staged.target, reason))],
+                    successful_count,
+                ));
+            }
+
+            written.push((&staged.target, original));
+        }
+
+        let committed: Vec<String> = self.staged.iter().map(|staged| staged.target.clone()).collect();
+        moon_info!("Atomic write committed {} file(s)", committed.len());
+        self.discard();
+        Ok(committed)
+    }
+
+    fn discard(&self) {
+        for staged in &self.staged {
+            let _ = fs::remove_file(&staged.staged_path);
+        }
+    }
+}
+
+/// Check that `content` parses as the language implied by `target`'s extension; other files are not verified
+fn verify_parses(target: &str, content: &str) -> std::result::Result<(), String> {
+    let Ok(source_type) = SourceType::from_path(Path::new(target)) else {
+        return Ok(());
+    };
+    let allocator = Allocator::default();
+    let ret = parse_guarded(&allocator, content, source_type)?;
+
+    match ret.errors.first() {
+        None => Ok(()),
+        Some(error) => Err(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_failed_verification_writes_no_files() {
+        let dir = tempdir().unwrap();
+        let mut transaction = WriteTransaction::new(dir.path().join("staging"));
+        transaction.stage("src/a.ts", "const a: number = 1;\n").unwrap();
+        transaction.stage("src/b.ts", "const b = {;\n").unwrap();
+        transaction.stage("src/c.js", "export const c = 3;\n").unwrap();
+
+        let mut writes = Vec::new();
+        let result = transaction.commit_with(|path, _| {
+            writes.push(path.to_string());
+            Ok(())
+        });
+
+        assert!(writes.is_empty(), "no file may be written when verification fails");
+        match result {
+            Err(Error::Multiple { errors, successful_count }) => {
+                assert_eq!(successful_count, 0);
+                assert_eq!(errors.len(), 1);
+                assert!(errors[0].to_string().contains("re-parse"));
+            }
+            other => panic!("expected verification failure, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_verified_files_are_committed() {
+        let dir = tempdir().unwrap();
+        let mut transaction = WriteTransaction::new(dir.path().join("staging"));
+        transaction.stage("src/a.ts", "const a = 1;\n").unwrap();
+        transaction.stage("src/b.tsx", "export const B = () => <div />;\n").unwrap();
+        let staged_paths: Vec<PathBuf> = transaction.staged().iter().map(|staged| staged.staged_path.clone()).collect();
+
+        let mut writes = Vec::new();
+        let committed = transaction
+            .commit_with(|path, content| {
+                writes.push((path.to_string(), content.to_string()));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(committed, vec!["src/a.ts", "src/b.tsx"]);
+        assert_eq!(writes[0], ("src/a.ts".to_string(), "const a = 1;\n".to_string()));
+        assert!(staged_paths.iter().all(|path| !path.exists()), "staging files are cleaned up");
+    }
+
+    #[test]
+    fn test_only_javascript_and_typescript_files_are_verified() {
+        let dir = tempdir().unwrap();
+        let mut transaction = WriteTransaction::new(dir.path().join("staging"));
+        transaction.stage("README.md", "# Usage {\n").unwrap();
+        transaction.stage("src/a.ts", "const a = 1;\n").unwrap();
+
+        let committed = transaction.commit_with(|_, _| Ok(())).unwrap();
+
+        assert_eq!(committed, vec!["README.md", "src/a.ts"]);
+    }
+
+    #[test]
+    fn test_failed_stage_discards_files_already_staged() {
+        let dir = tempdir().unwrap();
+        let staging_dir = dir.path().join("staging");
+        let mut transaction = WriteTransaction::new(&staging_dir);
+        transaction.stage("src/a.ts", "const a = 1;\n").unwrap();
+        let first = transaction.staged()[0].staged_path.clone();
+        // A directory where the next staged file would go makes the write fail
+        fs::create_dir(staging_dir.join("0001.staged")).unwrap();
+
+        assert!(transaction.stage("src/b.ts", "const b = 2;\n").is_err());
+
+        assert!(transaction.staged().is_empty());
+        assert!(!first.exists(), "files staged before the failure are cleaned up");
+    }
+
+    #[test]
+    fn test_write_failure_rolls_back_written_files() {
+        let dir = tempdir().unwrap();
+        let existing = dir.path().join("a.ts");
+        fs::write(&existing, "let a = 1;\n").unwrap();
+        let existing = existing.display().to_string();
+
+        let mut transaction = WriteTransaction::new(dir.path().join("staging"));
+        transaction.stage(&existing, "const a = 1;\n").unwrap();
+        transaction.stage("src/b.ts", "const b = 2;\n").unwrap();
+
+        let result = transaction.commit_with(|path, content| {
+            if path == "src/b.ts" {
+                return Err("disk full".to_string());
+            }
+            fs::write(path, content).map_err(|e| e.to_string())
+        });
+
+        assert!(matches!(result, Err(Error::Multiple { successful_count: 1, .. })));
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "let a = 1;\n");
+    }
+}